@@ -0,0 +1,43 @@
+//! Stamps the git commit the binary was built from into `ANSIBLE_OPERATOR_GIT_COMMIT`, for
+//! `ansible-operator --version`. Setting that variable in the build environment overrides the
+//! lookup (e.g. for builds from a source tarball); with neither, it reads `unknown`. The Kubernetes
+//! API version the enabled `k8s-openapi` feature targets goes into `ANSIBLE_OPERATOR_K8S_API_VERSION`.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=ANSIBLE_OPERATOR_GIT_COMMIT");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+
+    let commit = std::env::var("ANSIBLE_OPERATOR_GIT_COMMIT")
+        .ok()
+        .filter(|commit| !commit.is_empty())
+        .or_else(git_commit)
+        .unwrap_or_else(|| "unknown".into());
+
+    println!("cargo:rustc-env=ANSIBLE_OPERATOR_GIT_COMMIT={commit}");
+
+    let k8s_api_version = k8s_api_version().unwrap_or_else(|| "unknown".into());
+    println!("cargo:rustc-env=ANSIBLE_OPERATOR_K8S_API_VERSION={k8s_api_version}");
+}
+
+/// The version `k8s-openapi`'s build script publishes as `DEP_K8S_OPENAPI_<crate version>_VERSION`:
+/// `0x00MMmm00` for Kubernetes `MM.mm`.
+fn k8s_api_version() -> Option<String> {
+    let (_, version) = std::env::vars()
+        .find(|(key, _)| key.starts_with("DEP_K8S_OPENAPI_") && key.ends_with("_VERSION"))?;
+    let version = version.parse::<u32>().ok()?;
+    Some(format!("v{}.{}", version >> 16 & 0xff, version >> 8 & 0xff))
+}
+
+fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    let commit = String::from_utf8(output.stdout).ok()?;
+    Some(commit.trim().to_string()).filter(|commit| !commit.is_empty())
+}
@@ -54,6 +54,12 @@ pub struct OperatorConfig {
     /// Helm chart from `managedSsh.readiness` into the `[managed_ssh]` table; absent ⇒ all defaults.
     #[serde(default)]
     pub managed_ssh: ManagedSshConfig,
+
+    /// Whether the cluster supports `image` volumes (the `ImageVolume` feature, Kubernetes 1.31+),
+    /// which `template.collectionsImage` needs. Off by default: a plan using it is then refused as
+    /// degraded instead of creating a pod that can never start.
+    #[serde(default)]
+    pub image_volumes: bool,
 }
 
 /// The `[managed_ssh]` config table: tunables for the adaptive readiness gate. The base wait is
@@ -10,10 +10,11 @@
 //! `just docs`, or read the published site). Start there unless you are working on the operator
 //! itself.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use clap::{Parser, Subcommand};
 use futures_util::StreamExt as _;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
 use kube::CustomResourceExt as _;
 use kube::config::KubeConfigOptions;
 use tokio::join;
@@ -23,7 +24,7 @@ use tracing_subscriber::util::SubscriberInitExt as _;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::{fmt, layer::SubscriberExt as _};
 
-use v1beta1::ca::CertificateAuthority;
+use v1beta1::{ca::CertificateAuthority, playbookplancontroller::reconciler::ReconcileIntervals};
 
 mod config;
 mod utils;
@@ -31,10 +32,22 @@ mod v1beta1;
 
 use config::OperatorConfig;
 
+/// `--version` output: the crate version, the git commit it was built from and the Kubernetes API
+/// version the `k8s-openapi` types target (both see `build.rs`). `-V` prints just the crate version.
+const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "\ncommit: ",
+    env!("ANSIBLE_OPERATOR_GIT_COMMIT"),
+    "\nkubernetes api: ",
+    env!("ANSIBLE_OPERATOR_K8S_API_VERSION"),
+);
+
 #[derive(Parser)]
 #[command(
     name = "ansible-operator",
-    about = "Kubernetes operator for running Ansible playbooks against cluster nodes"
+    about = "Kubernetes operator for running Ansible playbooks against cluster nodes",
+    version,
+    long_version = LONG_VERSION
 )]
 struct Cli {
     #[command(subcommand)]
@@ -45,8 +58,36 @@ struct Cli {
 enum Command {
     /// Run the operator control loop (the normal in-cluster entrypoint).
     Run(RunArgs),
-    /// Print the CRD manifests (YAML) to stdout and exit.
-    Crds,
+    /// Print the manifests (YAML) of every CRD the operator serves to stdout and exit.
+    Crds(CrdsArgs),
+    /// Check PlaybookPlan manifests offline, without a cluster, and exit non-zero on any problem.
+    /// Other documents in the files (inventories, policies, ...) are skipped.
+    Validate(ValidateArgs),
+    /// Print the workspace Secret or ConfigMap (incl. the rendered inventory) and the Job the
+    /// operator would create for each PlaybookPlan in a file, without a cluster. Cluster Nodes and
+    /// anything read from live Secrets are replaced by placeholders.
+    Render(RenderArgs),
+}
+
+#[derive(clap::Args)]
+struct CrdsArgs {
+    /// Print only the CRD of this kind, e.g. `PlaybookPlan` (case-insensitive).
+    #[arg(long)]
+    kind: Option<String>,
+}
+
+#[derive(clap::Args)]
+struct RenderArgs {
+    /// YAML file with one or more PlaybookPlans. StaticInventories in the same file are used for
+    /// the plans that reference them.
+    file: String,
+}
+
+#[derive(clap::Args)]
+struct ValidateArgs {
+    /// YAML files to check; each may hold several `---`-separated documents.
+    #[arg(required = true)]
+    files: Vec<String>,
 }
 
 #[derive(clap::Args)]
@@ -55,32 +96,273 @@ struct RunArgs {
     /// chart-rendered ConfigMap mounted at the default path; override it for local runs.
     #[arg(long, short, default_value = config::DEFAULT_CONFIG_PATH)]
     config: String,
+    /// Confine the operator to this one namespace, for deployments without cluster-wide RBAC: only
+    /// PlaybookPlans and StaticInventories there are watched, it replaces the configured
+    /// `watch_namespaces`, and ClusterInventory/NodeAccessPolicy (which need Node access) are off.
+    #[arg(long, env = "WATCH_NAMESPACE")]
+    namespace: Option<String>,
+    /// Resolve and evaluate PlaybookPlans as usual but only log what would be created or written,
+    /// to preview a new operator version or config against a live cluster without touching it.
+    #[arg(long, env = "DRY_RUN")]
+    dry_run: bool,
+    /// How long a plan with nothing due waits before it is re-checked anyway, e.g. `1h` (the
+    /// default) or `30m`. Schedules and running Jobs requeue on their own, sooner.
+    #[arg(long, env = "REQUEUE_INTERVAL", value_parser = parse_requeue_interval)]
+    requeue_interval: Option<Duration>,
+    /// How soon a failed reconcile is first retried, e.g. `15s` (the default). Repeated apiserver
+    /// errors back off exponentially from there, up to 5 minutes.
+    #[arg(long, env = "ERROR_REQUEUE_INTERVAL", value_parser = parse_error_requeue_interval)]
+    error_requeue_interval: Option<Duration>,
+    /// How long after a scheduled tick a run may still start, for plans without
+    /// `startingDeadlineSeconds`, e.g. `30s` (the default). At least 5s.
+    #[arg(long, env = "SCHEDULE_WINDOW", value_parser = parse_schedule_window)]
+    schedule_window: Option<Duration>,
+    /// Serve only the PlaybookPlans matching this label selector, e.g. `shard=a` or
+    /// `!ansible.cloudbending.dev/instance`, so several operator instances can split plans between
+    /// them. Without it every plan is served. Also accepted as `--plan-selector`.
+    #[arg(long, visible_alias = "plan-selector", env = "WATCH_LABEL_SELECTOR")]
+    watch_label_selector: Option<String>,
+    /// How many Jobs the operator may run at once across all enrolled namespaces; runs due beyond
+    /// that wait for one to finish. Unlimited by default.
+    #[arg(long, env = "MAX_CONCURRENT_JOBS", value_parser = clap::value_parser!(u32).range(1..))]
+    max_concurrent_jobs: Option<u32>,
+}
+
+/// Parses a duration given as whole seconds (`90`) or a number with an `s`, `m` or `h` suffix.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("{value:?} is not a duration like 30s, 5m or 1h"))?;
+    let seconds = match unit {
+        "s" => number,
+        "m" => number.saturating_mul(60),
+        "h" => number.saturating_mul(3600),
+        _ => return Err(format!("{value:?} has an unknown unit; use s, m or h")),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// `parse_duration`, refusing values outside `min..=max`.
+fn parse_bounded_duration(value: &str, min: Duration, max: Duration) -> Result<Duration, String> {
+    let duration = parse_duration(value)?;
+    if duration < min || duration > max {
+        return Err(format!(
+            "must be between {}s and {}s",
+            min.as_secs(),
+            max.as_secs()
+        ));
+    }
+    Ok(duration)
+}
+
+fn parse_requeue_interval(value: &str) -> Result<Duration, String> {
+    parse_bounded_duration(
+        value,
+        Duration::from_secs(10),
+        Duration::from_secs(7 * 86_400),
+    )
+}
+
+fn parse_error_requeue_interval(value: &str) -> Result<Duration, String> {
+    parse_bounded_duration(value, Duration::from_secs(1), Duration::from_secs(3600))
+}
+
+fn parse_schedule_window(value: &str) -> Result<Duration, String> {
+    parse_bounded_duration(value, Duration::from_secs(5), Duration::from_secs(86_400))
 }
 
 #[tokio::main]
 async fn main() {
     match Cli::parse().command {
-        Command::Crds => print!("{}", render_crds()),
+        Command::Crds(args) => match render_crds(args.kind.as_deref()) {
+            Ok(crds) => print!("{crds}"),
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+        },
+        Command::Validate(args) => {
+            if !validate(&args.files) {
+                std::process::exit(1);
+            }
+        }
+        Command::Render(args) => match std::fs::read_to_string(&args.file)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| render_manifests(&contents))
+        {
+            Ok(rendered) => print!("{rendered}"),
+            Err(err) => {
+                eprintln!("{}: {err}", args.file);
+                std::process::exit(1);
+            }
+        },
         Command::Run(args) => run(args).await,
     }
 }
 
-/// Renders all CRDs as a single multi-document YAML string (for `kubectl apply` / Helm chart
-/// generation). See `chart/README.md` for how the bundled `crds/` snapshot is regenerated.
-fn render_crds() -> String {
-    let playbookplan = v1beta1::PlaybookPlan::crd();
-    let play = v1beta1::Play::crd();
-    let cluster_inventory = v1beta1::ClusterInventory::crd();
-    let static_inventory = v1beta1::StaticInventory::crd();
-    let node_access_policy = v1beta1::NodeAccessPolicy::crd();
-    [
-        serde_yaml::to_string(&playbookplan).unwrap(),
-        serde_yaml::to_string(&play).unwrap(),
-        serde_yaml::to_string(&cluster_inventory).unwrap(),
-        serde_yaml::to_string(&static_inventory).unwrap(),
-        serde_yaml::to_string(&node_access_policy).unwrap(),
+/// Renders every `PlaybookPlan` in `contents` into its workspace and Job, as one
+/// multi-document YAML string.
+fn render_manifests(contents: &str) -> Result<String, String> {
+    use serde::Deserialize as _;
+
+    let mut plans = Vec::new();
+    let mut static_inventories = std::collections::BTreeMap::new();
+
+    for document in serde_yaml::Deserializer::from_str(contents) {
+        let value = serde_yaml::Value::deserialize(document).map_err(|err| err.to_string())?;
+        match value.get("kind").and_then(|kind| kind.as_str()) {
+            Some("PlaybookPlan") => plans.push(
+                serde_yaml::from_value::<v1beta1::PlaybookPlan>(value)
+                    .map_err(|err| err.to_string())?,
+            ),
+            Some("StaticInventory") => {
+                let inventory = serde_yaml::from_value::<v1beta1::StaticInventory>(value)
+                    .map_err(|err| err.to_string())?;
+                let name = inventory.metadata.name.clone().unwrap_or_default();
+                static_inventories.insert(name, inventory);
+            }
+            _ => {}
+        }
+    }
+
+    if plans.is_empty() {
+        return Err("no PlaybookPlan found".into());
+    }
+
+    let mut documents = Vec::new();
+    for plan in &plans {
+        let preview = v1beta1::playbookplancontroller::render(plan, &static_inventories)
+            .map_err(|err| err.to_string())?;
+        documents.push(serde_yaml::to_string(&preview.workspace).map_err(|e| e.to_string())?);
+        documents.push(serde_yaml::to_string(&preview.job).map_err(|e| e.to_string())?);
+    }
+
+    Ok(documents.join("---\n"))
+}
+
+/// Validates every `PlaybookPlan` document in `files`, printing one line per problem (or per valid
+/// plan) to stdout. Returns whether everything passed, which needs at least one plan to check.
+fn validate(files: &[String]) -> bool {
+    let mut all_valid = true;
+    let mut validated = 0;
+
+    for file in files {
+        let plans = match std::fs::read_to_string(file) {
+            Ok(contents) => validate_manifests(&contents),
+            Err(err) => {
+                println!("{file}: cannot read file: {err}");
+                all_valid = false;
+                continue;
+            }
+        };
+
+        for plan in &plans {
+            if plan.problems.is_empty() {
+                println!("{file}: {}: ok", plan.name);
+            }
+            for problem in &plan.problems {
+                println!("{file}: {}: {problem}", plan.name);
+            }
+        }
+        all_valid &= plans.iter().all(|plan| plan.problems.is_empty());
+        validated += plans.len();
+    }
+
+    if validated == 0 {
+        println!("no PlaybookPlan found");
+        return false;
+    }
+
+    all_valid
+}
+
+/// What `validate_manifests` found for one `PlaybookPlan` document, or for a document that isn't
+/// valid YAML.
+#[derive(Debug, PartialEq)]
+struct ValidatedPlan {
+    /// The plan's name, or `document <index>` for one without.
+    name: String,
+    /// Everything wrong with it, empty if it's valid.
+    problems: Vec<String>,
+}
+
+/// Checks each `PlaybookPlan` among the YAML documents in `contents`, plus each document that
+/// doesn't parse, in order.
+fn validate_manifests(contents: &str) -> Vec<ValidatedPlan> {
+    use serde::Deserialize as _;
+
+    let mut plans = Vec::new();
+
+    for (index, document) in serde_yaml::Deserializer::from_str(contents).enumerate() {
+        let value = match serde_yaml::Value::deserialize(document) {
+            Ok(value) => value,
+            Err(err) => {
+                plans.push(ValidatedPlan {
+                    name: format!("document {index}"),
+                    problems: vec![format!("not valid YAML: {err}")],
+                });
+                continue;
+            }
+        };
+        if value.get("kind").and_then(|kind| kind.as_str()) != Some("PlaybookPlan") {
+            continue;
+        }
+
+        let name = value
+            .get("metadata")
+            .and_then(|metadata| metadata.get("name"))
+            .and_then(|name| name.as_str())
+            .map_or_else(|| format!("document {index}"), str::to_string);
+
+        let problems = match serde_yaml::from_value::<v1beta1::PlaybookPlan>(value) {
+            Ok(plan) => v1beta1::playbookplancontroller::validate(&plan)
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            Err(err) => vec![format!("does not match the PlaybookPlan schema: {err}")],
+        };
+        plans.push(ValidatedPlan { name, problems });
+    }
+
+    plans
+}
+
+/// Every CRD the operator serves, in the order `crds` prints them.
+fn crds() -> Vec<CustomResourceDefinition> {
+    vec![
+        v1beta1::PlaybookPlan::crd(),
+        v1beta1::Play::crd(),
+        v1beta1::ClusterInventory::crd(),
+        v1beta1::StaticInventory::crd(),
+        v1beta1::NodeAccessPolicy::crd(),
     ]
-    .join("---\n")
+}
+
+/// Renders all CRDs, or only the one whose kind is `kind`, as a single multi-document YAML string
+/// (for `kubectl apply` / Helm chart generation). See `chart/README.md` for how the bundled `crds/`
+/// snapshot is regenerated.
+fn render_crds(kind: Option<&str>) -> Result<String, String> {
+    let selected: Vec<_> = crds()
+        .into_iter()
+        .filter(|crd| kind.is_none_or(|kind| crd.spec.names.kind.eq_ignore_ascii_case(kind)))
+        .collect();
+    if selected.is_empty() {
+        let kinds: Vec<_> = crds().into_iter().map(|crd| crd.spec.names.kind).collect();
+        return Err(format!(
+            "unknown CRD kind {:?}; expected one of {}",
+            kind.unwrap_or_default(),
+            kinds.join(", ")
+        ));
+    }
+    Ok(selected
+        .iter()
+        .map(|crd| serde_yaml::to_string(crd).unwrap())
+        .collect::<Vec<_>>()
+        .join("---\n"))
 }
 
 async fn run(args: RunArgs) {
@@ -94,7 +376,15 @@ async fn run(args: RunArgs) {
     // rather than being hot-reloaded. Override the path with `run --config <path>` for local runs.
     let operator_config = OperatorConfig::load(&args.config)
         .unwrap_or_else(|e| panic!("failed to load operator config: {e}"));
-    let enrolled_namespaces = operator_config.enrolled_namespaces(&operator_namespace);
+    let enrolled_namespaces = match &args.namespace {
+        Some(namespace) => {
+            if !operator_config.watch_namespaces.is_empty() {
+                warn!("--namespace {namespace} is set; ignoring the configured watch_namespaces");
+            }
+            std::collections::BTreeSet::from([namespace.clone(), operator_namespace.clone()])
+        }
+        None => operator_config.enrolled_namespaces(&operator_namespace),
+    };
     tracing::info!(
         "enrolled namespaces (Secret/Job access is scoped to these): {:?}",
         enrolled_namespaces
@@ -128,6 +418,24 @@ async fn run(args: RunArgs) {
             .expect("failed to generate the operator's ephemeral SSH certificate authority"),
     );
 
+    let defaults = ReconcileIntervals::default();
+    let intervals = ReconcileIntervals {
+        requeue: args.requeue_interval.unwrap_or(defaults.requeue),
+        error_requeue: args
+            .error_requeue_interval
+            .unwrap_or(defaults.error_requeue),
+        schedule_window: args.schedule_window.unwrap_or(defaults.schedule_window),
+    };
+
+    match &args.watch_label_selector {
+        Some(selector) => tracing::info!("serving PlaybookPlans matching {selector:?}"),
+        None => tracing::info!("serving all PlaybookPlans (no --watch-label-selector)"),
+    }
+
+    if args.dry_run {
+        warn!("dry run: PlaybookPlans are evaluated but no Jobs, Secrets or status are written");
+    }
+
     let playbookplan_controller = v1beta1::playbookplancontroller::reconciler::new(
         client.clone(),
         operator_namespace,
@@ -135,6 +443,12 @@ async fn run(args: RunArgs) {
         ca,
         proxy_image,
         proxy_grace,
+        args.namespace.clone(),
+        args.dry_run,
+        operator_config.image_volumes,
+        intervals,
+        args.watch_label_selector.clone(),
+        args.max_concurrent_jobs.map(|max| max as usize),
     )
     .for_each(|res| async move {
         match res {
@@ -143,6 +457,15 @@ async fn run(args: RunArgs) {
         }
     });
 
+    // Both of these resolve Nodes, which a namespace-confined operator has no RBAC to read.
+    if let Some(namespace) = &args.namespace {
+        tracing::info!(
+            "confined to namespace {namespace}: ClusterInventory and NodeAccessPolicy controllers are disabled"
+        );
+        playbookplan_controller.await;
+        return;
+    }
+
     let inventory_controller =
         v1beta1::clusterinventorycontroller::new(client.clone()).for_each(|res| async move {
             match res {
@@ -169,13 +492,49 @@ async fn run(args: RunArgs) {
 fn setup_tracing() {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
-    tracing_subscriber::registry()
+    let registry = tracing_subscriber::registry()
         .with(fmt::layer())
-        .with(filter)
+        .with(filter);
+    #[cfg(feature = "otel")]
+    let registry = registry.with(otel_layer());
+
+    registry
         .try_init()
         .expect("tracing-subscriber setup failed");
 }
 
+/// Exports spans over OTLP/gRPC when `OTEL_EXPORTER_OTLP_ENDPOINT` is set; `None` (no export)
+/// otherwise. The exporter reads the endpoint and the other standard `OTEL_EXPORTER_OTLP_*`
+/// variables itself. Each reconcile is a span (see `playbookplancontroller::reconciler`), with
+/// kube's API requests as children.
+#[cfg(feature = "otel")]
+fn otel_layer<S>()
+-> Option<tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+
+    std::env::var_os("OTEL_EXPORTER_OTLP_ENDPOINT")?;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .build()
+        .expect("failed to set up the OTLP span exporter");
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            opentelemetry_sdk::Resource::builder()
+                .with_service_name("ansible-operator")
+                .build(),
+        )
+        .build();
+    let tracer = provider.tracer("ansible-operator");
+    opentelemetry::global::set_tracer_provider(provider);
+
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
 async fn discover_kubernetes_config() -> kube::Config {
     let from_default_kubeconfig =
         kube::Config::from_kubeconfig(&KubeConfigOptions::default()).await;
@@ -209,7 +568,87 @@ mod tests {
             Cli::try_parse_from(["ansible-operator", "run", "--config", "/etc/foo.toml"]).unwrap();
         match cli.command {
             Command::Run(args) => assert_eq!(args.config, "/etc/foo.toml"),
-            Command::Crds => panic!("expected the run subcommand"),
+            _ => panic!("expected the run subcommand"),
+        }
+    }
+
+    #[test]
+    fn run_parses_namespace_flag() {
+        let cli =
+            Cli::try_parse_from(["ansible-operator", "run", "--namespace", "team-a"]).unwrap();
+        match cli.command {
+            Command::Run(args) => assert_eq!(args.namespace.as_deref(), Some("team-a")),
+            _ => panic!("expected the run subcommand"),
+        }
+    }
+
+    #[test]
+    fn run_parses_dry_run_flag() {
+        let cli = Cli::try_parse_from(["ansible-operator", "run", "--dry-run"]).unwrap();
+        match cli.command {
+            Command::Run(args) => assert!(args.dry_run),
+            _ => panic!("expected the run subcommand"),
+        }
+    }
+
+    #[test]
+    fn run_accepts_plan_selector_as_an_alias_of_watch_label_selector() {
+        for flag in ["--watch-label-selector", "--plan-selector"] {
+            let cli = Cli::try_parse_from(["ansible-operator", "run", flag, "shard=a"]).unwrap();
+            match cli.command {
+                Command::Run(args) => {
+                    assert_eq!(args.watch_label_selector.as_deref(), Some("shard=a"))
+                }
+                _ => panic!("expected the run subcommand"),
+            }
+        }
+    }
+
+    #[test]
+    fn durations_parse_with_and_without_a_unit() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("45s"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("1d").is_err());
+        assert!(parse_duration("-5s").is_err());
+        assert!(parse_duration("1.5h").is_err());
+    }
+
+    #[test]
+    fn run_parses_intervals_and_enforces_their_minimums() {
+        let cli = Cli::try_parse_from([
+            "ansible-operator",
+            "run",
+            "--requeue-interval",
+            "30m",
+            "--error-requeue-interval",
+            "5s",
+            "--schedule-window",
+            "1m",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Run(args) => {
+                assert_eq!(args.requeue_interval, Some(Duration::from_secs(1800)));
+                assert_eq!(args.error_requeue_interval, Some(Duration::from_secs(5)));
+                assert_eq!(args.schedule_window, Some(Duration::from_secs(60)));
+            }
+            _ => panic!("expected the run subcommand"),
+        }
+
+        for (flag, value) in [
+            ("--requeue-interval", "5s"),
+            ("--error-requeue-interval", "0"),
+            ("--schedule-window", "4s"),
+            ("--schedule-window", "2d"),
+        ] {
+            assert!(
+                Cli::try_parse_from(["ansible-operator", "run", flag, value]).is_err(),
+                "{flag} {value}"
+            );
         }
     }
 
@@ -218,14 +657,174 @@ mod tests {
         let cli = Cli::try_parse_from(["ansible-operator", "run"]).unwrap();
         match cli.command {
             Command::Run(args) => assert_eq!(args.config, config::DEFAULT_CONFIG_PATH),
-            Command::Crds => panic!("expected the run subcommand"),
+            _ => panic!("expected the run subcommand"),
         }
     }
 
     #[test]
     fn crds_subcommand_parses() {
         let cli = Cli::try_parse_from(["ansible-operator", "crds"]).unwrap();
-        assert!(matches!(cli.command, Command::Crds));
+        assert!(matches!(
+            cli.command,
+            Command::Crds(CrdsArgs { kind: None })
+        ));
+    }
+
+    #[test]
+    fn render_crds_emits_every_crd_or_only_the_requested_kind() {
+        let all = render_crds(None).unwrap();
+        assert_eq!(
+            all.matches("kind: CustomResourceDefinition").count(),
+            crds().len()
+        );
+        assert_eq!(all.matches("---\n").count(), crds().len() - 1);
+
+        let one = render_crds(Some("staticinventory")).unwrap();
+        assert!(!one.contains("---"), "{one}");
+        assert!(
+            one.contains("name: staticinventories.ansible.cloudbending.dev"),
+            "{one}"
+        );
+
+        let err = render_crds(Some("Inventory")).unwrap_err();
+        assert!(err.contains("\"Inventory\""), "{err}");
+        assert!(
+            err.contains("PlaybookPlan, Play, ClusterInventory"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn version_flag_prints_build_info() {
+        let err = Cli::try_parse_from(["ansible-operator", "--version"])
+            .err()
+            .expect("--version short-circuits parsing");
+        assert_eq!(err.kind(), clap::error::ErrorKind::DisplayVersion);
+
+        let output = err.to_string();
+        assert!(output.contains(env!("CARGO_PKG_VERSION")), "{output}");
+        assert!(output.contains("commit: "), "{output}");
+        assert!(!env!("ANSIBLE_OPERATOR_GIT_COMMIT").is_empty());
+        assert!(output.contains("kubernetes api: v1."), "{output}");
+    }
+
+    #[test]
+    fn validate_reports_each_plan_and_skips_other_kinds() {
+        let manifests = r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: StaticInventory
+metadata:
+  name: edge
+spec: {}
+---
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: good
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+---
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: bad
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  schedule: "nope"
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+---
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: no-spec
+"#;
+
+        let plans = validate_manifests(manifests);
+
+        let names: Vec<_> = plans.iter().map(|plan| plan.name.as_str()).collect();
+        assert_eq!(names, ["good", "bad", "no-spec"]);
+        assert!(plans[0].problems.is_empty(), "{plans:?}");
+        let [problem] = plans[1].problems.as_slice() else {
+            panic!("expected one problem: {plans:?}");
+        };
+        assert!(problem.starts_with("schedule"), "{problem}");
+        let [problem] = plans[2].problems.as_slice() else {
+            panic!("expected one problem: {plans:?}");
+        };
+        assert!(
+            problem.starts_with("does not match the PlaybookPlan schema"),
+            "{problem}"
+        );
+    }
+
+    #[test]
+    fn the_bundled_examples_validate() {
+        let plans = validate_manifests(include_str!("../examples/v1beta1/oneshot-fail-pp.yaml"));
+        assert_eq!(
+            plans,
+            [ValidatedPlan {
+                name: "oneshot-fail".into(),
+                problems: Vec::new(),
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_fails_without_a_plan_to_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, contents: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            path.to_string_lossy().into_owned()
+        };
+        let inventory = write(
+            "inventory.yaml",
+            "apiVersion: ansible.cloudbending.dev/v1beta1\nkind: StaticInventory\nmetadata:\n  name: edge\nspec: {}\n",
+        );
+        let plan = write(
+            "plan.yaml",
+            include_str!("../examples/v1beta1/oneshot-fail-pp.yaml"),
+        );
+
+        assert!(!validate(std::slice::from_ref(&inventory)));
+        assert!(validate(&[inventory, plan]));
+    }
+
+    #[test]
+    fn validate_requires_at_least_one_file() {
+        assert!(Cli::try_parse_from(["ansible-operator", "validate"]).is_err());
+        let cli =
+            Cli::try_parse_from(["ansible-operator", "validate", "a.yaml", "b.yaml"]).unwrap();
+        match cli.command {
+            Command::Validate(args) => assert_eq!(args.files, vec!["a.yaml", "b.yaml"]),
+            _ => panic!("expected the validate subcommand"),
+        }
+    }
+
+    #[test]
+    fn render_prints_a_secret_and_a_job_per_plan() {
+        let rendered =
+            render_manifests(include_str!("../examples/v1beta1/oneshot-fail-pp.yaml")).unwrap();
+
+        let kinds: Vec<String> = serde_yaml::Deserializer::from_str(&rendered)
+            .map(|document| {
+                use serde::Deserialize as _;
+                let value = serde_yaml::Value::deserialize(document).unwrap();
+                value["kind"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(kinds, vec!["Secret", "Job"]);
+        assert!(rendered.contains("inventory.yml"), "{rendered}");
+    }
+
+    #[test]
+    fn render_without_a_plan_is_an_error() {
+        assert!(render_manifests("kind: StaticInventory\nspec: {}\n").is_err());
     }
 
     #[test]
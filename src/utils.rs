@@ -1,8 +1,68 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, future::Future, hash::Hash, sync::Arc, time::Duration};
 
-use kube::api::{Patch, PatchParams, PostParams};
+use futures_util::StreamExt as _;
+use kube::{
+    Resource,
+    api::{Patch, PatchParams, PostParams},
+    runtime::{
+        WatchStreamExt as _,
+        reflector::{Store, store::Writer},
+        watcher,
+    },
+};
 use serde::{Serialize, de::DeserializeOwned};
+use tracing::{debug, error};
 
+/// How many times `retry_on_conflict` runs its operation before handing a 409 back to the caller.
+const CONFLICT_RETRY_ATTEMPTS: u32 = 4;
+
+/// Delay before the first conflict retry; doubled on every further attempt.
+const CONFLICT_RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+
+/// Spawns a reflector keeping a `Store` of `api`'s objects current, for the mappers. A watch error
+/// (an apiserver restart, a revoked RBAC grant) is logged and retried with kube's default
+/// exponential backoff instead of in a tight loop; the watcher relists once it gets through again,
+/// so the store catches up rather than going stale. `kind` names the objects in the logs.
+pub fn spawn_reflector<K>(api: kube::Api<K>, kind: &'static str) -> Arc<Store<K>>
+where
+    K: Resource + DeserializeOwned + Clone + Debug + Send + Sync + 'static,
+    K::DynamicType: Default + Eq + Hash + Clone + Send + Sync,
+{
+    spawn_reflector_with_config(api, watcher::Config::default(), kind)
+}
+
+/// [`spawn_reflector`] watching with `config`, e.g. to cache only objects matching a label selector.
+pub fn spawn_reflector_with_config<K>(
+    api: kube::Api<K>,
+    config: watcher::Config,
+    kind: &'static str,
+) -> Arc<Store<K>>
+where
+    K: Resource + DeserializeOwned + Clone + Debug + Send + Sync + 'static,
+    K::DynamicType: Default + Eq + Hash + Clone + Send + Sync,
+{
+    let writer = Writer::<K>::default();
+    let reader = Arc::new(writer.as_reader());
+
+    let reflector = kube::runtime::reflector(writer, watcher(api, config).default_backoff());
+    tokio::spawn(async move {
+        reflector
+            .for_each(|event| async {
+                if let Err(e) = event {
+                    error!("{kind} reflector error: {e:?}");
+                }
+            })
+            .await;
+        // The backed-off watcher never ends on its own; should it, the store is frozen from here on.
+        error!("{kind} reflector stopped");
+    });
+
+    reader
+}
+
+/// Creates `resource` as `resource_name`, or server-side applies it over the existing object after
+/// `mutate_fn` has seen both. A create that loses the race against a concurrent one (409
+/// `AlreadyExists`) isn't an error: the object now exists, so it is updated like any other.
 pub async fn create_or_update<K>(
     api: &kube::Api<K>,
     field_manager: &str,
@@ -13,34 +73,81 @@ pub async fn create_or_update<K>(
 where
     K: DeserializeOwned + Serialize + Clone + Debug,
 {
-    if let Some(existing_resource) = api.get_opt(resource_name).await? {
-        let mut updated_resource = resource.clone();
-        mutate_fn(existing_resource, &mut updated_resource);
-
-        api.patch(
-            resource_name,
-            &PatchParams::apply(field_manager),
-            &Patch::Apply(&updated_resource),
-        )
-        .await?;
-    } else {
-        api.create(
-            &PostParams {
+    let existing_resource = match api.get_opt(resource_name).await? {
+        Some(existing_resource) => existing_resource,
+        None => {
+            let params = PostParams {
                 field_manager: Some(field_manager.into()),
                 ..Default::default()
-            },
-            &resource,
-        )
-        .await?;
-    }
+            };
+            match api.create(&params, &resource).await {
+                Err(err) if is_already_exists(&err) => {
+                    debug!("{resource_name} was created concurrently, updating it instead");
+                    api.get(resource_name).await?
+                }
+                result => return result.map(|_| ()),
+            }
+        }
+    };
+
+    let mut updated_resource = resource.clone();
+    mutate_fn(existing_resource, &mut updated_resource);
+
+    api.patch(
+        resource_name,
+        &PatchParams::apply(field_manager),
+        &Patch::Apply(&updated_resource),
+    )
+    .await?;
 
     Ok(())
 }
 
+/// Whether `err` is the apiserver refusing a create because an object of that name already exists.
+/// Most creates check for the object first, so this is another reconcile (or the previous tick)
+/// having created it in between, and rarely worth more than a debug line.
+pub fn is_already_exists(err: &kube::Error) -> bool {
+    matches!(err, kube::Error::Api(status) if status.reason == "AlreadyExists")
+}
+
+/// Whether `err` is the apiserver rejecting a write with 409 Conflict: another writer got there
+/// first, or a precondition no longer held. Benign for a level-triggered reconcile, which re-reads
+/// and tries again. A create refused with `AlreadyExists` is a 409 too, but not a conflict; see
+/// `is_already_exists`.
+pub fn is_conflict(err: &kube::Error) -> bool {
+    matches!(err, kube::Error::Api(status) if status.code == 409 && status.reason == "Conflict")
+}
+
+/// Runs `operation`, re-running it with a short, doubling backoff while it fails with a 409 Conflict,
+/// up to `CONFLICT_RETRY_ATTEMPTS` times in total. Any other error — and the last conflict once the
+/// attempts are used up — is returned unchanged, so the controller's own error requeue still applies.
+/// `operation` is called afresh on every attempt, so it should re-read whatever it writes against.
+pub async fn retry_on_conflict<T, F, Fut>(mut operation: F) -> Result<T, kube::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, kube::Error>>,
+{
+    let mut delay = CONFLICT_RETRY_BASE_DELAY;
+
+    for _ in 1..CONFLICT_RETRY_ATTEMPTS {
+        match operation().await {
+            Err(err) if is_conflict(&err) => {
+                debug!("Conflict ({err}), retrying in {delay:?}");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    operation().await
+}
+
 pub trait Condition {
     fn type_(&self) -> &str;
     fn status(&self) -> &str;
     fn reason(&self) -> Option<&str>;
+    fn observed_generation(&self) -> Option<i64>;
 }
 
 pub fn upsert_condition<T: Condition>(conditions: &mut Vec<T>, new_condition: T) {
@@ -48,9 +155,11 @@ pub fn upsert_condition<T: Condition>(conditions: &mut Vec<T>, new_condition: T)
         .iter_mut()
         .find(|c| c.type_() == new_condition.type_())
     {
-        // Skip change if we can't see a difference in the new value
+        // Skip change if we can't see a difference in the new value. A newer observedGeneration
+        // counts as one: the condition now vouches for a newer spec, even if it reads the same.
         if existing_condition.status() == new_condition.status()
             && existing_condition.reason() == new_condition.reason()
+            && existing_condition.observed_generation() == new_condition.observed_generation()
         {
             return;
         }
@@ -61,37 +170,422 @@ pub fn upsert_condition<T: Condition>(conditions: &mut Vec<T>, new_condition: T)
     }
 }
 
-fn encode_kubelike(mut num: u64) -> String {
-    const ALPHABET: &[u8] = b"bcdfghjklmnpqrstvwxz2456789";
-
-    if num == 0 {
-        return "a".repeat(6); // return "aaaaaa" if input is zero, fixed length
+/// The label keys a Kubernetes label selector string (`env=prod,tier in (a,b),!legacy`) refers to,
+/// in order. Only splits the requirements apart; the apiserver is what validates the syntax.
+pub fn selector_label_keys(selector: &str) -> Vec<String> {
+    let mut requirements = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in selector.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                requirements.push(&selector[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
     }
-    let base = ALPHABET.len() as u64;
-    let mut chars = Vec::new();
+    requirements.push(&selector[start..]);
+
+    requirements
+        .into_iter()
+        .map(|requirement| requirement.trim().trim_start_matches('!').trim_start())
+        .map(|requirement| {
+            let end = requirement
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '/' | '-')))
+                .unwrap_or(requirement.len());
+            requirement[..end].to_string()
+        })
+        .filter(|key| !key.is_empty())
+        .collect()
+}
+
+/// Digits of `generate_id`, Kubernetes' own `generateName` alphabet (no vowels, no look-alikes).
+const ID_ALPHABET: &[u8] = b"bcdfghjklmnpqrstvwxz2456789";
+
+/// Width of `generate_id`'s output.
+const ID_LEN: u32 = 5;
+
+/// Generate a short Kubernetes-like ID for use in resource names: the `ID_LEN` least-significant
+/// base-27 digits of `num`, always exactly `ID_LEN` characters of `ID_ALPHABET` (zero included).
+///
+/// That window holds 27^5 ≈ 14.3M values, so for a uniformly distributed `num` (an
+/// `ExecutionHash`, which is what every caller passes) two inputs share an ID with odds of about 1 in
+/// 14M — roughly even odds only after some 4,000 distinct hashes. Names built from it also carry the
+/// plan name, and nothing deduplicates by name alone: objects are matched to a run by the full
+/// 64-bit hash label. Job names use the wider [`generate_id_with_len`] instead.
+pub fn generate_id(num: u64) -> String {
+    generate_id_with_len(num, ID_LEN)
+}
+
+/// [`generate_id`] with `len` digits instead of `ID_LEN`. From 14 digits on, the whole of `num`
+/// fits, so longer IDs only add leading zeros (`b`s).
+pub fn generate_id_with_len(num: u64, len: u32) -> String {
+    let base = ID_ALPHABET.len() as u64;
 
-    while num > 0 {
-        let rem = (num % base) as usize;
-        chars.push(ALPHABET[rem] as char);
-        num /= base;
+    let mut rest = base.checked_pow(len).map_or(num, |window| num % window);
+    let mut chars = vec![0u8; len as usize];
+    for c in chars.iter_mut().rev() {
+        *c = ID_ALPHABET[(rest % base) as usize];
+        rest /= base;
     }
 
-    chars.reverse();
-    chars.into_iter().collect()
+    chars.iter().map(|&c| c as char).collect()
 }
 
-/// Generate a short Kubernetes-like ID for use in resource names
-pub fn generate_id(num: u64) -> String {
-    const LEN: usize = 5;
+/// Longest label value Kubernetes accepts — also the budget of a Job's name, which its pod carries
+/// as the `job-name` label.
+pub const MAX_LABEL_VALUE_LEN: usize = 63;
+
+/// Longest object name Kubernetes accepts for most kinds (a DNS subdomain).
+pub const MAX_NAME_LEN: usize = 253;
 
-    let encoded = encode_kubelike(num);
+/// `value` made usable inside an object name (a DNS subdomain) of at most `max_len` characters:
+/// lowercased, every run of characters other than `a-z`, `0-9`, `-` and `.` replaced by one `-`,
+/// dashes and extra dots next to a dot dropped (`a.-b` and `a..b` both become `a.b`), trimmed to
+/// begin and end alphanumerically, and cut to length. A value already fit for that comes back
+/// unchanged. Anything else gets a `-<id>` suffix (see `generate_id`) over the *original* value, so
+/// two hosts that differ only in what was replaced or cut off still get distinct names.
+///
+/// # Panics
+///
+/// Panics if `max_len` leaves no room for the suffix.
+pub fn sanitize_name(value: &str, max_len: usize) -> String {
+    use std::hash::Hasher as _;
+
+    let mut sanitized = String::with_capacity(value.len());
+    for c in value.chars().map(|c| c.to_ascii_lowercase()) {
+        match c {
+            'a'..='z' | '0'..='9' => sanitized.push(c),
+            // A dot ends a part, which must neither be empty nor end in `-`...
+            '.' => {
+                sanitized.truncate(sanitized.trim_end_matches(['-', '.']).len());
+                sanitized.push('.');
+            }
+            // ...nor begin with one.
+            _ if sanitized.ends_with(['-', '.']) => {}
+            _ => sanitized.push('-'),
+        }
+    }
+    let trimmed = sanitized.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+
+    if trimmed == value && value.len() <= max_len {
+        return value.to_string();
+    }
+
+    let mut hasher = twox_hash::XxHash3_64::new();
+    hasher.write(value.as_bytes());
+    let suffix = generate_id(hasher.finish());
+
+    let budget = max_len
+        .checked_sub(suffix.len() + 1)
+        .expect("max_len must leave room for the suffix");
+    let head =
+        trimmed[..trimmed.len().min(budget)].trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+    match head {
+        "" => suffix,
+        head => format!("{head}-{suffix}"),
+    }
+}
+
+/// Whether `value` can be used verbatim as a label value: at most `MAX_LABEL_VALUE_LEN` characters
+/// of `A-Z`, `a-z`, `0-9`, `-`, `_` and `.`, beginning and ending alphanumerically (or empty).
+pub fn is_label_value(value: &str) -> bool {
+    let alphanumeric_ends = value
+        .chars()
+        .next()
+        .is_none_or(|c| c.is_ascii_alphanumeric())
+        && value
+            .chars()
+            .last()
+            .is_none_or(|c| c.is_ascii_alphanumeric());
+    value.len() <= MAX_LABEL_VALUE_LEN
+        && alphanumeric_ends
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
 
-    if encoded.len() == LEN {
-        encoded
-    } else if encoded.len() > LEN {
-        encoded[encoded.len() - LEN..].to_string()
+/// `value` as a label value: verbatim where `is_label_value` allows, its `sanitize_name` form of at
+/// most `MAX_LABEL_VALUE_LEN` characters otherwise. Deterministic, so a selector built from the
+/// same value matches the label.
+pub fn label_value(value: &str) -> String {
+    if is_label_value(value) {
+        value.to_string()
     } else {
-        let padding = "a".repeat(LEN - encoded.len());
-        format!("{padding}{encoded}")
+        sanitize_name(value, MAX_LABEL_VALUE_LEN)
+    }
+}
+
+/// The error of the apiserver answering a request with `code` and `reason`, for tests.
+#[cfg(test)]
+pub fn api_error(code: u16, reason: &str) -> kube::Error {
+    kube::Error::Api(Box::new(kube::core::Status {
+        code,
+        reason: reason.into(),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn selector_label_keys_finds_the_key_of_every_requirement() {
+        assert_eq!(
+            selector_label_keys("ansible.cloudbending.dev/instance=prod"),
+            vec!["ansible.cloudbending.dev/instance"]
+        );
+        assert_eq!(
+            selector_label_keys("env in (prod, lab), !legacy,tier!=db ,team, zone notin (a)"),
+            vec!["env", "legacy", "tier", "team", "zone"]
+        );
+        assert!(selector_label_keys("").is_empty());
+        assert!(selector_label_keys(" , ").is_empty());
+    }
+
+    #[test]
+    fn generate_id_is_always_five_alphabet_characters() {
+        for num in [0, 1, 26, 27, 27u64.pow(5) - 1, 27u64.pow(5), u64::MAX] {
+            let id = generate_id(num);
+            assert_eq!(id.len(), 5, "{num}: {id}");
+            assert!(id.bytes().all(|c| ID_ALPHABET.contains(&c)), "{num}: {id}");
+        }
+        assert_eq!(generate_id(0), "bbbbb");
+        assert_eq!(generate_id(1), "bbbbc");
+    }
+
+    #[test]
+    fn generate_id_keeps_the_low_digits_so_only_values_27_pow_5_apart_collide() {
+        let window = 27u64.pow(5);
+        assert_eq!(generate_id(42), generate_id(42 + window));
+        assert_ne!(generate_id(42), generate_id(43));
+        assert_ne!(generate_id(0), generate_id(window - 1));
+    }
+
+    #[test]
+    fn generate_id_with_len_widens_the_window() {
+        let window = 27u64.pow(5);
+        assert_eq!(generate_id_with_len(42, 5), generate_id(42));
+        assert_ne!(
+            generate_id_with_len(42, 10),
+            generate_id_with_len(42 + window, 10)
+        );
+        assert!(generate_id_with_len(42, 10).ends_with(&generate_id(42)));
+
+        for len in [1, 10, 13, 14, 20] {
+            assert_eq!(generate_id_with_len(u64::MAX, len).len(), len as usize);
+        }
+        assert_eq!(
+            generate_id_with_len(u64::MAX, 20),
+            format!("bbbbbb{}", generate_id_with_len(u64::MAX, 14))
+        );
+    }
+
+    #[test]
+    fn generate_id_collisions_over_hashed_inputs_match_the_birthday_bound() {
+        use std::hash::Hasher as _;
+
+        // 10,000 distinct hashes into 27^5 IDs: about n²/2m ≈ 3.5 colliding pairs expected.
+        let mut seen = std::collections::HashSet::new();
+        let collisions = (0u64..10_000)
+            .filter(|i| {
+                let mut hasher = twox_hash::XxHash3_64::new();
+                hasher.write_u64(*i);
+                !seen.insert(generate_id(hasher.finish()))
+            })
+            .count();
+
+        assert!(collisions < 20, "{collisions} collisions");
+    }
+
+    #[test]
+    fn sanitize_name_keeps_valid_names_and_fixes_nasty_ones() {
+        assert_eq!(
+            sanitize_name("ccu.fritz.box", MAX_NAME_LEN),
+            "ccu.fritz.box"
+        );
+        assert_eq!(sanitize_name("worker-1", MAX_LABEL_VALUE_LEN), "worker-1");
+
+        let nasty = [
+            "Web_Server_01",
+            "_build__host_",
+            "ÄRGER.example.com",
+            "..",
+            "a..b",
+            "a-.b",
+            "a.-b",
+            "web._-.example.com",
+            "a".repeat(300).as_str(),
+            &format!("{}.corp.example.com", "node".repeat(20)),
+        ]
+        .map(ToString::to_string);
+        for host in &nasty {
+            for max_len in [MAX_LABEL_VALUE_LEN, 40, 10] {
+                let name = sanitize_name(host, max_len);
+                assert!(name.len() <= max_len, "{host:?} -> {name:?}");
+                assert!(
+                    name.bytes().all(|c| c.is_ascii_lowercase()
+                        || c.is_ascii_digit()
+                        || c == b'-'
+                        || c == b'.'),
+                    "{host:?} -> {name:?}"
+                );
+                assert!(
+                    name.starts_with(|c: char| c.is_ascii_alphanumeric()),
+                    "{name:?}"
+                );
+                assert!(
+                    name.ends_with(|c: char| c.is_ascii_alphanumeric()),
+                    "{name:?}"
+                );
+                assert!(is_label_value(&name), "{name:?}");
+                for invalid in ["..", ".-", "-."] {
+                    assert!(!name.contains(invalid), "{host:?} -> {name:?}");
+                }
+            }
+        }
+        assert!(sanitize_name("Web_Server_01", 63).starts_with("web-server-01-"));
+        for host in ["a..b", "a-.b", "a.-b"] {
+            assert!(sanitize_name(host, 63).starts_with("a.b-"), "{host:?}");
+        }
+        assert!(sanitize_name("web._-.example.com", 63).starts_with("web.example.com-"));
+
+        // Hosts differing only in what was replaced or cut off stay distinct.
+        assert_ne!(sanitize_name("web_1", 63), sanitize_name("web-1", 63));
+        assert_ne!(sanitize_name("Web-1", 63), sanitize_name("web_1", 63));
+        let long = "x".repeat(70);
+        assert_ne!(
+            sanitize_name(&format!("{long}a"), 63),
+            sanitize_name(&format!("{long}b"), 63)
+        );
+    }
+
+    #[test]
+    fn is_label_value_follows_the_kubernetes_rules() {
+        for valid in ["", "worker-1", "Web_Server.01", &"a".repeat(63)] {
+            assert!(is_label_value(valid), "{valid:?}");
+        }
+        for invalid in ["-web", "web.", "web server", "ccu:22", &"a".repeat(64)] {
+            assert!(!is_label_value(invalid), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn is_already_exists_needs_the_reason_not_just_a_409() {
+        assert!(is_already_exists(&api_error(409, "AlreadyExists")));
+        assert!(!is_already_exists(&api_error(409, "Conflict")));
+        assert!(!is_already_exists(&api_error(404, "NotFound")));
+    }
+
+    #[test]
+    fn is_conflict_needs_the_reason_not_just_a_409() {
+        assert!(is_conflict(&api_error(409, "Conflict")));
+        assert!(!is_conflict(&api_error(409, "AlreadyExists")));
+        assert!(!is_conflict(&api_error(404, "NotFound")));
+        assert!(!is_conflict(&api_error(500, "InternalError")));
+    }
+
+    #[tokio::test]
+    async fn create_or_update_updates_what_a_concurrent_create_got_in_first() {
+        use k8s_openapi::api::core::v1::Secret;
+        use kube::client::Body;
+
+        let (service, mut handle) =
+            tower_test::mock::pair::<http::Request<Body>, http::Response<Body>>();
+        let api = kube::Api::<Secret>::namespaced(kube::Client::new(service, "default"), "default");
+
+        let secret = serde_json::json!({
+            "apiVersion": "v1", "kind": "Secret",
+            "metadata": {"name": "ansible-workspace-web", "namespace": "default"},
+        });
+        let status = |code: u16, reason: &str| {
+            serde_json::json!({
+                "kind": "Status", "apiVersion": "v1", "status": "Failure",
+                "reason": reason, "message": reason, "code": code,
+            })
+        };
+        let responses = [
+            (http::Method::GET, 404, status(404, "NotFound")),
+            (http::Method::POST, 409, status(409, "AlreadyExists")),
+            (http::Method::GET, 200, secret.clone()),
+            (http::Method::PATCH, 200, secret.clone()),
+        ];
+        let server = tokio::spawn(async move {
+            for (method, code, response) in responses {
+                let (request, send) = handle.next_request().await.expect("a request");
+                assert_eq!(request.method(), method);
+                send.send_response(
+                    http::Response::builder()
+                        .status(code)
+                        .body(Body::from(serde_json::to_vec(&response).unwrap()))
+                        .unwrap(),
+                );
+            }
+        });
+
+        let desired: Secret = serde_json::from_value(secret).unwrap();
+        let mut mutated = false;
+        create_or_update(
+            &api,
+            "ansible-operator",
+            "ansible-workspace-web",
+            desired,
+            |_, _| mutated = true,
+        )
+        .await
+        .unwrap();
+
+        server.await.unwrap();
+        assert!(mutated);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_on_conflict_retries_a_conflict_then_succeeds() {
+        let calls = AtomicU32::new(0);
+
+        let result = retry_on_conflict(|| async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(api_error(409, "Conflict")),
+                _ => Ok("persisted"),
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "persisted");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_on_conflict_gives_up_after_the_attempt_budget() {
+        let calls = AtomicU32::new(0);
+
+        let result: Result<(), _> = retry_on_conflict(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(api_error(409, "Conflict"))
+        })
+        .await;
+
+        assert!(matches!(result, Err(kube::Error::Api(s)) if s.code == 409));
+        assert_eq!(calls.load(Ordering::SeqCst), CONFLICT_RETRY_ATTEMPTS);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_on_conflict_does_not_retry_other_errors() {
+        let calls = AtomicU32::new(0);
+
+        let result: Result<(), _> = retry_on_conflict(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(api_error(404, "NotFound"))
+        })
+        .await;
+
+        assert!(matches!(result, Err(kube::Error::Api(s)) if s.code == 404));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 }
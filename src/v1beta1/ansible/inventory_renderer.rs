@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 
 use serde_yaml::{Mapping, Value};
 
-use crate::v1beta1::ResolvedInventoryGroup;
+use crate::v1beta1::{NodeMetadata, ResolvedInventoryGroup};
 
 /// Connect timeout (seconds) rendered for a host we already know is unreachable — its proxy pod never
 /// became Ready, so `pod_ip` is the unroutable sentinel. Kept low because the dial is certain to
@@ -50,11 +50,19 @@ pub struct RenderContext<'a> {
     pub managed_ssh_hosts: &'a BTreeMap<String, ManagedSshHostInfo>,
     pub managed_ssh_client_key_path: &'a str,
     pub managed_ssh_known_hosts_path: &'a str,
-    /// `StaticInventory` resource name -> (private key mount path, known_hosts mount path).
+    /// `StaticInventory` resource name -> where its SSH key material is mounted.
     /// Resolved by the caller (which owns the mount-path conventions in
     /// `controllers::playbookplancontroller::paths`) rather than computed here, so this module
     /// stays decoupled from controller-internal path conventions.
-    pub ssh_paths_by_static_inventory: &'a BTreeMap<String, (String, String)>,
+    pub ssh_paths_by_static_inventory: &'a BTreeMap<String, SshPaths>,
+}
+
+/// Mount paths of one `StaticInventory`'s SSH key material inside the Job pod.
+pub struct SshPaths {
+    pub private_key: String,
+    /// Further identities offered after `private_key`, in order.
+    pub additional_private_keys: Vec<String>,
+    pub known_hosts: String,
 }
 
 pub fn render_inventory(
@@ -68,7 +76,7 @@ pub fn render_inventory(
         let mut host_entries = Mapping::new();
 
         for hostname in &hosts.hosts {
-            let vars = match group {
+            let mut vars = match group {
                 ResolvedInventoryGroup::ManagedSsh { .. } => {
                     render_managed_ssh_host_vars(hostname, ctx)
                 }
@@ -79,6 +87,10 @@ pub fn render_inventory(
                 } => render_ssh_host_vars(static_inventory_name, config, ctx),
             };
 
+            if let Some(metadata) = group.node_metadata(hostname) {
+                render_node_metadata_vars(metadata, &mut vars);
+            }
+
             host_entries.insert(Value::String(hostname.into()), Value::Mapping(vars));
         }
 
@@ -147,6 +159,23 @@ fn render_managed_ssh_host_vars(hostname: &str, ctx: &RenderContext) -> Mapping
     vars
 }
 
+/// `exposeNodeMetadata`'s selection as the `node_labels`/`node_annotations` host vars. Each is
+/// only rendered when selected, then always as a mapping, even one without any of the keys.
+fn render_node_metadata_vars(metadata: &NodeMetadata, vars: &mut Mapping) {
+    for (var, values) in [
+        ("node_labels", &metadata.labels),
+        ("node_annotations", &metadata.annotations),
+    ] {
+        if let Some(values) = values {
+            let values = values
+                .iter()
+                .map(|(key, value)| (Value::String(key.clone()), Value::String(value.clone())))
+                .collect();
+            vars.insert(Value::String(var.into()), Value::Mapping(values));
+        }
+    }
+}
+
 fn render_ssh_host_vars(
     static_inventory_name: &str,
     config: &crate::v1beta1::SshConfig,
@@ -158,16 +187,21 @@ fn render_ssh_host_vars(
         Value::String(config.user.clone()),
     );
 
-    if let Some((key_path, known_hosts_path)) =
-        ctx.ssh_paths_by_static_inventory.get(static_inventory_name)
-    {
+    if let Some(paths) = ctx.ssh_paths_by_static_inventory.get(static_inventory_name) {
         vars.insert(
             Value::String("ansible_ssh_private_key_file".into()),
-            Value::String(key_path.clone()),
+            Value::String(paths.private_key.clone()),
         );
+
+        // `ansible_ssh_private_key_file` takes a single key; any further ones become extra
+        // `IdentityFile`s, which OpenSSH tries in order after the first.
+        let mut common_args = format!("-o UserKnownHostsFile={}", paths.known_hosts);
+        for key in &paths.additional_private_keys {
+            common_args.push_str(&format!(" -o IdentityFile={key}"));
+        }
         vars.insert(
             Value::String("ansible_ssh_common_args".into()),
-            Value::String(format!("-o UserKnownHostsFile={known_hosts_path}")),
+            Value::String(common_args),
         );
     }
 
@@ -188,6 +222,7 @@ mod tests {
             },
             tolerations: None,
             variables: None,
+            node_metadata: Default::default(),
         };
 
         let mut managed_ssh_hosts = BTreeMap::new();
@@ -230,6 +265,7 @@ mod tests {
             },
             tolerations: None,
             variables: None,
+            node_metadata: Default::default(),
         };
 
         let mut managed_ssh_hosts = BTreeMap::new();
@@ -271,6 +307,8 @@ mod tests {
                 secret_ref: SecretRef {
                     name: "ssh-key".into(),
                 },
+                private_key_key: None,
+                additional_private_key_keys: None,
             },
             variables: None,
         };
@@ -279,10 +317,11 @@ mod tests {
         let mut ssh_paths = BTreeMap::new();
         ssh_paths.insert(
             "ccu".to_string(),
-            (
-                "/run/ansible-operator/ssh/ccu/id_rsa".to_string(),
-                "/run/ansible-operator/ssh/ccu/known_hosts".to_string(),
-            ),
+            SshPaths {
+                private_key: "/run/ansible-operator/ssh/ccu/id_rsa".to_string(),
+                additional_private_keys: Vec::new(),
+                known_hosts: "/run/ansible-operator/ssh/ccu/known_hosts".to_string(),
+            },
         );
         let ctx = RenderContext {
             managed_ssh_hosts: &managed_ssh_hosts,
@@ -297,6 +336,49 @@ mod tests {
         assert!(rendered.contains("/run/ansible-operator/ssh/ccu/id_rsa"));
     }
 
+    #[test]
+    fn additional_private_keys_are_offered_as_extra_identity_files() {
+        let group = ResolvedInventoryGroup::Ssh {
+            hosts: ResolvedHosts {
+                name: "external-devices".into(),
+                hosts: vec!["ccu.fritz.box".into()],
+            },
+            static_inventory_name: "ccu".into(),
+            config: SshConfig::default(),
+            variables: None,
+        };
+
+        let managed_ssh_hosts = BTreeMap::new();
+        let mut ssh_paths = BTreeMap::new();
+        ssh_paths.insert(
+            "ccu".to_string(),
+            SshPaths {
+                private_key: "/keys/id_ed25519".to_string(),
+                additional_private_keys: vec![
+                    "/keys/id_rsa".to_string(),
+                    "/keys/legacy".to_string(),
+                ],
+                known_hosts: "/keys/known_hosts".to_string(),
+            },
+        );
+        let ctx = RenderContext {
+            managed_ssh_hosts: &managed_ssh_hosts,
+            managed_ssh_client_key_path: "unused",
+            managed_ssh_known_hosts_path: "unused",
+            ssh_paths_by_static_inventory: &ssh_paths,
+        };
+
+        let rendered = render_inventory(&[group], &ctx).unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&rendered).unwrap();
+        let vars = &parsed["external-devices"]["hosts"]["ccu.fritz.box"];
+
+        assert_eq!(vars["ansible_ssh_private_key_file"], "/keys/id_ed25519");
+        assert_eq!(
+            vars["ansible_ssh_common_args"],
+            "-o UserKnownHostsFile=/keys/known_hosts -o IdentityFile=/keys/id_rsa -o IdentityFile=/keys/legacy"
+        );
+    }
+
     #[test]
     fn mixed_run_renders_both_groups_without_cross_contamination() {
         let managed = ResolvedInventoryGroup::ManagedSsh {
@@ -306,6 +388,7 @@ mod tests {
             },
             tolerations: None,
             variables: None,
+            node_metadata: Default::default(),
         };
         let ssh = ResolvedInventoryGroup::Ssh {
             hosts: ResolvedHosts {
@@ -318,6 +401,8 @@ mod tests {
                 secret_ref: SecretRef {
                     name: "ssh-key".into(),
                 },
+                private_key_key: None,
+                additional_private_key_keys: None,
             },
             variables: None,
         };
@@ -326,10 +411,11 @@ mod tests {
         let mut ssh_paths = BTreeMap::new();
         ssh_paths.insert(
             "ccu".to_string(),
-            (
-                "/run/ansible-operator/ssh/ccu/id_rsa".to_string(),
-                "/run/ansible-operator/ssh/ccu/known_hosts".to_string(),
-            ),
+            SshPaths {
+                private_key: "/run/ansible-operator/ssh/ccu/id_rsa".to_string(),
+                additional_private_keys: Vec::new(),
+                known_hosts: "/run/ansible-operator/ssh/ccu/known_hosts".to_string(),
+            },
         );
         let ctx = RenderContext {
             managed_ssh_hosts: &managed_ssh_hosts,
@@ -357,6 +443,7 @@ mod tests {
             variables: Some(GenericMap(serde_json::json!({
                 "ansible_python_interpreter": "/usr/bin/python3.11",
             }))),
+            node_metadata: Default::default(),
         };
 
         let mut managed_ssh_hosts = BTreeMap::new();
@@ -383,6 +470,45 @@ mod tests {
         assert!(rendered.contains("ansible_python_interpreter: /usr/bin/python3.11"));
     }
 
+    #[test]
+    fn renders_exposed_node_metadata_as_host_vars() {
+        let metadata = NodeMetadata {
+            node: "worker-1".into(),
+            labels: Some(BTreeMap::from([(
+                "topology.kubernetes.io/zone".to_string(),
+                "eu-1a".to_string(),
+            )])),
+            annotations: None,
+        };
+        let group = ResolvedInventoryGroup::ManagedSsh {
+            hosts: ResolvedHosts {
+                name: "workers".into(),
+                hosts: vec!["worker-1".into(), "worker-2".into()],
+            },
+            tolerations: None,
+            variables: None,
+            node_metadata: BTreeMap::from([("worker-1".to_string(), metadata)]),
+        };
+        let ctx = RenderContext {
+            managed_ssh_hosts: &BTreeMap::new(),
+            managed_ssh_client_key_path: "/run/ansible-operator/managed-ssh/client_key",
+            managed_ssh_known_hosts_path: "/run/ansible-operator/managed-ssh/known_hosts",
+            ssh_paths_by_static_inventory: &BTreeMap::new(),
+        };
+
+        let rendered = render_inventory(&[group], &ctx).unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&rendered).unwrap();
+        let hosts = &parsed["workers"]["hosts"];
+
+        assert_eq!(
+            hosts["worker-1"]["node_labels"]["topology.kubernetes.io/zone"],
+            "eu-1a"
+        );
+        // Annotations weren't selected, and worker-2 exposes nothing at all.
+        assert!(hosts["worker-1"].get("node_annotations").is_none());
+        assert!(hosts["worker-2"].get("node_labels").is_none());
+    }
+
     #[test]
     fn reserved_vars_cover_every_rendered_host_var() {
         // Render one host of each connection kind (the managed-ssh one unreachable, so it also
@@ -396,6 +522,7 @@ mod tests {
             },
             tolerations: None,
             variables: None,
+            node_metadata: Default::default(),
         };
         let ssh = ResolvedInventoryGroup::Ssh {
             hosts: ResolvedHosts {
@@ -408,6 +535,8 @@ mod tests {
                 secret_ref: SecretRef {
                     name: "ssh-key".into(),
                 },
+                private_key_key: None,
+                additional_private_key_keys: None,
             },
             variables: None,
         };
@@ -424,7 +553,11 @@ mod tests {
         let mut ssh_paths = BTreeMap::new();
         ssh_paths.insert(
             "ccu".to_string(),
-            ("/keys/id_rsa".to_string(), "/keys/known_hosts".to_string()),
+            SshPaths {
+                private_key: "/keys/id_rsa".to_string(),
+                additional_private_keys: vec!["/keys/id_ed25519".to_string()],
+                known_hosts: "/keys/known_hosts".to_string(),
+            },
         );
         let ctx = RenderContext {
             managed_ssh_hosts: &managed_ssh_hosts,
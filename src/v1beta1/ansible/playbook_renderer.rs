@@ -1,8 +1,173 @@
-use serde_yaml::Sequence;
+use std::sync::LazyLock;
 
-use crate::v1beta1;
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+
+use super::RenderError;
+
+/// Keys of which a play needs at least one to do anything.
+const PLAY_TASK_KEYS: [&str; 4] = ["tasks", "roles", "pre_tasks", "post_tasks"];
+
+/// Keys of a playbook entry that imports another playbook instead of being a play itself.
+const IMPORT_PLAYBOOK_KEYS: [&str; 2] = ["import_playbook", "ansible.builtin.import_playbook"];
+
+/// A `{{ plan_name }}`-style reference to one of the `TemplateValues`, with any spacing.
+static REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*(plan_name|plan_namespace|execution_hash)\s*\}\}")
+        .expect("template reference regex is valid")
+});
+
+/// Plan metadata a playbook can reference when its plan sets `template.renderTemplate`.
+pub struct TemplateValues<'a> {
+    pub plan_name: &'a str,
+    pub plan_namespace: &'a str,
+    pub execution_hash: &'a str,
+}
+
+/// Normalizes a playbook's text, rejecting anything that isn't a YAML list of plays each naming its
+/// `hosts` and something to run on them (or importing another playbook). With `values`, references
+/// to them are substituted first.
+pub fn render_playbook(
+    playbook: &str,
+    values: Option<&TemplateValues>,
+) -> Result<String, RenderError> {
+    let playbook = match values {
+        Some(values) => substitute(playbook, values),
+        None => playbook.to_string(),
+    };
+
+    let plays = match serde_yaml::from_str(&playbook)? {
+        Value::Sequence(plays) => plays,
+        other => return Err(RenderError::NotAList(kind_of(&other))),
+    };
+    for (index, play) in plays.iter().enumerate() {
+        let position = index + 1;
+        match play {
+            Value::Mapping(play) => check_play(position, play)?,
+            other => {
+                return Err(RenderError::PlayNotAMapping {
+                    position,
+                    found: kind_of(other),
+                });
+            }
+        }
+    }
 
-pub fn render_playbook(spec: &v1beta1::PlaybookPlanSpec) -> Result<String, super::RenderError> {
-    let plays: Sequence = serde_yaml::from_str(&spec.template.playbook)?;
     Ok(serde_yaml::to_string(&plays)?)
 }
+
+/// Checks one play, numbered from 1 as `position`, the way `ansible-playbook` would reject it.
+fn check_play(position: usize, play: &Mapping) -> Result<(), RenderError> {
+    if IMPORT_PLAYBOOK_KEYS
+        .iter()
+        .any(|key| play.contains_key(key))
+    {
+        return Ok(());
+    }
+    if !play.contains_key("hosts") {
+        return Err(RenderError::PlayWithoutHosts { position });
+    }
+    if !PLAY_TASK_KEYS.iter().any(|key| play.contains_key(key)) {
+        return Err(RenderError::PlayWithoutTasks { position });
+    }
+    Ok(())
+}
+
+/// What a YAML value is, for error messages.
+fn kind_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "empty",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Sequence(_) => "a list",
+        Value::Mapping(_) => "a mapping",
+        Value::Tagged(_) => "a tagged value",
+    }
+}
+
+/// Replaces `{{ plan_name }}`-style references (any spacing) to the values in `TemplateValues`.
+/// Every other `{{ ... }}` is left alone for Ansible's own Jinja templating.
+fn substitute(playbook: &str, values: &TemplateValues) -> String {
+    REFERENCE
+        .replace_all(playbook, |captures: &regex::Captures| match &captures[1] {
+            "plan_name" => values.plan_name.to_string(),
+            "plan_namespace" => values.plan_namespace.to_string(),
+            _ => values.execution_hash.to_string(),
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYBOOK: &str = r#"
+- hosts: all
+  tasks:
+    - ansible.builtin.debug:
+        msg: "{{ plan_namespace }}/{{plan_name}} at {{ execution_hash }} on {{ inventory_hostname }}"
+"#;
+
+    fn values() -> TemplateValues<'static> {
+        TemplateValues {
+            plan_name: "web",
+            plan_namespace: "team-a",
+            execution_hash: "abc123",
+        }
+    }
+
+    #[test]
+    fn plan_metadata_is_substituted_when_enabled() {
+        let rendered = render_playbook(PLAYBOOK, Some(&values())).unwrap();
+
+        assert!(
+            rendered.contains("team-a/web at abc123 on {{ inventory_hostname }}"),
+            "{rendered}"
+        );
+    }
+
+    fn rejection(playbook: &str) -> String {
+        render_playbook(playbook, None).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn a_playbook_must_be_a_list_of_mappings() {
+        assert_eq!(
+            rejection("hosts: all\ntasks: []\n"),
+            "the playbook is a mapping, not a list of plays"
+        );
+        assert_eq!(
+            rejection("- hosts: all\n  tasks: []\n- just a string\n"),
+            "play 2 is a string, not a mapping of play keywords"
+        );
+        assert!(rejection("- hosts: [all\n").contains("line 1"));
+    }
+
+    #[test]
+    fn every_play_needs_hosts_and_something_to_run() {
+        assert_eq!(rejection("- tasks: []\n"), "play 1 has no `hosts`");
+        assert_eq!(
+            rejection("- hosts: all\n  roles: [web]\n- hosts: all\n  vars: {}\n"),
+            "play 2 has no `tasks`, `roles`, `pre_tasks` or `post_tasks`"
+        );
+    }
+
+    #[test]
+    fn plays_with_only_pre_or_post_tasks_and_playbook_imports_are_accepted() {
+        let playbook = "- hosts: all\n  pre_tasks: []\n- hosts: all\n  post_tasks: []\n\
+                        - ansible.builtin.import_playbook: site.yml\n";
+
+        render_playbook(playbook, None).unwrap();
+    }
+
+    #[test]
+    fn braces_are_left_alone_when_disabled() {
+        let rendered = render_playbook(PLAYBOOK, None).unwrap();
+
+        assert!(
+            rendered.contains("{{ plan_namespace }}/{{plan_name}} at {{ execution_hash }}"),
+            "{rendered}"
+        );
+    }
+}
@@ -2,4 +2,19 @@
 pub enum RenderError {
     #[error(transparent)]
     SerializationError(#[from] serde_yaml::Error),
+
+    #[error("the playbook is {0}, not a list of plays")]
+    NotAList(&'static str),
+
+    #[error("play {position} is {found}, not a mapping of play keywords")]
+    PlayNotAMapping {
+        position: usize,
+        found: &'static str,
+    },
+
+    #[error("play {position} has no `hosts`")]
+    PlayWithoutHosts { position: usize },
+
+    #[error("play {position} has no `tasks`, `roles`, `pre_tasks` or `post_tasks`")]
+    PlayWithoutTasks { position: usize },
 }
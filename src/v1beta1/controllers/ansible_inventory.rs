@@ -1,13 +1,15 @@
+use std::collections::BTreeMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::v1beta1::{GenericMap, SshConfig, Toleration};
+use crate::v1beta1::{GenericMap, NodeMetadata, SshConfig, Toleration};
 
 pub trait AnsibleInventory {
     fn get_hosts(&self) -> Vec<ResolvedHosts>;
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ResolvedHosts {
     pub name: String,
@@ -27,6 +29,9 @@ pub enum ResolvedInventoryGroup {
         /// Author-supplied group variables from the owning `ClusterInventory`, rendered as
         /// Ansible group `vars:`. `None` when the group set none.
         variables: Option<GenericMap>,
+        /// The labels and annotations the owning `ClusterInventory` exposes, by node name, rendered
+        /// as the `node_labels`/`node_annotations` host vars. Nodes exposing nothing are absent.
+        node_metadata: BTreeMap<String, NodeMetadata>,
     },
     Ssh {
         hosts: ResolvedHosts,
@@ -49,6 +54,21 @@ impl ResolvedInventoryGroup {
         }
     }
 
+    pub fn hosts_mut(&mut self) -> &mut ResolvedHosts {
+        match self {
+            ResolvedInventoryGroup::ManagedSsh { hosts, .. } => hosts,
+            ResolvedInventoryGroup::Ssh { hosts, .. } => hosts,
+        }
+    }
+
+    /// The exposed labels and annotations of `host`, if its group exposes any.
+    pub fn node_metadata(&self, host: &str) -> Option<&NodeMetadata> {
+        match self {
+            ResolvedInventoryGroup::ManagedSsh { node_metadata, .. } => node_metadata.get(host),
+            ResolvedInventoryGroup::Ssh { .. } => None,
+        }
+    }
+
     /// Author-supplied group variables, if any, regardless of connection mechanism.
     pub fn variables(&self) -> Option<&GenericMap> {
         match self {
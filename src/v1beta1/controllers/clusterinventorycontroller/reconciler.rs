@@ -1,23 +1,33 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::Duration,
+};
 
-use futures::{Stream, StreamExt as _};
+use futures::Stream;
 use k8s_openapi::api::core::v1::Node;
 use kube::{
     Api,
-    api::{ListParams, Patch, PatchParams},
+    api::{ListParams, PartialObjectMeta, Patch, PatchParams},
     runtime::{
         Controller,
         controller::{self, Action},
-        reflector::{Lookup, ObjectRef, store::Writer},
+        reflector::{Lookup, ObjectRef},
         watcher,
     },
 };
-use tracing::error;
 
-use crate::v1beta1::{
-    self, ClusterInventory, ClusterInventoryStatus,
-    clusterinventorycontroller::mappers,
-    controllers::{nodeselector::node_matches, reconcile_error::ReconcileError},
+use crate::{
+    utils::{self, retry_on_conflict},
+    v1beta1::{
+        self, ClusterInventory, ClusterInventoryStatus, GroupSelectorDebug, NodeMetadata,
+        SelectorDebug,
+        clusterinventorycontroller::mappers,
+        controllers::{
+            nodeselector::{is_control_plane, is_ready, node_matches, unsupported_match_fields},
+            reconcile_error::ReconcileError,
+        },
+    },
 };
 
 struct ReconciliationContext {
@@ -38,28 +48,8 @@ pub fn new(
     let inventories_api: Api<v1beta1::ClusterInventory> = Api::all(client.clone());
     let nodes_api: Api<Node> = Api::all(client.clone());
 
-    let inventory_reflector_reader = {
-        let inventory_reflector_writer = Writer::<v1beta1::ClusterInventory>::default();
-        let inventory_reflector_reader = Arc::new(inventory_reflector_writer.as_reader());
-
-        let inventory_reflector = kube::runtime::reflector(
-            inventory_reflector_writer,
-            watcher(inventories_api.clone(), watcher::Config::default()),
-        );
-
-        tokio::spawn(async move {
-            inventory_reflector
-                .for_each(|event| async {
-                    match event {
-                        Ok(_) => {}
-                        Err(e) => error!("Reflector error: {e:?}"),
-                    }
-                })
-                .await;
-        });
-
-        inventory_reflector_reader
-    };
+    let inventory_reflector_reader =
+        utils::spawn_reflector(inventories_api.clone(), "ClusterInventory");
 
     Controller::new(inventories_api, watcher::Config::default())
         .watches(
@@ -84,37 +74,226 @@ async fn reconcile(
 
     let nodes_api: Api<Node> = Api::all(context.client.clone());
     let all_nodes = nodes_api.list_metadata(&ListParams::default()).await?;
+    // Readiness lives in the Node's status, which the metadata-only list above doesn't carry; only
+    // read whole Nodes when a group asks for it.
+    let not_ready = match object.spec.hosts.iter().any(|group| group.ready_nodes_only) {
+        true => not_ready_nodes(&nodes_api.list(&ListParams::default()).await?.items),
+        false => BTreeSet::new(),
+    };
+
+    let resolved_hosts = resolve_groups(&object.spec.hosts, &all_nodes.items, &not_ready);
+    let skipped_not_ready =
+        count_skipped_not_ready(&object.spec.hosts, &all_nodes.items, &not_ready);
+
+    let host_count: usize = resolved_hosts.iter().map(|group| group.hosts.len()).sum();
+
+    let selector_debug = object
+        .spec
+        .debug_selectors
+        .then(|| debug_selectors(&object.spec.hosts, &all_nodes.items, &resolved_hosts));
+
+    let node_metadata = expose_node_metadata(&object.spec.hosts, &all_nodes.items, &resolved_hosts);
+
+    let next_status = ClusterInventoryStatus {
+        host_count,
+        resolved_hosts,
+        skipped_not_ready,
+        selector_debug,
+        node_metadata,
+    };
+
+    let api: Api<ClusterInventory> = Api::namespaced(context.client.clone(), &namespace);
+    patch_status(&api, &object, next_status).await?;
+
+    // Such a group has already resolved to no Nodes above; fail the reconcile too, so the mistake
+    // is logged rather than just leaving the group silently empty.
+    if let Some(error) = unsupported_match_field(&object.spec.hosts) {
+        return Err(error);
+    }
+
+    Ok(Action::requeue(Duration::from_hours(1)))
+}
+
+/// The first `matchFields` requirement naming a Node field `node_matches` can't evaluate.
+fn unsupported_match_field(groups: &[v1beta1::InventoryHosts]) -> Option<ReconcileError> {
+    groups.iter().find_map(|group| {
+        let selector = group.match_labels.as_ref()?;
+        let field = *unsupported_match_fields(selector).first()?;
+        Some(ReconcileError::UnsupportedMatchField {
+            group: group.name.clone(),
+            field: field.to_string(),
+        })
+    })
+}
 
-    let to_resolve = &object.spec.hosts;
-    let resolved_hosts: Vec<v1beta1::ResolvedHosts> = to_resolve
+/// Names of the Nodes in `nodes` that aren't Ready.
+fn not_ready_nodes(nodes: &[Node]) -> BTreeSet<String> {
+    nodes
+        .iter()
+        .filter(|node| !is_ready(node))
+        .filter_map(|node| node.metadata.name.clone())
+        .collect()
+}
+
+/// Whether `group` selects `node` at all: its selector matches, and `excludeControlPlane` doesn't
+/// rule it out. Readiness is applied on top of that.
+fn group_selects(group: &v1beta1::InventoryHosts, node: &PartialObjectMeta<Node>) -> bool {
+    node_matches(node, group.match_labels.as_ref())
+        && !(group.exclude_control_plane && is_control_plane(node))
+}
+
+/// Whether `group` leaves out `node` for being in `not_ready`.
+fn skips_not_ready(
+    group: &v1beta1::InventoryHosts,
+    node: &PartialObjectMeta<Node>,
+    not_ready: &BTreeSet<String>,
+) -> bool {
+    group.ready_nodes_only && not_ready.contains(node.name().expect("name is set").as_ref())
+}
+
+/// Resolves every group of a `ClusterInventory` to the names of the Nodes its selector matches, in
+/// the order of `nodes`, less control-plane Nodes where the group sets `excludeControlPlane` and
+/// Nodes in `not_ready` where it sets `readyNodesOnly`. A group matching nothing still appears, with
+/// no hosts.
+fn resolve_groups(
+    groups: &[v1beta1::InventoryHosts],
+    nodes: &[PartialObjectMeta<Node>],
+    not_ready: &BTreeSet<String>,
+) -> Vec<v1beta1::ResolvedHosts> {
+    groups
         .iter()
         .map(|group| {
             let name = group.name.to_owned();
-            let hosts = all_nodes
+            let hosts = nodes
                 .iter()
-                .filter(|node| node_matches(node, group.match_labels.as_ref()))
+                .filter(|node| group_selects(group, node))
+                .filter(|node| !skips_not_ready(group, node, not_ready))
                 .map(|node| node.name().expect("name is set").to_string())
                 .collect();
 
             v1beta1::ResolvedHosts { name, hosts }
         })
-        .collect();
+        .collect()
+}
 
-    let host_count: usize = resolved_hosts.iter().map(|group| group.hosts.len()).sum();
+/// How many distinct Nodes some group selects but leaves out for not being Ready.
+fn count_skipped_not_ready(
+    groups: &[v1beta1::InventoryHosts],
+    nodes: &[PartialObjectMeta<Node>],
+    not_ready: &BTreeSet<String>,
+) -> usize {
+    nodes
+        .iter()
+        .filter(|node| {
+            groups
+                .iter()
+                .any(|group| group_selects(group, node) && skips_not_ready(group, node, not_ready))
+        })
+        .count()
+}
 
-    let next_status = ClusterInventoryStatus {
-        host_count,
-        resolved_hosts,
-    };
+/// The labels and annotations `groups` expose through `exposeNodeMetadata`, for every Node of
+/// `nodes` that one of those groups resolved to in `resolved`, in the order of `nodes`.
+fn expose_node_metadata(
+    groups: &[v1beta1::InventoryHosts],
+    nodes: &[PartialObjectMeta<Node>],
+    resolved: &[v1beta1::ResolvedHosts],
+) -> Vec<NodeMetadata> {
+    use kube::ResourceExt as _;
 
-    let api: Api<ClusterInventory> = Api::namespaced(context.client.clone(), &namespace);
-    patch_status(&api, &object, next_status).await?;
+    nodes
+        .iter()
+        .filter_map(|node| {
+            let name = node.name_any();
+            let selections: Vec<&v1beta1::ExposeNodeMetadata> = groups
+                .iter()
+                .zip(resolved)
+                .filter(|(_, resolved)| resolved.hosts.contains(&name))
+                .filter_map(|(group, _)| group.expose_node_metadata.as_ref())
+                .collect();
+            if selections.is_empty() {
+                return None;
+            }
 
-    Ok(Action::requeue(Duration::from_hours(1)))
+            let select = |keys: Vec<&String>, values: &BTreeMap<String, String>| {
+                (!keys.is_empty()).then(|| {
+                    keys.into_iter()
+                        .filter_map(|key| Some((key.clone(), values.get(key)?.clone())))
+                        .collect()
+                })
+            };
+            let labels = selections.iter().flat_map(|s| &s.labels).collect();
+            let annotations = selections.iter().flat_map(|s| &s.annotations).collect();
+            Some(NodeMetadata {
+                labels: select(labels, node.labels()),
+                annotations: select(annotations, node.annotations()),
+                node: name,
+            })
+        })
+        .collect()
+}
+
+/// Upper bound on `SelectorDebug::label_keys`, so a cluster with many distinct labels can't grow
+/// the status without limit.
+const MAX_DEBUG_LABEL_KEYS: usize = 100;
+
+/// The `debugSelectors` report for `groups`, evaluated against `nodes` into `resolved`.
+fn debug_selectors(
+    groups: &[v1beta1::InventoryHosts],
+    nodes: &[PartialObjectMeta<Node>],
+    resolved: &[v1beta1::ResolvedHosts],
+) -> SelectorDebug {
+    use kube::ResourceExt as _;
+
+    let present: BTreeSet<&str> = nodes
+        .iter()
+        .flat_map(|node| node.labels().keys())
+        .map(String::as_str)
+        .collect();
+
+    let groups = groups
+        .iter()
+        .zip(resolved)
+        .map(|(group, resolved)| {
+            let selector = group.match_labels.as_ref();
+            let referenced: BTreeSet<&str> = selector
+                .and_then(|s| s.match_labels.as_ref())
+                .into_iter()
+                .flat_map(|labels| labels.keys().map(String::as_str))
+                .chain(
+                    selector
+                        .and_then(|s| s.match_expressions.as_ref())
+                        .into_iter()
+                        .flatten()
+                        .map(|expr| expr.key.as_str()),
+                )
+                .collect();
+
+            GroupSelectorDebug {
+                name: group.name.clone(),
+                matched: resolved.hosts.len(),
+                unknown_label_keys: referenced
+                    .difference(&present)
+                    .map(ToString::to_string)
+                    .collect(),
+            }
+        })
+        .collect();
+
+    SelectorDebug {
+        nodes_considered: nodes.len(),
+        label_keys: present
+            .iter()
+            .take(MAX_DEBUG_LABEL_KEYS)
+            .map(ToString::to_string)
+            .collect(),
+        label_keys_truncated: present.len() > MAX_DEBUG_LABEL_KEYS,
+        groups,
+    }
 }
 
-/// Persists `status` via a JSON merge patch, not `Api::replace_status` — see the identical
-/// reasoning in `playbookplancontroller::reconciler::patch_status`.
+/// Persists `status` via a JSON merge patch, not `Api::replace_status`, retrying conflicts — see the
+/// identical reasoning in `playbookplancontroller::reconciler::patch_status`.
 async fn patch_status(
     api: &Api<ClusterInventory>,
     target: &ClusterInventory,
@@ -124,12 +303,336 @@ async fn patch_status(
         .name()
         .ok_or(ReconcileError::PreconditionFailed("name not set"))?;
 
-    api.patch_status(
-        &name,
-        &PatchParams::default(),
-        &Patch::Merge(serde_json::json!({ "status": status })),
-    )
-    .await?;
+    let params = PatchParams::default();
+    let patch = Patch::Merge(serde_json::json!({ "status": status }));
+    retry_on_conflict(|| api.patch_status(&name, &params, &patch)).await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1beta1::{InventoryHosts, NodeSelectorTerm, SelectorExpression, SelectorOperator};
+
+    fn node(name: &str, labels: &[(&str, &str)]) -> PartialObjectMeta<Node> {
+        let mut node = PartialObjectMeta::<Node>::default();
+        node.metadata.name = Some(name.into());
+        node.metadata.labels = Some(
+            labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        );
+        node
+    }
+
+    fn group(name: &str, selector: NodeSelectorTerm) -> InventoryHosts {
+        InventoryHosts {
+            name: name.into(),
+            match_labels: Some(selector),
+            match_expressions: None,
+            exclude_control_plane: false,
+            ready_nodes_only: false,
+            variables: None,
+            expose_node_metadata: None,
+        }
+    }
+
+    #[test]
+    fn groups_resolve_to_the_nodes_their_selectors_match() {
+        let nodes = [
+            node("cp-1", &[("node-role.kubernetes.io/control-plane", "")]),
+            node("worker-1", &[("pool", "general")]),
+            node("worker-2", &[("pool", "gpu")]),
+        ];
+        let groups = [
+            group(
+                "controlplane",
+                NodeSelectorTerm {
+                    match_labels: None,
+                    match_expressions: Some(vec![SelectorExpression {
+                        operator: SelectorOperator::Exists,
+                        key: "node-role.kubernetes.io/control-plane".into(),
+                        values: None,
+                    }]),
+                    match_fields: None,
+                },
+            ),
+            group(
+                "general",
+                NodeSelectorTerm {
+                    match_labels: Some(BTreeMap::from([("pool".into(), "general".into())])),
+                    match_expressions: None,
+                    match_fields: None,
+                },
+            ),
+            group(
+                "workers",
+                NodeSelectorTerm {
+                    match_labels: None,
+                    match_expressions: Some(vec![SelectorExpression {
+                        operator: SelectorOperator::In,
+                        key: "pool".into(),
+                        values: Some(vec!["general".into(), "gpu".into()]),
+                    }]),
+                    match_fields: None,
+                },
+            ),
+            group(
+                "arm",
+                NodeSelectorTerm {
+                    match_labels: Some(BTreeMap::from([("arch".into(), "arm64".into())])),
+                    match_expressions: None,
+                    match_fields: None,
+                },
+            ),
+        ];
+
+        let resolved: Vec<(String, Vec<String>)> =
+            resolve_groups(&groups, &nodes, &BTreeSet::new())
+                .into_iter()
+                .map(|group| (group.name, group.hosts))
+                .collect();
+
+        assert_eq!(
+            resolved,
+            vec![
+                ("controlplane".into(), vec!["cp-1".into()]),
+                ("general".into(), vec!["worker-1".into()]),
+                ("workers".into(), vec!["worker-1".into(), "worker-2".into()]),
+                ("arm".into(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn exclude_control_plane_drops_control_plane_and_master_nodes() {
+        let nodes = [
+            node("cp-1", &[("node-role.kubernetes.io/control-plane", "")]),
+            node("legacy-1", &[("node-role.kubernetes.io/master", "true")]),
+            node("worker-1", &[("kubernetes.io/os", "linux")]),
+        ];
+        let every_node = NodeSelectorTerm {
+            match_labels: None,
+            match_expressions: None,
+            match_fields: None,
+        };
+        let mut workers = group("workers", every_node.clone());
+        workers.exclude_control_plane = true;
+        let groups = [group("all", every_node), workers];
+
+        let resolved: Vec<(String, Vec<String>)> =
+            resolve_groups(&groups, &nodes, &BTreeSet::new())
+                .into_iter()
+                .map(|group| (group.name, group.hosts))
+                .collect();
+
+        assert_eq!(
+            resolved,
+            vec![
+                (
+                    "all".into(),
+                    vec!["cp-1".into(), "legacy-1".into(), "worker-1".into()]
+                ),
+                ("workers".into(), vec!["worker-1".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn exposed_node_metadata_covers_the_selected_keys_of_the_groups_nodes() {
+        let mut nodes = [
+            node("cp-1", &[("pool", "system")]),
+            node(
+                "worker-1",
+                &[("pool", "general"), ("topology.kubernetes.io/zone", "a")],
+            ),
+            node("worker-2", &[("pool", "general")]),
+        ];
+        nodes[1].metadata.annotations =
+            Some(BTreeMap::from([("rack".to_string(), "r12".to_string())]));
+        let general = NodeSelectorTerm {
+            match_labels: Some(BTreeMap::from([("pool".into(), "general".into())])),
+            match_expressions: None,
+            match_fields: None,
+        };
+        let mut zoned = group("zoned", general.clone());
+        zoned.expose_node_metadata = Some(v1beta1::ExposeNodeMetadata {
+            labels: vec!["topology.kubernetes.io/zone".into()],
+            annotations: vec![],
+        });
+        let mut racked = group("racked", general);
+        racked.expose_node_metadata = Some(v1beta1::ExposeNodeMetadata {
+            labels: vec!["pool".into()],
+            annotations: vec!["rack".into()],
+        });
+        let everything = NodeSelectorTerm {
+            match_labels: None,
+            match_expressions: None,
+            match_fields: None,
+        };
+        let groups = [zoned, racked, group("all", everything)];
+        let resolved = resolve_groups(&groups, &nodes, &BTreeSet::new());
+
+        let labels = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        assert_eq!(
+            expose_node_metadata(&groups, &nodes, &resolved),
+            vec![
+                NodeMetadata {
+                    node: "worker-1".into(),
+                    labels: Some(labels(&[
+                        ("pool", "general"),
+                        ("topology.kubernetes.io/zone", "a")
+                    ])),
+                    annotations: Some(labels(&[("rack", "r12")])),
+                },
+                // A key the Node doesn't carry is left out, not rendered empty.
+                NodeMetadata {
+                    node: "worker-2".into(),
+                    labels: Some(labels(&[("pool", "general")])),
+                    annotations: Some(BTreeMap::new()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ready_nodes_only_leaves_out_and_counts_not_ready_nodes() {
+        use k8s_openapi::api::core::v1::{NodeCondition, NodeStatus};
+
+        let full_node = |name: &str, ready: Option<&str>| Node {
+            metadata: kube::api::ObjectMeta {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+            status: Some(NodeStatus {
+                conditions: ready.map(|status| {
+                    vec![NodeCondition {
+                        type_: "Ready".into(),
+                        status: status.into(),
+                        ..Default::default()
+                    }]
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let not_ready = not_ready_nodes(&[
+            full_node("worker-1", Some("True")),
+            full_node("worker-2", Some("False")),
+            full_node("worker-3", Some("Unknown")),
+            full_node("worker-4", None),
+        ]);
+        assert_eq!(
+            not_ready,
+            BTreeSet::from(["worker-2".into(), "worker-3".into(), "worker-4".into()])
+        );
+
+        let nodes = [
+            node("worker-1", &[("pool", "general")]),
+            node("worker-2", &[("pool", "general")]),
+            node("worker-3", &[("pool", "gpu")]),
+            node("worker-4", &[("pool", "gpu")]),
+        ];
+        let pool = |pool: &str| NodeSelectorTerm {
+            match_labels: Some(BTreeMap::from([("pool".into(), pool.into())])),
+            match_expressions: None,
+            match_fields: None,
+        };
+        let mut general = group("general", pool("general"));
+        general.ready_nodes_only = true;
+        let groups = [general, group("gpu", pool("gpu"))];
+
+        let resolved: Vec<(String, Vec<String>)> = resolve_groups(&groups, &nodes, &not_ready)
+            .into_iter()
+            .map(|group| (group.name, group.hosts))
+            .collect();
+        assert_eq!(
+            resolved,
+            vec![
+                ("general".into(), vec!["worker-1".into()]),
+                ("gpu".into(), vec!["worker-3".into(), "worker-4".into()]),
+            ]
+        );
+        // Only worker-2 was selected by a readyNodesOnly group and left out.
+        assert_eq!(count_skipped_not_ready(&groups, &nodes, &not_ready), 1);
+    }
+
+    #[test]
+    fn selector_debug_names_label_keys_no_node_carries() {
+        let nodes = [
+            node(
+                "worker-1",
+                &[("pool", "general"), ("kubernetes.io/os", "linux")],
+            ),
+            node("worker-2", &[("pool", "gpu")]),
+        ];
+        let groups = [
+            group(
+                "typo",
+                NodeSelectorTerm {
+                    match_labels: Some(BTreeMap::from([("pol".into(), "general".into())])),
+                    match_expressions: Some(vec![SelectorExpression {
+                        operator: SelectorOperator::Exists,
+                        key: "kubernetes.io/os".into(),
+                        values: None,
+                    }]),
+                    match_fields: None,
+                },
+            ),
+            group(
+                "gpu",
+                NodeSelectorTerm {
+                    match_labels: Some(BTreeMap::from([("pool".into(), "gpu".into())])),
+                    match_expressions: None,
+                    match_fields: None,
+                },
+            ),
+        ];
+        let resolved = resolve_groups(&groups, &nodes, &BTreeSet::new());
+
+        let debug = debug_selectors(&groups, &nodes, &resolved);
+
+        assert_eq!(debug.nodes_considered, 2);
+        assert_eq!(debug.label_keys, vec!["kubernetes.io/os", "pool"]);
+        assert!(!debug.label_keys_truncated);
+        assert_eq!(
+            debug.groups,
+            vec![
+                GroupSelectorDebug {
+                    name: "typo".into(),
+                    matched: 0,
+                    unknown_label_keys: vec!["pol".into()],
+                },
+                GroupSelectorDebug {
+                    name: "gpu".into(),
+                    matched: 1,
+                    unknown_label_keys: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn selector_debug_bounds_the_label_keys() {
+        let labels: Vec<(String, String)> = (0..=MAX_DEBUG_LABEL_KEYS)
+            .map(|i| (format!("label-{i:03}"), "x".to_string()))
+            .collect();
+        let labels: Vec<(&str, &str)> = labels
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let nodes = [node("n1", &labels)];
+
+        let debug = debug_selectors(&[], &nodes, &[]);
+
+        assert_eq!(debug.label_keys.len(), MAX_DEBUG_LABEL_KEYS);
+        assert!(debug.label_keys_truncated);
+    }
+}
@@ -1,6 +1,6 @@
 use std::{sync::Arc, time::Duration};
 
-use futures::{Stream, StreamExt as _};
+use futures::Stream;
 use k8s_openapi::api::core::v1::{Namespace, Node};
 use kube::{
     Api, ResourceExt,
@@ -8,15 +8,19 @@ use kube::{
     runtime::{
         Controller,
         controller::{self, Action},
-        reflector::{Lookup, ObjectRef, store::Writer},
+        reflector::{Lookup, ObjectRef},
         watcher,
     },
 };
-use tracing::error;
 
-use crate::v1beta1::{
-    self, NodeAccessPolicy, NodeAccessPolicyStatus,
-    controllers::{nodeselector::selector_matches_fail_closed, reconcile_error::ReconcileError},
+use crate::{
+    utils::{self, retry_on_conflict},
+    v1beta1::{
+        self, NodeAccessPolicy, NodeAccessPolicyStatus,
+        controllers::{
+            nodeselector::selector_matches_fail_closed, reconcile_error::ReconcileError,
+        },
+    },
 };
 
 use super::mappers;
@@ -41,27 +45,7 @@ pub fn new(
     let namespaces_api: Api<Namespace> = Api::all(client.clone());
     let nodes_api: Api<Node> = Api::all(client.clone());
 
-    let policy_reflector_reader = {
-        let policy_reflector_writer = Writer::<NodeAccessPolicy>::default();
-        let policy_reflector_reader = Arc::new(policy_reflector_writer.as_reader());
-
-        let policy_reflector = kube::runtime::reflector(
-            policy_reflector_writer,
-            watcher(policies_api.clone(), watcher::Config::default()),
-        );
-
-        tokio::spawn(async move {
-            policy_reflector
-                .for_each(|event| async {
-                    if let Err(e) = event {
-                        error!("Reflector error: {e:?}");
-                    }
-                })
-                .await;
-        });
-
-        policy_reflector_reader
-    };
+    let policy_reflector_reader = utils::spawn_reflector(policies_api.clone(), "NodeAccessPolicy");
 
     // Recompute every policy's status when any namespace or node changes — a policy's
     // matchedNamespaces/allowedNodeCount depend on the whole set, not one object.
@@ -119,7 +103,7 @@ async fn reconcile(
     Ok(Action::requeue(Duration::from_hours(1)))
 }
 
-/// Persists `status` via a JSON merge patch — see the identical reasoning in
+/// Persists `status` via a JSON merge patch, retrying conflicts — see the identical reasoning in
 /// `playbookplancontroller::reconciler::patch_status`.
 async fn patch_status(
     api: &Api<NodeAccessPolicy>,
@@ -130,12 +114,9 @@ async fn patch_status(
         .name()
         .ok_or(ReconcileError::PreconditionFailed("name not set"))?;
 
-    api.patch_status(
-        &name,
-        &PatchParams::default(),
-        &Patch::Merge(serde_json::json!({ "status": status })),
-    )
-    .await?;
+    let params = PatchParams::default();
+    let patch = Patch::Merge(serde_json::json!({ "status": status }));
+    retry_on_conflict(|| api.patch_status(&name, &params, &patch)).await?;
 
     Ok(())
 }
@@ -5,11 +5,45 @@ use kube::api::PartialObjectMeta;
 
 use crate::v1beta1::{self, SelectorExpression, SelectorOperator};
 
+/// Node fields a `matchFields` requirement may name. Nodes are listed metadata-only, so only
+/// metadata can be offered; `metadata.name` is also all Kubernetes' own `matchFields` supports.
+pub const SUPPORTED_NODE_FIELDS: &[&str] = &["metadata.name"];
+
+/// Labels that mark a Node as part of the control plane; the value doesn't matter. `master` is what
+/// clusters before Kubernetes 1.20 (and some distributions since) set instead of `control-plane`.
+pub const CONTROL_PLANE_LABELS: &[&str] = &[
+    "node-role.kubernetes.io/control-plane",
+    "node-role.kubernetes.io/master",
+];
+
+/// Returns `true` if the node carries any of [`CONTROL_PLANE_LABELS`].
+pub fn is_control_plane(node: &PartialObjectMeta<Node>) -> bool {
+    use kube::ResourceExt as _;
+    let labels = node.labels();
+
+    CONTROL_PLANE_LABELS
+        .iter()
+        .any(|key| labels.contains_key(*key))
+}
+
+/// Returns `true` if the node reports its `Ready` condition as `True`. A Node without that condition
+/// (e.g. one that has just registered) is not Ready.
+pub fn is_ready(node: &Node) -> bool {
+    node.status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .into_iter()
+        .flatten()
+        .any(|condition| condition.type_ == "Ready" && condition.status == "True")
+}
+
 /// Returns `true` if the node satisfies the given selector term.
 ///
 /// A node satisfies a term when it matches **all** `matchLabels` key-value
-/// pairs and **all** `matchExpressions` expressions. Missing fields are
-/// treated as empty and therefore always satisfied.
+/// pairs, **all** `matchExpressions` and **all** `matchFields` expressions.
+/// Missing fields are treated as empty and therefore always satisfied. A
+/// `matchFields` requirement on an unsupported field (see
+/// [`unsupported_match_fields`]) is never satisfied.
 ///
 /// If `selector` is `None` the node is considered a match unconditionally.
 pub fn node_matches(
@@ -32,7 +66,24 @@ pub fn node_matches(
         .map(|match_expressions| node_matches_match_expressions(node, match_expressions))
         .unwrap_or(true);
 
-    matches_labels && matches_expressions
+    let matches_fields = selector
+        .match_fields
+        .as_ref()
+        .map(|match_fields| node_matches_match_fields(node, match_fields))
+        .unwrap_or(true);
+
+    matches_labels && matches_expressions && matches_fields
+}
+
+/// The `matchFields` keys of `selector` that aren't in [`SUPPORTED_NODE_FIELDS`], in order.
+pub fn unsupported_match_fields(selector: &v1beta1::NodeSelectorTerm) -> Vec<&str> {
+    selector
+        .match_fields
+        .iter()
+        .flatten()
+        .map(|expr| expr.key.as_str())
+        .filter(|key| !SUPPORTED_NODE_FIELDS.contains(key))
+        .collect()
 }
 
 fn node_matches_match_labels(node: &PartialObjectMeta<Node>, labels: &v1beta1::LabelMap) -> bool {
@@ -54,6 +105,19 @@ fn node_matches_match_expressions(
     exprs.iter().all(|expr| eval_expression(labels, expr))
 }
 
+fn node_matches_match_fields(node: &PartialObjectMeta<Node>, exprs: &[SelectorExpression]) -> bool {
+    let fields = BTreeMap::from_iter(
+        node.metadata
+            .name
+            .clone()
+            .map(|name| ("metadata.name".to_string(), name)),
+    );
+
+    exprs.iter().all(|expr| {
+        SUPPORTED_NODE_FIELDS.contains(&expr.key.as_str()) && eval_expression(&fields, expr)
+    })
+}
+
 /// Evaluates a single `matchExpressions` term against a raw label map.
 fn eval_expression(labels: &BTreeMap<String, String>, expr: &SelectorExpression) -> bool {
     match expr.operator {
@@ -73,7 +137,7 @@ fn eval_expression(labels: &BTreeMap<String, String>, expr: &SelectorExpression)
 /// matches *everything*. Works on any object's labels (Node, Namespace, …).
 pub fn selector_matches(
     labels: &BTreeMap<String, String>,
-    selector: &v1beta1::NodeSelectorTerm,
+    selector: &v1beta1::LabelSelector,
 ) -> bool {
     let matches_labels = selector
         .match_labels
@@ -100,7 +164,7 @@ pub fn selector_matches(
 /// selector should grant no access.
 pub fn selector_matches_fail_closed(
     labels: &BTreeMap<String, String>,
-    selector: &v1beta1::NodeSelectorTerm,
+    selector: &v1beta1::LabelSelector,
 ) -> bool {
     let is_empty = selector
         .match_labels
@@ -151,7 +215,10 @@ mod tests {
     use k8s_openapi::api::core::v1::Node;
     use kube::{Resource as _, api::PartialObjectMeta};
 
-    use super::{node_matches, node_matches_match_expressions, node_matches_match_labels};
+    use super::{
+        node_matches, node_matches_match_expressions, node_matches_match_labels,
+        unsupported_match_fields,
+    };
     use crate::v1beta1::{NodeSelectorTerm, SelectorExpression, SelectorOperator};
 
     fn make_node(
@@ -191,6 +258,7 @@ mod tests {
         let selector = NodeSelectorTerm {
             match_labels: None,
             match_expressions: None,
+            match_fields: None,
         };
         assert!(node_matches(&node, Some(&selector)));
     }
@@ -205,6 +273,7 @@ mod tests {
                 key: "spot".to_string(),
                 values: None,
             }]),
+            match_fields: None,
         };
         assert!(node_matches(&node, Some(&selector)));
     }
@@ -215,6 +284,7 @@ mod tests {
         let selector = NodeSelectorTerm {
             match_labels: Some(label_selector([("env", "prod")])),
             match_expressions: None,
+            match_fields: None,
         };
         assert!(!node_matches(&node, Some(&selector)));
     }
@@ -229,8 +299,69 @@ mod tests {
                 key: "spot".to_string(),
                 values: None,
             }]),
+            match_fields: None,
+        };
+        assert!(!node_matches(&node, Some(&selector)));
+    }
+
+    #[test]
+    fn match_fields_select_nodes_by_name() {
+        let mut node = make_node([("env", "prod")]);
+        node.metadata.name = Some("worker-1".into());
+        let by_name = |operator, names: &[&str]| NodeSelectorTerm {
+            match_labels: None,
+            match_expressions: None,
+            match_fields: Some(vec![SelectorExpression {
+                operator,
+                key: "metadata.name".to_string(),
+                values: Some(names.iter().map(ToString::to_string).collect()),
+            }]),
         };
+
+        assert!(node_matches(
+            &node,
+            Some(&by_name(SelectorOperator::In, &["worker-1", "worker-2"]))
+        ));
+        assert!(!node_matches(
+            &node,
+            Some(&by_name(SelectorOperator::In, &["worker-2"]))
+        ));
+        assert!(node_matches(
+            &node,
+            Some(&by_name(SelectorOperator::NotIn, &["cp-1"]))
+        ));
+        assert!(!node_matches(
+            &node,
+            Some(&by_name(SelectorOperator::NotIn, &["worker-1"]))
+        ));
+    }
+
+    #[test]
+    fn match_fields_on_an_unsupported_field_match_nothing_and_are_reported() {
+        let node = make_node([]);
+        let selector = NodeSelectorTerm {
+            match_labels: None,
+            match_expressions: None,
+            match_fields: Some(vec![
+                SelectorExpression {
+                    operator: SelectorOperator::DoesNotExist,
+                    key: "spec.unschedulable".to_string(),
+                    values: None,
+                },
+                SelectorExpression {
+                    operator: SelectorOperator::NotIn,
+                    key: "metadata.name".to_string(),
+                    values: Some(vec!["cp-1".to_string()]),
+                },
+            ]),
+        };
+
         assert!(!node_matches(&node, Some(&selector)));
+        assert_eq!(
+            unsupported_match_fields(&selector),
+            vec!["spec.unschedulable"]
+        );
+        assert!(unsupported_match_fields(&NodeSelectorTerm::default()).is_empty());
     }
 
     #[test]
@@ -462,6 +593,7 @@ mod tests {
     // --- fail-closed selector matching (NodeAccessPolicy) ---
 
     use super::{selector_matches, selector_matches_fail_closed};
+    use crate::v1beta1::LabelSelector;
 
     fn labels(
         pairs: impl IntoIterator<Item = (&'static str, &'static str)>,
@@ -475,7 +607,7 @@ mod tests {
     #[test]
     fn selector_matches_empty_selector_matches_everything() {
         // Default (non-fail-closed) semantics: empty selector matches any labels.
-        let empty = NodeSelectorTerm::default();
+        let empty = LabelSelector::default();
         assert!(selector_matches(&labels([("a", "1")]), &empty));
         assert!(selector_matches(&labels([]), &empty));
     }
@@ -483,12 +615,12 @@ mod tests {
     #[test]
     fn fail_closed_empty_selector_matches_nothing() {
         // The security-critical inversion: an empty ceiling grants no access.
-        let empty = NodeSelectorTerm::default();
+        let empty = LabelSelector::default();
         assert!(!selector_matches_fail_closed(&labels([("a", "1")]), &empty));
         assert!(!selector_matches_fail_closed(&labels([]), &empty));
 
         // An empty matchLabels map (not just `None`) is still "empty" → nothing.
-        let empty_map = NodeSelectorTerm {
+        let empty_map = LabelSelector {
             match_labels: Some(label_selector([])),
             match_expressions: Some(vec![]),
         };
@@ -500,7 +632,7 @@ mod tests {
 
     #[test]
     fn fail_closed_nonempty_selector_matches_like_normal() {
-        let sel = NodeSelectorTerm {
+        let sel = LabelSelector {
             match_labels: Some(label_selector([("node-pool", "business")])),
             match_expressions: None,
         };
@@ -514,7 +646,7 @@ mod tests {
         ));
 
         // "Allow all" must be expressed explicitly, e.g. Exists on a ubiquitous label.
-        let all = NodeSelectorTerm {
+        let all = LabelSelector {
             match_labels: None,
             match_expressions: Some(vec![SelectorExpression {
                 operator: SelectorOperator::Exists,
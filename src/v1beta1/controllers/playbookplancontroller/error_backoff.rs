@@ -0,0 +1,142 @@
+//! How long a failed reconcile waits before it is retried, by what failed. A transient apiserver
+//! problem (throttling, a 5xx, a dropped connection) backs off exponentially per plan, so a
+//! struggling apiserver isn't hammered at full rate; a problem only an edit can fix (a spec the
+//! renderer rejects, a missing playbook ConfigMap) waits a long, fixed delay instead — the watches
+//! re-trigger the plan as soon as that edit lands anyway. A plan's failure count is forgotten once
+//! it reconciles successfully — a reconcile superseded by a newer trigger doesn't count — or is
+//! deleted. A plan deleted without a cleanup (outside the enrolled namespaces, or in a dry run) is
+//! never retried again, so its count is also forgotten once it has gone unused for longer than any
+//! retry waits.
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::{utils, v1beta1::controllers::reconcile_error::ReconcileError};
+
+/// Upper bound of the exponential backoff, and the fixed delay of errors retrying can't fix.
+const MAX_ERROR_REQUEUE: Duration = Duration::from_secs(300);
+
+#[derive(Default)]
+pub struct ErrorBackoff {
+    /// Consecutive failures per plan, and when the last one was counted.
+    failures: Mutex<HashMap<String, (u32, Instant)>>,
+}
+
+impl ErrorBackoff {
+    /// Counts another failure of `key` and returns how long to wait before retrying it; `base` is
+    /// the delay of a first transient failure.
+    pub fn next_delay(&self, key: &str, error: &ReconcileError, base: Duration) -> Duration {
+        let now = Instant::now();
+        let mut failures = self.failures.lock().unwrap();
+        forget_idle(&mut failures, now, base);
+        let (count, last) = failures.entry(key.to_string()).or_insert((0, now));
+        *count = count.saturating_add(1);
+        *last = now;
+        delay(error, *count, base)
+    }
+
+    /// Forgets the failures of `key`, after it reconciled successfully or was deleted.
+    pub fn reset(&self, key: &str) {
+        self.failures.lock().unwrap().remove(key);
+    }
+}
+
+/// Drops the counts of plans that haven't failed for twice the longest retry delay: a plan still
+/// failing would have been retried by then, so these were deleted without a cleanup.
+fn forget_idle(failures: &mut HashMap<String, (u32, Instant)>, now: Instant, base: Duration) {
+    let idle = MAX_ERROR_REQUEUE.max(base) * 2;
+    failures.retain(|_, (_, last)| now.duration_since(*last) <= idle);
+}
+
+/// The delay before retrying the `failures`-th consecutive failure, this one being `error`.
+fn delay(error: &ReconcileError, failures: u32, base: Duration) -> Duration {
+    let cap = MAX_ERROR_REQUEUE.max(base);
+    match error {
+        // Another writer got there first; the next attempt re-reads and almost always succeeds.
+        ReconcileError::KubeError(error) if utils::is_conflict(error) => base,
+        ReconcileError::KubeError(_)
+        | ReconcileError::Finalizer(_)
+        | ReconcileError::CaError(_) => {
+            let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+            base.saturating_mul(factor).min(cap)
+        }
+        ReconcileError::PreconditionFailed(_)
+        | ReconcileError::ReservedInventoryVariable { .. }
+        | ReconcileError::UnsupportedMatchField { .. }
+        | ReconcileError::PlaybookSource(_)
+        | ReconcileError::ImageSource(_)
+        | ReconcileError::RenderError(_)
+        | ReconcileError::JsonSerializationError(_)
+        | ReconcileError::YamlSerializationError(_) => cap,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(15);
+
+    #[test]
+    fn transient_api_errors_back_off_exponentially_up_to_the_cap() {
+        let throttled = ReconcileError::from(utils::api_error(429, "TooManyRequests"));
+        let delays: Vec<_> = (1..=7).map(|n| delay(&throttled, n, BASE)).collect();
+        assert_eq!(
+            delays,
+            [15, 30, 60, 120, 240, 300, 300].map(Duration::from_secs)
+        );
+        let unavailable = ReconcileError::from(utils::api_error(503, "ServiceUnavailable"));
+        assert_eq!(delay(&unavailable, 40, BASE), MAX_ERROR_REQUEUE);
+    }
+
+    #[test]
+    fn conflicts_retry_at_the_base_delay_and_spec_problems_at_the_cap() {
+        let conflict = ReconcileError::from(utils::api_error(409, "Conflict"));
+        assert_eq!(delay(&conflict, 5, BASE), BASE);
+        // A refused create is no conflict to retry quickly.
+        let exists = ReconcileError::from(utils::api_error(409, "AlreadyExists"));
+        assert_eq!(delay(&exists, 5, BASE), BASE * 16);
+        let missing = ReconcileError::PlaybookSource("ConfigMap site-playbooks not found".into());
+        assert_eq!(delay(&missing, 1, BASE), MAX_ERROR_REQUEUE);
+        assert_eq!(
+            delay(&ReconcileError::PreconditionFailed("name not set"), 1, BASE),
+            MAX_ERROR_REQUEUE
+        );
+    }
+
+    #[test]
+    fn failures_are_counted_per_plan_and_forgotten_on_success() {
+        let backoff = ErrorBackoff::default();
+        let error = ReconcileError::from(utils::api_error(500, "InternalError"));
+
+        assert_eq!(backoff.next_delay("default/web", &error, BASE), BASE);
+        assert_eq!(backoff.next_delay("default/web", &error, BASE), BASE * 2);
+        assert_eq!(backoff.next_delay("default/db", &error, BASE), BASE);
+
+        backoff.reset("default/web");
+        assert_eq!(backoff.next_delay("default/web", &error, BASE), BASE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failures_of_plans_no_longer_retried_are_forgotten() {
+        let backoff = ErrorBackoff::default();
+        let error = ReconcileError::from(utils::api_error(500, "InternalError"));
+        backoff.next_delay("default/gone", &error, BASE);
+        backoff.next_delay("default/web", &error, BASE);
+
+        // Retried at the longest delay, `web` keeps counting; `gone` was never retried.
+        tokio::time::advance(MAX_ERROR_REQUEUE).await;
+        assert_eq!(backoff.next_delay("default/web", &error, BASE), BASE * 2);
+        tokio::time::advance(MAX_ERROR_REQUEUE * 2).await;
+        assert_eq!(backoff.next_delay("default/web", &error, BASE), BASE * 4);
+
+        assert!(
+            !backoff
+                .failures
+                .lock()
+                .unwrap()
+                .contains_key("default/gone")
+        );
+    }
+}
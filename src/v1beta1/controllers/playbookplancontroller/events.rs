@@ -0,0 +1,282 @@
+//! Kubernetes Events on a `PlaybookPlan`, so `kubectl describe playbookplan` tells what the operator
+//! did and why without reading its logs. Best-effort: a failed publish is logged and never fails the
+//! reconcile.
+//!
+//! Flooding is bounded by the shared `Recorder`: a repeat of the same reason and action on the same
+//! plan within a few minutes bumps the existing Event's series count instead of creating a new one,
+//! keeping the first Event's note. It doesn't compare notes, so the Job an Event is about is set as
+//! its `related` object: each Job gets its own `JobCreated`/`JobFailed` Event and a crash-looping
+//! run shows up as one `JobFailed (x12)`, not twelve Events. `ReconcileFailed` is likewise split by
+//! the step that failed.
+
+use chrono::{DateTime, FixedOffset};
+use k8s_openapi::api::{batch::v1::Job, core::v1::ObjectReference};
+use kube::{
+    Resource as _,
+    runtime::events::{Event, EventType, Recorder, Reporter},
+};
+use tracing::warn;
+
+use crate::v1beta1::{PlaybookPlan, controllers::reconcile_error::ReconcileError};
+
+/// `reportingController` of every Event the operator publishes.
+const REPORTING_CONTROLLER: &str = "ansible-operator";
+
+pub fn recorder(client: kube::Client) -> Recorder {
+    Recorder::new(client, Reporter::from(REPORTING_CONTROLLER))
+}
+
+/// Publishes `event` on `plan`, logging instead of failing if the apiserver rejects it.
+pub async fn publish(recorder: &Recorder, plan: &PlaybookPlan, event: Event) {
+    if let Err(e) = recorder.publish(&event, &plan.object_ref(&())).await {
+        warn!("failed to publish {} Event: {e}", event.reason);
+    }
+}
+
+/// A reference to `plan`'s Job `job_name`, for an Event's `related` object.
+fn job_reference(plan: &PlaybookPlan, job_name: &str) -> ObjectReference {
+    ObjectReference {
+        api_version: Some(Job::api_version(&()).into()),
+        kind: Some(Job::kind(&()).into()),
+        name: Some(job_name.into()),
+        namespace: plan.metadata.namespace.clone(),
+        ..Default::default()
+    }
+}
+
+pub fn job_created(plan: &PlaybookPlan, job_name: &str, hosts: &[String]) -> Event {
+    Event {
+        type_: EventType::Normal,
+        reason: "JobCreated".into(),
+        note: Some(format!(
+            "created Job {job_name} for host(s): {}",
+            hosts.join(", ")
+        )),
+        action: "CreateJob".into(),
+        secondary: Some(job_reference(plan, job_name)),
+    }
+}
+
+/// `None` when no host failed — there's nothing to warn about.
+pub fn job_failed(plan: &PlaybookPlan, job_name: &str, failed_hosts: &[String]) -> Option<Event> {
+    (!failed_hosts.is_empty()).then(|| Event {
+        type_: EventType::Warning,
+        reason: "JobFailed".into(),
+        note: Some(format!(
+            "Job {job_name} failed on host(s): {}",
+            failed_hosts.join(", ")
+        )),
+        action: "RunPlaybook".into(),
+        secondary: Some(job_reference(plan, job_name)),
+    })
+}
+
+pub fn playbook_rendered(generation: Option<i64>) -> Event {
+    let generation = generation.map_or_else(|| "?".to_string(), |g| g.to_string());
+    Event {
+        type_: EventType::Normal,
+        reason: "PlaybookRendered".into(),
+        note: Some(format!(
+            "rendered the workspace for generation {generation}"
+        )),
+        action: "Render".into(),
+        secondary: None,
+    }
+}
+
+/// `None` when no inline variable looks like a credential — there's nothing to warn about.
+pub fn secrets_in_config_map(variables: &[String]) -> Option<Event> {
+    (!variables.is_empty()).then(|| Event {
+        type_: EventType::Warning,
+        reason: "SecretsInConfigMap".into(),
+        note: Some(format!(
+            "workspaceStorage is ConfigMap, but inline variable(s) look like credentials: {}; move them to a secretRef",
+            variables.join(", ")
+        )),
+        action: "Render".into(),
+        secondary: None,
+    })
+}
+
+pub fn schedule_delayed(next_run: DateTime<FixedOffset>) -> Event {
+    Event {
+        type_: EventType::Normal,
+        reason: "ScheduleDelayed".into(),
+        note: Some(format!("next run at {}", next_run.to_rfc3339())),
+        action: "Schedule".into(),
+        secondary: None,
+    }
+}
+
+pub fn hosts_pruned(hosts: &[String]) -> Event {
+    Event {
+        type_: EventType::Normal,
+        reason: "HostsPruned".into(),
+        note: Some(format!(
+            "removed status of host(s) no longer eligible: {}",
+            hosts.join(", ")
+        )),
+        action: "PruneHosts".into(),
+        secondary: None,
+    }
+}
+
+pub fn hosts_skipped(hosts: &[String]) -> Event {
+    Event {
+        type_: EventType::Normal,
+        reason: "HostsSkipped".into(),
+        note: Some(format!(
+            "started a run without cordoned node(s): {}",
+            hosts.join(", ")
+        )),
+        action: "SkipHosts".into(),
+        secondary: None,
+    }
+}
+
+pub fn rollout_halted(failed_hosts: &[String]) -> Event {
+    Event {
+        type_: EventType::Warning,
+        reason: "RolloutHalted".into(),
+        note: Some(format!(
+            "failurePolicy is Halt and host(s) failed: {}; no further runs start until the playbook or its inputs change",
+            failed_hosts.join(", ")
+        )),
+        action: "Halt".into(),
+        secondary: None,
+    }
+}
+
+pub fn cleaned_up(jobs: &[String]) -> Event {
+    let note = match jobs {
+        [] => "deleted the workspace; no Jobs to stop".to_string(),
+        jobs => format!("deleted the workspace and Job(s) {}", jobs.join(", ")),
+    };
+    Event {
+        type_: EventType::Normal,
+        reason: "CleanedUp".into(),
+        note: Some(note),
+        action: "Cleanup".into(),
+        secondary: None,
+    }
+}
+
+pub fn stale_job_deleted(plan: &PlaybookPlan, job_name: &str) -> Event {
+    Event {
+        type_: EventType::Normal,
+        reason: "StaleJobDeleted".into(),
+        note: Some(format!(
+            "deleted finished Job {job_name} of an earlier playbook or Secret version"
+        )),
+        action: "DeleteJob".into(),
+        secondary: Some(job_reference(plan, job_name)),
+    }
+}
+
+pub fn reconcile_failed(error: &ReconcileError) -> Event {
+    Event {
+        type_: EventType::Warning,
+        reason: "ReconcileFailed".into(),
+        note: Some(error.to_string()),
+        action: failed_action(error).into(),
+        secondary: None,
+    }
+}
+
+/// The step of the reconcile `error` failed in, as the `action` of its `ReconcileFailed` Event.
+fn failed_action(error: &ReconcileError) -> &'static str {
+    match error {
+        ReconcileError::KubeError(_) => "Reconcile",
+        ReconcileError::PreconditionFailed(_) => "CheckPreconditions",
+        ReconcileError::ReservedInventoryVariable { .. }
+        | ReconcileError::UnsupportedMatchField { .. } => "ResolveInventory",
+        ReconcileError::PlaybookSource(_) => "ResolvePlaybook",
+        ReconcileError::ImageSource(_) => "ResolveImage",
+        ReconcileError::Finalizer(_) => "UpdateFinalizer",
+        ReconcileError::RenderError(_)
+        | ReconcileError::JsonSerializationError(_)
+        | ReconcileError::YamlSerializationError(_) => "Render",
+        ReconcileError::CaError(_) => "IssueCertificate",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> PlaybookPlan {
+        serde_yaml::from_str(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: web
+  namespace: default
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  mode: Recurring
+  template:
+    playbook: "- hosts: all"
+  inventoryRefs: []
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn job_failed_names_the_hosts_and_is_skipped_without_failures() {
+        assert!(job_failed(&plan(), "apply-web-abc-1", &[]).is_none());
+
+        let event = job_failed(
+            &plan(),
+            "apply-web-abc-1",
+            &["host-1".into(), "host-2".into()],
+        )
+        .unwrap();
+        assert_eq!(event.type_, EventType::Warning);
+        assert_eq!(event.reason, "JobFailed");
+        let note = event.note.unwrap();
+        assert!(note.contains("apply-web-abc-1"), "{note}");
+        assert!(note.contains("host-1, host-2"), "{note}");
+    }
+
+    #[tokio::test]
+    async fn events_of_different_jobs_keep_their_own_notes() {
+        use kube::client::Body;
+
+        let (service, mut handle) =
+            tower_test::mock::pair::<http::Request<Body>, http::Response<Body>>();
+        let recorder = recorder(kube::Client::new(service, "default"));
+        let server = tokio::spawn(async move {
+            let mut writes = Vec::new();
+            for _ in 0..3 {
+                let (request, send) = handle.next_request().await.expect("a request");
+                let method = request.method().clone();
+                let body = request.into_body().collect_bytes().await.unwrap();
+                send.send_response(
+                    http::Response::builder()
+                        .body(Body::from(body.to_vec()))
+                        .unwrap(),
+                );
+                let event: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                writes.push((method, event["note"].as_str().map(String::from)));
+            }
+            writes
+        });
+
+        let plan = plan();
+        let failed = |job: &str, host: &str| job_failed(&plan, job, &[host.into()]).unwrap();
+        publish(&recorder, &plan, failed("apply-web-abc-0", "host-1")).await;
+        publish(&recorder, &plan, failed("apply-web-def-0", "host-2")).await;
+        publish(&recorder, &plan, failed("apply-web-def-0", "host-2")).await;
+
+        let writes = server.await.unwrap();
+        // Two Jobs, two Events; only the true repeat is merged into the second one's series.
+        assert_eq!(writes[0].0, http::Method::POST);
+        assert!(writes[0].1.as_deref().unwrap().contains("host-1"));
+        assert_eq!(writes[1].0, http::Method::POST);
+        assert!(writes[1].1.as_deref().unwrap().contains("host-2"));
+        assert_eq!(writes[2].0, http::Method::PATCH);
+        assert!(writes[2].1.as_deref().unwrap().contains("host-2"));
+    }
+}
@@ -16,6 +16,15 @@ impl std::fmt::Display for ExecutionHash {
     }
 }
 
+/// Parses a hash back from its `Display` form, e.g. `.status.currentHash`.
+impl std::str::FromStr for ExecutionHash {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u64::from_str_radix(s, 16).map(ExecutionHash)
+    }
+}
+
 impl std::ops::Deref for ExecutionHash {
     type Target = u64;
 
@@ -54,6 +63,58 @@ impl ExecutionHash {
 
         ExecutionHash(self.0.wrapping_add(extra))
     }
+
+    /// Folds the Node labels and annotations a `ClusterInventory` exposes to the run's hosts into an
+    /// existing hash, treating them as content like [`Self::fold_inventory_variables`]: changing a
+    /// selected value re-applies the playbook. Order-insensitive, and a no-op without any, so
+    /// inventories that expose nothing keep their hash.
+    pub fn fold_node_metadata<'a>(
+        self,
+        metadata: impl IntoIterator<Item = &'a v1beta1::NodeMetadata>,
+    ) -> ExecutionHash {
+        let extra = metadata
+            .into_iter()
+            .map(|metadata| {
+                let mut hasher = twox_hash::XxHash3_64::new();
+                "node".hash(&mut hasher);
+                // Both maps are BTreeMaps, so this serialization is canonical.
+                serde_json::to_string(metadata)
+                    .unwrap_or_default()
+                    .hash(&mut hasher);
+                hasher.finish()
+            })
+            .fold(0u64, u64::wrapping_add);
+
+        ExecutionHash(self.0.wrapping_add(extra))
+    }
+
+    /// Folds `template.verifyPlaybook` into an existing hash, so changing it re-runs the plan like
+    /// changing the playbook does. `None` is a no-op, so plans without one keep their hash.
+    pub fn fold_verify_playbook(self, verify_playbook: Option<&str>) -> ExecutionHash {
+        self.fold_tagged("verify", verify_playbook)
+    }
+
+    /// Folds `spec.startAtTask` into an existing hash, like [`Self::fold_verify_playbook`].
+    pub fn fold_start_at_task(self, start_at_task: Option<&str>) -> ExecutionHash {
+        self.fold_tagged("start-at-task", start_at_task)
+    }
+
+    /// Folds `template.renderTemplate` into an existing hash, since it changes the playbook that is
+    /// run. Off is a no-op, like [`Self::fold_verify_playbook`]'s `None`.
+    pub fn fold_render_template(self, render_template: bool) -> ExecutionHash {
+        self.fold_tagged("render-template", render_template.then_some("true"))
+    }
+
+    /// Folds `value`, if any, tagged so equal values of different fields hash differently.
+    fn fold_tagged(self, tag: &str, value: Option<&str>) -> ExecutionHash {
+        let Some(value) = value else {
+            return self;
+        };
+        let mut hasher = twox_hash::XxHash3_64::new();
+        tag.hash(&mut hasher);
+        value.hash(&mut hasher);
+        ExecutionHash(self.0.wrapping_add(hasher.finish()))
+    }
 }
 
 /// Returns an iterator over hosts where the PlaybookPlan needs to be (re)applied.
@@ -278,6 +339,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn execution_hash_round_trips_through_its_display_form() {
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        assert_eq!(hash.to_string().parse::<ExecutionHash>(), Ok(hash));
+        assert!("".parse::<ExecutionHash>().is_err());
+    }
+
+    #[test]
+    pub fn test_fold_node_metadata_follows_selected_values_and_is_order_insensitive() {
+        let base = calculate_execution_hash("playbook", std::iter::empty());
+        let zone = |node: &str, zone: &str| v1beta1::NodeMetadata {
+            node: node.into(),
+            labels: Some(BTreeMap::from([(
+                "topology.kubernetes.io/zone".to_string(),
+                zone.to_string(),
+            )])),
+            annotations: None,
+        };
+        let (a, b) = (zone("worker-1", "a"), zone("worker-2", "b"));
+
+        // Nothing exposed is a no-op, so pre-existing inventories keep their hash.
+        assert_eq!(base.fold_node_metadata([]), base);
+        let folded = base.fold_node_metadata([&a, &b]);
+        assert_ne!(folded, base);
+        assert_eq!(base.fold_node_metadata([&b, &a]), folded);
+
+        let moved = zone("worker-2", "c");
+        assert_ne!(base.fold_node_metadata([&a, &moved]), folded);
+    }
+
+    #[test]
+    pub fn test_fold_verify_playbook_changes_hash_only_when_set() {
+        let base = calculate_execution_hash("playbook", std::iter::empty());
+
+        assert_eq!(base.fold_verify_playbook(None), base);
+        let verified = base.fold_verify_playbook(Some("- hosts: all"));
+        assert_ne!(verified, base);
+        assert_ne!(base.fold_verify_playbook(Some("- hosts: web")), verified);
+    }
+
+    #[test]
+    pub fn test_fold_start_at_task_is_distinct_from_other_folds() {
+        let base = calculate_execution_hash("playbook", std::iter::empty());
+
+        assert_eq!(base.fold_start_at_task(None), base);
+        let started = base.fold_start_at_task(Some("Restart nginx"));
+        assert_ne!(started, base);
+        assert_ne!(started, base.fold_verify_playbook(Some("Restart nginx")));
+    }
+
+    #[test]
+    pub fn test_fold_render_template_changes_hash_only_when_on() {
+        let base = calculate_execution_hash("playbook", std::iter::empty());
+
+        assert_eq!(base.fold_render_template(false), base);
+        let rendered = base.fold_render_template(true);
+        assert_ne!(rendered, base);
+        assert_ne!(rendered, base.fold_start_at_task(Some("true")));
+    }
+
     #[test]
     pub fn test_execution_hash_display() {
         // Given
@@ -0,0 +1,160 @@
+//! The plan author's own host guard — `spec.includeHosts`/`spec.excludeHosts` glob patterns applied
+//! to the resolved inventory, e.g. to make sure a destructive playbook can never reach `prod-db-*`
+//! however broad its inventory selectors turn out to be.
+//!
+//! Runs after `node_access::enforce`, on every group regardless of where it came from. Like that
+//! gate it can only shrink the host set, and an excluded host is gone before hashing, locking or
+//! job building ever see it.
+
+use regex::Regex;
+
+use crate::v1beta1::{PlaybookPlan, ResolvedInventoryGroup};
+
+/// Drops every host that doesn't pass the plan's include/exclude patterns, then any group left
+/// empty. Returns the sorted, de-duplicated names of the dropped hosts (for logging/status).
+/// `excludeHosts` always wins; an unset or empty `includeHosts` admits every host.
+pub fn apply(plan: &PlaybookPlan, groups: &mut Vec<ResolvedInventoryGroup>) -> Vec<String> {
+    let include = compile(plan.spec.include_hosts.as_deref());
+    let exclude = compile(plan.spec.exclude_hosts.as_deref());
+
+    if include.is_empty() && exclude.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dropped = Vec::new();
+
+    for group in groups.iter_mut() {
+        group.hosts_mut().hosts.retain(|host| {
+            let included = include.is_empty() || include.iter().any(|p| p.is_match(host));
+            let excluded = exclude.iter().any(|p| p.is_match(host));
+            let keep = included && !excluded;
+            if !keep {
+                dropped.push(host.clone());
+            }
+            keep
+        });
+    }
+
+    groups.retain(|group| !group.hosts().hosts.is_empty());
+
+    dropped.sort();
+    dropped.dedup();
+    dropped
+}
+
+fn compile(patterns: Option<&[String]>) -> Vec<Regex> {
+    patterns
+        .unwrap_or_default()
+        .iter()
+        .map(|pattern| glob_to_regex(pattern))
+        .collect()
+}
+
+/// Translates a shell-style glob into an anchored regex: `*` matches any run of characters, `?`
+/// exactly one, everything else literally. Escaping first means the result always compiles.
+fn glob_to_regex(pattern: &str) -> Regex {
+    let escaped = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+
+    Regex::new(&format!("^{escaped}$")).expect("an escaped glob is always a valid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1beta1::{ResolvedHosts, SecretRef, SshConfig};
+
+    fn managed(name: &str, hosts: &[&str]) -> ResolvedInventoryGroup {
+        ResolvedInventoryGroup::ManagedSsh {
+            hosts: ResolvedHosts {
+                name: name.into(),
+                hosts: hosts.iter().map(|h| h.to_string()).collect(),
+            },
+            tolerations: None,
+            variables: None,
+            node_metadata: Default::default(),
+        }
+    }
+
+    fn ssh(name: &str, hosts: &[&str]) -> ResolvedInventoryGroup {
+        ResolvedInventoryGroup::Ssh {
+            hosts: ResolvedHosts {
+                name: name.into(),
+                hosts: hosts.iter().map(|h| h.to_string()).collect(),
+            },
+            static_inventory_name: "static".into(),
+            config: SshConfig {
+                user: "root".into(),
+                secret_ref: SecretRef { name: "k".into() },
+                private_key_key: None,
+                additional_private_key_keys: None,
+            },
+            variables: None,
+        }
+    }
+
+    fn plan(include: &[&str], exclude: &[&str]) -> PlaybookPlan {
+        let mut plan = PlaybookPlan::new("plan", Default::default());
+        let to_vec = |patterns: &[&str]| {
+            (!patterns.is_empty()).then(|| patterns.iter().map(|p| p.to_string()).collect())
+        };
+        plan.spec.include_hosts = to_vec(include);
+        plan.spec.exclude_hosts = to_vec(exclude);
+        plan
+    }
+
+    fn hosts(groups: &[ResolvedInventoryGroup]) -> Vec<&str> {
+        groups
+            .iter()
+            .flat_map(|g| g.hosts().hosts.iter().map(String::as_str))
+            .collect()
+    }
+
+    #[test]
+    fn no_patterns_keeps_everything() {
+        let mut groups = vec![managed("workers", &["node-a", "node-b"])];
+
+        let dropped = apply(&plan(&[], &[]), &mut groups);
+
+        assert!(dropped.is_empty());
+        assert_eq!(hosts(&groups), vec!["node-a", "node-b"]);
+    }
+
+    #[test]
+    fn exclude_drops_matches_from_every_kind_of_group() {
+        let mut groups = vec![
+            managed("workers", &["node-a", "prod-db-1"]),
+            ssh("external", &["prod-db-2.example.com", "web-1.example.com"]),
+            ssh("databases", &["prod-db-3"]),
+        ];
+
+        let dropped = apply(&plan(&[], &["prod-db-*"]), &mut groups);
+
+        assert_eq!(
+            dropped,
+            vec!["prod-db-1", "prod-db-2.example.com", "prod-db-3"]
+        );
+        assert_eq!(hosts(&groups), vec!["node-a", "web-1.example.com"]);
+        // The group left empty is gone entirely.
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn include_admits_only_matches_and_exclude_still_wins() {
+        let mut groups = vec![managed("workers", &["web-1", "web-2", "web-10", "db-1"])];
+
+        let dropped = apply(&plan(&["web-?"], &["web-2"]), &mut groups);
+
+        assert_eq!(dropped, vec!["db-1", "web-10", "web-2"]);
+        assert_eq!(hosts(&groups), vec!["web-1"]);
+    }
+
+    #[test]
+    fn glob_metacharacters_other_than_star_and_question_mark_are_literal() {
+        assert!(glob_to_regex("host.example.com").is_match("host.example.com"));
+        assert!(!glob_to_regex("host.example.com").is_match("hostXexampleYcom"));
+        assert!(glob_to_regex("*").is_match("anything"));
+        assert!(!glob_to_regex("web-*").is_match("my-web-1"));
+    }
+}
@@ -0,0 +1,89 @@
+//! The order hosts are listed in within their inventory groups — `inventoryRefs[].order` — which is
+//! the order Ansible works through them, so e.g. a `serial: 1` canary always hits the same host first.
+//!
+//! Applied while resolving the inventory, before any filtering; the gates after it (`node_access`,
+//! `host_filter`) only drop hosts and keep the rest in place.
+
+use std::hash::{Hash, Hasher};
+
+use crate::v1beta1::{HostOrder, PlaybookPlan};
+
+/// The seed `HostOrder::Shuffled` orders a plan's hosts by: its UID, so each plan gets its own
+/// order, or its name before it has one (offline rendering).
+pub fn seed(plan: &PlaybookPlan) -> &str {
+    plan.metadata
+        .uid
+        .as_deref()
+        .or(plan.metadata.name.as_deref())
+        .unwrap_or_default()
+}
+
+/// Reorders one group's `hosts` as `order` says. A shuffle sorts by a hash of `seed` and each host
+/// name rather than permuting positions, so it doesn't depend on the order the hosts came in and a
+/// host joining or leaving the group doesn't move any other.
+pub fn apply(order: HostOrder, seed: &str, hosts: &mut [String]) {
+    match order {
+        HostOrder::AsIs => {}
+        HostOrder::Sorted => hosts.sort(),
+        HostOrder::Shuffled => {
+            hosts.sort_by_cached_key(|host| (shuffle_key(seed, host), host.clone()))
+        }
+    }
+}
+
+fn shuffle_key(seed: &str, host: &str) -> u64 {
+    let mut hasher = twox_hash::XxHash3_64::new();
+    (seed, host).hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosts(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn ordered(order: HostOrder, seed: &str, names: &[&str]) -> Vec<String> {
+        let mut hosts = hosts(names);
+        apply(order, seed, &mut hosts);
+        hosts
+    }
+
+    #[test]
+    fn as_is_keeps_and_sorted_sorts_the_inventory_order() {
+        let names = ["node-c", "node-a", "node-b"];
+
+        assert_eq!(ordered(HostOrder::AsIs, "uid", &names), hosts(&names));
+        assert_eq!(
+            ordered(HostOrder::Sorted, "uid", &names),
+            hosts(&["node-a", "node-b", "node-c"])
+        );
+    }
+
+    #[test]
+    fn a_shuffle_is_stable_per_seed_whatever_order_the_hosts_arrive_in() {
+        let names: Vec<String> = (0..20).map(|i| format!("node-{i:02}")).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let mut reversed = names.clone();
+        reversed.reverse();
+
+        let shuffled = ordered(HostOrder::Shuffled, "uid-1", &names);
+
+        assert_ne!(shuffled, hosts(&names));
+        assert_eq!(ordered(HostOrder::Shuffled, "uid-1", &reversed), shuffled);
+        assert_ne!(ordered(HostOrder::Shuffled, "uid-2", &names), shuffled);
+    }
+
+    #[test]
+    fn a_host_leaving_a_shuffled_group_leaves_the_others_in_place() {
+        let names = ["node-a", "node-b", "node-c", "node-d", "node-e"];
+        let mut shuffled = ordered(HostOrder::Shuffled, "uid", &names);
+
+        let without_b: Vec<&str> = names.iter().copied().filter(|n| *n != "node-b").collect();
+        shuffled.retain(|host| host != "node-b");
+
+        assert_eq!(ordered(HostOrder::Shuffled, "uid", &without_b), shuffled);
+    }
+}
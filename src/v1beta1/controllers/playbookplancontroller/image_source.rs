@@ -0,0 +1,130 @@
+//! Which image a run's Job uses: `spec.image` inline, or the ConfigMap key named by
+//! `spec.imageFrom`. Resolved once per reconcile, like the playbook (see `playbook_source`).
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::Api;
+
+use crate::v1beta1::{PlaybookPlan, controllers::reconcile_error::ReconcileError};
+
+/// Returns the image of `plan`, reading it from the cluster when it uses `imageFrom`.
+pub async fn resolve(
+    plan: &PlaybookPlan,
+    config_maps_api: &Api<ConfigMap>,
+) -> Result<String, ReconcileError> {
+    let Some(source) = &plan.spec.image_from else {
+        return inline(plan);
+    };
+
+    let config_map = config_maps_api.get_opt(&source.config_map_ref.name).await?;
+    from_config_map(plan, config_map.as_ref())
+}
+
+/// Surrounding whitespace is dropped, so a value written as a YAML block scalar (with its trailing
+/// newline) still is a usable image reference.
+fn from_config_map(
+    plan: &PlaybookPlan,
+    config_map: Option<&ConfigMap>,
+) -> Result<String, ReconcileError> {
+    let Some(source) = &plan.spec.image_from else {
+        return inline(plan);
+    };
+    let name = &source.config_map_ref.name;
+    let Some(config_map) = config_map else {
+        return Err(ReconcileError::ImageSource(format!(
+            "imageFrom ConfigMap {name:?} does not exist"
+        )));
+    };
+
+    config_map
+        .data
+        .as_ref()
+        .and_then(|data| data.get(&source.key))
+        .map(|image| image.trim())
+        .filter(|image| !image.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            ReconcileError::ImageSource(format!(
+                "imageFrom ConfigMap {name:?} has no non-empty key {:?}",
+                source.key
+            ))
+        })
+}
+
+/// `image` is optional in the schema since `imageFrom` may stand in for it, so a plan can arrive
+/// with neither.
+fn inline(plan: &PlaybookPlan) -> Result<String, ReconcileError> {
+    if plan.spec.image.is_empty() {
+        return Err(ReconcileError::ImageSource(
+            "neither image nor imageFrom is set".into(),
+        ));
+    }
+    Ok(plan.spec.image.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::v1beta1::{ConfigMapRef, ImageSource};
+
+    fn plan() -> PlaybookPlan {
+        let mut plan = PlaybookPlan::new("web", Default::default());
+        plan.spec.image_from = Some(ImageSource {
+            config_map_ref: ConfigMapRef {
+                name: "runner".into(),
+            },
+            key: "image".into(),
+        });
+        plan
+    }
+
+    #[test]
+    fn config_map_key_is_the_image() {
+        let config_map = ConfigMap {
+            data: Some(BTreeMap::from([(
+                "image".to_string(),
+                "registry.tld/ansible:2.18\n".to_string(),
+            )])),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_config_map(&plan(), Some(&config_map)).unwrap(),
+            "registry.tld/ansible:2.18"
+        );
+    }
+
+    #[test]
+    fn missing_config_map_or_key_names_what_is_missing() {
+        let err = from_config_map(&plan(), None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"imageFrom ConfigMap "runner" does not exist"#
+        );
+
+        let err = from_config_map(&plan(), Some(&ConfigMap::default())).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"imageFrom ConfigMap "runner" has no non-empty key "image""#
+        );
+    }
+
+    #[test]
+    fn inline_image_is_used_without_image_from() {
+        let mut plan = plan();
+        plan.spec.image_from = None;
+        plan.spec.image = "registry.tld/ansible:2.17".into();
+
+        assert_eq!(
+            from_config_map(&plan, None).unwrap(),
+            "registry.tld/ansible:2.17"
+        );
+
+        plan.spec.image = String::new();
+        assert_eq!(
+            from_config_map(&plan, None).unwrap_err().to_string(),
+            "neither image nor imageFrom is set"
+        );
+    }
+}
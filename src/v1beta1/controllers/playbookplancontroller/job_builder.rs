@@ -5,7 +5,11 @@ use k8s_openapi::{
         batch::{self, v1::Job},
         core::{
             self as kcore,
-            v1::{EmptyDirVolumeSource, EnvVar, KeyToPath, SecretVolumeSource, Volume},
+            v1::{
+                ConfigMapProjection, ConfigMapVolumeSource, EmptyDirVolumeSource, EnvVar,
+                KeyToPath, ProjectedVolumeSource, SecretProjection, SecretVolumeSource, Volume,
+                VolumeMount, VolumeProjection,
+            },
         },
     },
     apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference},
@@ -16,6 +20,10 @@ use kube::runtime::reflector::Lookup as _;
 /// `/dev/termination-log` carries the recap the reconciler reads back (see `advance_applying_run`).
 pub const ANSIBLE_CONTAINER_NAME: &str = "ansible-playbook";
 
+/// Name of the init container running `spec.preflight`'s ping playbook; its termination message
+/// carries that playbook's recap (see `configure_job_for_preflight`).
+pub const PREFLIGHT_CONTAINER_NAME: &str = "preflight";
+
 /// `ttlSecondsAfterFinished` for the ansible Job: the operator never deletes the Job or its pod
 /// itself, it leaves cleanup to Kubernetes' TTL controller so finished runs stay around briefly for
 /// inspection, then get reaped instead of accumulating forever.
@@ -40,6 +48,42 @@ const MIN_JOB_TTL_SECONDS_AFTER_FINISHED: i32 = 60;
 /// rejected — the same forgiving style as `MIN_JOB_TTL_SECONDS_AFTER_FINISHED`.
 const MAX_VERBOSITY: u8 = 4;
 
+/// Volume carrying the workspace Secret (playbook, inventory, callback plugin).
+const PLAYBOOK_VOLUME_NAME: &str = "playbook";
+
+/// Prefix of the volumes carrying `template.variables` read from ConfigMaps, numbered in
+/// declaration order.
+const CONFIG_MAP_VARS_VOLUME_PREFIX: &str = "config-map-vars-";
+
+/// Prefix of the volumes carrying `template.variables` read from Secrets, numbered in declaration
+/// order, so several entries may read different keys of one Secret.
+const SECRET_VARS_VOLUME_PREFIX: &str = "secret-vars-";
+
+/// Prefix of the volumes carrying `template.roles`, numbered in declaration order.
+const ROLE_VOLUME_PREFIX: &str = "role-";
+
+/// Volume carrying the run's `group_vars/` tree, see `group_vars_volume`.
+const GROUP_VARS_VOLUME_NAME: &str = "group-vars";
+
+/// Volume the init container installs `template.requirements` collections into, or the
+/// `template.collectionsImage` image volume.
+const COLLECTIONS_VOLUME_NAME: &str = "collections";
+
+/// Where the collections volume is mounted; the default collections path of the image's Ansible.
+const COLLECTIONS_MOUNT_PATH: &str = "/etc/ansible/collections";
+
+/// Volume carrying the `template.galaxy` token file, mounted into the collections init container only.
+const GALAXY_TOKEN_VOLUME_NAME: &str = "galaxy-token";
+
+/// Where the Galaxy token Secret is mounted; `ANSIBLE_GALAXY_TOKEN_PATH` points into it.
+const GALAXY_TOKEN_MOUNT_PATH: &str = "/run/ansible-operator/galaxy";
+
+/// Volume carrying the run's managed-ssh client identity, see `configure_job_for_managed_ssh_client_cert`.
+const MANAGED_SSH_CLIENT_VOLUME_NAME: &str = "managed-ssh-client";
+
+/// Prefix of the per-`StaticInventory` SSH key volumes, see `configure_job_for_ssh`.
+const SSH_VOLUME_PREFIX: &str = "ssh-";
+
 /// Resolves the effective Job TTL for a plan: its `spec.ttlSecondsAfterFinished` clamped up to
 /// `MIN_JOB_TTL_SECONDS_AFTER_FINISHED`, or the default when unset.
 fn effective_job_ttl(plan: &v1beta1::PlaybookPlan) -> i32 {
@@ -52,18 +96,47 @@ fn effective_job_ttl(plan: &v1beta1::PlaybookPlan) -> i32 {
 use crate::{
     utils,
     v1beta1::{
-        self, FilesSource, PlaybookPlan, PlaybookVariableSource, ResolvedInventoryGroup, SshConfig,
+        self, FilesSource, PlaybookPlan, PlaybookVariableSource, RequirementsInstall,
+        ResolvedInventoryGroup, SshConfig, TemplateMetadata, WorkspaceStorage,
         controllers::reconcile_error::ReconcileError,
         labels,
-        playbookplancontroller::{execution_evaluator::ExecutionHash, managed_ssh, paths},
+        playbookplancontroller::{
+            execution_evaluator::ExecutionHash,
+            managed_ssh, paths,
+            role_sources::RoleFiles,
+            workspace::{self, GroupVarsSource},
+        },
     },
 };
 
+/// Digits of the `<id>` in a Job's name, and in a managed-ssh proxy's (see
+/// `managed_ssh::resource_name`). Wider than `utils::generate_id`'s five: Jobs of one plan outlive
+/// many spec changes, and two hashes sharing an ID would make their runs' attempts compete for the
+/// same names. 27^10 ≈ 2×10^14 leaves that to chance only after ~10^7 distinct hashes.
+pub const JOB_ID_LEN: u32 = 10;
+
+/// `apply-<plan>-<id>-<retry>`, kept within the 63 characters of the `job-name` label Kubernetes
+/// puts on the Job's pod: a plan name too long for that is cut short by `utils::sanitize_name`.
+fn job_name(plan_name: &str, hash: &ExecutionHash, retry_count: u32) -> String {
+    const PREFIX: &str = "apply-";
+    let id = utils::generate_id_with_len(**hash, JOB_ID_LEN);
+    let suffix = format!("-{id}-{retry_count}");
+    let plan_name = utils::sanitize_name(
+        plan_name,
+        utils::MAX_LABEL_VALUE_LEN - PREFIX.len() - suffix.len(),
+    );
+    format!("{PREFIX}{plan_name}{suffix}")
+}
+
+/// `image` is the plan's image as resolved from `spec.image` or `spec.imageFrom`, `role_files` the
+/// plan's roles as resolved by `role_sources::resolve`.
 pub fn create_job_for_run(
     hash: &ExecutionHash,
     retry_count: u32,
     target_groups: &[ResolvedInventoryGroup],
     object: &PlaybookPlan,
+    image: &str,
+    role_files: &RoleFiles,
 ) -> Result<batch::v1::Job, ReconcileError> {
     let pb_name = object
         .metadata
@@ -77,7 +150,12 @@ pub fn create_job_for_run(
         .as_ref()
         .expect(".metadata.namespace must be set here");
 
-    let mut job = create_job_skeleton(object, object.spec.template.requirements.is_some())?;
+    // A `collectionsImage` already is the collections directory; nothing left to install.
+    let installs_requirements = object.spec.template.requirements.is_some()
+        && object.spec.template.collections_image.is_none()
+        && object.spec.template.requirements_install != RequirementsInstall::Skip;
+    let inventory = inventory_argument(object, target_groups);
+    let mut job = create_job_skeleton(object, image, installs_requirements, &inventory)?;
 
     if has_managed_ssh_group(target_groups) {
         let secret_name = managed_ssh::client_cert_secret_name(hash);
@@ -90,6 +168,10 @@ pub fn create_job_for_run(
     }
 
     configure_job_for_callback_plugin(&mut job);
+    configure_job_for_roles(&mut job, object, role_files);
+    if object.spec.preflight {
+        configure_job_for_preflight(&mut job);
+    }
     configure_job_for_node_affinity(&mut job, &managed_ssh_node_names(target_groups));
 
     job.metadata.namespace = Some(pb_namespace.into());
@@ -97,23 +179,38 @@ pub fn create_job_for_run(
     // retry_count must be in the name — the hash alone is unchanged between retries of an
     // identical spec, so without it a new run's Job name would collide with a completed prior
     // run's and get silently skipped by the idempotency check.
-    job.metadata.name = Some(format!(
-        "apply-{pb_name}-{}-{retry_count}",
-        utils::generate_id(**hash),
-    ));
+    job.metadata.name = Some(job_name(pb_name, hash, retry_count));
 
     let job_labels: BTreeMap<String, String> = BTreeMap::from([
-        (labels::PLAYBOOKPLAN_NAME.into(), pb_name.to_string()),
+        (
+            labels::PLAYBOOKPLAN_NAME.into(),
+            utils::label_value(pb_name),
+        ),
         (labels::PLAYBOOKPLAN_HASH.into(), hash.to_string()),
     ]);
-    job.metadata.labels = Some(job_labels.clone());
+    let job_template = object.spec.job_template.as_ref();
+    let job_metadata = job_template.and_then(|t| t.metadata.as_ref());
+    let pod_metadata = job_template
+        .and_then(|t| t.template.as_ref())
+        .and_then(|t| t.metadata.as_ref());
+
+    job.metadata.labels = Some(user_labels_then(job_metadata, &job_labels));
+    job.metadata.annotations = job_metadata.and_then(|m| m.annotations.clone());
+    // A plan name too long for the label is shortened there; keep it verbatim for readers.
+    if !utils::is_label_value(pb_name) {
+        job.metadata
+            .annotations
+            .get_or_insert_with(Default::default)
+            .insert(labels::PLAYBOOKPLAN_NAME.into(), pb_name.to_string());
+    }
 
     // The NetworkPolicy scoping managed-ssh proxy-pod ingress selects on the execution-hash
     // label of the actual running Pod, not just the Job object — Jobs don't carry their own
     // labels down to their Pods unless the pod template's own metadata sets them explicitly.
     if let Some(spec) = job.spec.as_mut() {
         spec.template.metadata = Some(ObjectMeta {
-            labels: Some(job_labels),
+            labels: Some(user_labels_then(pod_metadata, &job_labels)),
+            annotations: pod_metadata.and_then(|m| m.annotations.clone()),
             ..Default::default()
         });
     }
@@ -127,7 +224,9 @@ pub fn create_job_for_run(
 /// ephemeral managed-ssh proxy pods instead (see `managed_ssh.rs`).
 fn create_job_skeleton(
     plan: &v1beta1::PlaybookPlan,
+    image: &str,
     with_requirements: bool,
+    inventory: &str,
 ) -> Result<batch::v1::Job, ReconcileError> {
     let pb_name = plan.name().ok_or(ReconcileError::PreconditionFailed(
         "expected .metadata.name in PlaybookPlan",
@@ -147,31 +246,50 @@ fn create_job_skeleton(
         ..Default::default()
     }]);
 
-    let variable_secrets: Vec<&String> = extract_secret_names_for_variables(plan).collect();
-
-    let mut volumes = vec![kcore::v1::Volume {
-        name: "playbook".into(),
-        secret: Some(kcore::v1::SecretVolumeSource {
-            secret_name: Some(pb_name.into()),
-            ..Default::default()
-        }),
-        ..Default::default()
-    }];
+    let mut volumes = vec![workspace_volume(&workspace::secret_name(&pb_name), plan)];
 
     let mut volume_mounts = vec![kcore::v1::VolumeMount {
-        name: "playbook".into(),
+        name: PLAYBOOK_VOLUME_NAME.into(),
         mount_path: paths::WORKSPACE_MOUNT_PATH.into(),
         ..Default::default()
     }];
 
-    for secret_name in &variable_secrets {
+    let mut extra_vars_files = Vec::new();
+
+    for (index, (config_map_name, key)) in extract_config_maps_for_variables(plan).enumerate() {
+        let volume_name = format!("{CONFIG_MAP_VARS_VOLUME_PREFIX}{index}");
+        volumes.push(kcore::v1::Volume {
+            name: volume_name.clone(),
+            config_map: Some(ConfigMapVolumeSource {
+                name: config_map_name.clone(),
+                items: Some(vec![KeyToPath {
+                    key: key.into(),
+                    path: "variables.yaml".into(),
+                    mode: None,
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let directory = paths::config_map_variables_dir(index);
+        extra_vars_files.push(format!("{directory}/variables.yaml"));
+        volume_mounts.push(kcore::v1::VolumeMount {
+            name: volume_name,
+            mount_path: directory,
+            ..Default::default()
+        });
+    }
+
+    for (index, (secret_name, key)) in extract_secrets_for_variables(plan).enumerate() {
+        let volume_name = format!("{SECRET_VARS_VOLUME_PREFIX}{index}");
         volumes.push(kcore::v1::Volume {
-            name: secret_name.to_string(),
+            name: volume_name.clone(),
             secret: Some(SecretVolumeSource {
-                secret_name: Some(secret_name.to_string()),
+                secret_name: Some(secret_name.clone()),
                 default_mode: Some(0o0400),
                 items: Some(vec![KeyToPath {
-                    key: "variables.yaml".into(),
+                    key: key.into(),
                     path: "variables.yaml".into(),
                     mode: None,
                 }]),
@@ -180,15 +298,31 @@ fn create_job_skeleton(
             ..Default::default()
         });
 
+        let directory = paths::secret_variables_dir(index);
+        extra_vars_files.push(format!("{directory}/variables.yaml"));
         volume_mounts.push(kcore::v1::VolumeMount {
-            name: secret_name.to_string(),
-            mount_path: format!("{}/vars/{secret_name}", paths::WORKSPACE_MOUNT_PATH),
+            name: volume_name,
+            mount_path: directory,
+            ..Default::default()
+        });
+    }
+
+    if let Some(group_vars_volume) = group_vars_volume(&workspace::secret_name(&pb_name), plan) {
+        volumes.push(group_vars_volume);
+        volume_mounts.push(kcore::v1::VolumeMount {
+            name: GROUP_VARS_VOLUME_NAME.into(),
+            mount_path: paths::GROUP_VARS_DIR.into(),
             ..Default::default()
         });
     }
 
     for files_volume in extract_file_volumes(plan) {
-        volumes.push(files_volume?);
+        // `validation` refuses a plan with invalid files unless it opted into leaving them out.
+        match files_volume {
+            Ok(volume) => volumes.push(volume),
+            Err(_) if plan.spec.template.skip_invalid_files => continue,
+            Err(e) => return Err(e.into()),
+        }
         let volume = volumes.last().unwrap();
 
         volume_mounts.push(kcore::v1::VolumeMount {
@@ -202,33 +336,88 @@ fn create_job_skeleton(
         });
     }
 
+    let security_context: Option<kcore::v1::SecurityContext> =
+        plan.spec.security_context.clone().map(Into::into);
+
+    // After the operator's own mounts, so `validation` has ruled out any shadowing of them.
+    volumes.extend(extra_volumes(plan)?);
+    volume_mounts.extend(extra_volume_mounts(plan)?);
+
+    if let Some(collections_image) = &plan.spec.template.collections_image {
+        volumes.push(kcore::v1::Volume {
+            name: COLLECTIONS_VOLUME_NAME.into(),
+            image: Some(kcore::v1::ImageVolumeSource {
+                reference: Some(collections_image.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        volume_mounts.push(kcore::v1::VolumeMount {
+            name: COLLECTIONS_VOLUME_NAME.into(),
+            mount_path: COLLECTIONS_MOUNT_PATH.into(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+    }
+
     let mut init_containers = Vec::new();
 
-    // Add an initcontainer to install collections (workaround until we can use image volumes)
+    // Add an initcontainer to install collections, for clusters without image volumes (see above)
     if with_requirements {
         volumes.push(kcore::v1::Volume {
-            name: "collections".into(),
+            name: COLLECTIONS_VOLUME_NAME.into(),
             empty_dir: Some(EmptyDirVolumeSource::default()),
             ..Default::default()
         });
 
         volume_mounts.push(kcore::v1::VolumeMount {
-            name: "collections".into(),
-            mount_path: "/etc/ansible/collections".into(),
+            name: COLLECTIONS_VOLUME_NAME.into(),
+            mount_path: COLLECTIONS_MOUNT_PATH.into(),
             ..Default::default()
         });
 
+        let mut installer_mounts = volume_mounts.clone();
+        let mut installer_env = None;
+        if let Some(galaxy) = &plan.spec.template.galaxy
+            && let Some(secret_ref) = &galaxy.token_secret_ref
+        {
+            let token_key = galaxy.effective_token_key();
+            volumes.push(kcore::v1::Volume {
+                name: GALAXY_TOKEN_VOLUME_NAME.into(),
+                secret: Some(SecretVolumeSource {
+                    secret_name: Some(secret_ref.name.clone()),
+                    default_mode: Some(0o0400),
+                    items: Some(vec![KeyToPath {
+                        key: token_key.into(),
+                        path: token_key.into(),
+                        mode: None,
+                    }]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+            installer_mounts.push(kcore::v1::VolumeMount {
+                name: GALAXY_TOKEN_VOLUME_NAME.into(),
+                mount_path: GALAXY_TOKEN_MOUNT_PATH.into(),
+                read_only: Some(true),
+                ..Default::default()
+            });
+            // Read by `ansible-galaxy` for a `--server` that isn't configured with a token of its own.
+            installer_env = Some(vec![kcore::v1::EnvVar {
+                name: "ANSIBLE_GALAXY_TOKEN_PATH".into(),
+                value: Some(format!("{GALAXY_TOKEN_MOUNT_PATH}/{token_key}")),
+                ..Default::default()
+            }]);
+        }
+
         let collections_installer = kcore::v1::Container {
             name: "download-collections".into(),
-            image: Some(plan.spec.image.clone()),
+            image: Some(image.into()),
             working_dir: Some(paths::WORKSPACE_MOUNT_PATH.into()),
-            volume_mounts: Some(volume_mounts.clone()),
-            command: Some(vec![
-                "ansible-galaxy".into(),
-                "install".into(),
-                "-r".into(),
-                "requirements.yml".into(),
-            ]),
+            volume_mounts: Some(installer_mounts),
+            env: installer_env,
+            command: Some(galaxy_install_command(plan)),
+            security_context: security_context.clone(),
             ..Default::default()
         };
 
@@ -237,15 +426,16 @@ fn create_job_skeleton(
 
     let main_container = kcore::v1::Container {
         name: ANSIBLE_CONTAINER_NAME.into(),
-        image: Some(plan.spec.image.clone()),
+        image: Some(image.into()),
         working_dir: Some(paths::WORKSPACE_MOUNT_PATH.into()),
         volume_mounts: Some(volume_mounts),
-        command: Some(render_ansible_command(plan, variable_secrets)),
+        command: Some(render_ansible_command(plan, extra_vars_files, inventory)),
         // The recap callback writes to /dev/termination-log and the reconciler reads it back from
         // this container's state.terminated.message. These are the Kubernetes defaults, set
         // explicitly so the dependency is legible and can't be silently mutated away.
         termination_message_path: Some("/dev/termination-log".into()),
         termination_message_policy: Some("File".into()),
+        security_context,
         ..Default::default()
     };
 
@@ -254,7 +444,20 @@ fn create_job_skeleton(
         spec: Some(kcore::v1::PodSpec {
             restart_policy: Some("Never".into()), // todo: maybe configurable
             service_account_name: plan.spec.service_account_name.clone(),
-            automount_service_account_token: Some(plan.spec.service_account_name.is_some()),
+            automount_service_account_token: Some(
+                plan.spec
+                    .automount_service_account_token
+                    .unwrap_or(plan.spec.service_account_name.is_some()),
+            ),
+            node_selector: plan.spec.job_node_selector.clone(),
+            security_context: plan.spec.pod_security_context.clone().map(Into::into),
+            dns_policy: plan.spec.dns_policy.as_ref().map(|p| p.as_str().into()),
+            dns_config: plan.spec.dns_config.clone().map(Into::into),
+            host_aliases: plan
+                .spec
+                .host_aliases
+                .clone()
+                .map(|aliases| aliases.into_iter().map(Into::into).collect()),
             volumes: Some(volumes),
             containers: vec![main_container],
             init_containers: Some(init_containers),
@@ -366,7 +569,7 @@ fn configure_job_for_ssh(job: &mut Job, ssh_configs: &[(String, SshConfig)]) {
                 .expect("job should have a container");
 
             for (static_inventory_name, config) in ssh_configs {
-                let volume_name = format!("ssh-{static_inventory_name}");
+                let volume_name = format!("{SSH_VOLUME_PREFIX}{static_inventory_name}");
 
                 pod_spec.volumes.get_or_insert_default().push(Volume {
                     name: volume_name.clone(),
@@ -402,7 +605,7 @@ fn configure_job_for_managed_ssh_client_cert(job: &mut Job, secret_name: &str) {
                 .expect("job should have a container");
 
             pod_spec.volumes.get_or_insert_default().push(Volume {
-                name: "managed-ssh-client".into(),
+                name: MANAGED_SSH_CLIENT_VOLUME_NAME.into(),
                 secret: Some(SecretVolumeSource {
                     secret_name: Some(secret_name.to_string()),
                     default_mode: Some(0o0400),
@@ -415,7 +618,7 @@ fn configure_job_for_managed_ssh_client_cert(job: &mut Job, secret_name: &str) {
                 .volume_mounts
                 .get_or_insert_default()
                 .push(kcore::v1::VolumeMount {
-                    name: "managed-ssh-client".into(),
+                    name: MANAGED_SSH_CLIENT_VOLUME_NAME.into(),
                     mount_path: paths::MANAGED_SSH_CLIENT_DIR.into(),
                     ..Default::default()
                 });
@@ -450,18 +653,302 @@ fn configure_job_for_callback_plugin(job: &mut Job) {
     });
 }
 
+/// Mounts each of `template.roles` at `roles/<name>`, turning `__` in its keys into subdirectories,
+/// and points `ANSIBLE_ROLES_PATH` there ahead of Ansible's default locations, so roles the image
+/// ships stay usable. A role missing from `role_files` (its keys unknown, as in `preview`) is mounted
+/// with every key as a file of the role's top directory.
+fn configure_job_for_roles(job: &mut Job, plan: &PlaybookPlan, role_files: &RoleFiles) {
+    let Some(roles) = plan.spec.template.roles.as_ref().filter(|r| !r.is_empty()) else {
+        return;
+    };
+
+    let pod_spec = job
+        .spec
+        .as_mut()
+        .and_then(|spec| spec.template.spec.as_mut())
+        .expect("job should have a pod spec");
+    let main_container = pod_spec
+        .containers
+        .first_mut()
+        .expect("job should have a container");
+
+    for (index, role) in roles.iter().enumerate() {
+        let volume_name = format!("{ROLE_VOLUME_PREFIX}{index}");
+        let items = role_files
+            .get(&role.name)
+            .filter(|keys| !keys.is_empty())
+            .map(|keys| {
+                keys.iter()
+                    .map(|key| KeyToPath {
+                        key: key.clone(),
+                        path: key.replace("__", "/"),
+                        mode: None,
+                    })
+                    .collect()
+            });
+
+        let mut volume = Volume {
+            name: volume_name.clone(),
+            ..Default::default()
+        };
+        if let Some(secret_ref) = &role.secret_ref {
+            volume.secret = Some(SecretVolumeSource {
+                secret_name: Some(secret_ref.name.clone()),
+                default_mode: Some(0o0400),
+                items,
+                ..Default::default()
+            });
+        } else if let Some(config_map_ref) = &role.config_map_ref {
+            volume.config_map = Some(ConfigMapVolumeSource {
+                name: config_map_ref.name.clone(),
+                items,
+                ..Default::default()
+            });
+        }
+        pod_spec.volumes.get_or_insert_default().push(volume);
+
+        main_container
+            .volume_mounts
+            .get_or_insert_default()
+            .push(kcore::v1::VolumeMount {
+                name: volume_name,
+                mount_path: format!("{}/{}", paths::ROLES_DIR, role.name),
+                read_only: Some(true),
+                ..Default::default()
+            });
+    }
+
+    main_container.env.get_or_insert_default().push(EnvVar {
+        name: "ANSIBLE_ROLES_PATH".into(),
+        value: Some(format!(
+            "{}:~/.ansible/roles:/usr/share/ansible/roles:/etc/ansible/roles",
+            paths::ROLES_DIR
+        )),
+        ..Default::default()
+    });
+}
+
+/// Adds `spec.preflight`'s init container: a copy of the fully configured main container (same
+/// inventory, variables, SSH identities and recap callback) running the workspace's ping playbook
+/// instead. `ansible-playbook` exits non-zero if any host doesn't answer, and with the pod's
+/// `restartPolicy: Never` that fails the pod before the real playbook starts. Added last, so the
+/// collections install still runs first.
+fn configure_job_for_preflight(job: &mut Job) {
+    let pod_spec = job
+        .spec
+        .as_mut()
+        .and_then(|spec| spec.template.spec.as_mut())
+        .expect("job should have a pod spec");
+
+    let mut preflight = pod_spec
+        .containers
+        .first()
+        .expect("job should have a container")
+        .clone();
+    preflight.name = PREFLIGHT_CONTAINER_NAME.into();
+    if let Some(command) = preflight.command.as_mut() {
+        // Everything from `playbook.yml` on (the verify playbook and `--start-at-task`, if any)
+        // gives way to the ping.
+        if let Some(playbook) = command.iter().position(|arg| arg == "playbook.yml") {
+            command.truncate(playbook);
+            command.push(workspace::PREFLIGHT_PLAYBOOK_KEY.into());
+        }
+    }
+
+    pod_spec
+        .init_containers
+        .get_or_insert_default()
+        .push(preflight);
+}
+
+/// The Secrets `template.variables` reads, each with the key holding its variables, in declaration
+/// order. The same Secret may appear several times, with different keys.
+pub fn extract_secrets_for_variables(pp: &PlaybookPlan) -> impl Iterator<Item = (&String, &str)> {
+    pp.spec
+        .template
+        .variables
+        .iter()
+        .flatten()
+        .filter_map(variables_secret_and_key)
+}
+
+/// Like `extract_secrets_for_variables`, for the sources of `template.groupVariables`.
+pub fn extract_secrets_for_group_variables(
+    pp: &PlaybookPlan,
+) -> impl Iterator<Item = (&String, &str)> {
+    pp.spec
+        .template
+        .group_variables
+        .iter()
+        .flatten()
+        .flat_map(|group| &group.variables)
+        .filter_map(variables_secret_and_key)
+}
+
+fn variables_secret_and_key(source: &PlaybookVariableSource) -> Option<(&String, &str)> {
+    match source {
+        PlaybookVariableSource::SecretRef { secret_ref, key } => Some((
+            &secret_ref.name,
+            key.as_deref()
+                .unwrap_or(PlaybookVariableSource::DEFAULT_VARIABLES_KEY),
+        )),
+        PlaybookVariableSource::Inline { .. } | PlaybookVariableSource::ConfigMapRef { .. } => None,
+    }
+}
+
 pub fn extract_secret_names_for_variables(pp: &PlaybookPlan) -> impl Iterator<Item = &String> {
+    extract_secrets_for_variables(pp).map(|(name, _)| name)
+}
+
+pub fn extract_secret_names_for_group_variables(
+    pp: &PlaybookPlan,
+) -> impl Iterator<Item = &String> {
+    extract_secrets_for_group_variables(pp).map(|(name, _)| name)
+}
+
+/// The ConfigMaps `template.variables` reads, each with the key holding its variables, in
+/// declaration order.
+pub fn extract_config_maps_for_variables(
+    pp: &PlaybookPlan,
+) -> impl Iterator<Item = (&String, &str)> {
     pp.spec
         .template
         .variables
-        .as_ref()
-        .into_iter()
-        .flat_map(|variables| {
-            variables.iter().filter_map(|v| match v {
-                PlaybookVariableSource::Inline { inline: _ } => None,
-                PlaybookVariableSource::SecretRef { secret_ref } => Some(&secret_ref.name),
+        .iter()
+        .flatten()
+        .filter_map(|v| match v {
+            PlaybookVariableSource::ConfigMapRef {
+                config_map_ref,
+                key,
+            } => Some((
+                &config_map_ref.name,
+                key.as_deref()
+                    .unwrap_or(PlaybookVariableSource::DEFAULT_VARIABLES_KEY),
+            )),
+            PlaybookVariableSource::Inline { .. } | PlaybookVariableSource::SecretRef { .. } => {
+                None
+            }
+        })
+}
+
+pub fn extract_secret_names_for_roles(pp: &PlaybookPlan) -> impl Iterator<Item = &String> {
+    pp.spec
+        .template
+        .roles
+        .iter()
+        .flatten()
+        .filter_map(|role| role.secret_ref.as_ref().map(|secret_ref| &secret_ref.name))
+}
+
+pub fn extract_config_map_names_for_roles(pp: &PlaybookPlan) -> impl Iterator<Item = &String> {
+    pp.spec.template.roles.iter().flatten().filter_map(|role| {
+        role.config_map_ref
+            .as_ref()
+            .map(|config_map_ref| &config_map_ref.name)
+    })
+}
+
+/// Every ConfigMap the plan reads variables from, through `template.variables` or
+/// `template.groupVariables`.
+pub fn extract_config_map_names_for_variables(pp: &PlaybookPlan) -> impl Iterator<Item = &String> {
+    let group_variables =
+        pp.spec
+            .template
+            .group_variables
+            .iter()
+            .flatten()
+            .flat_map(|group| &group.variables)
+            .filter_map(|v| match v {
+                PlaybookVariableSource::ConfigMapRef { config_map_ref, .. } => {
+                    Some(&config_map_ref.name)
+                }
+                PlaybookVariableSource::Inline { .. }
+                | PlaybookVariableSource::SecretRef { .. } => None,
+            });
+
+    extract_config_maps_for_variables(pp)
+        .map(|(name, _)| name)
+        .chain(group_variables)
+}
+
+/// The workspace `workspace_name`, from the Secret or ConfigMap its plan's `workspaceStorage` says.
+fn workspace_volume(workspace_name: &str, plan: &PlaybookPlan) -> Volume {
+    let mut volume = Volume {
+        name: PLAYBOOK_VOLUME_NAME.into(),
+        ..Default::default()
+    };
+    match plan.spec.workspace_storage {
+        WorkspaceStorage::Secret => {
+            volume.secret = Some(SecretVolumeSource {
+                secret_name: Some(workspace_name.into()),
+                ..Default::default()
+            })
+        }
+        WorkspaceStorage::ConfigMap => {
+            volume.config_map = Some(ConfigMapVolumeSource {
+                name: workspace_name.into(),
+                ..Default::default()
             })
+        }
+    }
+    volume
+}
+
+/// Projects every `template.groupVariables` file — inline ones from the workspace, the rest from
+/// their own Secrets or ConfigMaps — into one `group_vars/` tree (see `workspace::group_vars_files`). `None` if
+/// the plan sets no group variables.
+fn group_vars_volume(workspace_name: &str, plan: &PlaybookPlan) -> Option<Volume> {
+    let files = workspace::group_vars_files(plan);
+    if files.is_empty() {
+        return None;
+    }
+
+    let sources = files
+        .into_iter()
+        .map(|file| {
+            let config_map_workspace = plan.spec.workspace_storage == WorkspaceStorage::ConfigMap;
+            let (name, key, from_config_map) = match file.source {
+                GroupVarsSource::Workspace { key, .. } => {
+                    (workspace_name.to_string(), key, config_map_workspace)
+                }
+                GroupVarsSource::Secret { name, key } => (name.to_string(), key.into(), false),
+                GroupVarsSource::ConfigMap { name, key } => (name.to_string(), key.into(), true),
+            };
+            let items = Some(vec![KeyToPath {
+                key,
+                path: file.path,
+                mode: None,
+            }]);
+            if from_config_map {
+                VolumeProjection {
+                    config_map: Some(ConfigMapProjection {
+                        name,
+                        items,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            } else {
+                VolumeProjection {
+                    secret: Some(SecretProjection {
+                        name,
+                        items,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            }
         })
+        .collect();
+
+    Some(Volume {
+        name: GROUP_VARS_VOLUME_NAME.into(),
+        projected: Some(ProjectedVolumeSource {
+            default_mode: Some(0o0400),
+            sources: Some(sources),
+        }),
+        ..Default::default()
+    })
 }
 
 pub fn extract_secret_names_for_files(pp: &PlaybookPlan) -> impl Iterator<Item = &String> {
@@ -478,6 +965,103 @@ pub fn extract_secret_names_for_files(pp: &PlaybookPlan) -> impl Iterator<Item =
         })
 }
 
+/// `spec.extraVolumes` as Kubernetes Volumes, passed through as written. Like `extract_file_volumes`,
+/// parsing is left to serde, so any volume kind the targeted Kubernetes version knows works.
+pub fn extra_volumes(pp: &PlaybookPlan) -> Result<Vec<Volume>, serde_json::Error> {
+    pp.spec
+        .extra_volumes
+        .iter()
+        .flatten()
+        .map(|volume| serde_json::from_value(volume.0.clone()))
+        .collect()
+}
+
+/// `spec.extraVolumeMounts` as Kubernetes VolumeMounts, for every container of the Job pod.
+pub fn extra_volume_mounts(pp: &PlaybookPlan) -> Result<Vec<VolumeMount>, serde_json::Error> {
+    pp.spec
+        .extra_volume_mounts
+        .iter()
+        .flatten()
+        .map(|mount| serde_json::from_value(mount.0.clone()))
+        .collect()
+}
+
+/// Whether the Job pod of `pp` already has a volume called `name`, or may get one: the fixed
+/// volumes, one per variables Secret and `template.files` entry, and the per-`StaticInventory` SSH
+/// key volumes.
+pub fn volume_name_reserved(pp: &PlaybookPlan, name: &str) -> bool {
+    [
+        PLAYBOOK_VOLUME_NAME,
+        GROUP_VARS_VOLUME_NAME,
+        COLLECTIONS_VOLUME_NAME,
+        GALAXY_TOKEN_VOLUME_NAME,
+        MANAGED_SSH_CLIENT_VOLUME_NAME,
+    ]
+    .contains(&name)
+        || [
+            SSH_VOLUME_PREFIX,
+            SECRET_VARS_VOLUME_PREFIX,
+            CONFIG_MAP_VARS_VOLUME_PREFIX,
+            ROLE_VOLUME_PREFIX,
+        ]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+        || pp.spec.template.files.iter().flatten().any(|file| {
+            let (FilesSource::Secret { name: file, .. } | FilesSource::Other { name: file, .. }) =
+                file;
+            file == name
+        })
+}
+
+/// Whether `mount_path` is, or lies below, a path the operator mounts its own volumes at.
+pub fn mount_path_reserved(mount_path: &str) -> bool {
+    let mount_path = mount_path.trim_end_matches('/');
+    [paths::WORKSPACE_MOUNT_PATH, COLLECTIONS_MOUNT_PATH]
+        .iter()
+        .any(|reserved| {
+            mount_path == *reserved
+                || mount_path
+                    .strip_prefix(reserved)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+}
+
+/// `metadata`'s user-set labels overlaid with the operator's `reserved` ones, which always win.
+fn user_labels_then(
+    metadata: Option<&TemplateMetadata>,
+    reserved: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut merged = metadata.and_then(|m| m.labels.clone()).unwrap_or_default();
+    merged.extend(reserved.clone());
+    merged
+}
+
+fn galaxy_install_command(plan: &PlaybookPlan) -> Vec<String> {
+    let mut command: Vec<String> = ["ansible-galaxy", "install", "-r", "requirements.yml"]
+        .map(Into::into)
+        .into();
+    if plan.spec.template.requirements_install == RequirementsInstall::Force {
+        command.push("--force".into());
+    }
+    if let Some(galaxy) = &plan.spec.template.galaxy {
+        command.extend(["--server".into(), galaxy.server.clone()]);
+    }
+    command
+}
+
+/// The `template.files` entries that aren't valid Kubernetes volumes, by name, with the reason.
+pub fn invalid_files(pp: &PlaybookPlan) -> Vec<(String, String)> {
+    let files = pp.spec.template.files.iter().flatten();
+
+    files
+        .zip(extract_file_volumes(pp))
+        .filter_map(|(source, volume)| {
+            let (FilesSource::Secret { name, .. } | FilesSource::Other { name, .. }) = source;
+            volume.err().map(|err| (name.clone(), err.to_string()))
+        })
+        .collect()
+}
+
 /// Takes the mostly schemarless volumes defined the PlaybookPlan and turns them into
 /// proper Kubernetes Volumes that can be used in a PodSpec. This is necessary because
 /// we don't want to handle every possible kind of volume in our code.
@@ -516,12 +1100,36 @@ fn extract_file_volumes(
     })
 }
 
+/// What the run's `-i` points at: the generated `inventory.yml`, the plan's own
+/// `template.inventoryFile`, or with `generateInventory: false` Ansible's comma-separated host list
+/// form (`host-1,host-2,` — the trailing comma is what marks it as a list, even of one host).
+fn inventory_argument(
+    plan: &v1beta1::PlaybookPlan,
+    target_groups: &[ResolvedInventoryGroup],
+) -> String {
+    if let Some(inventory_file) = &plan.spec.template.inventory_file {
+        return inventory_file.clone();
+    }
+    if plan.uses_generated_inventory() {
+        return "inventory.yml".into();
+    }
+
+    let mut hosts = BTreeSet::new();
+    target_groups
+        .iter()
+        .flat_map(|group| &group.hosts().hosts)
+        .filter(|host| hosts.insert(host.as_str()))
+        .map(|host| format!("{host},"))
+        .collect()
+}
+
 /// Builds the `ansible-playbook` invocation. Connection details no longer appear here at all —
 /// each host's connection mechanism is expressed as inventory vars in the rendered
 /// `inventory.yml` instead, so there's no more per-strategy `-c`/`-l`/`--private-key` branching.
 fn render_ansible_command(
     plan: &v1beta1::PlaybookPlan,
-    extra_vars_filepaths: Vec<&String>,
+    extra_vars_files: Vec<String>,
+    inventory: &str,
 ) -> Vec<String> {
     let static_vars_filenames: Vec<String> = plan
         .spec
@@ -532,7 +1140,8 @@ fn render_ansible_command(
             variables
                 .iter()
                 .filter_map(|source| match source {
-                    PlaybookVariableSource::SecretRef { secret_ref: _ } => None,
+                    PlaybookVariableSource::SecretRef { .. } => None,
+                    PlaybookVariableSource::ConfigMapRef { .. } => None,
                     PlaybookVariableSource::Inline { inline: _ } => Some(()),
                 })
                 .enumerate()
@@ -554,18 +1163,21 @@ fn render_ansible_command(
             .flat_map(|path| ["--extra-vars".into(), format!("@{path}")]),
     );
 
-    ansible_command.extend(extra_vars_filepaths.iter().flat_map(|path| {
-        [
-            "--extra-vars".into(),
-            format!(
-                "@{}/vars/{path}/variables.yaml",
-                paths::WORKSPACE_MOUNT_PATH
-            ),
-        ]
-    }));
+    ansible_command.extend(
+        extra_vars_files
+            .iter()
+            .flat_map(|path| ["--extra-vars".into(), format!("@{path}")]),
+    );
 
-    ansible_command.extend(["-i".into(), "inventory.yml".into()]);
+    ansible_command.extend(["-i".into(), inventory.into()]);
     ansible_command.push("playbook.yml".into());
+    if plan.spec.template.verify_playbook.is_some() {
+        ansible_command.push(workspace::VERIFY_PLAYBOOK_KEY.into());
+    }
+    // After the playbooks, so the preflight container, which swaps them for its ping, drops it too.
+    if let Some(task) = &plan.spec.start_at_task {
+        ansible_command.extend(["--start-at-task".into(), task.clone()]);
+    }
 
     ansible_command
 }
@@ -573,6 +1185,7 @@ fn render_ansible_command(
 #[cfg(test)]
 mod tests {
     use crate::v1beta1::PlaybookPlan;
+    use crate::v1beta1::playbookplancontroller::role_sources::RoleFiles;
 
     #[test]
     fn test_extract_file_volumes_generates_correct_volumes() {
@@ -642,44 +1255,498 @@ spec:
     }
 
     #[test]
-    fn render_ansible_command_has_no_connection_flags_and_uses_full_inventory() {
-        use crate::v1beta1::controllers::playbookplancontroller::job_builder::render_ansible_command;
+    fn invalid_files_volumes_are_reported_and_skipped_only_when_asked_to() {
+        let mut plan = minimal_plan();
+        plan.spec.template.files = Some(
+            serde_json::from_value(serde_json::json!([
+                {"name": "configs", "configMap": {"name": "ansible-configs"}},
+                {"name": "broken", "configMap": "ansible-configs"},
+            ]))
+            .unwrap(),
+        );
 
-        let yaml = r#"
-apiVersion: ansible.cloudbending.dev/v1beta1
-kind: PlaybookPlan
-metadata:
-  name: an-example
-spec:
-  image: docker.io/serversideup/ansible-core:2.18
-  mode: OneShot
-  inventoryRefs: []
-  template:
-    playbook: |
-      - hosts: all
-        tasks: []
-        "#;
-        let pp = serde_yaml::from_str::<PlaybookPlan>(yaml).unwrap();
+        let invalid = super::invalid_files(&plan);
+        let [(name, _)] = invalid.as_slice() else {
+            panic!("expected only 'broken' to be invalid, got {invalid:?}");
+        };
+        assert_eq!(name, "broken");
 
-        let command = render_ansible_command(&pp, Vec::new());
+        assert!(
+            super::create_job_skeleton(&plan, &plan.spec.image, false, "inventory.yml").is_err()
+        );
 
-        assert!(!command.iter().any(|arg| arg == "-c"));
-        assert!(!command.iter().any(|arg| arg == "-l"));
-        assert!(!command.iter().any(|arg| arg == "--private-key"));
-        assert!(command.iter().any(|arg| arg == "inventory.yml"));
-        assert!(command.iter().any(|arg| arg == "playbook.yml"));
-        // No verbosity requested -> no -v flag at all.
-        assert!(!command.iter().any(|arg| arg.starts_with("-v")));
+        plan.spec.template.skip_invalid_files = true;
+        let job =
+            super::create_job_skeleton(&plan, &plan.spec.image, false, "inventory.yml").unwrap();
+        let pod = job.spec.unwrap().template.spec.unwrap();
+        let volumes: Vec<_> = pod.volumes.unwrap().into_iter().map(|v| v.name).collect();
+        assert!(volumes.contains(&"configs".to_string()), "{volumes:?}");
+        assert!(!volumes.contains(&"broken".to_string()), "{volumes:?}");
     }
 
     #[test]
-    fn render_ansible_command_maps_verbosity_to_v_flags() {
-        use crate::v1beta1::controllers::playbookplancontroller::job_builder::render_ansible_command;
+    fn group_variables_are_projected_next_to_the_inventory() {
+        use super::{GROUP_VARS_VOLUME_NAME, paths};
+        use crate::v1beta1::{GroupVariables, PlaybookVariableSource, SecretRef};
+
+        let mut plan = minimal_plan();
+        plan.spec.template.group_variables = Some(vec![GroupVariables {
+            group: "webservers".into(),
+            variables: vec![
+                PlaybookVariableSource::Inline {
+                    inline: Default::default(),
+                },
+                PlaybookVariableSource::SecretRef {
+                    secret_ref: SecretRef {
+                        name: "web-tls".into(),
+                    },
+                    key: Some("tls.yml".into()),
+                },
+            ],
+        }]);
 
-        let v_flags = |plan: &PlaybookPlan| -> Vec<String> {
-            render_ansible_command(plan, Vec::new())
-                .into_iter()
-                .filter(|arg| arg.starts_with("-v"))
+        let job =
+            super::create_job_skeleton(&plan, &plan.spec.image, false, "inventory.yml").unwrap();
+        let pod = job.spec.unwrap().template.spec.unwrap();
+
+        let volume = pod
+            .volumes
+            .unwrap()
+            .into_iter()
+            .find(|v| v.name == GROUP_VARS_VOLUME_NAME)
+            .unwrap();
+        let projected: Vec<(String, String, String)> = volume
+            .projected
+            .unwrap()
+            .sources
+            .unwrap()
+            .into_iter()
+            .map(|source| {
+                let secret = source.secret.unwrap();
+                let item = secret.items.unwrap().remove(0);
+                (secret.name, item.key, item.path)
+            })
+            .collect();
+        assert_eq!(
+            projected,
+            vec![
+                (
+                    "ansible-workspace-an-example".to_string(),
+                    "group-variables-0.yml".to_string(),
+                    "webservers/000.yml".to_string()
+                ),
+                (
+                    "web-tls".to_string(),
+                    "tls.yml".to_string(),
+                    "webservers/001.yml".to_string()
+                ),
+            ]
+        );
+
+        let mounts = pod.containers[0].volume_mounts.as_ref().unwrap();
+        assert!(
+            mounts
+                .iter()
+                .any(|m| m.name == GROUP_VARS_VOLUME_NAME && m.mount_path == paths::GROUP_VARS_DIR)
+        );
+
+        // Group variables are not playbook-wide extra vars.
+        let command = pod.containers[0].command.as_ref().unwrap();
+        assert!(!command.iter().any(|arg| arg.contains("web-tls")));
+    }
+
+    #[test]
+    fn config_map_variables_are_mounted_and_passed_between_inline_and_secret_ones() {
+        use crate::v1beta1::PlaybookVariableSource;
+
+        let mut plan = minimal_plan();
+        plan.spec.template.variables = serde_yaml::from_str(
+            r#"
+- inline:
+    greeting: hello
+- secretRef:
+    name: credentials
+- configMapRef:
+    name: defaults
+- configMapRef:
+    name: site-settings
+  key: production.yaml
+"#,
+        )
+        .unwrap();
+        // Untagged, so the existing forms must still land on their own variants.
+        let variables = plan.spec.template.variables.as_ref().unwrap();
+        assert!(matches!(
+            variables[0],
+            PlaybookVariableSource::Inline { .. }
+        ));
+        assert!(matches!(
+            variables[1],
+            PlaybookVariableSource::SecretRef { .. }
+        ));
+        assert!(matches!(
+            &variables[3],
+            PlaybookVariableSource::ConfigMapRef { key: Some(key), .. } if key == "production.yaml"
+        ));
+
+        let job =
+            super::create_job_skeleton(&plan, &plan.spec.image, false, "inventory.yml").unwrap();
+        let pod = job.spec.unwrap().template.spec.unwrap();
+
+        let config_maps: Vec<(String, String)> = pod
+            .volumes
+            .unwrap()
+            .into_iter()
+            .filter_map(|volume| {
+                let config_map = volume.config_map?;
+                let item = config_map.items.unwrap().remove(0);
+                Some((config_map.name, item.key))
+            })
+            .collect();
+        assert_eq!(
+            config_maps,
+            vec![
+                ("defaults".to_string(), "variables.yaml".to_string()),
+                ("site-settings".to_string(), "production.yaml".to_string()),
+            ]
+        );
+
+        let extra_vars: Vec<&String> = pod.containers[0]
+            .command
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|arg| arg.starts_with('@'))
+            .collect();
+        assert_eq!(
+            extra_vars,
+            vec![
+                "@static-variables-0.yml",
+                "@/run/ansible-operator/config-map-vars/0/variables.yaml",
+                "@/run/ansible-operator/config-map-vars/1/variables.yaml",
+                "@/run/ansible-operator/secret-vars/0/variables.yaml",
+            ]
+        );
+    }
+
+    #[test]
+    fn roles_are_mounted_as_directory_trees_on_the_roles_path() {
+        let mut plan = minimal_plan();
+        plan.spec.preflight = true;
+        plan.spec.template.roles = serde_yaml::from_str(
+            r#"
+- name: hardening
+  secretRef:
+    name: hardening-role
+- name: motd
+  configMapRef:
+    name: motd-role
+"#,
+        )
+        .unwrap();
+        let role_files = RoleFiles::from([(
+            "hardening".to_string(),
+            vec!["tasks__main.yml".to_string(), "README.md".to_string()],
+        )]);
+
+        let hash = "1".parse::<super::ExecutionHash>().unwrap();
+        let job =
+            super::create_job_for_run(&hash, 0, &[], &plan, &plan.spec.image, &role_files).unwrap();
+        let pod = job.spec.unwrap().template.spec.unwrap();
+
+        let volumes = pod.volumes.unwrap();
+        let hardening = volumes.iter().find(|v| v.name == "role-0").unwrap();
+        let secret = hardening.secret.as_ref().unwrap();
+        assert_eq!(secret.secret_name.as_deref(), Some("hardening-role"));
+        let paths: Vec<(&str, &str)> = secret
+            .items
+            .iter()
+            .flatten()
+            .map(|item| (item.key.as_str(), item.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("tasks__main.yml", "tasks/main.yml"),
+                ("README.md", "README.md")
+            ]
+        );
+        // Keys unknown: every key becomes a file of the role's directory.
+        let motd = volumes.iter().find(|v| v.name == "role-1").unwrap();
+        let config_map = motd.config_map.as_ref().unwrap();
+        assert_eq!(config_map.name, "motd-role");
+        assert!(config_map.items.is_none());
+
+        // The preflight container is a copy of the main one, so it sees the roles too.
+        for container in pod
+            .containers
+            .iter()
+            .chain(pod.init_containers.iter().flatten())
+        {
+            let mounts: Vec<(&str, &str)> = container
+                .volume_mounts
+                .iter()
+                .flatten()
+                .filter(|m| m.name.starts_with(super::ROLE_VOLUME_PREFIX))
+                .map(|m| (m.name.as_str(), m.mount_path.as_str()))
+                .collect();
+            assert_eq!(
+                mounts,
+                [
+                    ("role-0", "/run/ansible-operator/roles/hardening"),
+                    ("role-1", "/run/ansible-operator/roles/motd"),
+                ],
+                "{}",
+                container.name
+            );
+            let roles_path = container
+                .env
+                .iter()
+                .flatten()
+                .find(|e| e.name == "ANSIBLE_ROLES_PATH")
+                .and_then(|e| e.value.as_deref());
+            assert!(
+                roles_path.is_some_and(|p| p.starts_with("/run/ansible-operator/roles:")),
+                "{roles_path:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keys_of_one_secret_get_a_volume_each() {
+        let mut plan = minimal_plan();
+        plan.spec.template.variables = serde_yaml::from_str(
+            r#"
+- secretRef:
+    name: app
+- secretRef:
+    name: app
+  key: values.yml
+"#,
+        )
+        .unwrap();
+
+        let job =
+            super::create_job_skeleton(&plan, &plan.spec.image, false, "inventory.yml").unwrap();
+        let pod = job.spec.unwrap().template.spec.unwrap();
+
+        let secrets: Vec<(String, String, String)> = pod
+            .volumes
+            .unwrap()
+            .into_iter()
+            .filter_map(|volume| {
+                let secret = volume.secret?;
+                let item = secret.items?.remove(0);
+                Some((volume.name, secret.secret_name.unwrap(), item.key))
+            })
+            .collect();
+        assert_eq!(
+            secrets,
+            vec![
+                (
+                    "secret-vars-0".to_string(),
+                    "app".to_string(),
+                    "variables.yaml".to_string()
+                ),
+                (
+                    "secret-vars-1".to_string(),
+                    "app".to_string(),
+                    "values.yml".to_string()
+                ),
+            ]
+        );
+
+        let mounts: Vec<(&str, &str)> = pod.containers[0]
+            .volume_mounts
+            .iter()
+            .flatten()
+            .filter(|mount| mount.name.starts_with(super::SECRET_VARS_VOLUME_PREFIX))
+            .map(|mount| (mount.name.as_str(), mount.mount_path.as_str()))
+            .collect();
+        assert_eq!(
+            mounts,
+            vec![
+                ("secret-vars-0", "/run/ansible-operator/secret-vars/0"),
+                ("secret-vars-1", "/run/ansible-operator/secret-vars/1"),
+            ]
+        );
+
+        let extra_vars: Vec<&String> = pod.containers[0]
+            .command
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|arg| arg.starts_with('@'))
+            .collect();
+        assert_eq!(
+            extra_vars,
+            vec![
+                "@/run/ansible-operator/secret-vars/0/variables.yaml",
+                "@/run/ansible-operator/secret-vars/1/variables.yaml",
+            ]
+        );
+    }
+
+    #[test]
+    fn render_ansible_command_has_no_connection_flags_and_uses_full_inventory() {
+        use crate::v1beta1::controllers::playbookplancontroller::job_builder::render_ansible_command;
+
+        let yaml = r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: an-example
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  mode: OneShot
+  inventoryRefs: []
+  template:
+    playbook: |
+      - hosts: all
+        tasks: []
+        "#;
+        let pp = serde_yaml::from_str::<PlaybookPlan>(yaml).unwrap();
+
+        let command = render_ansible_command(&pp, Vec::new(), "inventory.yml");
+
+        assert!(!command.iter().any(|arg| arg == "-c"));
+        assert!(!command.iter().any(|arg| arg == "-l"));
+        assert!(!command.iter().any(|arg| arg == "--private-key"));
+        assert!(command.iter().any(|arg| arg == "inventory.yml"));
+        assert!(command.iter().any(|arg| arg == "playbook.yml"));
+        // No verbosity requested -> no -v flag at all.
+        assert!(!command.iter().any(|arg| arg.starts_with("-v")));
+    }
+
+    #[test]
+    fn the_inventory_is_the_generated_one_the_plans_own_file_or_a_host_list() {
+        use crate::v1beta1::{ResolvedHosts, ResolvedInventoryGroup, SecretRef, SshConfig};
+
+        let group = |name: &str, hosts: &[&str]| ResolvedInventoryGroup::Ssh {
+            hosts: ResolvedHosts {
+                name: name.into(),
+                hosts: hosts.iter().map(ToString::to_string).collect(),
+            },
+            static_inventory_name: "edge".into(),
+            config: SshConfig {
+                user: "admin".into(),
+                secret_ref: SecretRef {
+                    name: "edge-key".into(),
+                },
+                private_key_key: None,
+                additional_private_key_keys: None,
+            },
+            variables: None,
+        };
+        let groups = [
+            group("routers", &["ccu.fritz.box", "gw.fritz.box"]),
+            group("gateways", &["gw.fritz.box"]),
+        ];
+        let mut plan = minimal_plan();
+
+        assert_eq!(super::inventory_argument(&plan, &groups), "inventory.yml");
+
+        plan.spec.generate_inventory = Some(false);
+        let inventory = super::inventory_argument(&plan, &groups);
+        assert_eq!(inventory, "ccu.fritz.box,gw.fritz.box,");
+        let command = super::render_ansible_command(&plan, vec![], &inventory);
+        let i = command.iter().position(|arg| arg == "-i").unwrap();
+        assert_eq!(command[i + 1], "ccu.fritz.box,gw.fritz.box,");
+        assert!(!command.iter().any(|arg| arg == "inventory.yml"));
+        assert_eq!(command.last().unwrap(), "playbook.yml");
+
+        plan.spec.template.inventory_file = Some("files/inventory/hosts.py".into());
+        assert_eq!(
+            super::inventory_argument(&plan, &groups),
+            "files/inventory/hosts.py"
+        );
+    }
+
+    #[test]
+    fn job_names_fit_the_job_name_label_even_for_long_plan_names() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        let id = crate::utils::generate_id_with_len(*hash, super::JOB_ID_LEN);
+
+        assert_eq!(
+            super::job_name("web", &hash, 1),
+            format!("apply-web-{id}-1")
+        );
+
+        let long_plan = "nightly-security-patching-for-the-edge-routers-of-every-branch-office";
+        for retry_count in [1, 12, u32::MAX] {
+            let name = super::job_name(long_plan, &hash, retry_count);
+            assert!(name.len() <= crate::utils::MAX_LABEL_VALUE_LEN, "{name}");
+            assert!(
+                name.starts_with("apply-nightly-security-patching"),
+                "{name}"
+            );
+            assert!(name.ends_with(&format!("-{id}-{retry_count}")), "{name}");
+        }
+        assert_ne!(
+            super::job_name(&format!("{long_plan}-a"), &hash, 1),
+            super::job_name(&format!("{long_plan}-b"), &hash, 1)
+        );
+    }
+
+    #[test]
+    fn jobs_of_long_plan_names_carry_only_valid_labels() {
+        use crate::v1beta1::{
+            controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash,
+            labels,
+        };
+
+        let long_plan = "a".repeat(100);
+        let mut pp = minimal_plan();
+        pp.metadata.name = Some(long_plan.clone());
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        let job = super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+            .unwrap();
+
+        let pod_labels = job
+            .spec
+            .as_ref()
+            .unwrap()
+            .template
+            .metadata
+            .as_ref()
+            .unwrap()
+            .labels
+            .as_ref();
+        for (key, value) in job.metadata.labels.iter().chain(pod_labels).flatten() {
+            assert!(crate::utils::is_label_value(value), "{key}={value}");
+        }
+        assert!(job.metadata.name.unwrap().len() <= crate::utils::MAX_LABEL_VALUE_LEN);
+        assert_eq!(
+            job.metadata.labels.unwrap()[labels::PLAYBOOKPLAN_NAME],
+            crate::utils::label_value(&long_plan)
+        );
+        assert_eq!(
+            job.metadata.annotations.unwrap()[labels::PLAYBOOKPLAN_NAME],
+            long_plan
+        );
+    }
+
+    #[test]
+    fn job_names_differ_for_hashes_that_share_a_short_id() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::ExecutionHash;
+
+        let hash = |n: u64| format!("{n:x}").parse::<ExecutionHash>().unwrap();
+        let (a, b) = (hash(0x5eed), hash(0x5eed + 27u64.pow(5)));
+        assert_eq!(crate::utils::generate_id(*a), crate::utils::generate_id(*b));
+
+        assert_ne!(super::job_name("web", &a, 1), super::job_name("web", &b, 1));
+    }
+
+    #[test]
+    fn render_ansible_command_maps_verbosity_to_v_flags() {
+        use crate::v1beta1::controllers::playbookplancontroller::job_builder::render_ansible_command;
+
+        let v_flags = |plan: &PlaybookPlan| -> Vec<String> {
+            render_ansible_command(plan, Vec::new(), "inventory.yml")
+                .into_iter()
+                .filter(|arg| arg.starts_with("-v"))
                 .collect()
         };
 
@@ -723,9 +1790,15 @@ spec:
         let pp = serde_yaml::from_str::<PlaybookPlan>(yaml).unwrap();
         let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
 
-        let attempt_1 = super::create_job_for_run(&hash, 1, &[], &pp).unwrap();
-        let attempt_2 = super::create_job_for_run(&hash, 2, &[], &pp).unwrap();
-        let attempt_1_again = super::create_job_for_run(&hash, 1, &[], &pp).unwrap();
+        let attempt_1 =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap();
+        let attempt_2 =
+            super::create_job_for_run(&hash, 2, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap();
+        let attempt_1_again =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap();
 
         let name_1 = attempt_1.name().unwrap().to_string();
         let name_2 = attempt_2.name().unwrap().to_string();
@@ -782,9 +1855,12 @@ spec:
             },
             tolerations: None,
             variables: None,
+            node_metadata: Default::default(),
         }];
 
-        let job = super::create_job_for_run(&hash, 1, &groups, &pp).unwrap();
+        let job =
+            super::create_job_for_run(&hash, 1, &groups, &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap();
         let node_affinity = job
             .spec
             .unwrap()
@@ -823,7 +1899,7 @@ spec:
 
         let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
         let ttl = |plan: &PlaybookPlan| {
-            super::create_job_for_run(&hash, 1, &[], plan)
+            super::create_job_for_run(&hash, 1, &[], plan, &plan.spec.image, &RoleFiles::new())
                 .unwrap()
                 .spec
                 .unwrap()
@@ -866,17 +1942,76 @@ spec:
                 secret_ref: SecretRef {
                     name: "ssh-key".into(),
                 },
+                private_key_key: None,
+                additional_private_key_keys: None,
             },
             variables: None,
         }];
 
-        let job = super::create_job_for_run(&hash, 1, &groups, &pp).unwrap();
+        let job =
+            super::create_job_for_run(&hash, 1, &groups, &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap();
         assert!(
             job.spec.unwrap().template.spec.unwrap().affinity.is_none(),
             "StaticInventory hosts aren't cluster nodes, so nothing constrains placement"
         );
     }
 
+    #[test]
+    fn job_node_selector_is_applied_to_the_pod_spec_for_every_inventory_kind() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+        use crate::v1beta1::{ResolvedHosts, ResolvedInventoryGroup, SecretRef, SshConfig};
+        use std::collections::BTreeMap;
+
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        let node_selector = |plan: &PlaybookPlan, groups: &[ResolvedInventoryGroup]| {
+            super::create_job_for_run(&hash, 1, groups, plan, &plan.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap()
+                .node_selector
+        };
+
+        // Unset -> no nodeSelector at all.
+        assert_eq!(node_selector(&minimal_plan(), &[]), None);
+
+        let pool = BTreeMap::from([("pool".to_string(), "ansible-runners".to_string())]);
+        let mut pp = minimal_plan();
+        pp.spec.job_node_selector = Some(pool.clone());
+
+        let ssh = ResolvedInventoryGroup::Ssh {
+            hosts: ResolvedHosts {
+                name: "external".into(),
+                hosts: vec!["ccu.fritz.box".into()],
+            },
+            static_inventory_name: "ccu".into(),
+            config: SshConfig {
+                user: "root".into(),
+                secret_ref: SecretRef {
+                    name: "ssh-key".into(),
+                },
+                private_key_key: None,
+                additional_private_key_keys: None,
+            },
+            variables: None,
+        };
+        let managed = ResolvedInventoryGroup::ManagedSsh {
+            hosts: ResolvedHosts {
+                name: "workers".into(),
+                hosts: vec!["node-a".into()],
+            },
+            tolerations: None,
+            variables: None,
+            node_metadata: Default::default(),
+        };
+
+        assert_eq!(node_selector(&pp, &[ssh]), Some(pool.clone()));
+        assert_eq!(node_selector(&pp, &[managed]), Some(pool));
+    }
+
     #[test]
     fn no_service_account_means_no_token_is_mounted() {
         use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
@@ -885,13 +2020,14 @@ spec:
         assert!(pp.spec.service_account_name.is_none());
         let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
 
-        let pod_spec = super::create_job_for_run(&hash, 1, &[], &pp)
-            .unwrap()
-            .spec
-            .unwrap()
-            .template
-            .spec
-            .unwrap();
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
 
         assert_eq!(pod_spec.service_account_name, None);
         // Fail-closed: without a ServiceAccount named, the pod carries no API token.
@@ -906,15 +2042,584 @@ spec:
         pp.spec.service_account_name = Some("playbook-sa".into());
         let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
 
-        let pod_spec = super::create_job_for_run(&hash, 1, &[], &pp)
-            .unwrap()
-            .spec
-            .unwrap()
-            .template
-            .spec
-            .unwrap();
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
 
         assert_eq!(pod_spec.service_account_name, Some("playbook-sa".into()));
         assert_eq!(pod_spec.automount_service_account_token, Some(true));
     }
+
+    #[test]
+    fn automount_service_account_token_overrides_the_service_account_default() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+
+        let mut pp = minimal_plan();
+        pp.spec.service_account_name = Some("playbook-sa".into());
+        pp.spec.automount_service_account_token = Some(false);
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        assert_eq!(pod_spec.service_account_name, Some("playbook-sa".into()));
+        assert_eq!(pod_spec.automount_service_account_token, Some(false));
+    }
+
+    #[test]
+    fn preflight_pings_every_host_in_an_init_container_before_the_playbook() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+
+        let mut pp = minimal_plan();
+        pp.spec.preflight = true;
+        pp.spec.template.requirements = Some("collections: []".into());
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        let init_containers = pod_spec.init_containers.unwrap();
+        let names: Vec<_> = init_containers.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["download-collections", super::PREFLIGHT_CONTAINER_NAME]
+        );
+
+        let preflight = &init_containers[1];
+        let main = &pod_spec.containers[0];
+        let preflight_command = preflight.command.as_deref().unwrap();
+        let main_command = main.command.as_deref().unwrap();
+        assert_eq!(preflight_command.last().unwrap(), "preflight.yml");
+        assert_eq!(
+            preflight_command[..preflight_command.len() - 1],
+            main_command[..main_command.len() - 1]
+        );
+        assert_eq!(preflight.volume_mounts, main.volume_mounts);
+        assert_eq!(preflight.env, main.env);
+    }
+
+    #[test]
+    fn a_verify_playbook_runs_after_the_playbook_but_not_in_preflight() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+
+        let mut pp = minimal_plan();
+        pp.spec.template.verify_playbook = Some("- hosts: all".into());
+        let command = super::render_ansible_command(&pp, vec![], "inventory.yml");
+        assert_eq!(command[command.len() - 2..], ["playbook.yml", "verify.yml"]);
+
+        pp.spec.preflight = true;
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        let main_command = pod_spec.containers[0].command.as_deref().unwrap();
+        assert_eq!(main_command, command);
+        let preflight_command = pod_spec.init_containers.unwrap()[0]
+            .command
+            .clone()
+            .unwrap();
+        assert_eq!(
+            preflight_command[..],
+            [
+                &main_command[..main_command.len() - 2],
+                &["preflight.yml".into()]
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn start_at_task_follows_the_playbooks_and_is_dropped_by_preflight() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+
+        let mut pp = minimal_plan();
+        pp.spec.start_at_task = Some("Restart nginx".into());
+        let command = super::render_ansible_command(&pp, vec![], "inventory.yml");
+        assert_eq!(
+            command[command.len() - 3..],
+            ["playbook.yml", "--start-at-task", "Restart nginx"]
+        );
+
+        pp.spec.preflight = true;
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+        let preflight_command = pod_spec.init_containers.unwrap()[0]
+            .command
+            .clone()
+            .unwrap();
+        assert!(!preflight_command.iter().any(|arg| arg == "--start-at-task"));
+        assert_eq!(preflight_command.last().unwrap(), "preflight.yml");
+    }
+
+    #[test]
+    fn dns_policy_and_config_are_applied_to_the_pod() {
+        use crate::v1beta1::{
+            DnsPolicy, PodDnsConfig, PodDnsConfigOption,
+            controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash,
+        };
+
+        let mut pp = minimal_plan();
+        pp.spec.dns_policy = Some(DnsPolicy::None);
+        pp.spec.dns_config = Some(PodDnsConfig {
+            nameservers: Some(vec!["10.0.0.53".into()]),
+            searches: Some(vec!["corp.example.com".into()]),
+            options: Some(vec![PodDnsConfigOption {
+                name: "ndots".into(),
+                value: Some("2".into()),
+            }]),
+        });
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        assert_eq!(pod_spec.dns_policy.as_deref(), Some("None"));
+        let dns_config = pod_spec.dns_config.unwrap();
+        assert_eq!(dns_config.nameservers, Some(vec!["10.0.0.53".into()]));
+        assert_eq!(dns_config.searches, Some(vec!["corp.example.com".into()]));
+        let option = &dns_config.options.unwrap()[0];
+        assert_eq!(option.name.as_deref(), Some("ndots"));
+        assert_eq!(option.value.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn host_aliases_are_applied_to_the_pod() {
+        use crate::v1beta1::{
+            HostAlias,
+            controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash,
+        };
+
+        let mut pp = minimal_plan();
+        pp.spec.host_aliases = Some(vec![HostAlias {
+            ip: "192.168.1.10".into(),
+            hostnames: vec!["ccu.fritz.box".into(), "ccu".into()],
+        }]);
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        let aliases = pod_spec.host_aliases.unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].ip, "192.168.1.10");
+        assert_eq!(
+            aliases[0].hostnames,
+            Some(vec!["ccu.fritz.box".into(), "ccu".into()])
+        );
+    }
+
+    #[test]
+    fn security_contexts_are_applied_to_the_pod_and_every_container() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+        use crate::v1beta1::{ResolvedHosts, ResolvedInventoryGroup, SecretRef, SshConfig};
+
+        let mut pp: PlaybookPlan = serde_yaml::from_str(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: an-example
+  namespace: default
+  uid: 11111111-1111-1111-1111-111111111111
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  podSecurityContext:
+    runAsUser: 1000
+    runAsNonRoot: true
+    fsGroup: 1000
+    seccompProfile:
+      type: RuntimeDefault
+  securityContext:
+    allowPrivilegeEscalation: false
+    readOnlyRootFilesystem: true
+    capabilities:
+      drop: [ALL]
+  template:
+    playbook: "- hosts: all"
+    requirements: "collections: []"
+"#,
+        )
+        .unwrap();
+        let ssh = ResolvedInventoryGroup::Ssh {
+            hosts: ResolvedHosts {
+                name: "external".into(),
+                hosts: vec!["ccu.fritz.box".into()],
+            },
+            static_inventory_name: "ccu".into(),
+            config: SshConfig {
+                user: "root".into(),
+                secret_ref: SecretRef {
+                    name: "ssh-key".into(),
+                },
+                private_key_key: None,
+                additional_private_key_keys: None,
+            },
+            variables: None,
+        };
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[ssh], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        let pod = pod_spec.security_context.unwrap();
+        assert_eq!(pod.run_as_user, Some(1000));
+        assert_eq!(pod.run_as_non_root, Some(true));
+        assert_eq!(pod.fs_group, Some(1000));
+        assert_eq!(pod.seccomp_profile.unwrap().type_, "RuntimeDefault");
+
+        let init_containers = pod_spec.init_containers.unwrap();
+        assert!(!init_containers.is_empty());
+        for container in init_containers.iter().chain(&pod_spec.containers) {
+            let context = container.security_context.as_ref().unwrap();
+            assert_eq!(
+                context.allow_privilege_escalation,
+                Some(false),
+                "{}",
+                container.name
+            );
+            assert_eq!(
+                context.read_only_root_filesystem,
+                Some(true),
+                "{}",
+                container.name
+            );
+            assert_eq!(
+                context.capabilities.as_ref().unwrap().drop,
+                Some(vec!["ALL".to_string()])
+            );
+        }
+
+        // Unset stays unset, leaving the namespace's and image's defaults in charge.
+        pp.spec.pod_security_context = None;
+        pp.spec.security_context = None;
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+        assert!(pod_spec.security_context.is_none());
+        assert!(pod_spec.containers[0].security_context.is_none());
+    }
+
+    #[test]
+    fn user_labels_and_annotations_coexist_with_the_reserved_labels() {
+        use crate::v1beta1::{
+            controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash,
+            labels,
+        };
+
+        let mut pp = minimal_plan();
+        pp.spec.job_template = Some(
+            serde_json::from_value(serde_json::json!({
+                "metadata": {
+                    "labels": {"cost-center": "ops", labels::PLAYBOOKPLAN_NAME: "spoofed"},
+                    "annotations": {"team": "infra"},
+                },
+                "template": {"metadata": {"labels": {"network-zone": "dmz"}}},
+            }))
+            .unwrap(),
+        );
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+
+        let job = super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+            .unwrap();
+
+        let job_labels = job.metadata.labels.unwrap();
+        assert_eq!(job_labels["cost-center"], "ops");
+        assert_eq!(
+            job_labels[labels::PLAYBOOKPLAN_NAME],
+            pp.metadata.name.unwrap()
+        );
+        assert_eq!(job_labels[labels::PLAYBOOKPLAN_HASH], hash.to_string());
+        assert_eq!(job.metadata.annotations.unwrap()["team"], "infra");
+
+        let pod_metadata = job.spec.unwrap().template.metadata.unwrap();
+        let pod_labels = pod_metadata.labels.unwrap();
+        assert_eq!(pod_labels["network-zone"], "dmz");
+        assert_eq!(pod_labels[labels::PLAYBOOKPLAN_HASH], hash.to_string());
+        assert!(!pod_labels.contains_key("cost-center"));
+        assert!(pod_metadata.annotations.is_none());
+    }
+
+    #[test]
+    fn requirements_install_skips_or_forces_the_collections_init_container() {
+        use crate::v1beta1::{
+            RequirementsInstall,
+            controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash,
+        };
+
+        let mut pp = minimal_plan();
+        pp.spec.template.requirements = Some("collections: []".into());
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        let pod_spec = |pp: &PlaybookPlan| {
+            super::create_job_for_run(&hash, 1, &[], pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap()
+        };
+
+        pp.spec.template.requirements_install = RequirementsInstall::Skip;
+        let skipped = pod_spec(&pp);
+        assert!(skipped.init_containers.is_none_or(|c| c.is_empty()));
+        assert!(
+            !skipped
+                .volumes
+                .unwrap()
+                .iter()
+                .any(|v| v.name == super::COLLECTIONS_VOLUME_NAME)
+        );
+
+        pp.spec.template.requirements_install = RequirementsInstall::Force;
+        let forced = pod_spec(&pp);
+        let installer = &forced.init_containers.unwrap()[0];
+        assert_eq!(
+            installer
+                .command
+                .as_deref()
+                .unwrap()
+                .last()
+                .map(String::as_str),
+            Some("--force")
+        );
+    }
+
+    #[test]
+    fn a_collections_image_is_mounted_instead_of_installing_requirements() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+
+        let mut pp = minimal_plan();
+        pp.spec.template.requirements = Some("collections: []".into());
+        pp.spec.template.collections_image = Some("registry.example.com/collections:1".into());
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        assert!(pod_spec.init_containers.is_none_or(|c| c.is_empty()));
+        let volume = pod_spec
+            .volumes
+            .iter()
+            .flatten()
+            .find(|v| v.name == super::COLLECTIONS_VOLUME_NAME)
+            .unwrap();
+        assert_eq!(
+            volume.image.as_ref().unwrap().reference.as_deref(),
+            Some("registry.example.com/collections:1")
+        );
+        let mount = pod_spec.containers[0]
+            .volume_mounts
+            .iter()
+            .flatten()
+            .find(|m| m.name == super::COLLECTIONS_VOLUME_NAME)
+            .unwrap();
+        assert_eq!(mount.mount_path, super::COLLECTIONS_MOUNT_PATH);
+        assert_eq!(mount.read_only, Some(true));
+    }
+
+    #[test]
+    fn a_galaxy_server_and_its_token_reach_only_the_collections_init_container() {
+        use crate::v1beta1::{
+            GalaxyConfig, SecretRef,
+            controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash,
+        };
+
+        let mut pp = minimal_plan();
+        pp.spec.template.requirements = Some("collections: []".into());
+        pp.spec.template.galaxy = Some(GalaxyConfig {
+            server: "https://galaxy.example.com/api/".into(),
+            token_secret_ref: Some(SecretRef {
+                name: "galaxy-mirror".into(),
+            }),
+            token_key: None,
+        });
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        let installer = &pod_spec.init_containers.as_ref().unwrap()[0];
+        let command = installer.command.as_deref().unwrap();
+        assert!(
+            command.ends_with(&["--server".into(), "https://galaxy.example.com/api/".into()]),
+            "{command:?}"
+        );
+        let env = installer.env.as_deref().unwrap();
+        assert_eq!(env[0].name, "ANSIBLE_GALAXY_TOKEN_PATH");
+        assert_eq!(
+            env[0].value.as_deref(),
+            Some("/run/ansible-operator/galaxy/galaxy_token")
+        );
+        let mount = installer
+            .volume_mounts
+            .iter()
+            .flatten()
+            .find(|m| m.name == super::GALAXY_TOKEN_VOLUME_NAME)
+            .unwrap();
+        assert_eq!(mount.read_only, Some(true));
+
+        let volume = pod_spec
+            .volumes
+            .iter()
+            .flatten()
+            .find(|v| v.name == super::GALAXY_TOKEN_VOLUME_NAME)
+            .unwrap();
+        let secret = volume.secret.as_ref().unwrap();
+        assert_eq!(secret.secret_name.as_deref(), Some("galaxy-mirror"));
+        assert_eq!(secret.default_mode, Some(0o400));
+
+        let ansible = &pod_spec.containers[0];
+        assert!(
+            !ansible
+                .env
+                .iter()
+                .flatten()
+                .any(|e| e.name == "ANSIBLE_GALAXY_TOKEN_PATH")
+        );
+        assert!(
+            !ansible
+                .volume_mounts
+                .iter()
+                .flatten()
+                .any(|m| m.name == super::GALAXY_TOKEN_VOLUME_NAME)
+        );
+    }
+
+    #[test]
+    fn extra_volumes_are_mounted_at_their_own_paths_in_every_container() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+
+        let mut pp = minimal_plan();
+        pp.spec.template.requirements = Some("collections: []".into());
+        pp.spec.extra_volumes = Some(vec![crate::v1beta1::GenericMap(serde_json::json!({
+            "name": "ca-bundle",
+            "configMap": {"name": "corporate-ca"},
+        }))]);
+        pp.spec.extra_volume_mounts = Some(vec![crate::v1beta1::GenericMap(serde_json::json!({
+            "name": "ca-bundle",
+            "mountPath": "/etc/ssl/certs",
+            "readOnly": true,
+        }))]);
+        let hash = calculate_execution_hash("- hosts: all", std::iter::empty());
+
+        let pod_spec =
+            super::create_job_for_run(&hash, 1, &[], &pp, &pp.spec.image, &RoleFiles::new())
+                .unwrap()
+                .spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap();
+
+        let volume = pod_spec
+            .volumes
+            .unwrap()
+            .into_iter()
+            .find(|v| v.name == "ca-bundle")
+            .unwrap();
+        assert_eq!(volume.config_map.unwrap().name, "corporate-ca");
+
+        let containers = pod_spec.init_containers.unwrap();
+        for container in containers.iter().chain(&pod_spec.containers) {
+            let mount = container
+                .volume_mounts
+                .iter()
+                .flatten()
+                .find(|m| m.name == "ca-bundle")
+                .unwrap_or_else(|| panic!("{} lacks the mount", container.name));
+            assert_eq!(mount.mount_path, "/etc/ssl/certs");
+            assert_eq!(mount.read_only, Some(true));
+        }
+    }
+
+    #[test]
+    fn reserved_volume_names_and_mount_paths() {
+        let pp = minimal_plan();
+
+        for name in [
+            "playbook",
+            "collections",
+            "group-vars",
+            "managed-ssh-client",
+            "ssh-edge",
+        ] {
+            assert!(super::volume_name_reserved(&pp, name), "{name}");
+        }
+        assert!(!super::volume_name_reserved(&pp, "ca-bundle"));
+
+        for path in [
+            "/run/ansible-operator",
+            "/run/ansible-operator/ssh/",
+            "/etc/ansible/collections/x",
+        ] {
+            assert!(super::mount_path_reserved(path), "{path}");
+        }
+        for path in ["/etc/ssl/certs", "/run", "/run/ansible-operator-extra"] {
+            assert!(!super::mount_path_reserved(path), "{path}");
+        }
+    }
 }
@@ -9,7 +9,7 @@ use k8s_openapi::{
 use kube::{Api, api::PostParams};
 use tracing::{debug, warn};
 
-use crate::v1beta1::controllers::reconcile_error::ReconcileError;
+use crate::{utils, v1beta1::controllers::reconcile_error::ReconcileError};
 
 /// How long a Lease is considered valid without being renewed. Deliberately short and renewed
 /// every reconcile tick (not sized to a run's total length) so a crashed operator only leaves a
@@ -122,8 +122,10 @@ fn build_lease(name: &str, holder_identity: &str, now: DateTime<Utc>) -> Lease {
     }
 }
 
-fn is_conflict(err: &kube::Error) -> bool {
-    matches!(err, kube::Error::Api(status) if status.code == 409)
+/// Whether a write of a Lease lost the race against another writer: a replace whose
+/// resourceVersion went stale, or a create beaten to the name.
+fn lost_write_race(err: &kube::Error) -> bool {
+    utils::is_conflict(err) || utils::is_already_exists(err)
 }
 
 /// A deterministic global order for acquiring per-host Leases, keyed by the (hashed) lease name so
@@ -193,7 +195,7 @@ pub async fn ensure_locks(
             // Someone else raced us between our read and write — treat as blocked for this tick and
             // stop; we'll re-evaluate from scratch next time rather than treating it as a hard
             // failure. We didn't see the winning holder, so we can't name it.
-            Err(err) if is_conflict(&err) => {
+            Err(err) if lost_write_race(&err) => {
                 debug!("Lease conflict for host {host}, will retry next tick");
                 blocked = Some(BlockedBy {
                     host: host.clone(),
@@ -293,7 +295,7 @@ pub async fn renew_locks(
                     Ok(()) => {}
                     // Raced with another writer; the next tick re-reads and renews again, still
                     // well within the lease duration.
-                    Err(err) if is_conflict(&err) => {
+                    Err(err) if lost_write_race(&err) => {
                         debug!("Lease renewal for host {host} conflicted, will retry next tick");
                     }
                     Err(err) => return Err(err.into()),
@@ -332,7 +334,7 @@ pub async fn release_locks(
 
         match api.delete(&name, &Default::default()).await {
             Ok(_) => {}
-            Err(err) if is_conflict(&err) => {
+            Err(err) if utils::is_conflict(&err) => {
                 // Someone else already reclaimed/deleted it — nothing left for us to release.
             }
             Err(err) => return Err(err.into()),
@@ -22,7 +22,9 @@ use kube::{
     api::{DeleteParams, ListParams, PostParams},
 };
 
-use super::paths;
+use tracing::debug;
+
+use super::{job_builder::JOB_ID_LEN, paths};
 use crate::{
     utils,
     v1beta1::{
@@ -175,6 +177,30 @@ fn node_ready_heartbeat_age_secs(node: &Node, now_epoch_secs: i64) -> Option<i64
     Some(now_epoch_secs - last.0.as_second())
 }
 
+/// Whether `node` is cordoned. A proxy pod doesn't tolerate the resulting
+/// `node.kubernetes.io/unschedulable` taint, so on such a node it stays `Pending` until its grace
+/// window runs out.
+fn node_unschedulable(node: &Node) -> bool {
+    node.spec
+        .as_ref()
+        .and_then(|spec| spec.unschedulable)
+        .unwrap_or(false)
+}
+
+/// Those of `hosts` (managed-ssh node names) whose Node in `nodes` (this reconcile's live list) is
+/// cordoned. A Node that no longer exists isn't reported; the proxy readiness gate deals with it.
+pub fn unschedulable_nodes(nodes: &[Node], hosts: &[String]) -> Vec<String> {
+    hosts
+        .iter()
+        .filter(|host| {
+            nodes
+                .iter()
+                .any(|node| node.metadata.name.as_ref() == Some(*host) && node_unschedulable(node))
+        })
+        .cloned()
+        .collect()
+}
+
 /// The effective grace for a pre-`Running` pod: `grace_seconds / aggressiveness^k` for the first tier
 /// `k` whose boundary the heartbeat age falls within, `0` past the last boundary. An unknown age ⇒
 /// full grace (never shorten on missing data). A healthy node's heartbeat is always recent, so it
@@ -220,15 +246,16 @@ fn merge_default_tolerations(
     merged
 }
 
-/// Deterministic, human-readable resource name for a (host, run) pair. The host is used verbatim
-/// (not hashed) since managed-ssh only targets `ClusterInventory` hosts, i.e. real Node names,
-/// which are already valid Kubernetes object name components. The run uses `utils::generate_id`'s
-/// short-id, matching `job_builder::create_job_for_run`'s Job naming.
+/// Deterministic, human-readable resource name for a (host, run) pair. Node names already are valid
+/// name components, but one near the 253-character limit isn't once prefixed, so the host goes
+/// through `utils::sanitize_name` — a no-op for every usual Node name. The run is identified by the
+/// same `JOB_ID_LEN`-digit ID as its Job: with a shorter one, two runs whose hashes share it would
+/// find each other's proxy on a host and use it, though it only admits the other run's certificate.
 fn resource_name(host: &str, execution_hash: &ExecutionHash) -> String {
-    format!(
-        "ansible-sshd-{host}-{}",
-        utils::generate_id(**execution_hash)
-    )
+    const PREFIX: &str = "ansible-sshd-";
+    let id = utils::generate_id_with_len(**execution_hash, JOB_ID_LEN);
+    let host = utils::sanitize_name(host, utils::MAX_NAME_LEN - PREFIX.len() - id.len() - 1);
+    format!("{PREFIX}{host}-{id}")
 }
 
 /// Name of this run's client-cert Secret, shared by `job_builder`'s mount and `ensure_client_cert`.
@@ -236,16 +263,29 @@ pub fn client_cert_secret_name(execution_hash: &ExecutionHash) -> String {
     format!("managed-ssh-client-{execution_hash}")
 }
 
+/// The `PLAYBOOKPLAN_HOST` label holds the host verbatim where it's a valid label value — any Node
+/// name of up to 63 characters — and its `utils::sanitize_name` form otherwise, with the verbatim
+/// host kept in the annotation of the same key (see `run_annotations`). Only the label's presence is
+/// ever selected on, never its value.
 fn run_labels(execution_hash: &ExecutionHash, host: &str) -> BTreeMap<String, String> {
     BTreeMap::from([
         (
             labels::PLAYBOOKPLAN_HASH.to_string(),
             execution_hash.to_string(),
         ),
-        (labels::PLAYBOOKPLAN_HOST.to_string(), host.to_string()),
+        (
+            labels::PLAYBOOKPLAN_HOST.to_string(),
+            utils::label_value(host),
+        ),
     ])
 }
 
+/// The verbatim host, for a host `run_labels` couldn't put verbatim into its label.
+fn run_annotations(host: &str) -> Option<BTreeMap<String, String>> {
+    (!utils::is_label_value(host))
+        .then(|| BTreeMap::from([(labels::PLAYBOOKPLAN_HOST.to_string(), host.to_string())]))
+}
+
 /// `ForceCommand` routes every session through `enter-host.sh` rather than `ChrootDirectory` —
 /// nsenter-ing the host's mount namespace already makes `/` the host's real root, so no chroot
 /// step is needed. `UsePAM` is omitted: some minimal sshd builds reject it outright (no PAM
@@ -353,6 +393,7 @@ fn build_secret(
         metadata: ObjectMeta {
             name: Some(name.to_string()),
             labels: Some(run_labels(execution_hash, host)),
+            annotations: run_annotations(host),
             ..Default::default()
         },
         string_data: Some(string_data),
@@ -444,6 +485,7 @@ fn build_pod(
         metadata: ObjectMeta {
             name: Some(name.to_string()),
             labels: Some(run_labels(execution_hash, host)),
+            annotations: run_annotations(host),
             ..Default::default()
         },
         spec: Some(PodSpec {
@@ -598,7 +640,12 @@ async fn ensure_client_cert(
         ..Default::default()
     };
 
-    secrets_api.create(&PostParams::default(), &secret).await?;
+    match secrets_api.create(&PostParams::default(), &secret).await {
+        Err(err) if utils::is_already_exists(&err) => debug!("Client cert Secret already exists"),
+        result => {
+            result?;
+        }
+    }
 
     Ok(())
 }
@@ -638,7 +685,14 @@ pub async fn ensure_proxy_infra(
         });
         if netpol_api.get_opt(&netpol_name).await?.is_none() {
             let netpol = build_network_policy(&netpol_name, execution_hash, job_namespace);
-            netpol_api.create(&PostParams::default(), &netpol).await?;
+            match netpol_api.create(&PostParams::default(), &netpol).await {
+                Err(err) if utils::is_already_exists(&err) => {
+                    debug!("NetworkPolicy {netpol_name} already exists")
+                }
+                result => {
+                    result?;
+                }
+            }
         }
 
         ensure_client_cert(&job_secrets_api, execution_hash, ca, plan_owner).await?;
@@ -655,7 +709,14 @@ pub async fn ensure_proxy_infra(
 
         if secrets_api.get_opt(&name).await?.is_none() {
             let secret = build_secret(&name, execution_hash, host, ca)?;
-            secrets_api.create(&PostParams::default(), &secret).await?;
+            match secrets_api.create(&PostParams::default(), &secret).await {
+                Err(err) if utils::is_already_exists(&err) => {
+                    debug!("Proxy Secret {name} already exists")
+                }
+                result => {
+                    result?;
+                }
+            }
         }
 
         // Create the pod for EVERY host, including a NotReady one — we want to attempt scheduling it.
@@ -663,7 +724,13 @@ pub async fn ensure_proxy_infra(
             Some(pod) => pod,
             None => {
                 let pod = build_pod(&name, &name, execution_hash, host, tolerations, proxy_image);
-                pods_api.create(&PostParams::default(), &pod).await?
+                match pods_api.create(&PostParams::default(), &pod).await {
+                    Err(err) if utils::is_already_exists(&err) => {
+                        debug!("Proxy pod {name} already exists, reading it instead");
+                        pods_api.get(&name).await?
+                    }
+                    result => result?,
+                }
             }
         };
 
@@ -759,6 +826,41 @@ pub async fn cleanup_proxy_infra(
 mod tests {
     use super::*;
 
+    #[test]
+    fn nasty_host_names_still_make_valid_proxy_names_and_labels() {
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+
+        let hash = calculate_execution_hash("playbook", std::iter::empty());
+        let long_node = format!("{}.compute.internal", "ip-10-0-0-1".repeat(22));
+        assert!(long_node.len() > 240);
+
+        let name = resource_name(&long_node, &hash);
+        assert!(name.len() <= utils::MAX_NAME_LEN, "{name}");
+        assert!(name.starts_with("ansible-sshd-ip-10-0-0-1"), "{name}");
+
+        let labels = run_labels(&hash, &long_node);
+        let host_label = &labels[labels::PLAYBOOKPLAN_HOST];
+        assert!(utils::is_label_value(host_label), "{host_label}");
+        assert_eq!(
+            run_annotations(&long_node).unwrap()[labels::PLAYBOOKPLAN_HOST],
+            long_node
+        );
+
+        // A usual Node name is used verbatim everywhere, with no annotation needed.
+        assert_eq!(
+            resource_name("worker-1.cluster.local", &hash),
+            format!(
+                "ansible-sshd-worker-1.cluster.local-{}",
+                utils::generate_id_with_len(*hash, JOB_ID_LEN)
+            )
+        );
+        assert_eq!(
+            run_labels(&hash, "worker-1.cluster.local")[labels::PLAYBOOKPLAN_HOST],
+            "worker-1.cluster.local"
+        );
+        assert!(run_annotations("worker-1.cluster.local").is_none());
+    }
+
     #[test]
     fn resource_name_is_deterministic_per_host_and_run() {
         use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
@@ -776,10 +878,63 @@ mod tests {
         assert_ne!(a1, other_host, "different host, same run must differ");
         assert_eq!(
             a1,
-            format!("ansible-sshd-worker-1-{}", utils::generate_id(*hash_a))
+            format!(
+                "ansible-sshd-worker-1-{}",
+                utils::generate_id_with_len(*hash_a, JOB_ID_LEN)
+            )
         );
     }
 
+    #[test]
+    fn resource_names_differ_for_hashes_that_share_a_short_id() {
+        let hash = |n: u64| format!("{n:x}").parse::<ExecutionHash>().unwrap();
+        let (a, b) = (hash(0x5eed), hash(0x5eed + 27u64.pow(5)));
+        assert_eq!(utils::generate_id(*a), utils::generate_id(*b));
+
+        assert_ne!(resource_name("worker-1", &a), resource_name("worker-1", &b));
+    }
+
+    #[tokio::test]
+    async fn ensure_client_cert_accepts_a_secret_created_concurrently() {
+        use crate::v1beta1::ca::CertificateAuthority;
+        use crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash;
+        use kube::client::Body;
+
+        let (service, mut handle) =
+            tower_test::mock::pair::<http::Request<Body>, http::Response<Body>>();
+        let api = Api::<Secret>::namespaced(kube::Client::new(service, "default"), "default");
+        let status = |code: u16, reason: &str| {
+            serde_json::json!({
+                "kind": "Status", "apiVersion": "v1", "status": "Failure",
+                "reason": reason, "message": reason, "code": code,
+            })
+        };
+        let responses = [
+            (http::Method::GET, 404, status(404, "NotFound")),
+            (http::Method::POST, 409, status(409, "AlreadyExists")),
+        ];
+        let server = tokio::spawn(async move {
+            for (method, code, response) in responses {
+                let (request, send) = handle.next_request().await.expect("a request");
+                assert_eq!(request.method(), method);
+                send.send_response(
+                    http::Response::builder()
+                        .status(code)
+                        .body(Body::from(serde_json::to_vec(&response).unwrap()))
+                        .unwrap(),
+                );
+            }
+        });
+
+        let ca = CertificateAuthority::generate().unwrap();
+        let hash = calculate_execution_hash("playbook", std::iter::empty());
+        ensure_client_cert(&api, &hash, &ca, &OwnerReference::default())
+            .await
+            .unwrap();
+
+        server.await.unwrap();
+    }
+
     #[test]
     fn build_secret_writes_the_run_hash_as_the_sole_authorized_principal() {
         use crate::v1beta1::ca::CertificateAuthority;
@@ -1017,6 +1172,27 @@ mod tests {
         assert_eq!(node_ready_heartbeat_age_secs(&Node::default(), 1_300), None);
     }
 
+    #[test]
+    fn unschedulable_nodes_reports_only_cordoned_nodes_that_exist() {
+        let nodes = ["healthy", "cordoned"].map(|name| Node {
+            metadata: ObjectMeta {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+            spec: Some(k8s_openapi::api::core::v1::NodeSpec {
+                unschedulable: Some(name == "cordoned"),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let hosts = ["healthy", "cordoned", "gone"].map(String::from);
+        let unschedulable = unschedulable_nodes(&nodes, &hosts);
+
+        assert_eq!(unschedulable, vec!["cordoned".to_string()]);
+        assert!(!node_unschedulable(&Node::default()));
+    }
+
     fn policy(aggressiveness: u32) -> ProxyGracePolicy {
         ProxyGracePolicy::new(600, aggressiveness, [3, 7, 30])
     }
@@ -1,10 +1,15 @@
 use std::sync::Arc;
 
-use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::{
+    api::core::v1::{ConfigMap, Secret},
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+};
 use kube::runtime::reflector::{ObjectRef, Store};
 use tracing::debug;
 
-use crate::v1beta1::{self, NodeAccessPolicy};
+use crate::v1beta1::{self, ClusterInventory, InventoryRef, NodeAccessPolicy, StaticInventory};
+
+use super::job_builder;
 
 /// Returns a closure that maps a `NodeAccessPolicy` change to *every* PlaybookPlan, so their
 /// managed-ssh node clamping is re-evaluated promptly when an admin edits a policy. A policy's
@@ -29,13 +34,16 @@ pub fn node_access_policy_to_playbookplans(
     }
 }
 
-/// Returns a closure that maps a Secret to all PlaybookPlans that reference it.
+/// Returns a closure that maps a Secret to all PlaybookPlans that reference it — directly (variables
+/// and files), or as the SSH key Secret of a `StaticInventory` they target, so a plan waiting on a
+/// missing key Secret starts as soon as it's created.
 ///
 /// # Panics
 ///
 /// Panics if the secret returned from the apiserver does not have a name.
 pub fn secret_to_playbookplans(
     secret_reflector_reader: Arc<kube::runtime::reflector::Store<v1beta1::PlaybookPlan>>,
+    static_inventory_reader: Arc<Store<v1beta1::StaticInventory>>,
 ) -> impl Fn(Secret) -> Vec<ObjectRef<v1beta1::PlaybookPlan>> {
     move |secret| {
         let secret_name = secret
@@ -44,34 +52,14 @@ pub fn secret_to_playbookplans(
             .as_deref()
             .expect("Secret must have a name");
 
+        let static_inventories = static_inventory_reader.state();
+
         secret_reflector_reader
             .state()
             .iter()
             .filter(|resource| resource.metadata.namespace == secret.metadata.namespace)
             .filter(|plan| {
-                if let Some(vars) = &plan.spec.template.variables
-                    && vars.iter().any(|var| {
-                        matches!(
-                            var,
-                            v1beta1::PlaybookVariableSource::SecretRef { secret_ref }
-                            if secret_ref.name == secret_name
-                        )
-                    })
-                {
-                    return true;
-                }
-
-                if let Some(files) = &plan.spec.template.files {
-                    return files.iter().any(|file| {
-                        matches!(
-                            file,
-                            v1beta1::FilesSource::Secret { name: _, secret_ref }
-                            if secret_ref.name == secret_name
-                        )
-                    });
-                }
-
-                false
+                plan_references_secret(plan, secret_name, static_inventories.iter().map(|si| &**si))
             })
             .map(|plan| ObjectRef::from(&**plan))
             .inspect(|obj_ref| {
@@ -83,3 +71,360 @@ pub fn secret_to_playbookplans(
             .collect::<Vec<_>>()
     }
 }
+
+/// Whether `plan` reads the Secret `secret_name` from its own namespace. `static_inventories` may
+/// span every namespace; only those in the plan's namespace that it references count.
+fn plan_references_secret<'a>(
+    plan: &v1beta1::PlaybookPlan,
+    secret_name: &str,
+    mut static_inventories: impl Iterator<Item = &'a v1beta1::StaticInventory>,
+) -> bool {
+    if let Some(vars) = &plan.spec.template.variables
+        && vars.iter().any(|var| {
+            matches!(
+                var,
+                v1beta1::PlaybookVariableSource::SecretRef { secret_ref, .. }
+                if secret_ref.name == secret_name
+            )
+        })
+    {
+        return true;
+    }
+
+    if let Some(source) = &plan.spec.template.playbook_from
+        && source
+            .secret_ref
+            .as_ref()
+            .is_some_and(|secret_ref| secret_ref.name == secret_name)
+    {
+        return true;
+    }
+
+    if job_builder::extract_secret_names_for_group_variables(plan).any(|name| name == secret_name) {
+        return true;
+    }
+
+    if job_builder::extract_secret_names_for_roles(plan).any(|name| name == secret_name) {
+        return true;
+    }
+
+    if let Some(files) = &plan.spec.template.files
+        && files.iter().any(|file| {
+            matches!(
+                file,
+                v1beta1::FilesSource::Secret { name: _, secret_ref }
+                if secret_ref.name == secret_name
+            )
+        })
+    {
+        return true;
+    }
+
+    static_inventories.any(|si| {
+        si.metadata.namespace == plan.metadata.namespace
+            && si.spec.ssh.secret_ref.name == secret_name
+            && plan.spec.inventory_refs.iter().any(|inventory_ref| {
+                inventory_ref.static_inventory.as_deref() == si.metadata.name.as_deref()
+            })
+    })
+}
+
+/// Returns a closure that maps a ConfigMap to the PlaybookPlans reading their playbook
+/// (`template.playbookFrom`), image (`imageFrom`), variables (a `configMapRef` in
+/// `template.variables`/`groupVariables`) or a role (`template.roles`) from it, so editing the
+/// playbook, the variables or a role re-applies the plan and a new image is picked up.
+pub fn config_map_to_playbookplans(
+    playbookplan_reader: Arc<Store<v1beta1::PlaybookPlan>>,
+) -> impl Fn(ConfigMap) -> Vec<ObjectRef<v1beta1::PlaybookPlan>> {
+    move |config_map| {
+        let Some(config_map_name) = config_map.metadata.name.as_deref() else {
+            return Vec::new();
+        };
+
+        playbookplan_reader
+            .state()
+            .iter()
+            .filter(|plan| plan.metadata.namespace == config_map.metadata.namespace)
+            .filter(|plan| plan_reads_config_map(plan, config_map_name))
+            .map(|plan| ObjectRef::from(&**plan))
+            .inspect(|obj_ref| {
+                debug!("Reconcile of {obj_ref} triggered by ConfigMap {config_map_name}")
+            })
+            .collect::<Vec<_>>()
+    }
+}
+
+fn plan_reads_config_map(plan: &v1beta1::PlaybookPlan, config_map_name: &str) -> bool {
+    let playbook_from = plan
+        .spec
+        .template
+        .playbook_from
+        .as_ref()
+        .and_then(|source| source.config_map_ref.as_ref())
+        .is_some_and(|config_map_ref| config_map_ref.name == config_map_name);
+
+    let image_from = plan
+        .spec
+        .image_from
+        .as_ref()
+        .is_some_and(|source| source.config_map_ref.name == config_map_name);
+
+    let variables = job_builder::extract_config_map_names_for_variables(plan)
+        .any(|name| name == config_map_name);
+
+    let roles =
+        job_builder::extract_config_map_names_for_roles(plan).any(|name| name == config_map_name);
+
+    playbook_from || image_from || variables || roles
+}
+
+/// Returns a closure that maps a `ClusterInventory` to the PlaybookPlans targeting it, so a change
+/// of its resolved Nodes (its status) or group variables reaches them right away rather than at
+/// their next periodic requeue.
+pub fn cluster_inventory_to_playbookplans(
+    playbookplan_reader: Arc<Store<v1beta1::PlaybookPlan>>,
+) -> impl Fn(ClusterInventory) -> Vec<ObjectRef<v1beta1::PlaybookPlan>> {
+    move |inventory| {
+        plans_targeting(&playbookplan_reader, &inventory.metadata, |inventory_ref| {
+            inventory_ref.cluster_inventory.as_deref()
+        })
+    }
+}
+
+/// Returns a closure that maps a `StaticInventory` to the PlaybookPlans targeting it, so added or
+/// removed hosts and changed SSH settings reach them right away.
+pub fn static_inventory_to_playbookplans(
+    playbookplan_reader: Arc<Store<v1beta1::PlaybookPlan>>,
+) -> impl Fn(StaticInventory) -> Vec<ObjectRef<v1beta1::PlaybookPlan>> {
+    move |inventory| {
+        plans_targeting(&playbookplan_reader, &inventory.metadata, |inventory_ref| {
+            inventory_ref.static_inventory.as_deref()
+        })
+    }
+}
+
+/// The plans in the inventory's namespace with an `inventoryRefs` entry naming it, as read by
+/// `referenced` for the inventory's kind.
+fn plans_targeting(
+    playbookplan_reader: &Store<v1beta1::PlaybookPlan>,
+    inventory: &ObjectMeta,
+    referenced: impl Fn(&InventoryRef) -> Option<&str>,
+) -> Vec<ObjectRef<v1beta1::PlaybookPlan>> {
+    let Some(inventory_name) = inventory.name.as_deref() else {
+        return Vec::new();
+    };
+
+    playbookplan_reader
+        .state()
+        .iter()
+        .filter(|plan| plan.metadata.namespace == inventory.namespace)
+        .filter(|plan| {
+            plan.spec
+                .inventory_refs
+                .iter()
+                .any(|inventory_ref| referenced(inventory_ref) == Some(inventory_name))
+        })
+        .map(|plan| ObjectRef::from(&**plan))
+        .inspect(|obj_ref| debug!("Reconcile of {obj_ref} triggered by inventory {inventory_name}"))
+        .collect::<Vec<_>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1beta1::{InventoryRef, SecretRef, StaticInventorySpec};
+
+    fn static_inventory(namespace: &str, name: &str, secret: &str) -> v1beta1::StaticInventory {
+        let mut si = v1beta1::StaticInventory::new(
+            name,
+            StaticInventorySpec {
+                hosts: vec![],
+                ssh: v1beta1::SshConfig {
+                    user: "root".into(),
+                    secret_ref: SecretRef {
+                        name: secret.into(),
+                    },
+                    ..Default::default()
+                },
+            },
+        );
+        si.metadata.namespace = Some(namespace.into());
+        si
+    }
+
+    fn plan_targeting(static_inventory: &str) -> v1beta1::PlaybookPlan {
+        let mut plan = v1beta1::PlaybookPlan::new("plan", Default::default());
+        plan.metadata.namespace = Some("team-a".into());
+        plan.spec.inventory_refs = vec![InventoryRef {
+            cluster_inventory: None,
+            static_inventory: Some(static_inventory.into()),
+            ..Default::default()
+        }];
+        plan
+    }
+
+    #[test]
+    fn playbook_source_maps_to_the_plan() {
+        let mut plan = plan_targeting("edge");
+        plan.spec.template.playbook_from = Some(v1beta1::PlaybookSource {
+            config_map_ref: Some(v1beta1::ConfigMapRef {
+                name: "playbooks".into(),
+            }),
+            secret_ref: None,
+            key: "site.yml".into(),
+        });
+
+        assert!(plan_reads_config_map(&plan, "playbooks"));
+        assert!(!plan_reads_config_map(&plan, "other"));
+        assert!(!plan_references_secret(
+            &plan,
+            "playbooks",
+            std::iter::empty()
+        ));
+
+        let source = plan.spec.template.playbook_from.as_mut().unwrap();
+        source.config_map_ref = None;
+        source.secret_ref = Some(SecretRef {
+            name: "playbooks".into(),
+        });
+
+        assert!(!plan_reads_config_map(&plan, "playbooks"));
+        assert!(plan_references_secret(
+            &plan,
+            "playbooks",
+            std::iter::empty()
+        ));
+    }
+
+    #[test]
+    fn image_source_maps_to_the_plan() {
+        let mut plan = plan_targeting("edge");
+        plan.spec.image_from = Some(v1beta1::ImageSource {
+            config_map_ref: v1beta1::ConfigMapRef {
+                name: "ansible-runner".into(),
+            },
+            key: "image".into(),
+        });
+
+        assert!(plan_reads_config_map(&plan, "ansible-runner"));
+        assert!(!plan_reads_config_map(&plan, "playbooks"));
+    }
+
+    #[test]
+    fn variables_config_map_maps_to_the_plan() {
+        let config_map_ref = |name: &str| v1beta1::PlaybookVariableSource::ConfigMapRef {
+            config_map_ref: v1beta1::ConfigMapRef { name: name.into() },
+            key: None,
+        };
+        let mut plan = plan_targeting("edge");
+        plan.spec.template.variables = Some(vec![config_map_ref("defaults")]);
+        plan.spec.template.group_variables = Some(vec![v1beta1::GroupVariables {
+            group: "routers".into(),
+            variables: vec![config_map_ref("router-defaults")],
+        }]);
+
+        assert!(plan_reads_config_map(&plan, "defaults"));
+        assert!(plan_reads_config_map(&plan, "router-defaults"));
+        assert!(!plan_reads_config_map(&plan, "other"));
+        assert!(!plan_references_secret(
+            &plan,
+            "defaults",
+            std::iter::empty()
+        ));
+    }
+
+    #[test]
+    fn role_sources_map_to_the_plan() {
+        let mut plan = plan_targeting("edge");
+        plan.spec.template.roles = Some(vec![
+            v1beta1::RoleSource {
+                name: "hardening".into(),
+                secret_ref: Some(SecretRef {
+                    name: "hardening-role".into(),
+                }),
+                config_map_ref: None,
+            },
+            v1beta1::RoleSource {
+                name: "motd".into(),
+                secret_ref: None,
+                config_map_ref: Some(v1beta1::ConfigMapRef {
+                    name: "motd-role".into(),
+                }),
+            },
+        ]);
+
+        assert!(plan_references_secret(
+            &plan,
+            "hardening-role",
+            std::iter::empty()
+        ));
+        assert!(plan_reads_config_map(&plan, "motd-role"));
+        assert!(!plan_reads_config_map(&plan, "hardening-role"));
+        assert!(!plan_references_secret(
+            &plan,
+            "motd-role",
+            std::iter::empty()
+        ));
+    }
+
+    #[test]
+    fn an_inventory_maps_to_the_plans_targeting_it_by_kind_and_namespace() {
+        let mut writer = kube::runtime::reflector::store::Writer::default();
+        let mut other_namespace = plan_targeting("edge");
+        other_namespace.metadata.namespace = Some("team-b".into());
+        let mut cluster_nodes = v1beta1::PlaybookPlan::new("nodes", Default::default());
+        cluster_nodes.metadata.namespace = Some("team-a".into());
+        cluster_nodes.spec.inventory_refs = vec![InventoryRef {
+            cluster_inventory: Some("edge".into()),
+            ..Default::default()
+        }];
+        for plan in [plan_targeting("edge"), other_namespace, cluster_nodes] {
+            writer.apply_watcher_event(&kube::runtime::watcher::Event::Apply(plan));
+        }
+        let reader = Arc::new(writer.as_reader());
+
+        let mut edge = static_inventory("team-a", "edge", "edge-key");
+        let static_mapped = static_inventory_to_playbookplans(Arc::clone(&reader))(edge.clone());
+        edge.metadata.name = Some("other".into());
+        let other_mapped = static_inventory_to_playbookplans(Arc::clone(&reader))(edge);
+        let mut cluster_inventory = ClusterInventory::new("edge", Default::default());
+        cluster_inventory.metadata.namespace = Some("team-a".into());
+        let cluster_mapped = cluster_inventory_to_playbookplans(reader)(cluster_inventory);
+
+        let names = |refs: Vec<ObjectRef<v1beta1::PlaybookPlan>>| {
+            refs.into_iter()
+                .map(|obj_ref| format!("{}/{}", obj_ref.namespace.unwrap(), obj_ref.name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(static_mapped), vec!["team-a/plan"]);
+        assert!(other_mapped.is_empty());
+        assert_eq!(names(cluster_mapped), vec!["team-a/nodes"]);
+    }
+
+    #[test]
+    fn ssh_key_secret_of_a_targeted_static_inventory_maps_to_the_plan() {
+        let inventories = [
+            static_inventory("team-a", "edge", "edge-key"),
+            static_inventory("team-a", "other", "other-key"),
+            static_inventory("team-b", "edge", "team-b-key"),
+        ];
+        let plan = plan_targeting("edge");
+
+        assert!(plan_references_secret(
+            &plan,
+            "edge-key",
+            inventories.iter()
+        ));
+        // An inventory the plan doesn't reference, or a same-named one in another namespace.
+        assert!(!plan_references_secret(
+            &plan,
+            "other-key",
+            inventories.iter()
+        ));
+        assert!(!plan_references_secret(
+            &plan,
+            "team-b-key",
+            inventories.iter()
+        ));
+    }
+}
@@ -1,18 +1,34 @@
 mod callback_output;
+mod error_backoff;
+mod events;
 mod execution_evaluator;
+mod host_filter;
+mod host_order;
+mod image_source;
 mod job_builder;
 mod locking;
 mod managed_ssh;
 mod mappers;
 mod node_access;
+mod object_locks;
 mod paths;
 mod play_history;
+mod playbook_source;
+mod preview;
 pub mod reconciler;
+mod role_sources;
 mod status;
 mod triggers;
+mod validation;
 mod workspace;
 
 /// The operator-tunable readiness-grace policy for managed-ssh proxy pods, built from config in
 /// `main.rs` and threaded into the reconciler. Re-exported so `main.rs` can name it without exposing
 /// the rest of the (private) `managed_ssh` module.
 pub use managed_ssh::ProxyGracePolicy;
+
+/// Offline `PlaybookPlan` checks backing the `validate` subcommand.
+pub use validation::validate;
+
+/// Offline rendering of a plan's workspace Secret and Job, backing the `render` subcommand.
+pub use preview::render;
@@ -7,7 +7,10 @@
 //! or buggy node set can never reach a node no policy allowed. Fail-closed: a namespace with no
 //! matching policy may target no managed-ssh nodes at all.
 
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
 
 use k8s_openapi::api::core::v1::{Namespace, Node};
 use kube::{Api, ResourceExt as _, api::ListParams, runtime::reflector::Store};
@@ -17,19 +20,40 @@ use crate::v1beta1::{
     controllers::{nodeselector::selector_matches_fail_closed, reconcile_error::ReconcileError},
 };
 
+/// The cluster's Nodes, listed *live* once per reconcile for everything that judges managed-ssh
+/// hosts by their Node — this policy and the cordon check — so they agree on one snapshot. Empty
+/// without a managed-ssh group: nothing then reads Nodes, so the API call is skipped.
+///
+/// Not served from a cache: it's the authoritative allow-set for a security gate, so it must not
+/// serve a node stale-labelled into a pool it has since left.
+pub async fn list_nodes(
+    client: &kube::Client,
+    groups: &[ResolvedInventoryGroup],
+) -> Result<Vec<Node>, ReconcileError> {
+    let has_managed_ssh = groups
+        .iter()
+        .any(|g| matches!(g, ResolvedInventoryGroup::ManagedSsh { .. }));
+    if !has_managed_ssh {
+        return Ok(Vec::new());
+    }
+
+    let nodes: Api<Node> = Api::all(client.clone());
+    Ok(nodes.list(&ListParams::default()).await?.items)
+}
+
 /// Clamps every managed-ssh group in `groups` to the nodes `plan_namespace` is permitted to target,
 /// dropping now-empty managed-ssh groups. Returns the sorted, de-duplicated host names that were
 /// excluded (for logging/status). Non-managed-ssh (`StaticInventory`) groups are untouched — they
 /// carry their own credentials and aren't node-root, so they're outside this policy.
 ///
 /// `policies` is the reflector-cached view of the cluster's `NodeAccessPolicy` resources
-/// (cluster-scoped, admin-authored, stable) — cheap to read every reconcile. The Node set is fetched
-/// *live* rather than cached: it's the authoritative allow-set for a security gate, so it must not
-/// serve a node stale-labelled into a pool it has since left.
+/// (cluster-scoped, admin-authored, stable) — cheap to read every reconcile. `nodes` is this
+/// reconcile's live Node list (see `list_nodes`); every group is evaluated against it.
 pub async fn enforce(
     client: &kube::Client,
     policies: &Store<NodeAccessPolicy>,
     plan_namespace: &str,
+    nodes: &[Node],
     groups: &mut Vec<ResolvedInventoryGroup>,
 ) -> Result<Vec<String>, ReconcileError> {
     let has_managed_ssh = groups
@@ -40,44 +64,38 @@ pub async fn enforce(
         return Ok(Vec::new());
     }
 
-    let allowed = allowed_nodes_for_namespace(client, policies, plan_namespace).await?;
-    Ok(clamp_managed_ssh_groups(groups, &allowed))
-}
-
-/// The union of Nodes granted to `plan_namespace` by the cached `NodeAccessPolicy` resources.
-/// Fail-closed at every step: no matching policy, or a policy with an empty selector, contributes
-/// nothing.
-async fn allowed_nodes_for_namespace(
-    client: &kube::Client,
-    policies: &Store<NodeAccessPolicy>,
-    plan_namespace: &str,
-) -> Result<HashSet<String>, ReconcileError> {
     // The namespace's own labels — Kubernetes always stamps `kubernetes.io/metadata.name`, so a
     // policy can target a single namespace by name via that label.
     let namespaces: Api<Namespace> = Api::all(client.clone());
     let namespace = namespaces.get(plan_namespace).await?;
-    let namespace_labels = namespace.labels().clone();
 
     // Policies are admin-authored, cluster-scoped, cached by the reflector.
-    let policies = policies.state();
+    let allowed = allowed_nodes(&policies.state(), namespace.labels(), nodes);
+    Ok(clamp_managed_ssh_groups(groups, &allowed))
+}
+
+/// The union of `nodes` granted to a namespace labelled `namespace_labels` by `policies`.
+/// Fail-closed at every step: no matching policy, or a policy with an empty selector, contributes
+/// nothing.
+fn allowed_nodes(
+    policies: &[Arc<NodeAccessPolicy>],
+    namespace_labels: &BTreeMap<String, String>,
+    nodes: &[Node],
+) -> HashSet<String> {
     let granting: Vec<&NodeAccessPolicy> = policies
         .iter()
         .map(|policy| policy.as_ref())
         .filter(|policy| {
-            selector_matches_fail_closed(&namespace_labels, &policy.spec.namespace_selector)
+            selector_matches_fail_closed(namespace_labels, &policy.spec.namespace_selector)
         })
         .collect();
 
     if granting.is_empty() {
         // Default-deny: an ungoverned namespace gets no managed-ssh nodes.
-        return Ok(HashSet::new());
+        return HashSet::new();
     }
 
-    let nodes: Api<Node> = Api::all(client.clone());
-    let nodes = nodes.list_metadata(&ListParams::default()).await?;
-
-    let allowed = nodes
-        .items
+    nodes
         .iter()
         .filter(|node| {
             let labels = node.labels();
@@ -86,9 +104,7 @@ async fn allowed_nodes_for_namespace(
                 .any(|policy| selector_matches_fail_closed(labels, &policy.spec.node_selector))
         })
         .filter_map(|node| node.metadata.name.clone())
-        .collect();
-
-    Ok(allowed)
+        .collect()
 }
 
 /// Pure intersection step: retain only `allowed` hosts in each managed-ssh group, drop groups left
@@ -124,7 +140,9 @@ fn clamp_managed_ssh_groups(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::v1beta1::{ResolvedHosts, SecretRef, SshConfig};
+    use crate::v1beta1::{
+        LabelSelector, NodeAccessPolicySpec, ResolvedHosts, SecretRef, SshConfig,
+    };
 
     fn managed(name: &str, hosts: &[&str]) -> ResolvedInventoryGroup {
         ResolvedInventoryGroup::ManagedSsh {
@@ -134,6 +152,7 @@ mod tests {
             },
             tolerations: None,
             variables: None,
+            node_metadata: Default::default(),
         }
     }
 
@@ -147,6 +166,8 @@ mod tests {
             config: SshConfig {
                 user: "root".into(),
                 secret_ref: SecretRef { name: "k".into() },
+                private_key_key: None,
+                additional_private_key_keys: None,
             },
             variables: None,
         }
@@ -210,4 +231,86 @@ mod tests {
             "the ssh group survives alongside the clamped managed one"
         );
     }
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn node(name: &str, pool: &str) -> Node {
+        Node {
+            metadata: kube::api::ObjectMeta {
+                name: Some(name.into()),
+                labels: Some(labels(&[("pool", pool)])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn policy(team: &str, pool: &str) -> Arc<NodeAccessPolicy> {
+        let selector = |pairs: &[(&str, &str)]| LabelSelector {
+            match_labels: Some(labels(pairs)),
+            match_expressions: None,
+        };
+        Arc::new(NodeAccessPolicy::new(
+            &format!("{team}-{pool}"),
+            NodeAccessPolicySpec {
+                namespace_selector: selector(&[("team", team)]),
+                node_selector: selector(&[("pool", pool)]),
+            },
+        ))
+    }
+
+    #[test]
+    fn every_group_is_clamped_against_the_same_node_list() {
+        let nodes = [
+            node("node-a", "gpu"),
+            node("node-b", "gpu"),
+            node("node-c", "general"),
+        ];
+        let policies = [policy("ml", "gpu"), policy("web", "general")];
+        let allowed = allowed_nodes(&policies, &labels(&[("team", "ml")]), &nodes);
+
+        // Two ClusterInventories (six groups between them would behave the same) overlapping on
+        // the same Nodes.
+        let mut groups = vec![
+            managed("trainers", &["node-a", "node-b"]),
+            managed("all-nodes", &["node-a", "node-b", "node-c"]),
+            managed("general", &["node-c"]),
+            ssh("external", &["host.example.com"]),
+        ];
+        let dropped = clamp_managed_ssh_groups(&mut groups, &allowed);
+
+        assert_eq!(dropped, vec!["node-c".to_string()]);
+        let remaining: Vec<_> = groups
+            .iter()
+            .map(|group| (group.hosts().name.as_str(), group.hosts().hosts.clone()))
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![
+                ("trainers", vec!["node-a".to_string(), "node-b".to_string()]),
+                (
+                    "all-nodes",
+                    vec!["node-a".to_string(), "node-b".to_string()]
+                ),
+                ("external", vec!["host.example.com".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn allowed_nodes_is_empty_without_a_granting_policy_or_a_listed_node() {
+        let nodes = [node("node-a", "gpu")];
+
+        let ungoverned = allowed_nodes(&[policy("ml", "gpu")], &labels(&[("team", "web")]), &nodes);
+        assert!(ungoverned.is_empty());
+
+        // A node the policy would grant but that isn't in this reconcile's list is not allowed.
+        let not_listed = allowed_nodes(&[policy("ml", "gpu")], &labels(&[("team", "ml")]), &[]);
+        assert!(not_listed.is_empty());
+    }
 }
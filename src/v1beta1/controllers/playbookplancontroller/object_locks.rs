@@ -0,0 +1,162 @@
+//! Per-plan coalescing of reconciles. The controller runtime already runs at most one reconcile per
+//! object at a time, but that is a property of its scheduler, not of `reconcile` — a second trigger
+//! path (a test, a future spawned task) could overlap two reconciles of one plan, and their status
+//! patches would then land in whichever order the apiserver sees them. `ObjectLocks` makes the
+//! guarantee explicit: reconciles of one plan run one at a time, and when several triggers pile up
+//! behind a running one, only the newest goes ahead once it finishes — the others are dropped, so
+//! the status the newest reconcile computes is the last one written.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use tokio::sync::OwnedMutexGuard;
+
+#[derive(Default)]
+pub struct ObjectLocks {
+    slots: Mutex<HashMap<String, Arc<Slot>>>,
+}
+
+/// One plan's lock, plus the ticket of the newest trigger that asked for it.
+#[derive(Default)]
+struct Slot {
+    latest: AtomicU64,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+/// Exclusive right to reconcile one plan; released (and the plan's slot freed once nobody else
+/// waits for it) on drop.
+pub struct Turn {
+    locks: Arc<ObjectLocks>,
+    key: String,
+    slot: Arc<Slot>,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl ObjectLocks {
+    /// Waits for the running reconcile of `key` (if any) to finish. `None` if a newer trigger for
+    /// `key` arrived meanwhile: that one supersedes this one, which should then do nothing.
+    pub async fn enter(self: &Arc<Self>, key: &str) -> Option<Turn> {
+        let slot = self
+            .slots
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+        let ticket = slot.latest.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let guard = slot.lock.clone().lock_owned().await;
+        let turn = Turn {
+            locks: self.clone(),
+            key: key.to_string(),
+            slot,
+            guard: Some(guard),
+        };
+        (turn.slot.latest.load(Ordering::SeqCst) == ticket).then_some(turn)
+    }
+}
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        self.guard.take();
+
+        let mut slots = self.locks.slots.lock().unwrap();
+        // One reference is the map's, one is ours; any other belongs to a trigger still waiting.
+        if Arc::strong_count(&self.slot) == 2 {
+            slots.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::AtomicUsize, time::Duration};
+
+    use super::*;
+
+    /// How many triggers for `key` have drawn a ticket since its slot was created.
+    fn tickets(locks: &ObjectLocks, key: &str) -> u64 {
+        locks.slots.lock().unwrap()[key]
+            .latest
+            .load(Ordering::SeqCst)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn many_triggers_for_one_plan_never_overlap_and_the_newest_always_runs() {
+        let locks = Arc::new(ObjectLocks::default());
+        let running = Arc::new(AtomicUsize::new(0));
+        let ran = Arc::new(Mutex::new(Vec::new()));
+
+        for round in 0..20 {
+            // The first run holds its turn until every other trigger has queued up behind it.
+            let first = locks.enter("default/web").await.unwrap();
+            running.fetch_add(1, Ordering::SeqCst);
+            let queued: Vec<_> = (1..50)
+                .map(|i| {
+                    let (locks, running, ran) = (locks.clone(), running.clone(), ran.clone());
+                    tokio::spawn(async move {
+                        let Some(_turn) = locks.enter("default/web").await else {
+                            return;
+                        };
+                        assert_eq!(running.fetch_add(1, Ordering::SeqCst), 0, "overlapping");
+                        tokio::time::sleep(Duration::from_micros(200)).await;
+                        ran.lock().unwrap().push((round, i));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                    })
+                })
+                .collect();
+            while tickets(&locks, "default/web") < 50 {
+                tokio::task::yield_now().await;
+            }
+            ran.lock().unwrap().push((round, 0));
+            running.fetch_sub(1, Ordering::SeqCst);
+            drop(first);
+            for task in queued {
+                task.await.unwrap();
+            }
+
+            // Coalesced: of the 49 queued triggers, only the newest ran after the first.
+            let ran = std::mem::take(&mut *ran.lock().unwrap());
+            assert_eq!(ran.len(), 2, "round {round}: {ran:?}");
+            assert_eq!(ran[0], (round, 0));
+        }
+
+        assert!(locks.slots.lock().unwrap().is_empty(), "slot leaked");
+    }
+
+    #[tokio::test]
+    async fn different_plans_do_not_wait_for_each_other() {
+        let locks = Arc::new(ObjectLocks::default());
+
+        let _web = locks.enter("default/web").await.unwrap();
+        let db = tokio::time::timeout(Duration::from_secs(1), locks.enter("default/db")).await;
+
+        assert!(db.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn a_trigger_queued_behind_a_running_reconcile_supersedes_earlier_ones() {
+        let locks = Arc::new(ObjectLocks::default());
+
+        let running = locks.enter("default/web").await.unwrap();
+        let older = tokio::spawn({
+            let locks = locks.clone();
+            async move { locks.enter("default/web").await.is_some() }
+        });
+        tokio::task::yield_now().await;
+        let newer = tokio::spawn({
+            let locks = locks.clone();
+            async move { locks.enter("default/web").await.is_some() }
+        });
+        tokio::task::yield_now().await;
+        drop(running);
+
+        assert!(!older.await.unwrap());
+        assert!(newer.await.unwrap());
+    }
+}
@@ -6,6 +6,24 @@
 /// already mounted at.
 pub const WORKSPACE_MOUNT_PATH: &str = "/run/ansible-operator";
 
+/// `template.groupVariables` land here, next to `inventory.yml`, which is where Ansible looks for
+/// `group_vars/<group>/` files.
+pub const GROUP_VARS_DIR: &str = "/run/ansible-operator/group_vars";
+
+/// `template.roles` land here, one directory per role; `ANSIBLE_ROLES_PATH` points at it.
+pub const ROLES_DIR: &str = "/run/ansible-operator/roles";
+
+/// Directory the `index`th ConfigMap of `template.variables` is mounted at.
+pub fn config_map_variables_dir(index: usize) -> String {
+    format!("{WORKSPACE_MOUNT_PATH}/config-map-vars/{index}")
+}
+
+/// Directory the `index`th Secret of `template.variables` is mounted at. Numbered rather than named
+/// after the Secret, since two entries may read different keys of the same one.
+pub fn secret_variables_dir(index: usize) -> String {
+    format!("{WORKSPACE_MOUNT_PATH}/secret-vars/{index}")
+}
+
 /// Directory holding this run's managed-ssh client identity (one client cert/key per run,
 /// trusted by every proxy pod that run via the CA — not per-host).
 pub const MANAGED_SSH_CLIENT_DIR: &str = "/run/ansible-operator/managed-ssh";
@@ -28,8 +46,13 @@ pub fn static_inventory_ssh_dir(static_inventory_name: &str) -> String {
     format!("/run/ansible-operator/ssh/{static_inventory_name}")
 }
 
-pub fn static_inventory_ssh_key_path(static_inventory_name: &str) -> String {
-    format!("{}/id_rsa", static_inventory_ssh_dir(static_inventory_name))
+/// The whole key Secret is mounted at `static_inventory_ssh_dir`, so the private key's file is named
+/// after whichever Secret key holds it (`SshConfig::effective_private_key_key`).
+pub fn static_inventory_ssh_key_path(static_inventory_name: &str, private_key_key: &str) -> String {
+    format!(
+        "{}/{private_key_key}",
+        static_inventory_ssh_dir(static_inventory_name)
+    )
 }
 
 pub fn static_inventory_known_hosts_path(static_inventory_name: &str) -> String {
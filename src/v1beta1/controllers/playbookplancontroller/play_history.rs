@@ -12,15 +12,18 @@ use kube::{
 };
 use tracing::debug;
 
-use crate::v1beta1::{
-    HostOutcome, Play, PlayHostResult, PlayPhase, PlayRecap, PlaySpec, PlayStatus, PlaybookPlan,
-    ResolvedHosts,
-    controllers::reconcile_error::ReconcileError,
-    labels,
-    playbookplancontroller::{
-        callback_output::{CallbackOutput, HostStats},
-        execution_evaluator::ExecutionHash,
-        reconciler::playbookplan_owner_ref,
+use crate::{
+    utils,
+    v1beta1::{
+        HostOutcome, Play, PlayHostResult, PlayPhase, PlayRecap, PlaySpec, PlayStatus,
+        PlaybookPlan, ResolvedHosts,
+        controllers::reconcile_error::ReconcileError,
+        labels,
+        playbookplancontroller::{
+            callback_output::{CallbackOutput, HostStats},
+            execution_evaluator::ExecutionHash,
+            reconciler::playbookplan_owner_ref,
+        },
     },
 };
 
@@ -62,7 +65,7 @@ pub async fn record_running(
     match api.create(&post_params(), &object).await {
         Ok(_) => {}
         // Created concurrently by another tick — leave whatever status it already has.
-        Err(err) if is_conflict(&err) => return Ok(()),
+        Err(err) if utils::is_already_exists(&err) => return Ok(()),
         Err(err) => return Err(err.into()),
     }
 
@@ -91,7 +94,7 @@ pub async fn record_finished(
         let object = build_play(play)?;
         match api.create(&post_params(), &object).await {
             Ok(_) => {}
-            Err(err) if is_conflict(&err) => {}
+            Err(err) if utils::is_already_exists(&err) => {}
             Err(err) => return Err(err.into()),
         }
     }
@@ -100,6 +103,20 @@ pub async fn record_finished(
     patch_status(&api, play.job_name, &status).await
 }
 
+/// Deletes the `Play` of the Job `job_name`, which was replaced before it ran anything, so it
+/// doesn't linger as `Running`.
+pub async fn discard(
+    client: &kube::Client,
+    namespace: &str,
+    job_name: &str,
+) -> Result<(), ReconcileError> {
+    let api = Api::<Play>::namespaced(client.clone(), namespace);
+    match api.delete(job_name, &DeleteParams::default()).await {
+        Err(err) if !is_not_found(&err) => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
 /// Deletes the oldest `Play`s for `plan` beyond its success/failure history limits.
 pub async fn prune(
     client: &kube::Client,
@@ -114,7 +131,11 @@ pub async fn prune(
 
     let api = Api::<Play>::namespaced(client.clone(), namespace);
     let plays = api
-        .list(&ListParams::default().labels(&format!("{}={plan_name}", labels::PLAYBOOKPLAN_NAME)))
+        .list(&ListParams::default().labels(&format!(
+            "{}={}",
+            labels::PLAYBOOKPLAN_NAME,
+            utils::label_value(&plan_name)
+        )))
         .await?;
 
     let (successful_limit, failed_limit) = effective_limits(plan);
@@ -203,7 +224,7 @@ fn build_play(play: &PlayRef<'_>) -> Result<Play, ReconcileError> {
     );
     object.metadata.labels = Some(BTreeMap::from([(
         labels::PLAYBOOKPLAN_NAME.to_string(),
-        plan_name.to_string(),
+        utils::label_value(&plan_name),
     )]));
     object.metadata.owner_references = Some(vec![playbookplan_owner_ref(play.plan)?]);
 
@@ -326,10 +347,6 @@ fn post_params() -> PostParams {
     }
 }
 
-fn is_conflict(err: &kube::Error) -> bool {
-    matches!(err, kube::Error::Api(status) if status.code == 409)
-}
-
 fn is_not_found(err: &kube::Error) -> bool {
     matches!(err, kube::Error::Api(status) if status.code == 404)
 }
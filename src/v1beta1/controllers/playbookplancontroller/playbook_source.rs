@@ -0,0 +1,162 @@
+//! Where a run's playbook text comes from: `template.playbook` inline, or the ConfigMap/Secret key
+//! named by `template.playbookFrom`. Resolved once per reconcile so the execution hash and the
+//! rendered workspace always see the same text.
+
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::Api;
+
+use crate::v1beta1::{PlaybookPlan, PlaybookSource, controllers::reconcile_error::ReconcileError};
+
+/// Returns the playbook text of `plan`, reading it from the cluster when it uses `playbookFrom`.
+/// Validation guarantees a `playbookFrom` names exactly one of a ConfigMap or a Secret.
+pub async fn resolve(
+    plan: &PlaybookPlan,
+    secrets_api: &Api<Secret>,
+    config_maps_api: &Api<ConfigMap>,
+) -> Result<String, ReconcileError> {
+    let Some(source) = &plan.spec.template.playbook_from else {
+        return Ok(plan.spec.template.playbook.clone());
+    };
+
+    if let Some(config_map_ref) = &source.config_map_ref {
+        let config_map = config_maps_api.get_opt(&config_map_ref.name).await?;
+        return from_config_map(source, config_map.as_ref());
+    }
+
+    if let Some(secret_ref) = &source.secret_ref {
+        let secret = secrets_api.get_opt(&secret_ref.name).await?;
+        return from_secret(source, secret.as_ref());
+    }
+
+    Err(ReconcileError::PlaybookSource(
+        "playbookFrom names neither a ConfigMap nor a Secret".into(),
+    ))
+}
+
+fn from_config_map(
+    source: &PlaybookSource,
+    config_map: Option<&ConfigMap>,
+) -> Result<String, ReconcileError> {
+    let name = source
+        .config_map_ref
+        .as_ref()
+        .map_or("", |r| r.name.as_str());
+    let Some(config_map) = config_map else {
+        return Err(missing("ConfigMap", name, None));
+    };
+
+    config_map
+        .data
+        .as_ref()
+        .and_then(|data| data.get(&source.key))
+        .cloned()
+        .ok_or_else(|| missing("ConfigMap", name, Some(&source.key)))
+}
+
+/// Keys may be in `data` or (on objects not yet round-tripped through the apiserver) `stringData`.
+fn from_secret(source: &PlaybookSource, secret: Option<&Secret>) -> Result<String, ReconcileError> {
+    let name = source.secret_ref.as_ref().map_or("", |r| r.name.as_str());
+    let Some(secret) = secret else {
+        return Err(missing("Secret", name, None));
+    };
+
+    if let Some(value) = secret.data.as_ref().and_then(|d| d.get(&source.key)) {
+        return String::from_utf8(value.0.clone()).map_err(|_| {
+            ReconcileError::PlaybookSource(format!(
+                "key {:?} of Secret {name:?} is not valid UTF-8",
+                source.key
+            ))
+        });
+    }
+
+    secret
+        .string_data
+        .as_ref()
+        .and_then(|d| d.get(&source.key))
+        .cloned()
+        .ok_or_else(|| missing("Secret", name, Some(&source.key)))
+}
+
+fn missing(kind: &str, name: &str, key: Option<&str>) -> ReconcileError {
+    ReconcileError::PlaybookSource(match key {
+        None => format!("playbookFrom {kind} {name:?} does not exist"),
+        Some(key) => format!("playbookFrom {kind} {name:?} has no key {key:?}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::ByteString;
+
+    use super::*;
+    use crate::v1beta1::{ConfigMapRef, SecretRef};
+
+    fn config_map_source() -> PlaybookSource {
+        PlaybookSource {
+            config_map_ref: Some(ConfigMapRef {
+                name: "playbooks".into(),
+            }),
+            secret_ref: None,
+            key: "site.yml".into(),
+        }
+    }
+
+    #[test]
+    fn config_map_key_is_the_playbook() {
+        let config_map = ConfigMap {
+            data: Some(BTreeMap::from([(
+                "site.yml".to_string(),
+                "- hosts: all\n  tasks: []\n".to_string(),
+            )])),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_config_map(&config_map_source(), Some(&config_map)).unwrap(),
+            "- hosts: all\n  tasks: []\n"
+        );
+    }
+
+    #[test]
+    fn missing_config_map_or_key_names_what_is_missing() {
+        let err = from_config_map(&config_map_source(), None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"playbookFrom ConfigMap "playbooks" does not exist"#
+        );
+
+        let err = from_config_map(&config_map_source(), Some(&ConfigMap::default())).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"playbookFrom ConfigMap "playbooks" has no key "site.yml""#
+        );
+    }
+
+    #[test]
+    fn secret_key_is_read_from_data_or_string_data() {
+        let source = PlaybookSource {
+            config_map_ref: None,
+            secret_ref: Some(SecretRef {
+                name: "playbooks".into(),
+            }),
+            key: "site.yml".into(),
+        };
+
+        let from_data = Secret {
+            data: Some(BTreeMap::from([(
+                "site.yml".to_string(),
+                ByteString(b"[]".to_vec()),
+            )])),
+            ..Default::default()
+        };
+        assert_eq!(from_secret(&source, Some(&from_data)).unwrap(), "[]");
+
+        let from_string_data = Secret {
+            string_data: Some(BTreeMap::from([("site.yml".to_string(), "[]".to_string())])),
+            ..Default::default()
+        };
+        assert_eq!(from_secret(&source, Some(&from_string_data)).unwrap(), "[]");
+    }
+}
@@ -0,0 +1,309 @@
+//! Offline rendering of what the operator would create for a `PlaybookPlan`, for
+//! `ansible-operator render`: the workspace Secret or ConfigMap (with its inventory) and the run's Job, built by
+//! the same `workspace`/`job_builder` code the reconciler uses — but without a cluster, so
+//! everything the reconciler would look up live is stood in for.
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::batch::v1::Job;
+
+use crate::v1beta1::{
+    HostOrder, PlaybookPlan, ResolvedHosts, ResolvedInventoryGroup, SecretRef, SshConfig,
+    StaticInventory, ansible::ManagedSshHostInfo, controllers::reconcile_error::ReconcileError,
+};
+
+use super::{
+    execution_evaluator::calculate_execution_hash, host_order, job_builder, managed_ssh, workspace,
+};
+
+/// Host standing in for the Nodes a `ClusterInventory` would resolve to at run time.
+const SYNTHETIC_NODE: &str = "example-node";
+
+/// Address standing in for a managed-ssh proxy pod's IP, which only exists once a run starts.
+const SYNTHETIC_PROXY_IP: &str = "<proxy-pod-ip>";
+
+/// Image standing in for one read from a ConfigMap via `imageFrom`.
+const SYNTHETIC_IMAGE: &str = "<image-from-config-map>";
+
+/// Playbook standing in for one read from a ConfigMap/Secret via `playbookFrom`.
+const SYNTHETIC_PLAYBOOK: &str = "- hosts: all\n  tasks: []\n";
+
+pub struct Preview {
+    pub workspace: workspace::Workspace,
+    pub job: Job,
+}
+
+/// Renders the workspace (a Secret or ConfigMap, see `spec.workspaceStorage`) and first-attempt Job
+/// for `plan`.
+///
+/// `StaticInventory`s referenced by the plan are taken from `static_inventories` when present, so
+/// their real hosts and SSH settings show up; one that isn't is replaced by a single synthetic host.
+/// A `ClusterInventory` always renders as one synthetic Node (`SYNTHETIC_NODE`), since its hosts
+/// come from the live Node list. Missing metadata is filled with placeholders, a `playbookFrom`
+/// playbook is replaced by an empty play (`SYNTHETIC_PLAYBOOK`), an `imageFrom` image by
+/// `SYNTHETIC_IMAGE`, and the execution hash covers the playbook only — the contents of referenced
+/// Secrets can't be read offline.
+pub fn render(
+    plan: &PlaybookPlan,
+    static_inventories: &BTreeMap<String, StaticInventory>,
+) -> Result<Preview, ReconcileError> {
+    let mut plan = plan.clone();
+    plan.metadata
+        .namespace
+        .get_or_insert_with(|| "default".into());
+    plan.metadata
+        .uid
+        .get_or_insert_with(|| "00000000-0000-0000-0000-000000000000".into());
+    plan.metadata.generation.get_or_insert(1);
+
+    let groups = synthetic_groups(&plan, static_inventories);
+    let managed_ssh_hosts: BTreeMap<String, ManagedSshHostInfo> = groups
+        .iter()
+        .filter(|group| matches!(group, ResolvedInventoryGroup::ManagedSsh { .. }))
+        .flat_map(|group| group.hosts().hosts.iter())
+        .map(|host| {
+            let info = ManagedSshHostInfo {
+                pod_ip: SYNTHETIC_PROXY_IP.into(),
+                port: managed_ssh::PROXY_SSH_PORT,
+                unreachable: false,
+            };
+            (host.clone(), info)
+        })
+        .collect();
+
+    let playbook = match &plan.spec.template.playbook_from {
+        Some(_) => SYNTHETIC_PLAYBOOK,
+        None => plan.spec.template.playbook.as_str(),
+    };
+    let hash = calculate_execution_hash(playbook, std::iter::empty())
+        .fold_verify_playbook(plan.spec.template.verify_playbook.as_deref())
+        .fold_start_at_task(plan.spec.start_at_task.as_deref())
+        .fold_render_template(plan.spec.template.render_template);
+    let image = match &plan.spec.image_from {
+        Some(_) => SYNTHETIC_IMAGE,
+        None => plan.spec.image.as_str(),
+    };
+
+    Ok(Preview {
+        workspace: workspace::render(&plan, playbook, &hash, &groups, &managed_ssh_hosts)?,
+        // Role keys are read from the cluster; without one, each role's files all land in its top
+        // directory.
+        job: job_builder::create_job_for_run(&hash, 1, &groups, &plan, image, &Default::default())?,
+    })
+}
+
+/// `hosts` in the order the reconciler would list them, see `host_order`.
+fn ordered(order: HostOrder, plan: &PlaybookPlan, hosts: &[String]) -> Vec<String> {
+    let mut hosts = hosts.to_vec();
+    host_order::apply(order, host_order::seed(plan), &mut hosts);
+    hosts
+}
+
+fn synthetic_groups(
+    plan: &PlaybookPlan,
+    static_inventories: &BTreeMap<String, StaticInventory>,
+) -> Vec<ResolvedInventoryGroup> {
+    let mut groups = Vec::new();
+
+    for inventory_ref in &plan.spec.inventory_refs {
+        if let Some(name) = &inventory_ref.cluster_inventory {
+            groups.push(ResolvedInventoryGroup::ManagedSsh {
+                hosts: ResolvedHosts {
+                    name: name.clone(),
+                    hosts: vec![SYNTHETIC_NODE.into()],
+                },
+                tolerations: None,
+                variables: None,
+                node_metadata: BTreeMap::new(),
+            });
+        }
+
+        if let Some(name) = &inventory_ref.static_inventory {
+            match static_inventories.get(name) {
+                Some(si) => {
+                    groups.extend(
+                        si.spec
+                            .hosts
+                            .iter()
+                            .map(|group| ResolvedInventoryGroup::Ssh {
+                                hosts: ResolvedHosts {
+                                    name: group.name.clone(),
+                                    hosts: ordered(inventory_ref.order, plan, &group.hosts),
+                                },
+                                static_inventory_name: name.clone(),
+                                config: si.spec.ssh.clone(),
+                                variables: group.variables.clone(),
+                            }),
+                    )
+                }
+                None => groups.push(ResolvedInventoryGroup::Ssh {
+                    hosts: ResolvedHosts {
+                        name: name.clone(),
+                        hosts: vec![format!("{name}.example.com")],
+                    },
+                    static_inventory_name: name.clone(),
+                    config: SshConfig {
+                        user: "<user>".into(),
+                        secret_ref: SecretRef {
+                            name: format!("<{name}-ssh-secret>"),
+                        },
+                        ..Default::default()
+                    },
+                    variables: None,
+                }),
+            }
+        }
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1beta1::{InventoryRef, StaticInventoryGroup, StaticInventorySpec};
+
+    fn plan() -> PlaybookPlan {
+        let mut plan = PlaybookPlan::new("preview", Default::default());
+        plan.spec.image = "docker.io/serversideup/ansible-core:2.18".into();
+        plan.spec.template.playbook = "- hosts: all\n  tasks: []\n".into();
+        plan.spec.inventory_refs = vec![
+            InventoryRef {
+                cluster_inventory: Some("cluster-nodes".into()),
+                static_inventory: None,
+                ..Default::default()
+            },
+            InventoryRef {
+                cluster_inventory: None,
+                static_inventory: Some("edge".into()),
+                ..Default::default()
+            },
+        ];
+        plan
+    }
+
+    fn inventory(workspace: &workspace::Workspace) -> &str {
+        workspace.file("inventory.yml").unwrap()
+    }
+
+    #[test]
+    fn renders_synthetic_hosts_without_a_cluster() {
+        let preview = render(&plan(), &BTreeMap::new()).unwrap();
+
+        let inventory = inventory(&preview.workspace);
+        assert!(inventory.contains(SYNTHETIC_NODE), "{inventory}");
+        assert!(inventory.contains(SYNTHETIC_PROXY_IP), "{inventory}");
+        assert!(inventory.contains("edge.example.com"), "{inventory}");
+
+        let workspace::Workspace::Secret(secret) = &preview.workspace else {
+            panic!("expected a Secret workspace");
+        };
+        assert_eq!(secret.metadata.namespace.as_deref(), Some("default"));
+        assert!(
+            preview
+                .job
+                .metadata
+                .name
+                .as_deref()
+                .unwrap()
+                .starts_with("apply-preview-")
+        );
+    }
+
+    #[test]
+    fn uses_static_inventories_from_the_same_manifests() {
+        let mut edge = StaticInventory::new(
+            "edge",
+            StaticInventorySpec {
+                hosts: vec![StaticInventoryGroup {
+                    name: "routers".into(),
+                    hosts: vec!["ccu.fritz.box".into()],
+                    variables: None,
+                }],
+                ssh: SshConfig {
+                    user: "admin".into(),
+                    secret_ref: SecretRef {
+                        name: "router-key".into(),
+                    },
+                    ..Default::default()
+                },
+            },
+        );
+        edge.metadata.namespace = Some("default".into());
+        let static_inventories = BTreeMap::from([("edge".to_string(), edge)]);
+
+        let preview = render(&plan(), &static_inventories).unwrap();
+
+        let inventory = inventory(&preview.workspace);
+        assert!(inventory.contains("routers"), "{inventory}");
+        assert!(inventory.contains("ccu.fritz.box"), "{inventory}");
+        assert!(inventory.contains("ansible_user: admin"), "{inventory}");
+
+        let volumes = preview
+            .job
+            .spec
+            .unwrap()
+            .template
+            .spec
+            .unwrap()
+            .volumes
+            .unwrap();
+        assert!(volumes.iter().any(
+            |v| v.secret.as_ref().and_then(|s| s.secret_name.as_deref()) == Some("router-key")
+        ));
+    }
+
+    #[test]
+    fn a_config_map_workspace_is_rendered_and_mounted_as_one() {
+        use crate::v1beta1::{GroupVariables, PlaybookVariableSource, WorkspaceStorage};
+
+        let mut plan = plan();
+        plan.spec.workspace_storage = WorkspaceStorage::ConfigMap;
+        plan.spec.template.group_variables = Some(vec![GroupVariables {
+            group: "edge".into(),
+            variables: vec![PlaybookVariableSource::Inline {
+                inline: Default::default(),
+            }],
+        }]);
+
+        let preview = render(&plan, &BTreeMap::new()).unwrap();
+
+        let workspace::Workspace::ConfigMap(config_map) = &preview.workspace else {
+            panic!("expected a ConfigMap workspace");
+        };
+        assert_eq!(
+            config_map.metadata.name.as_deref(),
+            Some("ansible-workspace-preview")
+        );
+        assert!(inventory(&preview.workspace).contains("edge.example.com"));
+
+        let volumes = preview
+            .job
+            .spec
+            .unwrap()
+            .template
+            .spec
+            .unwrap()
+            .volumes
+            .unwrap();
+        let playbook = volumes.iter().find(|v| v.name == "playbook").unwrap();
+        assert!(playbook.secret.is_none());
+        assert_eq!(
+            playbook.config_map.as_ref().map(|c| c.name.as_str()),
+            Some("ansible-workspace-preview")
+        );
+        let group_vars = volumes.iter().find(|v| v.name == "group-vars").unwrap();
+        let sources = group_vars
+            .projected
+            .as_ref()
+            .unwrap()
+            .sources
+            .as_ref()
+            .unwrap();
+        assert!(sources[0].secret.is_none());
+        assert_eq!(
+            sources[0].config_map.as_ref().map(|c| c.name.as_str()),
+            Some("ansible-workspace-preview")
+        );
+    }
+}
@@ -1,52 +1,74 @@
-use chrono::{DateTime, FixedOffset, TimeZone, Utc};
-use futures_util::{Stream, StreamExt as _};
+use chrono::{DateTime, FixedOffset, Utc};
+use futures_util::Stream;
 use k8s_openapi::api::{
     batch::v1::Job,
     coordination::v1::Lease,
-    core::v1::{Pod, Secret},
+    core::v1::{ConfigMap, Pod, Secret},
 };
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use k8s_openapi::{ByteString, apimachinery::pkg::apis::meta::v1::OwnerReference};
 use kube::{
     Api,
-    api::{ListParams, Patch, PatchParams, PostParams},
+    api::{DeleteParams, ListParams, Patch, PatchParams, PostParams, Preconditions},
     runtime::{
         Controller,
         controller::Action,
+        finalizer,
         reflector::{ObjectRef, Store, store::Writer},
         watcher,
     },
 };
 use std::{collections::BTreeMap, sync::Arc};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, info, warn};
 
 use crate::v1beta1::{
-    AnsibleInventory, ClusterInventory, ExecutionMode, GenericMap, NodeAccessPolicy, Phase,
-    PlaybookPlanStatus, ResolvedHosts, ResolvedInventoryGroup, StaticInventory, Toleration,
-    ansible, flatten_hosts, labels,
+    AnsibleInventory, ClusterInventory, ExecutionMode, FailurePolicy, GenericMap, NodeAccessPolicy,
+    NodeMetadata, Phase, PlaybookPlanStatus, RecurringTargets, ResolvedHosts,
+    ResolvedInventoryGroup, StaticInventory, Toleration, WorkspaceStorage, ansible, flatten_hosts,
+    labels,
     playbookplancontroller::{
+        error_backoff::ErrorBackoff,
         execution_evaluator::{ExecutionHash, find_all_hosts},
-        locking, managed_ssh,
-        triggers::{Timing, evaluate_schedule, forecast_next_run},
-        workspace::{self, render_secret},
+        host_order, locking, managed_ssh,
+        object_locks::ObjectLocks,
+        triggers::{
+            Timing, duration_until_or_zero, evaluate_interval, evaluate_schedule,
+            forecast_next_run, schedule_jitter,
+        },
+        validation::ValidationError,
+        workspace::{self, Workspace},
     },
 };
 use crate::{
-    utils::create_or_update,
+    utils::{self, create_or_update, retry_on_conflict},
     v1beta1::{
         self, PlaybookPlan,
         ca::CertificateAuthority,
         controllers::reconcile_error::ReconcileError,
         playbookplancontroller::{
-            callback_output,
+            callback_output, events,
             execution_evaluator::{self, find_outdated_hosts},
-            job_builder, mappers, node_access, play_history, status,
+            host_filter, image_source, job_builder, mappers, node_access, play_history,
+            playbook_source,
+            role_sources::{self, RoleFiles},
+            status,
         },
     },
 };
 
-/// Default grace window after a scheduled tick during which a run may still start, when the plan
-/// does not set `spec.startingDeadlineSeconds`. See that field's docs.
-const DEFAULT_STARTING_DEADLINE_SECONDS: u32 = 30;
+/// How often a plan is re-checked while its run's Job is active, so completion is noticed (and
+/// `Running`/`Ready` updated) promptly even if the owned-Job watch misses the event.
+const JOB_RUNNING_REQUEUE: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Finalizer that holds a deleted plan back until `cleanup` has stopped its Jobs and removed its
+/// workspace Secret.
+const FINALIZER: &str = "ansible.cloudbending.dev/cleanup";
+
+/// How soon to retry after the final status write lost a conflict even after its retries.
+const STATUS_CONFLICT_REQUEUE: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How often an `InventoryOnly` plan re-resolves its inventories. Node changes don't trigger a
+/// reconcile, so this bounds how stale its published hosts can get.
+const INVENTORY_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
 
 struct ReconciliationContext {
     client: kube::Client,
@@ -75,6 +97,52 @@ struct ReconciliationContext {
     /// How long to wait for a `NotReady` node's proxy pod to become Ready before treating the node as
     /// unreachable, scaled by the node's heartbeat age. From the chart's `managedSsh.readiness`.
     proxy_grace: managed_ssh::ProxyGracePolicy,
+    /// The single namespace the operator is confined to (`run --namespace`), if any. Without
+    /// cluster-scoped reads there are no Nodes or `NodeAccessPolicy`s to resolve managed-ssh hosts
+    /// against, so such an operator only serves `StaticInventory` targets.
+    watch_namespace: Option<String>,
+    /// Publishes Events on plans (see `events`). Shared so its dedup cache spans reconciles.
+    recorder: kube::runtime::events::Recorder,
+    /// `run --dry-run` (`DRY_RUN`): resolve and evaluate plans as usual but only log what would be
+    /// created or written — no Secret, Job, Lease, proxy pod, Event, finalizer or status write.
+    dry_run: bool,
+    /// Runs reconciles of one plan one at a time, coalescing queued triggers (see `object_locks`).
+    object_locks: Arc<ObjectLocks>,
+    /// Whether the cluster supports `image` volumes (config `image_volumes`); plans with a
+    /// `collectionsImage` are refused without them, see `unsupported_by_cluster`.
+    image_volumes: bool,
+    intervals: ReconcileIntervals,
+    /// Per-plan consecutive failure counts deciding how long `error_policy` backs off.
+    error_backoff: ErrorBackoff,
+    /// The label keys `run --watch-label-selector` refers to. A run's Job carries its plan's labels
+    /// under these keys, so the owned-Job watch can be narrowed by the same selector.
+    selector_label_keys: Vec<String>,
+    /// `run --max-concurrent-jobs` (`MAX_CONCURRENT_JOBS`): how many operator Jobs may run at once
+    /// across all enrolled namespaces before further runs are deferred, see `throttle`.
+    max_concurrent_jobs: Option<usize>,
+}
+
+/// Operator-wide reconcile timing, set by `run --requeue-interval`, `--error-requeue-interval` and
+/// `--schedule-window` (see `main.rs`).
+#[derive(Debug, Clone)]
+pub struct ReconcileIntervals {
+    /// How long a plan with nothing due waits before it is re-checked without a watch event.
+    pub requeue: std::time::Duration,
+    /// How soon a reconcile that failed is first retried; see `error_backoff` for later retries.
+    pub error_requeue: std::time::Duration,
+    /// Grace window after a scheduled tick during which a run may still start, for plans that don't
+    /// set `spec.startingDeadlineSeconds`. See that field's docs.
+    pub schedule_window: std::time::Duration,
+}
+
+impl Default for ReconcileIntervals {
+    fn default() -> Self {
+        Self {
+            requeue: std::time::Duration::from_secs(3600),
+            error_requeue: std::time::Duration::from_secs(15),
+            schedule_window: std::time::Duration::from_secs(30),
+        }
+    }
 }
 
 /// Per-tick identifiers shared by `try_start_run` and `advance_applying_run`: the resource's
@@ -91,8 +159,18 @@ struct RunContext<'a> {
     /// Shared so the Job/proxy/render path and the Play history record see the same grouped set.
     run_groups: &'a [ResolvedInventoryGroup],
     holder_identity: &'a str,
+    /// The playbook text as resolved by `playbook_source::resolve` — what `execution_hash` covers.
+    playbook: &'a str,
+    /// The image as resolved by `image_source::resolve`. Not part of `execution_hash`: a new image
+    /// is used from the next run on, but doesn't re-run hosts by itself.
+    image: &'a str,
+    /// The files of `template.roles` as resolved by `role_sources::resolve`.
+    role_files: &'a RoleFiles,
 }
 
+// One independent operator setting per argument, as `main.rs` reads them; see
+// `managed_ssh::ensure_proxy_infra` for the same trade-off.
+#[allow(clippy::too_many_arguments)]
 pub fn new(
     client: kube::Client,
     operator_namespace: String,
@@ -100,6 +178,12 @@ pub fn new(
     ca: Arc<CertificateAuthority>,
     proxy_image: String,
     proxy_grace: managed_ssh::ProxyGracePolicy,
+    watch_namespace: Option<String>,
+    dry_run: bool,
+    image_volumes: bool,
+    intervals: ReconcileIntervals,
+    label_selector: Option<String>,
+    max_concurrent_jobs: Option<usize>,
 ) -> impl Stream<
     Item = Result<
         (ObjectRef<v1beta1::PlaybookPlan>, Action),
@@ -109,57 +193,46 @@ pub fn new(
     // PlaybookPlans are still watched cluster-wide so a plan created in a *non*-enrolled namespace is
     // seen and reported (`Phase::UnauthorizedNamespace`) rather than silently ignored (CRD reads stay
     // cluster-wide — see R1). Secret/Job watches below, by contrast, are scoped to the enrolled set.
-    let playbookplans_api: Api<v1beta1::PlaybookPlan> = Api::all(client.clone());
+    // Confined to `watch_namespace`, they're watched there only, as are StaticInventories below.
+    let playbookplans_api: Api<v1beta1::PlaybookPlan> = scoped_api(&client, &watch_namespace);
     // NodeAccessPolicy is cluster-scoped (admin-authored via cluster RBAC); cache/watch all of them.
     let node_access_policies_api: Api<NodeAccessPolicy> = Api::all(client.clone());
 
     let enrolled_namespaces = Arc::new(enrolled_namespaces);
 
-    let playbookplan_reflector_reader = {
-        let playbookplan_reflector_writer = Writer::<v1beta1::PlaybookPlan>::default();
-        let playbookplan_reflector_reader = Arc::new(playbookplan_reflector_writer.as_reader());
-
-        let playbookplan_reflector = kube::runtime::reflector(
-            playbookplan_reflector_writer,
-            watcher(playbookplans_api.clone(), watcher::Config::default()),
-        );
-
-        tokio::spawn(async move {
-            playbookplan_reflector
-                .for_each(|event| async {
-                    match event {
-                        Ok(_) => {}
-                        Err(e) => error!("Reflector error: {e:?}"),
-                    }
-                })
-                .await;
-        });
-
-        playbookplan_reflector_reader
+    // With `--watch-label-selector`, this instance only ever sees the plans it selects: the
+    // controller's own watch and the reflector the Secret/ConfigMap/policy mappers look plans up in
+    // both use it, so those mappers can't name another instance's plans either. Owned Jobs carry
+    // the selected labels (see `propagate_selector_labels`) and are watched by the same selector.
+    let selected = |config: watcher::Config| match &label_selector {
+        Some(selector) => config.labels(selector),
+        None => config,
     };
+    let selector_label_keys = label_selector
+        .as_deref()
+        .map(utils::selector_label_keys)
+        .unwrap_or_default();
 
-    let node_access_policy_reflector_reader = {
-        let writer = Writer::<NodeAccessPolicy>::default();
-        let reader = Arc::new(writer.as_reader());
-
-        let reflector = kube::runtime::reflector(
-            writer,
-            watcher(node_access_policies_api.clone(), watcher::Config::default()),
-        );
-
-        tokio::spawn(async move {
-            reflector
-                .for_each(|event| async {
-                    if let Err(e) = event {
-                        error!("NodeAccessPolicy reflector error: {e:?}");
-                    }
-                })
-                .await;
-        });
+    let playbookplan_reflector_reader = utils::spawn_reflector_with_config(
+        playbookplans_api.clone(),
+        selected(watcher::Config::default()),
+        "PlaybookPlan",
+    );
 
-        reader
+    // A namespace-confined operator can't list cluster-scoped policies; leaving the store empty is
+    // harmless, as it never resolves managed-ssh hosts to clamp.
+    let node_access_policy_reflector_reader = match watch_namespace {
+        None => utils::spawn_reflector(node_access_policies_api.clone(), "NodeAccessPolicy"),
+        Some(_) => Arc::new(Writer::default().as_reader()),
     };
 
+    // Only used to map an SSH key Secret back to the plans targeting a StaticInventory that names it;
+    // `resolve_inventory` itself still reads StaticInventories live.
+    let static_inventory_reflector_reader = utils::spawn_reflector(
+        scoped_api::<v1beta1::StaticInventory>(&client, &watch_namespace),
+        "StaticInventory",
+    );
+
     let context = Arc::new(ReconciliationContext {
         client: client.clone(),
         operator_namespace,
@@ -168,15 +241,44 @@ pub fn new(
         node_access_policies: Arc::clone(&node_access_policy_reflector_reader),
         proxy_image,
         proxy_grace,
+        watch_namespace: watch_namespace.clone(),
+        recorder: events::recorder(client.clone()),
+        dry_run,
+        object_locks: Default::default(),
+        image_volumes,
+        intervals,
+        error_backoff: Default::default(),
+        selector_label_keys,
+        max_concurrent_jobs,
     });
 
-    let mut controller = Controller::new(playbookplans_api, watcher::Config::default()).watches(
-        node_access_policies_api,
-        watcher::Config::default(),
-        mappers::node_access_policy_to_playbookplans(Arc::clone(&playbookplan_reflector_reader)),
-    );
+    // Referenced inventories are watched so their plans see host changes right away; a confined
+    // operator refuses `ClusterInventory` targets anyway.
+    let mut controller = Controller::new(playbookplans_api, selected(watcher::Config::default()))
+        .watches(
+            scoped_api::<v1beta1::StaticInventory>(&client, &watch_namespace),
+            watcher::Config::default(),
+            mappers::static_inventory_to_playbookplans(Arc::clone(&playbookplan_reflector_reader)),
+        );
+    if watch_namespace.is_none() {
+        controller = controller
+            .watches(
+                node_access_policies_api,
+                watcher::Config::default(),
+                mappers::node_access_policy_to_playbookplans(Arc::clone(
+                    &playbookplan_reflector_reader,
+                )),
+            )
+            .watches(
+                Api::<ClusterInventory>::all(client.clone()),
+                watcher::Config::default(),
+                mappers::cluster_inventory_to_playbookplans(Arc::clone(
+                    &playbookplan_reflector_reader,
+                )),
+            );
+    }
 
-    // Owned-Job and referenced-Secret watches are set up per enrolled namespace instead of once
+    // Owned-Job and referenced-Secret/ConfigMap watches are set up per enrolled namespace instead of once
     // cluster-wide: the operator holds `jobs`/`secrets` RBAC only in these namespaces (R1), so a
     // cluster-wide `Api::all` watch would 403. A Secret edit in an enrolled namespace still promptly
     // re-triggers its plan (preserving "input changed -> reapply"); the merged effect is identical to
@@ -184,20 +286,295 @@ pub fn new(
     for namespace in enrolled_namespaces.iter() {
         let jobs_api: Api<Job> = Api::namespaced(client.clone(), namespace);
         let secrets_api: Api<Secret> = Api::namespaced(client.clone(), namespace);
+        let config_maps_api: Api<ConfigMap> = Api::namespaced(client.clone(), namespace);
         controller = controller
-            .owns(jobs_api, watcher::Config::default())
+            .owns(jobs_api, selected(watcher::Config::default()))
+            .watches(
+                config_maps_api,
+                watcher::Config::default(),
+                mappers::config_map_to_playbookplans(Arc::clone(&playbookplan_reflector_reader)),
+            )
             .watches(
                 secrets_api,
                 watcher::Config::default(),
-                mappers::secret_to_playbookplans(Arc::clone(&playbookplan_reflector_reader)),
+                mappers::secret_to_playbookplans(
+                    Arc::clone(&playbookplan_reflector_reader),
+                    Arc::clone(&static_inventory_reflector_reader),
+                ),
             );
     }
 
-    controller.run(
-        reconcile,
-        |_, _, _| Action::requeue(std::time::Duration::from_secs(15)),
-        Arc::clone(&context),
+    controller.run(reconcile, error_policy, Arc::clone(&context))
+}
+
+/// Requeues a failed reconcile after a delay chosen by `error_backoff` from what failed and how
+/// often it has in a row, recording the error as a Warning Event on the plan. Only in enrolled
+/// namespaces: elsewhere the operator has no Event RBAC, and the plan's status already says why it
+/// isn't served.
+fn error_policy(
+    object: Arc<v1beta1::PlaybookPlan>,
+    error: &ReconcileError,
+    context: Arc<ReconciliationContext>,
+) -> Action {
+    let key = ObjectRef::from_obj(&*object).to_string();
+    let requeue = context
+        .error_backoff
+        .next_delay(&key, error, context.intervals.error_requeue);
+    debug!(
+        requeue_secs = requeue.as_secs(),
+        "{key}: retrying after {requeue:?}"
+    );
+
+    let enrolled = object
+        .metadata
+        .namespace
+        .as_ref()
+        .is_some_and(|namespace| context.enrolled_namespaces.contains(namespace));
+    if enrolled && !context.dry_run {
+        let event = events::reconcile_failed(error);
+        tokio::spawn(async move { events::publish(&context.recorder, &object, event).await });
+    }
+
+    Action::requeue(requeue)
+}
+
+/// An `Api` over every namespace, or only `watch_namespace` when the operator is confined to one.
+fn scoped_api<K>(client: &kube::Client, watch_namespace: &Option<String>) -> Api<K>
+where
+    K: kube::Resource<Scope = k8s_openapi::NamespaceResourceScope>,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    match watch_namespace {
+        Some(namespace) => Api::namespaced(client.clone(), namespace),
+        None => Api::all(client.clone()),
+    }
+}
+
+/// Controller entry point: runs `apply` for a live plan and `cleanup` for a deleted one, with
+/// `FINALIZER` keeping the plan around until the latter has finished.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        namespace = object.metadata.namespace.as_deref(),
+        name = object.metadata.name.as_deref(),
+        generation = object.metadata.generation,
     )
+)]
+async fn reconcile(
+    object: Arc<v1beta1::PlaybookPlan>,
+    context: Arc<ReconciliationContext>,
+) -> Result<Action, ReconcileError> {
+    let (namespace, name, _) = extract_resource_info(&object)?;
+
+    // Held until this reconcile returns, so its status write can't interleave with another one's.
+    let Some(_turn) = context
+        .object_locks
+        .enter(&format!("{namespace}/{name}"))
+        .await
+    else {
+        debug!("{namespace}/{name}: superseded by a newer trigger, leaving the reconcile to it");
+        return Ok(Action::await_change());
+    };
+
+    // Only a reconcile that did its work clears the plan's failure count; a superseded one didn't.
+    let key = ObjectRef::from_obj(&*object).to_string();
+    let result = reconcile_turn(object, Arc::clone(&context)).await;
+    if result.is_ok() {
+        context.error_backoff.reset(&key);
+    }
+    result
+}
+
+/// The part of `reconcile` that runs once it's the plan's turn.
+async fn reconcile_turn(
+    object: Arc<v1beta1::PlaybookPlan>,
+    context: Arc<ReconciliationContext>,
+) -> Result<Action, ReconcileError> {
+    use kube::ResourceExt as _;
+
+    let (namespace, name, _) = extract_resource_info(&object)?;
+
+    // A dry run doesn't add the finalizer, so there is nothing to hold a deleted plan back for.
+    if context.dry_run {
+        if object.metadata.deletion_timestamp.is_some() {
+            info!(
+                "[dry run] {namespace}/{name} is being deleted; would delete its Jobs and workspace {:?}",
+                object.spec.workspace_storage
+            );
+            return Ok(Action::await_change());
+        }
+        return apply(object, context).await;
+    }
+
+    // The finalizer is only managed in enrolled namespaces: elsewhere the operator may neither patch
+    // the plan nor touch Secrets/Jobs, and `apply`'s enrollment guard never let the plan run, so
+    // there is nothing to clean up either.
+    if !context.enrolled_namespaces.contains(namespace) {
+        if object.metadata.deletion_timestamp.is_some() {
+            if object.finalizers().iter().any(|f| f == FINALIZER) {
+                warn!(
+                    "{namespace}/{name} is being deleted but its namespace is no longer enrolled; remove the {FINALIZER} finalizer by hand"
+                );
+            }
+            return Ok(Action::await_change());
+        }
+        return apply(object, context).await;
+    }
+
+    let api = Api::<v1beta1::PlaybookPlan>::namespaced(context.client.clone(), namespace);
+    finalizer::finalizer(&api, FINALIZER, object, |event| async {
+        match event {
+            finalizer::Event::Apply(object) => apply(object, context.clone()).await,
+            finalizer::Event::Cleanup(object) => cleanup(object, context.clone()).await,
+        }
+    })
+    .await
+    .map_err(|e| match e {
+        finalizer::Error::ApplyFailed(e) | finalizer::Error::CleanupFailed(e) => e,
+        e => ReconcileError::Finalizer(Box::new(e)),
+    })
+}
+
+/// Tears down what a deleted plan leaves behind before its finalizer is removed. Its Jobs are
+/// deleted with foreground propagation, so a run in flight is stopped through the normal pod
+/// termination (grace period included) instead of being left to the garbage collector, and its
+/// workspace (Secret or ConfigMap) is deleted. A run in flight also holds host Leases and
+/// managed-ssh proxy infra in the operator namespace, which owner references can't reach, so those
+/// are released here too. Nothing waits for the pods to actually stop.
+async fn cleanup(
+    object: Arc<v1beta1::PlaybookPlan>,
+    context: Arc<ReconciliationContext>,
+) -> Result<Action, ReconcileError> {
+    let (namespace, name, _) = extract_resource_info(&object)?;
+
+    let jobs = delete_jobs(&context.client, namespace, name).await?;
+    let workspace_name = workspace::secret_name(name);
+    match object.spec.workspace_storage {
+        WorkspaceStorage::Secret => ignore_not_found(
+            Api::<Secret>::namespaced(context.client.clone(), namespace)
+                .delete(&workspace_name, &DeleteParams::default())
+                .await,
+        )?,
+        WorkspaceStorage::ConfigMap => ignore_not_found(
+            Api::<ConfigMap>::namespaced(context.client.clone(), namespace)
+                .delete(&workspace_name, &DeleteParams::default())
+                .await,
+        )?,
+    }
+
+    if let Some(status) = object
+        .status
+        .as_ref()
+        .filter(|s| s.phase == Phase::Applying)
+        && let Ok(hash) = status.current_hash.parse::<ExecutionHash>()
+    {
+        let leases_api =
+            Api::<Lease>::namespaced(context.client.clone(), &context.operator_namespace);
+        let holder_identity = format!("{namespace}/{name}/{hash}");
+        locking::release_locks(&leases_api, &find_all_hosts(status), &holder_identity).await?;
+        managed_ssh::cleanup_proxy_infra(
+            &context.client,
+            &context.operator_namespace,
+            namespace,
+            &hash,
+        )
+        .await?;
+    }
+
+    info!(
+        "{namespace}/{name} deleted; cleaned up Jobs {jobs:?} and its workspace {:?} {workspace_name}",
+        object.spec.workspace_storage
+    );
+    events::publish(&context.recorder, &object, events::cleaned_up(&jobs)).await;
+    // The plan is gone for good: its failure count must not outlive it.
+    context
+        .error_backoff
+        .reset(&ObjectRef::from_obj(&*object).to_string());
+
+    Ok(Action::await_change())
+}
+
+/// Deletes every Job of the plan `name` with foreground propagation. Returns their names.
+async fn delete_jobs(
+    client: &kube::Client,
+    namespace: &str,
+    name: &str,
+) -> Result<Vec<String>, ReconcileError> {
+    let jobs_api = Api::<Job>::namespaced(client.clone(), namespace);
+    let lp = ListParams::default().labels(&format!(
+        "{}={}",
+        labels::PLAYBOOKPLAN_NAME,
+        utils::label_value(name)
+    ));
+
+    let mut deleted = Vec::new();
+    for job in jobs_api.list_metadata(&lp).await? {
+        let Some(job_name) = job.metadata.name else {
+            continue;
+        };
+        ignore_not_found(
+            jobs_api
+                .delete(&job_name, &DeleteParams::foreground())
+                .await,
+        )?;
+        deleted.push(job_name);
+    }
+
+    Ok(deleted)
+}
+
+/// Deletes the finished Jobs of `plan_name` left over from earlier execution hashes (spec edits,
+/// Secret rotations), which are never looked at again, and returns their names. Jobs still running
+/// are left alone, as is `keep` (the Job of the run just evaluated). Background propagation: the
+/// pods are finished, so nothing needs to wait for them.
+async fn delete_stale_jobs(
+    jobs_api: &Api<Job>,
+    plan_name: &str,
+    current_hash: &ExecutionHash,
+    keep: &str,
+) -> Result<Vec<String>, ReconcileError> {
+    let lp = ListParams::default().labels(&format!(
+        "{}={}",
+        labels::PLAYBOOKPLAN_NAME,
+        utils::label_value(plan_name)
+    ));
+    let jobs = jobs_api.list(&lp).await?;
+
+    let mut deleted = Vec::new();
+    for job_name in stale_jobs(&jobs.items, current_hash, keep) {
+        info!("Deleting Job {job_name} of an earlier execution hash");
+        ignore_not_found(jobs_api.delete(job_name, &DeleteParams::background()).await)?;
+        deleted.push(job_name.to_string());
+    }
+
+    Ok(deleted)
+}
+
+/// The finished Jobs in `jobs` labelled with an execution hash other than `current_hash`, except
+/// `keep`. A Job without the hash label isn't one the operator can date, so it's never selected.
+fn stale_jobs<'a>(jobs: &'a [Job], current_hash: &ExecutionHash, keep: &str) -> Vec<&'a str> {
+    let current_hash = current_hash.to_string();
+
+    jobs.iter()
+        .filter(|job| {
+            job.metadata
+                .labels
+                .as_ref()
+                .and_then(|job_labels| job_labels.get(labels::PLAYBOOKPLAN_HASH))
+                .is_some_and(|hash| *hash != current_hash)
+        })
+        .filter(|job| status::job_finished(job))
+        .filter_map(|job| job.metadata.name.as_deref())
+        .filter(|name| *name != keep)
+        .collect()
+}
+
+/// A delete that found nothing to delete has still achieved what it was for.
+fn ignore_not_found<T>(result: Result<T, kube::Error>) -> Result<(), kube::Error> {
+    match result {
+        Err(kube::Error::Api(status)) if status.code == 404 => Ok(()),
+        result => result.map(|_| ()),
+    }
 }
 
 /// Reconciles one PlaybookPlan. Level-triggered/idempotent "ensure" style — every step re-derives
@@ -208,15 +585,11 @@ pub fn new(
 ///   (once the Job is finished: parse+record results, cleanup). A single tick can walk through
 ///   both halves — e.g. Pending -> locks acquired -> proxy ready -> Job created -> immediately
 ///   checked for completion — since nothing here is gated on a persisted step, only on `Phase`.
-async fn reconcile(
+async fn apply(
     object: Arc<v1beta1::PlaybookPlan>,
     context: Arc<ReconciliationContext>,
 ) -> Result<Action, ReconcileError> {
-    if object.metadata.deletion_timestamp.is_some() {
-        return Ok(Action::await_change());
-    }
-
-    let (namespace, name, _) = extract_resource_info(&object)?;
+    let (namespace, name, generation) = extract_resource_info(&object)?;
 
     let api = Api::<v1beta1::PlaybookPlan>::namespaced(context.client.clone(), namespace);
 
@@ -229,7 +602,9 @@ async fn reconcile(
         warn!(
             "PlaybookPlan {namespace}/{name} is in a namespace not enrolled for ansible-operator; refusing to run (add it to the chart's watchNamespaces)"
         );
-        if object.status.as_ref().map(|s| &s.phase) != Some(&Phase::UnauthorizedNamespace) {
+        if object.status.as_ref().map(|s| &s.phase) != Some(&Phase::UnauthorizedNamespace)
+            && !context.dry_run
+        {
             let mut status = object.status.clone().unwrap_or_default();
             status.phase = Phase::UnauthorizedNamespace;
             status.summary = Some(format!(
@@ -241,21 +616,68 @@ async fn reconcile(
     }
 
     let secrets_api = Api::<Secret>::namespaced(context.client.clone(), namespace);
+    let config_maps_api = Api::<ConfigMap>::namespaced(context.client.clone(), namespace);
 
-    let mut requeue_after = std::time::Duration::from_secs(3600);
+    let mut requeue_after = context.intervals.requeue;
     let mut resource_status = object.status.clone().unwrap_or_default();
+    // Set up front so every condition computed below carries it, but only persisted by the final
+    // `patch_status` — a reconcile that bails out early with an error leaves the old value in place.
+    resource_status.observed_generation = Some(generation);
+
+    let invalid_files = job_builder::invalid_files(&object);
+    if !invalid_files.is_empty() {
+        warn!("{namespace}/{name} has invalid files volumes: {invalid_files:?}");
+    }
+    status::set_files_valid_condition(&mut resource_status, &invalid_files);
+
+    // A spec that can never work (see `validation`) isn't worth resolving inventory for; report it
+    // and wait for the edit that fixes it. A run already in flight is left to finish first, unless
+    // its schedule or time zone is what's broken: finishing it means forecasting the next run. A
+    // playbook that doesn't render is the exception for plans that run it: it's reported below,
+    // with the rest of the status still computed, like one from `playbookFrom` that can only be
+    // checked there.
+    let runs_playbooks = !matches!(object.spec.mode, ExecutionMode::InventoryOnly);
+    let errors = super::validate(&object);
+    let invalid = match resource_status.phase {
+        Phase::Applying => errors
+            .iter()
+            .find(|error| error.breaks_schedule())
+            .map(ToString::to_string),
+        _ => errors
+            .iter()
+            .find(|error| !(runs_playbooks && matches!(error, ValidationError::Playbook(_))))
+            .map(ToString::to_string)
+            .or_else(|| unsupported_when_confined(&object, context.watch_namespace.as_deref()))
+            .or_else(|| unsupported_by_cluster(&object, context.image_volumes)),
+    };
+    if let Some(invalid) = invalid {
+        warn!("{namespace}/{name} has an invalid spec: {invalid}");
+        if !context.dry_run {
+            status::evaluate_degraded_condition(&mut resource_status, Some(&invalid), None);
+            patch_status(&api, &object, resource_status).await?;
+        }
+        return Ok(Action::await_change());
+    }
 
     // Step 0: resolve inventory (kept separate per-resource, not flattened — connection
     // mechanism is implicit by which resource produced a group).
     let mut target_groups = resolve_inventory(&context, &object).await?;
+    // An `InventoryOnly` plan never connects to its hosts, so their SSH keys are none of its concern.
+    let inventory_only = matches!(object.spec.mode, ExecutionMode::InventoryOnly);
+    let ssh_secret_problem = match inventory_only {
+        true => None,
+        false => check_ssh_secrets(&secrets_api, &mut target_groups).await?,
+    };
 
     // Step 0b: NodeAccessPolicy enforcement — clamp managed-ssh (ClusterInventory) nodes to what
     // this namespace is permitted to target, before eligible_hosts and any proxy infra derive from
     // them. Fail-closed: an ungoverned namespace resolves to zero managed-ssh nodes.
+    let nodes = node_access::list_nodes(&context.client, &target_groups).await?;
     let excluded_nodes = node_access::enforce(
         &context.client,
         &context.node_access_policies,
         namespace,
+        &nodes,
         &mut target_groups,
     )
     .await?;
@@ -266,8 +688,40 @@ async fn reconcile(
         );
     }
 
+    // Step 0c: the plan's own include/exclude guard, after policy clamping so it sees (and reports)
+    // only hosts the plan could otherwise have reached.
+    let excluded_hosts = host_filter::apply(&object, &mut target_groups);
+    if !excluded_hosts.is_empty() {
+        info!("{namespace}/{name} excludes hosts {excluded_hosts:?} via includeHosts/excludeHosts");
+    }
+    resource_status.excluded_hosts = excluded_hosts;
+
     resource_status.eligible_hosts = flatten_hosts(&target_groups);
 
+    if status::reset_hosts_on_generation_change(
+        &mut resource_status,
+        generation,
+        object.spec.reset_host_status_on_change,
+    ) {
+        info!(
+            "{namespace}/{name} changed to generation {generation}; reset the state of all hosts"
+        );
+    }
+
+    if object.prunes_removed_hosts() {
+        let pruned = status::prune_removed_hosts(&mut resource_status);
+        if !pruned.is_empty() {
+            info!("{namespace}/{name} dropped the status of hosts no longer eligible: {pruned:?}");
+            if !context.dry_run {
+                events::publish(&context.recorder, &object, events::hosts_pruned(&pruned)).await;
+            }
+        }
+    }
+
+    if inventory_only {
+        return publish_inventory(&context, &api, &object, &target_groups, resource_status).await;
+    }
+
     // Inventory-author group variables are part of the execution hash (a change re-applies the
     // playbook to otherwise-current hosts). Keyed by group name; groups without variables
     // contribute nothing, so inventories that set none hash exactly as before.
@@ -280,14 +734,69 @@ async fn reconcile(
         })
         .collect();
 
+    let playbook = playbook_source::resolve(&object, &secrets_api, &config_maps_api).await?;
+    let image = image_source::resolve(&object, &config_maps_api).await?;
+    resource_status.resolved_image = Some(image.clone());
+    let role_files = role_sources::resolve(&object, &secrets_api, &config_maps_api).await?;
     let related_secrets = get_related_secrets(&object);
-    let execution_hash = hash_playbook_inputs(
-        &object.spec.template.playbook,
+    let related_config_maps: Vec<&String> =
+        job_builder::extract_config_map_names_for_variables(&object)
+            .chain(job_builder::extract_config_map_names_for_roles(&object))
+            .collect();
+    let variables_secret_keys: Vec<(&String, &str)> =
+        job_builder::extract_secrets_for_variables(&object)
+            .chain(job_builder::extract_secrets_for_group_variables(&object))
+            .collect();
+    let (execution_hash, missing) = hash_playbook_inputs(
+        &playbook,
         &related_secrets,
+        &variables_secret_keys,
         &secrets_api,
+        &related_config_maps,
+        &config_maps_api,
         &inventory_variables,
     )
-    .await;
+    .await?;
+    let node_metadata: BTreeMap<&str, &NodeMetadata> = target_groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .hosts()
+                .hosts
+                .iter()
+                .filter_map(|host| Some((host.as_str(), group.node_metadata(host)?)))
+        })
+        .collect();
+    let execution_hash = execution_hash
+        .fold_verify_playbook(object.spec.template.verify_playbook.as_deref())
+        .fold_start_at_task(object.spec.start_at_task.as_deref())
+        .fold_render_template(object.spec.template.render_template)
+        .fold_node_metadata(node_metadata.into_values());
+    let execution_hash = stable_execution_hash(
+        execution_hash,
+        &[missing.secrets.as_slice(), &missing.config_maps].concat(),
+        &resource_status.current_hash,
+    );
+    let secret_problem = if !missing.secrets.is_empty() {
+        Some(status::SecretProblem::Unresolved {
+            secrets: missing.secrets,
+        })
+    } else if !missing.config_maps.is_empty() {
+        Some(status::SecretProblem::UnresolvedConfigMaps {
+            config_maps: missing.config_maps,
+        })
+    } else if let Some((secret, key)) = missing.secret_keys.into_iter().next() {
+        Some(status::SecretProblem::MissingVariablesKey { secret, key })
+    } else {
+        ssh_secret_problem
+    };
+    status::set_dependencies_ready_condition(&mut resource_status, secret_problem.as_ref());
+
+    // A playbook that doesn't render would only fail the run's start over and over; report it and
+    // start nothing until it's fixed.
+    let playbook_problem = workspace::render_playbook(&object, &playbook, &execution_hash)
+        .err()
+        .map(|err| format!("the playbook is invalid: {err}"));
 
     if resource_status.current_hash != execution_hash.to_string() {
         resource_status.phase = Phase::Pending;
@@ -300,23 +809,51 @@ async fn reconcile(
     }
 
     // Step 1: compute outdated hosts / evaluate schedule — unchanged from before.
-    let tz = object.timezone().unwrap();
+    let tz = object
+        .timezone()
+        .map_err(|_| ReconcileError::PreconditionFailed("timeZone is invalid"))?;
     let now = || Utc::now().with_timezone(&tz);
-    let time_window = chrono::Duration::seconds(
-        object
-            .spec
-            .starting_deadline_seconds
-            .unwrap_or(DEFAULT_STARTING_DEADLINE_SECONDS)
-            .into(),
-    );
-    let timing = evaluate_schedule(object.spec.schedule.as_deref(), now(), time_window);
+    let time_window = match object.spec.starting_deadline_seconds {
+        Some(seconds) => chrono::Duration::seconds(seconds.into()),
+        None => chrono::Duration::from_std(context.intervals.schedule_window)
+            .expect("--schedule-window is bounded when parsed"),
+    };
+    let timing = match object.spec.interval_seconds {
+        Some(_) => evaluate_interval(resource_status.next_run, now()),
+        None => evaluate_schedule(
+            object.spec.schedule.as_deref(),
+            now(),
+            time_window,
+            schedule_jitter(&object),
+        )
+        .ok_or(ReconcileError::PreconditionFailed("schedule never fires"))?,
+    };
     let outdated_hosts = find_outdated_hosts(&resource_status, &execution_hash)?;
     let all_hosts = find_all_hosts(&resource_status);
 
-    let hosts_to_trigger = match object.spec.mode {
-        ExecutionMode::OneShot => outdated_hosts.clone(),
-        ExecutionMode::Recurring => all_hosts.clone(),
-    };
+    let mut hosts_to_trigger = select_hosts_to_trigger(
+        &object.spec.mode,
+        object.spec.recurring_targets,
+        &outdated_hosts,
+        &all_hosts,
+    );
+
+    // Step 1b: `skipUnschedulableNodes` leaves cordoned nodes out, since their proxy pod could never
+    // be scheduled. Decided only while no run is in flight, so a run keeps the host set it started
+    // with even if a node is cordoned or uncordoned meanwhile.
+    if resource_status.phase != Phase::Applying {
+        resource_status.skipped_hosts = if object.spec.skip_unschedulable_nodes {
+            let (managed_ssh_hosts, _) = managed_ssh_hosts_and_tolerations(&target_groups);
+            let candidates: Vec<String> = managed_ssh_hosts
+                .into_iter()
+                .filter(|host| hosts_to_trigger.contains(host))
+                .collect();
+            managed_ssh::unschedulable_nodes(&nodes, &candidates)
+        } else {
+            Vec::new()
+        };
+    }
+    hosts_to_trigger.retain(|host| !resource_status.skipped_hosts.contains(host));
 
     // Filter the resolved inventory to this run's hosts once, preserving the user's groups, so the
     // Job/proxy/render path and the Play history record share one grouped view.
@@ -330,20 +867,58 @@ async fn reconcile(
         hosts_to_trigger: &hosts_to_trigger,
         run_groups: &run_groups,
         holder_identity: &holder_identity,
+        playbook: &playbook,
+        image: &image,
+        role_files: &role_files,
     };
 
-    let eligible_to_start = is_eligible_to_start(
-        object.spec.suspend,
-        &object.spec.mode,
-        object.spec.schedule.is_some(),
-        !hosts_to_trigger.is_empty(),
-    );
+    // `failurePolicy: Halt` after a failed run: a changed hash resets the phase to `Pending` above,
+    // and switching the policy back to `Continue` lifts the halt as well.
+    let halted =
+        resource_status.phase == Phase::Halted && object.spec.failure_policy == FailurePolicy::Halt;
+    let eligible_to_start = !halted
+        && is_eligible_to_start(
+            object.spec.suspend,
+            &object.spec.mode,
+            object.spec.schedule.is_some() || object.spec.interval_seconds.is_some(),
+            !hosts_to_trigger.is_empty(),
+        );
+
+    // Everything above only read the cluster; everything below may write to it.
+    if context.dry_run {
+        for action in dry_run_actions(&run, &object, &resource_status, &timing, eligible_to_start) {
+            info!("[dry run] {namespace}/{name}: {action}");
+        }
+        return Ok(Action::requeue(match timing {
+            Timing::Delayed(until) => duration_until_or_zero(until, now()),
+            Timing::Now(_) => requeue_after,
+        }));
+    }
 
-    if eligible_to_start && resource_status.phase != Phase::Applying {
+    if eligible_to_start
+        && resource_status.phase != Phase::Applying
+        && let Some(problem) = &playbook_problem
+    {
+        // Like a missing Secret below, the fix (an edit of the plan or of its `playbookFrom`
+        // source) re-triggers this plan.
+        warn!("{namespace}/{name} not started: {problem}");
+    } else if eligible_to_start
+        && resource_status.phase != Phase::Applying
+        && let Some(problem) = &secret_problem
+    {
+        // Nothing to poll for: creating or fixing the Secret (or ConfigMap) re-triggers this plan
+        // through `mappers::secret_to_playbookplans` (`config_map_to_playbookplans`). `Degraded`
+        // below reports it.
+        warn!("{namespace}/{name} not started: {problem}");
+    } else if eligible_to_start && resource_status.phase != Phase::Applying {
         match timing {
             Timing::Delayed(until) => {
-                requeue_after = (until - now()).to_std().unwrap();
+                requeue_after = duration_until_or_zero(until, now());
                 resource_status.phase = Phase::Scheduled;
+                if resource_status.next_run != Some(until.fixed_offset()) {
+                    let event = events::schedule_delayed(until.fixed_offset());
+                    events::publish(&context.recorder, &object, event).await;
+                }
                 resource_status.next_run = Some(until.fixed_offset());
             }
             Timing::Now(start) => {
@@ -354,10 +929,15 @@ async fn reconcile(
                     // `evaluate_schedule` keeps returning `Now` for the rest of that window, so
                     // don't start another — sleep until the next slot instead. Without this a run
                     // that finishes inside its own grace window is immediately re-triggered.
-                    if let Some(schedule) = object.spec.schedule.as_deref() {
-                        let next =
-                            forecast_next_run(schedule, now(), Some(chrono::Duration::seconds(-5)));
-                        requeue_after = (next - now()).to_std().unwrap_or_default();
+                    if let Some(schedule) = object.spec.schedule.as_deref()
+                        && let Some(next) = forecast_next_run(
+                            schedule,
+                            now(),
+                            Some(chrono::Duration::seconds(-5)),
+                            schedule_jitter(&object),
+                        )
+                    {
+                        requeue_after = duration_until_or_zero(next, now());
                         resource_status.next_run = Some(next.fixed_offset());
                     }
                 } else if let Some(d) =
@@ -370,6 +950,14 @@ async fn reconcile(
                     // re-trigger inside its grace window. `None` for unscheduled plans, which have
                     // no slot and are never suppressed.
                     resource_status.last_triggered_run = this_slot;
+
+                    let skipped = resource_status.skipped_hosts.clone();
+                    if !skipped.is_empty() {
+                        info!("{namespace}/{name} skips cordoned nodes {skipped:?} in this run");
+                        status::record_skipped_hosts(&mut resource_status, &skipped, now());
+                        let event = events::hosts_skipped(&skipped);
+                        events::publish(&context.recorder, &object, event).await;
+                    }
                 }
             }
         };
@@ -386,16 +974,110 @@ async fn reconcile(
     // step so it also clears the next slot a just-finished Recurring run would have set. A run still
     // in progress is untouched (it has no `nextRun` anyway) and is left to finish; the phase keeps
     // reflecting the plan's real state, with the `Suspended` printer column (from `.spec.suspend`)
-    // signalling the pause. The schedule path recomputes `nextRun` once the plan resumes.
+    // and condition signalling the pause. The schedule path recomputes `nextRun` once the plan
+    // resumes.
     if object.spec.suspend {
         resource_status.next_run = None;
     }
+    status::set_suspended_condition(&mut resource_status, object.spec.suspend);
+
+    status::record_host_groups(&mut resource_status);
+    status::evaluate_progress(&mut resource_status);
+    let still_outdated = find_outdated_hosts(&resource_status, &run.execution_hash)?;
+    status::evaluate_playbook_ready(&mut resource_status, playbook_problem.as_deref());
+    status::mark_ready_outdated(&mut resource_status, &still_outdated);
+    status::evaluate_degraded_condition(
+        &mut resource_status,
+        playbook_problem.as_deref(),
+        secret_problem.as_ref(),
+    );
 
-    patch_status(&api, &object, resource_status).await?;
+    // `patch_status` already retries conflicts; one that outlasts those retries means another writer
+    // keeps getting in first. Not worth an error log — come back shortly and recompute from scratch.
+    match patch_status(&api, &object, resource_status).await {
+        Err(ReconcileError::KubeError(e)) if utils::is_conflict(&e) => {
+            debug!("Status of {namespace}/{name} changed concurrently; requeueing");
+            return Ok(Action::requeue(STATUS_CONFLICT_REQUEUE));
+        }
+        result => result?,
+    }
 
     Ok(Action::requeue(requeue_after))
 }
 
+/// What `apply` would go on to do for `run` if this weren't a dry run, one line each: start the run
+/// or schedule it, and write the status it computed so far.
+fn dry_run_actions<Tz: chrono::TimeZone>(
+    run: &RunContext<'_>,
+    object: &PlaybookPlan,
+    status: &PlaybookPlanStatus,
+    timing: &Timing<Tz>,
+    eligible_to_start: bool,
+) -> Vec<String> {
+    let mut actions = Vec::new();
+
+    if status.phase == Phase::Applying {
+        let job = status.current_job_name.as_deref().unwrap_or("<unknown>");
+        actions.push(format!(
+            "would follow the run of Job {job} until it finishes"
+        ));
+    } else if eligible_to_start {
+        match timing {
+            Timing::Delayed(until) => actions.push(format!(
+                "would schedule the next run for {}",
+                until.fixed_offset().to_rfc3339()
+            )),
+            Timing::Now(_) => {
+                let job = job_builder::create_job_for_run(
+                    &run.execution_hash,
+                    status.retry_count + 1,
+                    run.run_groups,
+                    object,
+                    run.image,
+                    run.role_files,
+                )
+                .ok()
+                .and_then(|job| job.metadata.name)
+                .unwrap_or_else(|| "<unnamed>".into());
+                let (managed_ssh_hosts, _) = managed_ssh_hosts_and_tolerations(run.run_groups);
+
+                actions.push(format!(
+                    "would lock host(s) {}",
+                    run.hosts_to_trigger.join(", ")
+                ));
+                if !managed_ssh_hosts.is_empty() {
+                    actions.push(format!(
+                        "would start managed-ssh proxy pods for {}",
+                        managed_ssh_hosts.join(", ")
+                    ));
+                }
+                actions.push(format!(
+                    "would render workspace {:?} {} and create Job {job} running {}",
+                    object.spec.workspace_storage,
+                    workspace::secret_name(run.name),
+                    run.image
+                ));
+                if object.spec.publish_inventory {
+                    actions.push(format!(
+                        "would publish the inventory in ConfigMap {}",
+                        workspace::inventory_config_map_name(run.name)
+                    ));
+                }
+            }
+        }
+    } else {
+        actions.push("would start no run".into());
+    }
+
+    actions.push(format!(
+        "would write status: phase {:?}, currentHash {}, {} host(s) to run",
+        status.phase,
+        status.current_hash,
+        run.hosts_to_trigger.len()
+    ));
+    actions
+}
+
 /// Whether the current schedule slot (`start`, the grace window's start) already had a run started
 /// for it, per the persisted `last_triggered_run`. Unscheduled ticks carry no slot (`None`) and are
 /// never suppressed — there is nothing to dedupe against. `DateTime` equality compares instants, so
@@ -407,6 +1089,39 @@ fn slot_already_triggered(
     start.is_some() && start == last_triggered_run
 }
 
+/// The hosts a run started now would target: the outdated ones for `OneShot`, and for `Recurring`
+/// every host or, with `recurringTargets: OutdatedOnly`, again only the outdated ones.
+fn select_hosts_to_trigger(
+    mode: &ExecutionMode,
+    recurring_targets: RecurringTargets,
+    outdated_hosts: &[String],
+    all_hosts: &[String],
+) -> Vec<String> {
+    match (mode, recurring_targets) {
+        (ExecutionMode::OneShot, _) => outdated_hosts.to_vec(),
+        (ExecutionMode::Recurring, RecurringTargets::AllHosts) => all_hosts.to_vec(),
+        (ExecutionMode::Recurring, RecurringTargets::OutdatedOnly) => outdated_hosts.to_vec(),
+        (ExecutionMode::InventoryOnly, _) => Vec::new(),
+    }
+}
+
+/// The mode whose terminal outcome a finished run gets. A `Recurring` plan with `recurringTargets:
+/// OutdatedOnly` whose hosts are all current has nothing for its next tick to do, so it settles like
+/// a `OneShot` plan instead of advertising a next run that would start nothing; the next hash
+/// change schedules it again.
+fn terminal_mode(
+    mode: &ExecutionMode,
+    recurring_targets: RecurringTargets,
+    outdated_count: usize,
+) -> &ExecutionMode {
+    match (mode, recurring_targets) {
+        (ExecutionMode::Recurring, RecurringTargets::OutdatedOnly) if outdated_count == 0 => {
+            &ExecutionMode::OneShot
+        }
+        _ => mode,
+    }
+}
+
 /// Whether a run is eligible to *start* this tick, from whether the plan is suspended plus the mode,
 /// whether a schedule is set, and whether any hosts still need triggering. Pure so the gating is
 /// unit-testable — in particular the invariants that a suspended plan never starts and that a
@@ -421,7 +1136,11 @@ fn slot_already_triggered(
 ///     hosts up-to-date, so an outdated-based gate would fire once and never again). It's gated only
 ///     on having a schedule to tick on; slot dedup via `last_triggered_run` is what stops a single
 ///     tick from starting more than one run, and without a schedule there'd be no slot to dedup
-///     against — it would busy-loop. That's why the schedule check lives here.
+///     against — it would busy-loop. That's why the schedule check lives here. `intervalSeconds`
+///     counts as a schedule: the `next_run` set an interval after each finished run (see
+///     `triggers::evaluate_interval`) is what keeps it from re-triggering straight away. With
+///     `recurringTargets: OutdatedOnly` its hosts to trigger are the outdated ones, so it goes
+///     quiet like OneShot once they are all current.
 fn is_eligible_to_start(
     suspended: bool,
     mode: &ExecutionMode,
@@ -433,7 +1152,53 @@ fn is_eligible_to_start(
         && match mode {
             ExecutionMode::OneShot => true,
             ExecutionMode::Recurring => has_schedule,
+            ExecutionMode::InventoryOnly => false,
+        }
+}
+
+/// The whole reconcile of an `InventoryOnly` plan once its inventories are resolved into
+/// `target_groups` and `resource_status.eligible_hosts`: record them, publish their inventory in
+/// the plan's inventory ConfigMap and come back after `INVENTORY_REFRESH_INTERVAL`. No playbook,
+/// image, lock, proxy pod, workspace Secret or Job is ever looked at; managed-ssh hosts, having no
+/// proxy pod, are published without an address.
+async fn publish_inventory(
+    context: &ReconciliationContext,
+    api: &Api<PlaybookPlan>,
+    object: &PlaybookPlan,
+    target_groups: &[ResolvedInventoryGroup],
+    mut resource_status: PlaybookPlanStatus,
+) -> Result<Action, ReconcileError> {
+    let (namespace, name, _) = extract_resource_info(object)?;
+    let hosts = find_all_hosts(&resource_status);
+
+    if context.dry_run {
+        info!(
+            "[dry run] {namespace}/{name}: would publish inventory hosts {hosts:?} in ConfigMap {}",
+            workspace::inventory_config_map_name(name)
+        );
+        return Ok(Action::requeue(INVENTORY_REFRESH_INTERVAL));
+    }
+
+    let inventory = workspace::render_inventory(target_groups, &BTreeMap::new())?;
+    let config_maps_api = Api::<ConfigMap>::namespaced(context.client.clone(), namespace);
+    upsert_inventory_config_map(
+        &config_maps_api,
+        workspace::render_inventory_config_map(object, inventory),
+    )
+    .await?;
+
+    resource_status.phase = Phase::InventoryResolved;
+    resource_status.summary = Some(format!("{} host(s) resolved", hosts.len()));
+    resource_status.next_run = None;
+    status::evaluate_inventory_only_conditions(&mut resource_status);
+    status::evaluate_degraded_condition(&mut resource_status, None, None);
+
+    match patch_status(api, object, resource_status).await {
+        Err(ReconcileError::KubeError(e)) if utils::is_conflict(&e) => {
+            Ok(Action::requeue(STATUS_CONFLICT_REQUEUE))
         }
+        result => result.map(|()| Action::requeue(INVENTORY_REFRESH_INTERVAL)),
+    }
 }
 
 /// Steps 2-5: acquire this run's per-host locks (all-or-nothing, renewed every tick for as long
@@ -453,6 +1218,15 @@ async fn try_start_run(
 
     let run_groups = run.run_groups;
 
+    // Before taking any locks, so a deferred run doesn't hold its hosts from other plans' runs. A
+    // run that took them on an earlier tick and is waiting for its proxy pods isn't deferred: it
+    // would keep holding them while it waits.
+    if !status::is_waiting_for_nodes(resource_status)
+        && let Some(requeue) = throttle(context, resource_status).await?
+    {
+        return Ok(Some(requeue));
+    }
+
     if let Some(blocked) =
         locking::ensure_locks(&leases_api, run.hosts_to_trigger, run.holder_identity).await?
     {
@@ -537,26 +1311,97 @@ async fn try_start_run(
 
     // Proxy pod IPs are fresh every run even with an unchanged spec, so rendering is also
     // triggered on "a run is starting now", not generation alone.
-    if workspace::is_missing(&secrets_api, run.name).await? || workspace::is_outdated(object, true)
-    {
-        debug!("Rendering playbook to secret");
-        upsert_workspace_secret(
-            &secrets_api,
-            run.name,
-            render_secret(object, run_groups, &managed_ssh_hosts_map)?,
-        )
-        .await?;
+    let workspace_name = workspace::secret_name(run.name);
+    let config_maps_api = Api::<ConfigMap>::namespaced(context.client.clone(), run.namespace);
+    let workspace_missing = match object.spec.workspace_storage {
+        WorkspaceStorage::Secret => workspace::is_missing(&secrets_api, &workspace_name).await?,
+        WorkspaceStorage::ConfigMap => {
+            workspace::is_missing(&config_maps_api, &workspace_name).await?
+        }
+    };
+    let mut workspace_hash = None;
+    if workspace_missing || workspace::is_outdated(object, true) {
+        debug!("Rendering playbook to the workspace");
+        let rendered = workspace::render(
+            object,
+            run.playbook,
+            &run.execution_hash,
+            run_groups,
+            &managed_ssh_hosts_map,
+        )?;
+        workspace_hash = rendered.content_hash().map(str::to_string);
+        let inventory = rendered.file("inventory.yml").map(str::to_string);
+        match rendered {
+            Workspace::Secret(secret) => {
+                upsert_workspace_secret(&secrets_api, &workspace_name, secret).await?
+            }
+            Workspace::ConfigMap(config_map) => {
+                upsert_workspace_config_map(&config_maps_api, config_map).await?
+            }
+        }
+        if object.spec.publish_inventory
+            && let Some(inventory) = inventory
+        {
+            let config_map = workspace::render_inventory_config_map(object, inventory);
+            upsert_inventory_config_map(&config_maps_api, config_map).await?;
+        }
+        if resource_status.last_rendered_generation != object.metadata.generation {
+            // A spec change is the only way `workspaceStorage` changes, so only then can the plan
+            // have a workspace of the other kind left over.
+            delete_unused_workspace(object, &secrets_api, &config_maps_api, &workspace_name)
+                .await?;
+            let event = events::playbook_rendered(object.metadata.generation);
+            events::publish(&context.recorder, object, event).await;
+            if object.spec.workspace_storage == WorkspaceStorage::ConfigMap
+                && let Some(event) =
+                    events::secrets_in_config_map(&workspace::secret_looking_variables(object))
+            {
+                events::publish(&context.recorder, object, event).await;
+            }
+        }
         resource_status.last_rendered_generation = object.metadata.generation;
+
+        // Best-effort, like deleting stale Jobs: an orphaned workspace is clutter, not a fault.
+        let plans_api = Api::<PlaybookPlan>::namespaced(context.client.clone(), run.namespace);
+        match workspace::delete_orphans(&secrets_api, &plans_api, run.name).await {
+            Ok(deleted) if !deleted.is_empty() => {
+                info!("Deleted orphaned workspace Secret(s) {deleted:?}");
+            }
+            Ok(_) => {}
+            Err(e) => warn!("failed to delete orphaned workspace Secrets: {e}"),
+        }
+        match workspace::delete_orphans(&config_maps_api, &plans_api, run.name).await {
+            Ok(deleted) if !deleted.is_empty() => {
+                info!("Deleted orphaned workspace ConfigMap(s) {deleted:?}");
+            }
+            Ok(_) => {}
+            Err(e) => warn!("failed to delete orphaned workspace ConfigMaps: {e}"),
+        }
     }
 
-    spawn_ansible_job(
+    let previous_job_name = resource_status.current_job_name.clone();
+    let replaced = spawn_ansible_job(
         &jobs_api,
         run.execution_hash,
         run_groups,
         object,
+        run.image,
+        run.role_files,
+        workspace_hash.as_deref(),
+        &context.selector_label_keys,
         resource_status,
     )
     .await?;
+    if let Some(replaced) = replaced {
+        play_history::discard(&context.client, run.namespace, &replaced).await?;
+    }
+
+    if let Some(job_name) = resource_status.current_job_name.as_deref()
+        && resource_status.current_job_name != previous_job_name
+    {
+        let event = events::job_created(object, job_name, run.hosts_to_trigger);
+        events::publish(&context.recorder, object, event).await;
+    }
 
     // Record this attempt as a Play (history), named after the Job spawn just settled on. The
     // attempt number is `retry_count`, which `spawn_ansible_job` set for exactly this Job.
@@ -616,7 +1461,7 @@ async fn advance_applying_run(
             None,
             resource_status,
         );
-        return Ok(Some(std::time::Duration::from_secs(15)));
+        return Ok(Some(JOB_RUNNING_REQUEUE));
     }
 
     // The Job either finished, or is already gone — reaped by Kubernetes' TTL controller (its result
@@ -626,7 +1471,7 @@ async fn advance_applying_run(
     // a reaped run from wedging in `Applying` forever. The recap comes from the container's
     // termination message (what the callback wrote to /dev/termination-log), not logs — a dedicated
     // channel that isn't interleaved with playbook output and needs no `pods/log` access.
-    let parsed = match &job {
+    let pods = match &job {
         Some(_) => {
             let pods_api: Api<Pod> = Api::namespaced(context.client.clone(), run.namespace);
             pods_api
@@ -636,18 +1481,37 @@ async fn advance_applying_run(
                 })
                 .await?
                 .items
-                .iter()
-                .find_map(termination_message)
-                .as_deref()
-                .and_then(callback_output::parse_callback_output)
         }
-        None => None,
+        None => Vec::new(),
+    };
+    let recap_of = |container: &str| {
+        pods.iter()
+            .find_map(|pod| termination_message(pod, container))
+            .as_deref()
+            .and_then(callback_output::parse_callback_output)
     };
+    let parsed = recap_of(job_builder::ANSIBLE_CONTAINER_NAME);
+
+    // The preflight's recap only says who answered a ping; it never counts as the playbook having
+    // run on a host, so it stays out of the host outcomes.
+    if object.spec.preflight {
+        let preflight = recap_of(job_builder::PREFLIGHT_CONTAINER_NAME);
+        status::set_reachable_condition(resource_status, preflight.as_ref());
+    }
 
     status::evaluate_host_outcomes(
         run.hosts_to_trigger,
         parsed.as_ref(),
         &run.execution_hash,
+        &job_name,
+        job.as_ref(),
+        resource_status,
+    );
+    status::record_run_times(
+        run.hosts_to_trigger,
+        &job_name,
+        job.as_ref(),
+        Utc::now().fixed_offset(),
         resource_status,
     );
     status::evaluate_playbookplan_conditions(
@@ -656,6 +1520,10 @@ async fn advance_applying_run(
         parsed.as_ref(),
         resource_status,
     );
+    let failed_hosts = status::hosts_failed_in(&job_name, run.hosts_to_trigger, resource_status);
+    if let Some(event) = events::job_failed(object, &job_name, &failed_hosts) {
+        events::publish(&context.recorder, object, event).await;
+    }
 
     // Stamp the terminal recap onto this attempt's Play (durable run history), then prune old ones.
     let inventory = flatten_hosts(run.run_groups);
@@ -684,6 +1552,21 @@ async fn advance_applying_run(
     .await?;
     locking::release_locks(&leases_api, run.hosts_to_trigger, run.holder_identity).await?;
 
+    // Best-effort: a Job left behind is only clutter, and its TTL still reaps it eventually.
+    match delete_stale_jobs(&jobs_api, run.name, &run.execution_hash, &job_name).await {
+        Ok(deleted) => {
+            for job in deleted {
+                events::publish(
+                    &context.recorder,
+                    object,
+                    events::stale_job_deleted(object, &job),
+                )
+                .await;
+            }
+        }
+        Err(e) => warn!("failed to delete Jobs of earlier runs of {}: {e}", run.name),
+    }
+
     let total_count: usize = resource_status
         .eligible_hosts
         .iter()
@@ -691,20 +1574,44 @@ async fn advance_applying_run(
         .sum();
     let outdated_count = find_outdated_hosts(resource_status, &run.execution_hash)?.len();
 
-    // Recurring with no schedule can't reschedule; the eligibility gate normally stops such a plan
-    // from ever starting, so reaching here means the schedule was removed mid-run. Log the anomaly —
-    // `decide_terminal` deliberately leaves the plan in `Applying` for this case.
-    if matches!(object.spec.mode, ExecutionMode::Recurring) && object.spec.schedule.is_none() {
-        warn!("Mode is Recurring but schedule is not set!");
+    // Recurring with neither schedule nor interval can't reschedule; the eligibility gate normally
+    // stops such a plan from ever starting, so reaching here means both were removed mid-run. Log the
+    // anomaly — `decide_terminal` deliberately leaves the plan in `Applying` for this case.
+    if matches!(object.spec.mode, ExecutionMode::Recurring)
+        && object.spec.schedule.is_none()
+        && object.spec.interval_seconds.is_none()
+    {
+        warn!("Mode is Recurring but neither schedule nor intervalSeconds is set!");
     }
 
-    let outcome = decide_terminal(
-        &object.spec.mode,
+    let tz = object
+        .timezone()
+        .map_err(|_| ReconcileError::PreconditionFailed("timeZone is invalid"))?;
+    let outcome = status::decide_terminal(
+        terminal_mode(
+            &object.spec.mode,
+            object.spec.recurring_targets,
+            outdated_count,
+        ),
         object.spec.schedule.as_deref(),
+        schedule_jitter(object),
+        object.interval(),
         outdated_count,
         total_count,
-        Utc::now().with_timezone(&object.timezone().unwrap()),
+        Utc::now().with_timezone(&tz),
     );
+    let outcome = match object.spec.failure_policy {
+        FailurePolicy::Continue => outcome,
+        FailurePolicy::Halt => status::halt_on_failure(outcome, &failed_hosts),
+    };
+    if outcome.phase == Phase::Halted {
+        events::publish(
+            &context.recorder,
+            object,
+            events::rollout_halted(&failed_hosts),
+        )
+        .await;
+    }
 
     resource_status.summary = Some(outcome.summary);
     resource_status.phase = outcome.phase;
@@ -713,78 +1620,18 @@ async fn advance_applying_run(
     Ok(outcome.requeue)
 }
 
-/// The terminal-state decision for a finished run: what the plan's `phase`, `next_run`, `summary`,
-/// and the caller's requeue duration become once this run's Job has reached a terminal state. Pure
-/// (every wall-clock/inventory input is passed in) so the per-mode matrix is unit-testable without a
-/// kube client:
-///   - OneShot resolves to `Succeeded`/`Failed` solely by whether any host is still outdated and
-///     never reschedules.
-///   - Recurring with a schedule reschedules to the next slot and requeues until then.
-///   - Recurring *without* a schedule is the dead-end the eligibility gate normally prevents (the
-///     caller logs it): nothing to reschedule against, so the plan stays `Applying`.
-struct TerminalOutcome {
-    phase: Phase,
-    next_run: Option<DateTime<FixedOffset>>,
-    summary: String,
-    requeue: Option<std::time::Duration>,
-}
-
-fn decide_terminal<Tz: TimeZone>(
-    mode: &ExecutionMode,
-    schedule: Option<&str>,
-    outdated_count: usize,
-    total_count: usize,
-    now: DateTime<Tz>,
-) -> TerminalOutcome {
-    let summary = match outdated_count {
-        0 => format!("{total_count}/{total_count} up-to-date"),
-        n => format!("{n}/{total_count} outdated"),
-    };
-
-    match mode {
-        ExecutionMode::OneShot => TerminalOutcome {
-            phase: if outdated_count == 0 {
-                Phase::Succeeded
-            } else {
-                Phase::Failed
-            },
-            next_run: None,
-            summary,
-            requeue: None,
-        },
-        ExecutionMode::Recurring => match schedule {
-            Some(schedule) => {
-                let next =
-                    forecast_next_run(schedule, now.clone(), Some(chrono::Duration::seconds(-5)));
-                let requeue = (next.clone() - now).to_std().ok();
-                TerminalOutcome {
-                    phase: Phase::Scheduled,
-                    next_run: Some(next.fixed_offset()),
-                    summary,
-                    requeue,
-                }
-            }
-            // Any prior forecast is now unreachable, so clear `next_run` and hold at `Applying`.
-            None => TerminalOutcome {
-                phase: Phase::Applying,
-                next_run: None,
-                summary,
-                requeue: None,
-            },
-        },
-    }
-}
-
-/// The `ansible-playbook` container's termination message — the recap the callback wrote to
-/// `/dev/termination-log`, surfaced by the kubelet as `state.terminated.message`. `None` if the
-/// pod has no such terminated container yet or it wrote nothing (hard crash before the stats hook).
-fn termination_message(pod: &Pod) -> Option<String> {
-    pod.status
-        .as_ref()?
+/// The termination message of `container` (the `ansible-playbook` container, or the preflight init
+/// container) — the recap the callback wrote to `/dev/termination-log`, surfaced by the kubelet as
+/// `state.terminated.message`. `None` if the pod has no such terminated container yet or it wrote
+/// nothing (hard crash before the stats hook).
+fn termination_message(pod: &Pod, container: &str) -> Option<String> {
+    let status = pod.status.as_ref()?;
+    status
         .container_statuses
-        .as_ref()?
         .iter()
-        .find(|cs| cs.name == job_builder::ANSIBLE_CONTAINER_NAME)
+        .chain(status.init_container_statuses.iter())
+        .flatten()
+        .find(|cs| cs.name == container)
         .and_then(|cs| cs.state.as_ref())
         .and_then(|state| state.terminated.as_ref())
         .and_then(|terminated| terminated.message.clone())
@@ -823,11 +1670,13 @@ fn filter_groups_to_hosts(
                 ResolvedInventoryGroup::ManagedSsh {
                     tolerations,
                     variables,
+                    node_metadata,
                     ..
                 } => ResolvedInventoryGroup::ManagedSsh {
                     hosts: filtered_hosts,
                     tolerations: tolerations.clone(),
                     variables: variables.clone(),
+                    node_metadata: node_metadata.clone(),
                 },
                 ResolvedInventoryGroup::Ssh {
                     static_inventory_name,
@@ -871,6 +1720,68 @@ fn managed_ssh_hosts_and_tolerations(
     (hosts, tolerations)
 }
 
+async fn upsert_inventory_config_map(
+    api: &Api<ConfigMap>,
+    config_map: ConfigMap,
+) -> Result<(), ReconcileError> {
+    let name = config_map
+        .metadata
+        .name
+        .clone()
+        .expect(".metadata.name must be set at this point");
+    Ok(create_or_update(
+        api,
+        "ansible-operator",
+        &name,
+        config_map,
+        |_, desired_state| desired_state.metadata.managed_fields = None,
+    )
+    .await?)
+}
+
+async fn upsert_workspace_config_map(
+    api: &Api<ConfigMap>,
+    config_map: ConfigMap,
+) -> Result<(), ReconcileError> {
+    let name = config_map
+        .metadata
+        .name
+        .clone()
+        .expect(".metadata.name must be set at this point");
+    Ok(create_or_update(
+        api,
+        "ansible-operator",
+        &name,
+        config_map,
+        // Unlike the Secret's `stringData`, `data` is applied as a whole: keys the plan no longer
+        // renders are dropped by server-side apply.
+        |_, desired_state| desired_state.metadata.managed_fields = None,
+    )
+    .await?)
+}
+
+/// Deletes the plan's workspace of the kind its `workspaceStorage` doesn't pick, which is left
+/// behind when the plan switched between Secret and ConfigMap.
+async fn delete_unused_workspace(
+    object: &PlaybookPlan,
+    secrets_api: &Api<Secret>,
+    config_maps_api: &Api<ConfigMap>,
+    workspace_name: &str,
+) -> Result<(), kube::Error> {
+    match object.spec.workspace_storage {
+        WorkspaceStorage::Secret => ignore_not_found(
+            config_maps_api
+                .delete(workspace_name, &DeleteParams::default())
+                .await,
+        ),
+        WorkspaceStorage::ConfigMap => ignore_not_found(
+            secrets_api
+                .delete(workspace_name, &DeleteParams::default())
+                .await,
+        ),
+    }
+}
+
 async fn upsert_workspace_secret(
     api: &Api<Secret>,
     secret_name: &str,
@@ -911,7 +1822,11 @@ async fn upsert_workspace_secret(
 /// staleness is handled independently via `workspace::is_outdated`/`is_missing`.
 fn get_related_secrets(playbookplan: &PlaybookPlan) -> Vec<&String> {
     job_builder::extract_secret_names_for_variables(playbookplan)
+        .chain(job_builder::extract_secret_names_for_group_variables(
+            playbookplan,
+        ))
         .chain(job_builder::extract_secret_names_for_files(playbookplan))
+        .chain(job_builder::extract_secret_names_for_roles(playbookplan))
         .collect()
 }
 
@@ -920,6 +1835,17 @@ fn get_related_secrets(playbookplan: &PlaybookPlan) -> Vec<&String> {
 /// many async steps between reading `target` and this final write, long enough that a concurrent
 /// write to the same object routinely lands first and would reject a version-checked PUT with a
 /// 409. A merge patch carries no such precondition.
+///
+/// The apiserver can still answer a 409 when this plan's own rapid reconciles contend on the same
+/// object, so the write goes through `retry_on_conflict`: a few quick retries with backoff, instead
+/// of surfacing the conflict and waiting out the 15s error requeue. Nothing needs re-reading between
+/// attempts — the patch is built from this tick's computed status, not from a stale `resourceVersion`.
+/// See `status_merge_patch` for how removed `hostsStatus` entries are deleted.
+///
+/// Nothing is sent when `status` equals what `target` already carries: most reconciles of a settled
+/// plan compute the same status again, and writing it anyway would still bump the plan's
+/// `resourceVersion` and wake every watch on it. Conditions don't spoil that comparison, since
+/// `upsert_condition` keeps an unchanged condition's original `lastTransitionTime`.
 async fn patch_status(
     api: &Api<PlaybookPlan>,
     target: &PlaybookPlan,
@@ -927,41 +1853,155 @@ async fn patch_status(
 ) -> Result<(), ReconcileError> {
     use kube::runtime::reflector::Lookup as _;
 
+    if target.status.as_ref() == Some(&status) {
+        return Ok(());
+    }
+
     let name = target
         .name()
         .ok_or(ReconcileError::PreconditionFailed("name not set"))?;
 
-    api.patch_status(
-        &name,
-        &PatchParams::default(),
-        &Patch::Merge(serde_json::json!({ "status": status })),
-    )
-    .await?;
+    let params = PatchParams::default();
+    let patch = Patch::Merge(status_merge_patch(target.status.as_ref(), &status)?);
+    retry_on_conflict(|| api.patch_status(&name, &params, &patch)).await?;
 
     Ok(())
 }
 
+/// The merge patch writing `status`. A merge patch leaves map keys it doesn't mention untouched, so
+/// a `hostsStatus` entry dropped since `previous` (see `status::prune_removed_hosts`) is sent as an
+/// explicit `null`, which is what deletes it on the server.
+fn status_merge_patch(
+    previous: Option<&PlaybookPlanStatus>,
+    status: &PlaybookPlanStatus,
+) -> Result<serde_json::Value, ReconcileError> {
+    let mut status_json = serde_json::to_value(status)?;
+
+    let removed_hosts = previous
+        .and_then(|previous| previous.hosts_status.as_ref())
+        .into_iter()
+        .flat_map(|hosts| hosts.keys())
+        .filter(|host| {
+            !status
+                .hosts_status
+                .as_ref()
+                .is_some_and(|hosts| hosts.contains_key(*host))
+        });
+
+    if let Some(hosts_status) = status_json
+        .get_mut("hostsStatus")
+        .and_then(|hosts| hosts.as_object_mut())
+    {
+        for host in removed_hosts {
+            hosts_status.insert(host.clone(), serde_json::Value::Null);
+        }
+    }
+
+    Ok(serde_json::json!({ "status": status_json }))
+}
+
+/// The referenced Secrets and ConfigMaps `hash_playbook_inputs` found missing, by name, and the
+/// variables keys missing from Secrets that do exist, as `(secret, key)`.
+#[derive(Debug, Default, PartialEq)]
+struct MissingInputs {
+    secrets: Vec<String>,
+    config_maps: Vec<String>,
+    secret_keys: Vec<(String, String)>,
+}
+
+/// A referenced Secret or ConfigMap that doesn't exist contributes nothing, just as one without data
+/// does, and is returned by name alongside the hash; see `stable_execution_hash` for what the caller
+/// makes of that. Any other error fails the reconcile rather than hashing a partial set of inputs: a
+/// single apiserver hiccup must not change the hash and re-run the playbook on every host.
 async fn hash_playbook_inputs(
     playbook: &str,
     secret_names: &[&String],
+    secret_keys: &[(&String, &str)],
     secrets_api: &Api<Secret>,
+    config_map_names: &[&String],
+    config_maps_api: &Api<ConfigMap>,
     inventory_variables: &[(&str, &serde_json::Value)],
-) -> ExecutionHash {
+) -> Result<(ExecutionHash, MissingInputs), ReconcileError> {
     let secrets = futures::future::join_all(
         secret_names
             .iter()
             .map(|secret_name| secrets_api.get(secret_name)),
     )
     .await;
+    let config_maps = futures::future::join_all(
+        config_map_names
+            .iter()
+            .map(|config_map_name| config_maps_api.get(config_map_name)),
+    )
+    .await;
 
-    let variables_secrets: Vec<BTreeMap<_, _>> = secrets
-        .iter()
-        .filter_map(|result| result.as_ref().ok())
-        .filter_map(|secret| secret.data.clone())
-        .collect();
+    let mut variables_secrets: Vec<BTreeMap<_, _>> = Vec::new();
+    let mut missing = MissingInputs::default();
+    for (secret_name, result) in secret_names.iter().zip(secrets) {
+        match result {
+            Ok(secret) => {
+                for (_, key) in secret_keys
+                    .iter()
+                    .filter(|(name, key)| name == secret_name && !secret_has_key(&secret, key))
+                {
+                    let missing_key = (secret_name.to_string(), key.to_string());
+                    if !missing.secret_keys.contains(&missing_key) {
+                        missing.secret_keys.push(missing_key);
+                    }
+                }
+                variables_secrets.extend(secret.data);
+            }
+            Err(kube::Error::Api(status)) if status.code == 404 => {
+                missing.secrets.push(secret_name.to_string());
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    for (config_map_name, result) in config_map_names.iter().zip(config_maps) {
+        match result {
+            Ok(config_map) => variables_secrets.push(config_map_data(config_map)),
+            Err(kube::Error::Api(status)) if status.code == 404 => {
+                missing.config_maps.push(config_map_name.to_string());
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let hash = execution_evaluator::calculate_execution_hash(playbook, variables_secrets.iter())
+        .fold_inventory_variables(inventory_variables.iter().copied());
+    Ok((hash, missing))
+}
+
+/// A ConfigMap's `data` and `binaryData` as one map of bytes, the shape the execution hash takes
+/// a Secret's data in.
+fn config_map_data(config_map: ConfigMap) -> BTreeMap<String, ByteString> {
+    let data = config_map
+        .data
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| (key, ByteString(value.into_bytes())));
+    config_map
+        .binary_data
+        .into_iter()
+        .flatten()
+        .chain(data)
+        .collect()
+}
 
-    execution_evaluator::calculate_execution_hash(playbook, variables_secrets.iter())
-        .fold_inventory_variables(inventory_variables.iter().copied())
+/// The execution hash a plan goes on with. While a referenced Secret is missing, that's the hash it
+/// already had, if any: no run can start without the Secret, so the inputs it hashes haven't
+/// changed for any run. A Secret that is deleted and recreated with the same content therefore
+/// leaves the hash, and every host's state, untouched, instead of flipping it twice and re-running
+/// the playbook everywhere. Only a plan that never had a hash takes the one computed without it.
+fn stable_execution_hash(
+    computed: ExecutionHash,
+    missing_secrets: &[String],
+    current_hash: &str,
+) -> ExecutionHash {
+    match current_hash.parse() {
+        Ok(previous) if !missing_secrets.is_empty() => previous,
+        _ => computed,
+    }
 }
 
 /// Resolves every inventory this PlaybookPlan references into `ResolvedInventoryGroup`s,
@@ -1021,9 +2061,26 @@ async fn resolve_inventory(
         return Err(ReconcileError::KubeError(first));
     }
 
+    // Each referenced inventory's `order`; the first reference wins should one be named twice.
+    let mut cluster_orders = BTreeMap::new();
+    let mut static_orders = BTreeMap::new();
+    for inventory_ref in inventory_refs.iter().rev() {
+        if let Some(name) = &inventory_ref.cluster_inventory {
+            cluster_orders.insert(name.as_str(), inventory_ref.order);
+        }
+        if let Some(name) = &inventory_ref.static_inventory {
+            static_orders.insert(name.as_str(), inventory_ref.order);
+        }
+    }
+    let seed = host_order::seed(object);
+
     let mut groups = Vec::new();
 
     for ci in cluster_inventories.into_iter().map(Result::unwrap) {
+        let order = cluster_orders
+            .get(ci.name_any().as_str())
+            .copied()
+            .unwrap_or_default();
         let tolerations = ci.spec.tolerations.clone();
         // Group variables live on the spec's InventoryHosts, but get_hosts() returns the resolved
         // node lists from status; re-join them by group name.
@@ -1033,29 +2090,49 @@ async fn resolve_inventory(
             .iter()
             .filter_map(|group| group.variables.as_ref().map(|v| (group.name.as_str(), v)))
             .collect();
-        for hosts in ci.get_hosts() {
+        let node_metadata: BTreeMap<&str, &NodeMetadata> = ci
+            .status
+            .iter()
+            .flat_map(|status| &status.node_metadata)
+            .map(|metadata| (metadata.node.as_str(), metadata))
+            .collect();
+        for mut hosts in ci.get_hosts() {
+            host_order::apply(order, seed, &mut hosts.hosts);
             let variables = variables_by_group
                 .get(hosts.name.as_str())
                 .copied()
                 .cloned();
             reject_reserved_variables(&hosts.name, variables.as_ref())?;
+            let node_metadata = hosts
+                .hosts
+                .iter()
+                .filter_map(|host| node_metadata.get(host.as_str()))
+                .map(|&metadata| (metadata.node.clone(), metadata.clone()))
+                .collect();
             groups.push(ResolvedInventoryGroup::ManagedSsh {
                 hosts,
                 tolerations: tolerations.clone(),
                 variables,
+                node_metadata,
             });
         }
     }
 
     for si in static_inventories.into_iter().map(Result::unwrap) {
         let static_inventory_name = si.name_any();
+        let order = static_orders
+            .get(static_inventory_name.as_str())
+            .copied()
+            .unwrap_or_default();
         let config = si.spec.ssh.clone();
         for group in &si.spec.hosts {
             reject_reserved_variables(&group.name, group.variables.as_ref())?;
+            let mut hosts = group.hosts.clone();
+            host_order::apply(order, seed, &mut hosts);
             groups.push(ResolvedInventoryGroup::Ssh {
                 hosts: ResolvedHosts {
                     name: group.name.clone(),
-                    hosts: group.hosts.clone(),
+                    hosts,
                 },
                 static_inventory_name: static_inventory_name.clone(),
                 config: config.clone(),
@@ -1067,6 +2144,115 @@ async fn resolve_inventory(
     Ok(groups)
 }
 
+/// Why `plan` can't be served by an operator confined to `watch_namespace`, if it is: resolving a
+/// `ClusterInventory` needs cluster-wide Node (and `NodeAccessPolicy`) reads such an operator lacks.
+fn unsupported_when_confined(plan: &PlaybookPlan, watch_namespace: Option<&str>) -> Option<String> {
+    let watch_namespace = watch_namespace?;
+    let index = plan
+        .spec
+        .inventory_refs
+        .iter()
+        .position(|inventory_ref| inventory_ref.cluster_inventory.is_some())?;
+
+    Some(format!(
+        "inventoryRefs[{index}] references a ClusterInventory, which the operator can't resolve while \
+         confined to namespace '{watch_namespace}'; only StaticInventory targets are supported"
+    ))
+}
+
+/// Why `plan` can't run on this cluster, if it needs a feature the operator config says is missing:
+/// a `collectionsImage` without image volumes would give a pod that never starts.
+fn unsupported_by_cluster(plan: &PlaybookPlan, image_volumes: bool) -> Option<String> {
+    (plan.spec.template.collections_image.is_some() && !image_volumes).then(|| {
+        "template.collectionsImage needs image volumes, which the operator config doesn't enable \
+         (image_volumes)"
+            .to_string()
+    })
+}
+
+/// Reads the SSH key Secret of every `Ssh` group once, defaulting `privateKeyKey` from its type and
+/// checking it has every key the Job would mount. Returns the first problem found, naming the Secret
+/// and key, so the caller can hold the run back instead of starting a pod that never gets past
+/// mounting its volumes.
+async fn check_ssh_secrets(
+    secrets_api: &Api<Secret>,
+    groups: &mut [ResolvedInventoryGroup],
+) -> Result<Option<status::SecretProblem>, ReconcileError> {
+    let mut secrets: BTreeMap<String, Option<Secret>> = BTreeMap::new();
+    let mut problem = None;
+
+    for group in groups.iter_mut() {
+        let ResolvedInventoryGroup::Ssh { config, .. } = group else {
+            continue;
+        };
+
+        let secret_name = config.secret_ref.name.clone();
+        if !secrets.contains_key(&secret_name) {
+            let secret = secrets_api.get_opt(&secret_name).await?;
+            secrets.insert(secret_name.clone(), secret);
+        }
+        let secret = secrets[&secret_name].as_ref();
+
+        if config.private_key_key.is_none() {
+            config.private_key_key = default_private_key_key(secret);
+        }
+
+        if problem.is_none() {
+            problem = ssh_secret_problem(config, secret);
+        }
+    }
+
+    Ok(problem)
+}
+
+/// Whether `secret` holds `key`, in `data` or (on objects not yet round-tripped through the
+/// apiserver) `stringData`.
+fn secret_has_key(secret: &Secret, key: &str) -> bool {
+    secret.data.as_ref().is_some_and(|d| d.contains_key(key))
+        || secret
+            .string_data
+            .as_ref()
+            .is_some_and(|d| d.contains_key(key))
+}
+
+/// Whether `secret` can serve as the key Secret for `config`: it must exist and hold the private key
+/// plus every additional one.
+fn ssh_secret_problem(
+    config: &v1beta1::SshConfig,
+    secret: Option<&Secret>,
+) -> Option<status::SecretProblem> {
+    let secret_name = &config.secret_ref.name;
+    let Some(secret) = secret else {
+        return Some(status::SecretProblem::Missing {
+            secret: secret_name.clone(),
+        });
+    };
+
+    std::iter::once(config.effective_private_key_key())
+        .chain(
+            config
+                .additional_private_key_keys
+                .iter()
+                .flatten()
+                .map(String::as_str),
+        )
+        .find(|key| !secret_has_key(secret, key))
+        .map(|key| status::SecretProblem::MissingKey {
+            secret: secret_name.clone(),
+            key: key.to_string(),
+        })
+}
+
+/// The Secret key a `StaticInventory`'s private key is read from when it doesn't say: Kubernetes'
+/// own `kubernetes.io/ssh-auth` Secrets keep it under `ssh-privatekey`; anything else is left to
+/// `SshConfig::effective_private_key_key`'s `id_rsa` fallback.
+fn default_private_key_key(secret: Option<&Secret>) -> Option<String> {
+    secret
+        .and_then(|secret| secret.type_.as_deref())
+        .filter(|type_| *type_ == v1beta1::SSH_AUTH_SECRET_TYPE)
+        .map(|_| v1beta1::SSH_AUTH_PRIVATE_KEY_KEY.to_string())
+}
+
 /// Fails the reconcile if an inventory group sets a variable the operator manages for
 /// connection/isolation (see [`ansible::RESERVED_HOST_VARS`]). Runs at resolve time, before any
 /// proxy infra or hashing, so a bad inventory surfaces as a clear error rather than a silently
@@ -1176,67 +2362,151 @@ fn decide_job_action(existing: &[Job], current_retry_count: u32) -> JobAction {
 /// fresh (quorum) `list` by the run's hash label reliably sees a Job a previous tick just created.
 /// If one is still active, adopt it; otherwise this is a genuinely new attempt (first run, or a
 /// retry after the previous one reached a terminal state) and we create the next numbered Job.
+///
+/// Every Job is annotated with `workspace_hash`, the content hash of the workspace it was created
+/// for. The workspace is re-rendered on each tick that gets here, so an active Job may no longer
+/// match it, e.g. after proxy pods were recreated with new IPs. One that hasn't started yet (see
+/// `workspace_drifted`) is deleted and replaced by the next attempt, whose name is returned so its
+/// `Play` can be discarded too; one that has started is adopted anyway.
+// The resolved inputs of the Job are passed one by one, as `create_job_for_run` takes them.
+#[allow(clippy::too_many_arguments)]
 async fn spawn_ansible_job(
     api: &Api<Job>,
     hash: ExecutionHash,
     run_groups: &[ResolvedInventoryGroup],
     playbookplan: &PlaybookPlan,
+    image: &str,
+    role_files: &RoleFiles,
+    workspace_hash: Option<&str>,
+    selector_label_keys: &[String],
     resource_status: &mut PlaybookPlanStatus,
-) -> Result<(), ReconcileError> {
+) -> Result<Option<String>, ReconcileError> {
     use kube::runtime::reflector::Lookup as _;
 
     let existing = api
         .list(&ListParams::default().labels(&format!("{}={hash}", labels::PLAYBOOKPLAN_HASH)))
         .await?;
 
-    let job_name = match decide_job_action(&existing.items, resource_status.retry_count) {
+    let mut action = decide_job_action(&existing.items, resource_status.retry_count);
+    let mut replaced = None;
+    if let JobAction::Adopt { job_name } = &action
+        && let Some(job) = existing
+            .items
+            .iter()
+            .find(|job| job.name().as_deref() == Some(job_name))
+        && workspace_drifted(job, workspace_hash)
+    {
+        // Only delete the Job as it was read: once its pod is Ready its resourceVersion has moved
+        // on, the precondition fails, and the Job is adopted after all.
+        let params = DeleteParams {
+            preconditions: Some(Preconditions {
+                resource_version: job.metadata.resource_version.clone(),
+                uid: job.metadata.uid.clone(),
+            }),
+            ..DeleteParams::background()
+        };
+        match api.delete(job_name, &params).await {
+            Err(err) if utils::is_conflict(&err) => {
+                debug!("Job {job_name} changed while checking its workspace, adopting it");
+            }
+            result => {
+                ignore_not_found(result)?;
+                info!(
+                    "Replaced Job {job_name}: it hadn't started and was created for other workspace content"
+                );
+                // Past the replaced Job's own attempt number, which the cached status may lag: its
+                // name stays taken until the deletion is through.
+                let attempt = job_name
+                    .rsplit_once('-')
+                    .and_then(|(_, attempt)| attempt.parse().ok())
+                    .unwrap_or(0);
+                replaced = Some(job_name.clone());
+                action = JobAction::CreateNext {
+                    retry_count: resource_status.retry_count.max(attempt) + 1,
+                };
+            }
+        }
+    }
+
+    let job_name = match action {
         JobAction::Adopt { job_name } => {
             debug!("Adopting already-active job {job_name} for this run");
             job_name
         }
-        JobAction::CreateNext { retry_count } => {
+        JobAction::CreateNext { mut retry_count } => {
             // A genuinely new attempt. `retry_count` climbs monotonically so the new name is
             // expected not to collide with an already-finished attempt's; it's reset to 0 in
             // `reconcile` whenever `current_hash` changes.
-            resource_status.retry_count = retry_count;
-
-            let job =
-                job_builder::create_job_for_run(&hash, retry_count, run_groups, playbookplan)?;
-            let job_name = job
-                .name()
-                .expect(".metadata.name must be set at this point")
-                .to_string();
-
-            info!("Creating job {job_name}");
-            match api
-                .create(
-                    &PostParams {
-                        field_manager: Some("ansible-operator".into()),
-                        ..Default::default()
-                    },
-                    &job,
-                )
-                .await
-            {
-                Ok(_) => {}
-                // A Job by this exact name already exists. In principle `retry_count` should always
-                // be ahead of every name already in the cluster, but if a previous tick created a
-                // Job and then errored *before* `patch_status` ran, the bump above never got
-                // persisted — so this tick recomputes the same name a real Job already holds.
+            let mut attempts_left = MAX_JOB_NAME_ATTEMPTS;
+            loop {
+                resource_status.retry_count = retry_count;
+
+                let mut job = job_builder::create_job_for_run(
+                    &hash,
+                    retry_count,
+                    run_groups,
+                    playbookplan,
+                    image,
+                    role_files,
+                )?;
+                propagate_selector_labels(&mut job, playbookplan, selector_label_keys);
+                if let Some(workspace_hash) = workspace_hash {
+                    job.metadata
+                        .annotations
+                        .get_or_insert_with(Default::default)
+                        .insert(labels::WORKSPACE_HASH.into(), workspace_hash.into());
+                }
+                let job_name = job
+                    .name()
+                    .expect(".metadata.name must be set at this point")
+                    .to_string();
+
+                info!("Creating job {job_name}");
+                let err = match api
+                    .create(
+                        &PostParams {
+                            field_manager: Some("ansible-operator".into()),
+                            ..Default::default()
+                        },
+                        &job,
+                    )
+                    .await
+                {
+                    Ok(_) => break job_name,
+                    Err(err) if utils::is_already_exists(&err) => err,
+                    Err(err) => return Err(err.into()),
+                };
+
+                // A Job by this exact name already exists. In principle `retry_count` should
+                // always be ahead of every name already in the cluster, but if a previous tick
+                // created a Job and then errored *before* `patch_status` ran, the bump above never
+                // got persisted — so this tick recomputes the same name a real Job already holds.
                 // Treating that as fatal (instead of adopting it here) would be the actual bug:
                 // erroring via `?` skips `patch_status` too, so nothing this tick would get
                 // persisted either, and the next tick would recompute the exact same name and hit
                 // the exact same 409 — a permanent stall on one name, observed live. Adopting
                 // instead means current_job_name/phase are persisted this tick regardless, so the
-                // run can proceed against whatever Job holds that name, and the next genuinely-new
-                // attempt computes its retry_count from state that now matches reality.
-                Err(err) if is_conflict(&err) => {
-                    info!("Job {job_name} already exists, adopting it");
+                // run can proceed against whatever Job holds that name, and the next
+                // genuinely-new attempt computes its retry_count from state that now matches
+                // reality. Two reconciles racing between the `list` above and this `create` end
+                // up here the same way; whichever lost simply adopts the Job the other created.
+                //
+                // That only holds if the Job is this run's. One of another run whose hash shares
+                // the name's ID would never report on this run's hosts, so its attempt number is
+                // skipped instead. A Job gone again by now is left to the next tick.
+                match api.get_opt(&job_name).await? {
+                    Some(holder) if job_belongs_to_run(&holder, &hash) => {
+                        debug!("Job {job_name} already exists, adopting it");
+                        break job_name;
+                    }
+                    Some(_) if attempts_left > 1 => {
+                        warn!("Job {job_name} belongs to another run, skipping to the next name");
+                        attempts_left -= 1;
+                        retry_count += 1;
+                    }
+                    _ => return Err(err.into()),
                 }
-                Err(err) => return Err(err.into()),
             }
-
-            job_name
         }
     };
 
@@ -1244,11 +2514,99 @@ async fn spawn_ansible_job(
     resource_status.phase = Phase::Applying;
     resource_status.next_run = None;
 
-    Ok(())
+    Ok(replaced)
+}
+
+/// Whether `job` was created for other workspace content than `workspace_hash` and its pod hasn't
+/// started the playbook yet (no pod Ready or done), so replacing it loses nothing. A Job without
+/// the annotation predates it, and is never taken for drifted.
+fn workspace_drifted(job: &Job, workspace_hash: Option<&str>) -> bool {
+    let Some(workspace_hash) = workspace_hash else {
+        return false;
+    };
+    let created_for = job
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(labels::WORKSPACE_HASH));
+    let started = job.status.as_ref().is_some_and(|status| {
+        [status.ready, status.succeeded, status.failed]
+            .into_iter()
+            .any(|count| count.unwrap_or(0) > 0)
+    });
+
+    created_for.is_some_and(|created_for| created_for != workspace_hash)
+        && !started
+        && !status::job_finished(job)
+}
+
+/// Counts the operator's unfinished Jobs — those labelled with their plan — in every enrolled
+/// namespace, the only ones it may list Jobs in.
+async fn running_operator_jobs(context: &ReconciliationContext) -> Result<usize, ReconcileError> {
+    let lp = ListParams::default().labels(labels::PLAYBOOKPLAN_NAME);
+    let mut running = 0;
+    for namespace in context.enrolled_namespaces.iter() {
+        let jobs_api = Api::<Job>::namespaced(context.client.clone(), namespace);
+        running += jobs_api
+            .list(&lp)
+            .await?
+            .iter()
+            .filter(|job| !status::job_finished(job))
+            .count();
+    }
+    Ok(running)
+}
+
+/// Defers a run while `--max-concurrent-jobs` operator Jobs are already running, returning when to
+/// check again — as often as a running Job is — and setting the `Throttled` condition; without a
+/// cap this does nothing. Plans starting at the same moment each see the same count, so the cap can
+/// briefly be exceeded.
+async fn throttle(
+    context: &ReconciliationContext,
+    resource_status: &mut PlaybookPlanStatus,
+) -> Result<Option<std::time::Duration>, ReconcileError> {
+    let Some(max) = context.max_concurrent_jobs else {
+        return Ok(None);
+    };
+    let running = running_operator_jobs(context).await?;
+    if running < max {
+        status::set_throttled_condition(resource_status, None);
+        return Ok(None);
+    }
+    debug!("{running} operator Jobs are running (max {max}); deferring the run");
+    status::set_throttled_condition(resource_status, Some((running, max)));
+    Ok(Some(JOB_RUNNING_REQUEUE))
+}
+
+/// Copies `plan`'s labels under `keys` (those `--watch-label-selector` refers to) onto `job`, so a
+/// Job watch narrowed by that selector still sees the Jobs of every plan it selects. Labels the Job
+/// already has are kept, and no other label of the plan is copied: tools like Argo CD track
+/// resources by label, and must not mistake the Job for one of theirs.
+fn propagate_selector_labels(job: &mut Job, plan: &PlaybookPlan, keys: &[String]) {
+    let Some(plan_labels) = plan.metadata.labels.as_ref() else {
+        return;
+    };
+    let job_labels = job.metadata.labels.get_or_insert_with(Default::default);
+    for key in keys {
+        if let Some(value) = plan_labels.get(key) {
+            job_labels
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
 }
 
-fn is_conflict(err: &kube::Error) -> bool {
-    matches!(err, kube::Error::Api(status) if status.code == 409)
+/// How many attempt numbers `spawn_ansible_job` tries per tick while each one's name is held by
+/// another run's Job.
+const MAX_JOB_NAME_ATTEMPTS: u32 = 3;
+
+/// Whether `job` carries this run's `PLAYBOOKPLAN_HASH` label, i.e. may be adopted for it.
+fn job_belongs_to_run(job: &Job, hash: &ExecutionHash) -> bool {
+    job.metadata
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(labels::PLAYBOOKPLAN_HASH))
+        .is_some_and(|value| *value == hash.to_string())
 }
 
 #[cfg(test)]
@@ -1268,6 +2626,7 @@ mod tests {
             },
             tolerations,
             variables: None,
+            node_metadata: Default::default(),
         }
     }
 
@@ -1287,6 +2646,8 @@ mod tests {
                 secret_ref: SecretRef {
                     name: "ssh-key".into(),
                 },
+                private_key_key: None,
+                additional_private_key_keys: None,
             },
             variables: None,
         }
@@ -1367,18 +2728,93 @@ mod tests {
     }
 
     #[test]
-    fn is_conflict_matches_only_409() {
-        let conflict = kube::Error::Api(Box::new(kube::core::Status {
-            code: 409,
+    fn ssh_auth_secrets_default_to_their_standard_private_key_key() {
+        let secret = |type_: Option<&str>| Secret {
+            type_: type_.map(str::to_string),
             ..Default::default()
-        }));
-        let not_found = kube::Error::Api(Box::new(kube::core::Status {
-            code: 404,
+        };
+
+        assert_eq!(
+            default_private_key_key(Some(&secret(Some("kubernetes.io/ssh-auth")))).as_deref(),
+            Some("ssh-privatekey")
+        );
+        assert_eq!(default_private_key_key(Some(&secret(Some("Opaque")))), None);
+        assert_eq!(default_private_key_key(Some(&secret(None))), None);
+        assert_eq!(default_private_key_key(None), None);
+    }
+
+    #[test]
+    fn confined_operator_rejects_only_cluster_inventory_refs() {
+        let mut plan = PlaybookPlan::new("plan", Default::default());
+        plan.spec.inventory_refs = vec![
+            v1beta1::InventoryRef {
+                cluster_inventory: None,
+                static_inventory: Some("edge".into()),
+                ..Default::default()
+            },
+            v1beta1::InventoryRef {
+                cluster_inventory: Some("cluster-nodes".into()),
+                static_inventory: None,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(unsupported_when_confined(&plan, None), None);
+        let message = unsupported_when_confined(&plan, Some("team-a")).unwrap();
+        assert!(message.starts_with("inventoryRefs[1]"), "{message}");
+
+        plan.spec.inventory_refs.pop();
+        assert_eq!(unsupported_when_confined(&plan, Some("team-a")), None);
+    }
+
+    #[test]
+    fn a_collections_image_needs_image_volumes() {
+        let mut plan = PlaybookPlan::new("plan", Default::default());
+        assert_eq!(unsupported_by_cluster(&plan, false), None);
+
+        plan.spec.template.collections_image = Some("registry.example.com/collections:1".into());
+        assert!(unsupported_by_cluster(&plan, false).is_some());
+        assert_eq!(unsupported_by_cluster(&plan, true), None);
+    }
+
+    #[test]
+    fn ssh_secret_problem_names_the_missing_secret_or_key() {
+        use k8s_openapi::ByteString;
+
+        let config = v1beta1::SshConfig {
+            user: "root".into(),
+            secret_ref: v1beta1::SecretRef {
+                name: "router-key".into(),
+            },
+            additional_private_key_keys: Some(vec!["id_ed25519".into()]),
             ..Default::default()
-        }));
+        };
+        let secret = |keys: &[&str]| Secret {
+            data: Some(
+                keys.iter()
+                    .map(|k| (k.to_string(), ByteString(b"key".to_vec())))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
 
-        assert!(is_conflict(&conflict));
-        assert!(!is_conflict(&not_found));
+        assert_eq!(
+            ssh_secret_problem(&config, None),
+            Some(status::SecretProblem::Missing {
+                secret: "router-key".into()
+            })
+        );
+        assert_eq!(
+            ssh_secret_problem(&config, Some(&secret(&["id_rsa"]))),
+            Some(status::SecretProblem::MissingKey {
+                secret: "router-key".into(),
+                key: "id_ed25519".into(),
+            })
+        );
+        assert_eq!(
+            ssh_secret_problem(&config, Some(&secret(&["id_rsa", "id_ed25519"]))),
+            None
+        );
     }
 
     #[test]
@@ -1480,22 +2916,108 @@ mod tests {
     }
 
     #[test]
-    fn slot_already_triggered_suppresses_only_a_repeat_of_the_same_slot() {
-        let slot = |s: &str| Some(s.parse::<DateTime<FixedOffset>>().unwrap());
-
-        // Unscheduled ticks (no slot) are never suppressed.
-        assert!(!slot_already_triggered(None, None));
-        assert!(!slot_already_triggered(None, slot("2025-08-12T20:00:00Z")));
+    fn only_a_pending_job_for_other_workspace_content_has_drifted() {
+        use k8s_openapi::api::batch::v1::JobStatus;
 
-        // The first time a slot is seen it hasn't been triggered yet.
-        assert!(!slot_already_triggered(slot("2025-08-12T20:00:00Z"), None));
+        let job = |annotation: Option<&str>, status: JobStatus| Job {
+            metadata: kube::api::ObjectMeta {
+                annotations: annotation
+                    .map(|hash| BTreeMap::from([(labels::WORKSPACE_HASH.into(), hash.into())])),
+                ..Default::default()
+            },
+            status: Some(status),
+            ..Default::default()
+        };
+        let pending = JobStatus {
+            active: Some(1),
+            ..Default::default()
+        };
+        let running = JobStatus {
+            active: Some(1),
+            ready: Some(1),
+            ..Default::default()
+        };
 
-        // The same slot already recorded -> suppress the re-trigger inside its grace window.
-        assert!(slot_already_triggered(
-            slot("2025-08-12T20:00:00Z"),
-            slot("2025-08-12T20:00:00Z"),
+        assert!(workspace_drifted(
+            &job(Some("old"), pending.clone()),
+            Some("new")
         ));
-
+        assert!(!workspace_drifted(
+            &job(Some("new"), pending.clone()),
+            Some("new")
+        ));
+        assert!(!workspace_drifted(&job(Some("old"), running), Some("new")));
+        // Jobs from before the annotation, and runs that rendered no workspace, are left alone.
+        assert!(!workspace_drifted(&job(None, pending.clone()), Some("new")));
+        assert!(!workspace_drifted(&job(Some("old"), pending), None));
+    }
+
+    #[test]
+    fn only_labels_the_selector_names_are_propagated_to_the_job() {
+        let mut plan = PlaybookPlan::new("web", PlaybookPlanSpec::default());
+        plan.metadata.labels = Some(BTreeMap::from([
+            ("ansible.cloudbending.dev/instance".into(), "prod".into()),
+            ("app.kubernetes.io/instance".into(), "argo-app".into()),
+        ]));
+        let mut job = Job::default();
+        job.metadata.labels = Some(BTreeMap::from([(
+            labels::PLAYBOOKPLAN_NAME.into(),
+            "web".into(),
+        )]));
+
+        let keys = utils::selector_label_keys("ansible.cloudbending.dev/instance=prod,tier");
+        propagate_selector_labels(&mut job, &plan, &keys);
+
+        assert_eq!(
+            job.metadata.labels.unwrap(),
+            BTreeMap::from([
+                (labels::PLAYBOOKPLAN_NAME.into(), "web".into()),
+                ("ansible.cloudbending.dev/instance".into(), "prod".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn only_a_job_labelled_with_the_run_hash_is_adopted() {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+        let hash = |n: u64| format!("{n:x}").parse::<ExecutionHash>().unwrap();
+        // Two hashes whose short IDs collide, as far as the old job names were concerned.
+        let (ours, theirs) = (hash(0x5eed), hash(0x5eed + 27u64.pow(5)));
+        assert_eq!(utils::generate_id(*ours), utils::generate_id(*theirs));
+
+        let job = |hash: Option<&ExecutionHash>| Job {
+            metadata: ObjectMeta {
+                name: Some("apply-web-x-1".into()),
+                labels: hash
+                    .map(|h| BTreeMap::from([(labels::PLAYBOOKPLAN_HASH.into(), h.to_string())])),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert!(job_belongs_to_run(&job(Some(&ours)), &ours));
+        assert!(!job_belongs_to_run(&job(Some(&theirs)), &ours));
+        assert!(!job_belongs_to_run(&job(None), &ours));
+    }
+
+    #[test]
+    fn slot_already_triggered_suppresses_only_a_repeat_of_the_same_slot() {
+        let slot = |s: &str| Some(s.parse::<DateTime<FixedOffset>>().unwrap());
+
+        // Unscheduled ticks (no slot) are never suppressed.
+        assert!(!slot_already_triggered(None, None));
+        assert!(!slot_already_triggered(None, slot("2025-08-12T20:00:00Z")));
+
+        // The first time a slot is seen it hasn't been triggered yet.
+        assert!(!slot_already_triggered(slot("2025-08-12T20:00:00Z"), None));
+
+        // The same slot already recorded -> suppress the re-trigger inside its grace window.
+        assert!(slot_already_triggered(
+            slot("2025-08-12T20:00:00Z"),
+            slot("2025-08-12T20:00:00Z"),
+        ));
+
         // Equality is by instant, so an equivalent moment in another offset still matches.
         assert!(slot_already_triggered(
             slot("2025-08-12T22:00:00+02:00"),
@@ -1538,6 +3060,1230 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pruned_hosts_are_deleted_by_the_status_patch() {
+        let host_status = || v1beta1::HostStatus {
+            last_applied_hash: "abc".into(),
+            ..Default::default()
+        };
+        let previous = PlaybookPlanStatus {
+            hosts_status: Some(BTreeMap::from([
+                ("host-1".to_string(), host_status()),
+                ("host-2".to_string(), host_status()),
+            ])),
+            ..Default::default()
+        };
+        let mut status = previous.clone();
+        status.eligible_hosts = vec![ResolvedHosts {
+            name: "all".into(),
+            hosts: vec!["host-1".into()],
+        }];
+        status::prune_removed_hosts(&mut status);
+
+        let patch = status_merge_patch(Some(&previous), &status).unwrap();
+
+        let hosts_status = &patch["status"]["hostsStatus"];
+        assert!(hosts_status["host-1"].is_object());
+        assert!(hosts_status["host-2"].is_null());
+        assert!(hosts_status.as_object().unwrap().contains_key("host-2"));
+
+        // Nothing removed, nothing nulled.
+        let patch = status_merge_patch(Some(&status), &status).unwrap();
+        assert!(
+            !patch["status"]["hostsStatus"]
+                .as_object()
+                .unwrap()
+                .contains_key("host-2")
+        );
+    }
+
+    /// Drives `patch_status` against a mocked apiserver that answers the first request with a 409
+    /// Conflict: the status must go out as a merge patch on the `status` subresource, and the
+    /// identical patch must be sent again after the conflict.
+    #[tokio::test(start_paused = true)]
+    async fn patch_status_sends_a_merge_patch_and_retries_a_conflict() {
+        use kube::client::Body;
+
+        let (service, mut handle) =
+            tower_test::mock::pair::<http::Request<Body>, http::Response<Body>>();
+        let api = Api::<PlaybookPlan>::namespaced(kube::Client::new(service, "default"), "default");
+
+        let mut plan = PlaybookPlan::new("web", Default::default());
+        plan.metadata.namespace = Some("default".into());
+        let status = PlaybookPlanStatus {
+            phase: Phase::Succeeded,
+            summary: Some("1/1 up-to-date".into()),
+            ..Default::default()
+        };
+        let expected = status_merge_patch(None, &status).unwrap();
+        let mut persisted = plan.clone();
+        persisted.status = Some(status.clone());
+        let persisted = serde_json::to_value(&persisted).unwrap();
+
+        let server = tokio::spawn(async move {
+            let mut bodies = Vec::new();
+            for response_status in [409, 200] {
+                let (request, send) = handle.next_request().await.expect("a request");
+                assert_eq!(request.method(), http::Method::PATCH);
+                assert_eq!(
+                    request.uri().path(),
+                    "/apis/ansible.cloudbending.dev/v1beta1/namespaces/default/playbookplans/web/status"
+                );
+                assert_eq!(
+                    request.headers()[http::header::CONTENT_TYPE],
+                    "application/merge-patch+json"
+                );
+                let body = request.into_body().collect_bytes().await.unwrap();
+                bodies.push(serde_json::from_slice::<serde_json::Value>(&body).unwrap());
+
+                let response = match response_status {
+                    409 => serde_json::json!({
+                        "kind": "Status", "apiVersion": "v1", "status": "Failure",
+                        "reason": "Conflict", "message": "conflict", "code": 409,
+                    }),
+                    _ => persisted.clone(),
+                };
+                send.send_response(
+                    http::Response::builder()
+                        .status(response_status)
+                        .body(Body::from(serde_json::to_vec(&response).unwrap()))
+                        .unwrap(),
+                );
+            }
+            bodies
+        });
+
+        patch_status(&api, &plan, status).await.unwrap();
+
+        let bodies = server.await.unwrap();
+        assert_eq!(bodies, vec![expected.clone(), expected]);
+    }
+
+    /// Method, path (with query) and JSON body of a request the mocked apiserver received.
+    type Recorded = (http::Method, String, serde_json::Value);
+
+    /// A context whose client is answered by `respond`, which sees every request's method, path
+    /// (with query) and body. The requests are returned once the context has been dropped.
+    fn mocked_context(
+        dry_run: bool,
+        respond: impl Fn(&http::Method, &str, &serde_json::Value) -> (u16, serde_json::Value)
+        + Send
+        + 'static,
+    ) -> (
+        Arc<ReconciliationContext>,
+        tokio::task::JoinHandle<Vec<Recorded>>,
+    ) {
+        use kube::client::Body;
+
+        let (service, mut handle) =
+            tower_test::mock::pair::<http::Request<Body>, http::Response<Body>>();
+        let client = kube::Client::new(service, "default");
+
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            while let Some((request, send)) = handle.next_request().await {
+                let method = request.method().clone();
+                let path = request.uri().to_string();
+                let body = request.into_body().collect_bytes().await.unwrap();
+                let body = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+
+                let (code, response) = respond(&method, &path, &body);
+                send.send_response(
+                    http::Response::builder()
+                        .status(code)
+                        .body(Body::from(serde_json::to_vec(&response).unwrap()))
+                        .unwrap(),
+                );
+                requests.push((method, path, body));
+            }
+            requests
+        });
+
+        let context = ReconciliationContext {
+            client: client.clone(),
+            operator_namespace: "ansible-operator".into(),
+            enrolled_namespaces: Arc::new(["default".to_string()].into()),
+            ca: Arc::new(CertificateAuthority::generate().unwrap()),
+            node_access_policies: Arc::new(Writer::default().as_reader()),
+            proxy_image: "proxy:latest".into(),
+            proxy_grace: managed_ssh::ProxyGracePolicy {
+                grace_seconds: 0,
+                aggressiveness: 1,
+                threshold_secs: [0; 3],
+            },
+            watch_namespace: None,
+            recorder: events::recorder(client),
+            dry_run,
+            object_locks: Default::default(),
+            image_volumes: false,
+            intervals: ReconcileIntervals::default(),
+            error_backoff: Default::default(),
+            selector_label_keys: Vec::new(),
+            max_concurrent_jobs: None,
+        };
+        (Arc::new(context), server)
+    }
+
+    fn api_status(code: u16) -> serde_json::Value {
+        serde_json::json!({
+            "kind": "Status", "apiVersion": "v1",
+            "status": if code < 400 { "Success" } else { "Failure" }, "code": code,
+        })
+    }
+
+    fn deployed_plan() -> PlaybookPlan {
+        let mut plan = PlaybookPlan::new("web", Default::default());
+        plan.metadata.namespace = Some("default".into());
+        plan.metadata.generation = Some(1);
+        plan
+    }
+
+    #[tokio::test]
+    async fn a_new_plan_in_an_enrolled_namespace_gets_the_cleanup_finalizer_first() {
+        let plan = deployed_plan();
+        let mut persisted = serde_json::to_value(&plan).unwrap();
+        persisted["metadata"]["finalizers"] = serde_json::json!([FINALIZER]);
+
+        let (context, server) = mocked_context(false, move |_, _, _| (200, persisted.clone()));
+
+        let action = reconcile(Arc::new(plan), context).await.unwrap();
+        assert_eq!(action, Action::await_change());
+
+        let requests = server.await.unwrap();
+        let [(method, path, body)] = requests.as_slice() else {
+            panic!("expected only the finalizer patch, got {requests:?}");
+        };
+        assert_eq!(method, http::Method::PATCH);
+        assert!(
+            path.starts_with(
+                "/apis/ansible.cloudbending.dev/v1beta1/namespaces/default/playbookplans/web"
+            ),
+            "{path}"
+        );
+        assert!(body.to_string().contains(FINALIZER), "{body}");
+    }
+
+    #[tokio::test]
+    async fn a_deleted_plan_stops_its_jobs_and_deletes_its_workspace_before_releasing_the_finalizer()
+     {
+        let mut plan = deployed_plan();
+        plan.metadata.finalizers = Some(vec![FINALIZER.into()]);
+        plan.metadata.deletion_timestamp =
+            Some(k8s_openapi::apimachinery::pkg::apis::meta::v1::Time(
+                k8s_openapi::jiff::Timestamp::UNIX_EPOCH,
+            ));
+        let persisted = serde_json::to_value(&plan).unwrap();
+
+        let (context, server) = mocked_context(false, move |method, path, _| {
+            match (method.as_str(), path.split('?').next().unwrap()) {
+                ("GET", "/apis/batch/v1/namespaces/default/jobs") => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "meta.k8s.io/v1", "kind": "PartialObjectMetadataList",
+                        "metadata": {}, "items": [{"metadata": {"name": "apply-web-abc-1"}}],
+                    }),
+                ),
+                ("DELETE", "/apis/batch/v1/namespaces/default/jobs/apply-web-abc-1") => {
+                    (200, api_status(200))
+                }
+                ("DELETE", "/api/v1/namespaces/default/secrets/ansible-workspace-web") => {
+                    (404, api_status(404))
+                }
+                ("PATCH", _) => (200, persisted.clone()),
+                _ => (201, serde_json::json!({})),
+            }
+        });
+
+        let action = reconcile(Arc::new(plan), context).await.unwrap();
+        assert_eq!(action, Action::await_change());
+
+        let requests = server.await.unwrap();
+        let (_, list, _) = &requests[0];
+        assert!(
+            list.contains("labelSelector=ansible.cloudbending.dev%2Fplaybookplan%3Dweb"),
+            "{list}"
+        );
+
+        let (method, path, body) = &requests[1];
+        assert_eq!(method, http::Method::DELETE);
+        assert!(path.contains("/jobs/apply-web-abc-1"), "{path}");
+        assert_eq!(body["propagationPolicy"], "Foreground");
+
+        let (method, path, _) = &requests[2];
+        assert_eq!(method, http::Method::DELETE);
+        assert!(path.contains("/secrets/ansible-workspace-web"), "{path}");
+
+        let (method, path, body) = requests.last().unwrap();
+        assert_eq!(method, http::Method::PATCH);
+        assert!(path.contains("/playbookplans/web"), "{path}");
+        assert!(body.to_string().contains(r#""op":"remove""#), "{body}");
+    }
+
+    #[tokio::test]
+    async fn a_superseded_reconcile_keeps_the_failure_count() {
+        let plan = Arc::new(deployed_plan());
+        let key = ObjectRef::from_obj(&*plan).to_string();
+        let (context, server) = mocked_context(false, |method, path, _| {
+            panic!("a superseded reconcile must not call the apiserver: {method} {path}")
+        });
+        let base = std::time::Duration::from_secs(15);
+        let transient = || ReconcileError::from(utils::api_error(503, "ServiceUnavailable"));
+        context.error_backoff.next_delay(&key, &transient(), base);
+
+        // A reconcile waits behind a running one, and a newer trigger arrives behind it.
+        let running = context.object_locks.enter("default/web").await.unwrap();
+        let superseded = tokio::spawn(reconcile(Arc::clone(&plan), Arc::clone(&context)));
+        tokio::task::yield_now().await;
+        let locks = Arc::clone(&context.object_locks);
+        let newer = tokio::spawn(async move { locks.enter("default/web").await.is_some() });
+        tokio::task::yield_now().await;
+        drop(running);
+
+        assert_eq!(superseded.await.unwrap().unwrap(), Action::await_change());
+        assert!(newer.await.unwrap());
+        // Still the second failure in a row, not a first one after a reset.
+        assert_eq!(
+            context.error_backoff.next_delay(&key, &transient(), base),
+            base * 2
+        );
+        drop(context);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_deleted_plan_with_a_config_map_workspace_deletes_the_config_map() {
+        let mut plan = deployed_plan();
+        plan.spec.workspace_storage = WorkspaceStorage::ConfigMap;
+        plan.metadata.finalizers = Some(vec![FINALIZER.into()]);
+        plan.metadata.deletion_timestamp =
+            Some(k8s_openapi::apimachinery::pkg::apis::meta::v1::Time(
+                k8s_openapi::jiff::Timestamp::UNIX_EPOCH,
+            ));
+        let persisted = serde_json::to_value(&plan).unwrap();
+
+        let (context, server) = mocked_context(false, move |method, path, _| {
+            match (method.as_str(), path.split('?').next().unwrap()) {
+                ("GET", "/apis/batch/v1/namespaces/default/jobs") => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "meta.k8s.io/v1", "kind": "PartialObjectMetadataList",
+                        "metadata": {}, "items": [],
+                    }),
+                ),
+                ("DELETE", _) => (404, api_status(404)),
+                ("PATCH", _) => (200, persisted.clone()),
+                _ => (201, serde_json::json!({})),
+            }
+        });
+
+        reconcile(Arc::new(plan), context).await.unwrap();
+
+        let requests = server.await.unwrap();
+        let deleted: Vec<&str> = requests
+            .iter()
+            .filter(|(method, _, _)| method == http::Method::DELETE)
+            .map(|(_, path, _)| path.split('?').next().unwrap())
+            .collect();
+        assert_eq!(
+            deleted,
+            vec!["/api/v1/namespaces/default/configmaps/ansible-workspace-web"]
+        );
+    }
+
+    #[test]
+    fn stale_jobs_are_the_finished_ones_of_other_hashes() {
+        let current =
+            execution_evaluator::calculate_execution_hash("- hosts: all", std::iter::empty());
+        let older =
+            execution_evaluator::calculate_execution_hash("- hosts: old", std::iter::empty());
+        let job = |name: &str, hash: Option<&ExecutionHash>, finished: Option<&str>| {
+            serde_json::json!({
+                "metadata": {
+                    "name": name,
+                    "labels": hash.map(|hash| serde_json::json!({
+                        labels::PLAYBOOKPLAN_NAME: "web",
+                        labels::PLAYBOOKPLAN_HASH: hash.to_string(),
+                    })),
+                },
+                "status": {
+                    "conditions": finished
+                        .map(|type_| serde_json::json!([{"type": type_, "status": "True"}])),
+                },
+            })
+        };
+        let jobs: kube::core::ObjectList<Job> = serde_json::from_value(serde_json::json!({
+            "apiVersion": "batch/v1", "kind": "JobList", "metadata": {},
+            "items": [
+                job("apply-web-old-1", Some(&older), Some("Failed")),
+                job("apply-web-old-2", Some(&older), Some("Complete")),
+                job("apply-web-old-3", Some(&older), None),
+                job("apply-web-cur-1", Some(&current), Some("Complete")),
+                job("apply-web-cur-2", Some(&current), None),
+                job("unlabelled", None, Some("Complete")),
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            stale_jobs(&jobs.items, &current, "apply-web-cur-2"),
+            vec!["apply-web-old-1", "apply-web-old-2"]
+        );
+        // The run just evaluated is kept even if it was an older hash's (a spec edit mid-run).
+        assert_eq!(
+            stale_jobs(&jobs.items, &current, "apply-web-old-2"),
+            vec!["apply-web-old-1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn a_dry_run_resolves_the_plan_but_never_writes_to_the_cluster() {
+        let mut plan = deployed_plan();
+        plan.metadata.uid = Some("4a1c5b0e-0000-0000-0000-000000000000".into());
+        plan.spec.image = "docker.io/serversideup/ansible-core:2.18".into();
+        plan.spec.template.playbook = "- hosts: all\n  tasks: []\n".into();
+        plan.spec.inventory_refs = vec![v1beta1::InventoryRef {
+            cluster_inventory: None,
+            static_inventory: Some("edge".into()),
+            ..Default::default()
+        }];
+
+        let (context, server) = mocked_context(true, move |method, path, _| {
+            if method != http::Method::GET {
+                return (500, api_status(500));
+            }
+            match path.split('?').next().unwrap() {
+                "/apis/ansible.cloudbending.dev/v1beta1/namespaces/default/staticinventories/edge" => {
+                    (
+                        200,
+                        serde_json::json!({
+                            "apiVersion": "ansible.cloudbending.dev/v1beta1", "kind": "StaticInventory",
+                            "metadata": {"name": "edge", "namespace": "default"},
+                            "spec": {
+                                "hosts": [{"name": "routers", "hosts": ["ccu.fritz.box"]}],
+                                "ssh": {"user": "admin", "secretRef": {"name": "edge-key"}},
+                            },
+                        }),
+                    )
+                }
+                "/api/v1/namespaces/default/secrets/edge-key" => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "v1", "kind": "Secret",
+                        "metadata": {"name": "edge-key", "namespace": "default"},
+                        "type": "kubernetes.io/ssh-auth",
+                        "data": {"ssh-privatekey": "a2V5"},
+                    }),
+                ),
+                _ => (404, api_status(404)),
+            }
+        });
+
+        let action = reconcile(Arc::new(plan), context).await.unwrap();
+        assert_ne!(
+            action,
+            Action::await_change(),
+            "stopped before evaluating the run"
+        );
+
+        let requests = server.await.unwrap();
+        let writes: Vec<_> = requests
+            .iter()
+            .filter(|(method, _, _)| method != http::Method::GET)
+            .collect();
+        assert!(
+            writes.is_empty(),
+            "dry run wrote to the cluster: {writes:?}"
+        );
+        assert!(
+            requests
+                .iter()
+                .any(|(_, path, _)| path.contains("/staticinventories/edge")),
+            "{requests:?}"
+        );
+    }
+
+    /// A deployed `OneShot` plan of an empty play on the `edge` StaticInventory's one host.
+    fn edge_plan() -> PlaybookPlan {
+        let mut plan = deployed_plan();
+        plan.metadata.uid = Some("4a1c5b0e-0000-0000-0000-000000000000".into());
+        plan.metadata.finalizers = Some(vec![FINALIZER.into()]);
+        plan.spec.image = "docker.io/serversideup/ansible-core:2.18".into();
+        plan.spec.template.playbook = "- hosts: all\n  tasks: []\n".into();
+        plan.spec.inventory_refs = vec![v1beta1::InventoryRef {
+            cluster_inventory: None,
+            static_inventory: Some("edge".into()),
+            ..Default::default()
+        }];
+        plan
+    }
+
+    /// Reconciles `plan` against an apiserver serving the `edge` StaticInventory and its SSH key
+    /// Secret, and returns the status written — failing if anything else was written, so no run
+    /// was started.
+    async fn reconcile_starting_no_run(plan: PlaybookPlan) -> PlaybookPlanStatus {
+        let persisted = serde_json::to_value(&plan).unwrap();
+        let (context, server) = mocked_context(false, move |method, path, _| {
+            match (method.as_str(), path.split('?').next().unwrap()) {
+                (
+                    "GET",
+                    "/apis/ansible.cloudbending.dev/v1beta1/namespaces/default/staticinventories/edge",
+                ) => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "ansible.cloudbending.dev/v1beta1", "kind": "StaticInventory",
+                        "metadata": {"name": "edge", "namespace": "default"},
+                        "spec": {
+                            "hosts": [{"name": "routers", "hosts": ["ccu.fritz.box"]}],
+                            "ssh": {"user": "admin", "secretRef": {"name": "edge-key"}},
+                        },
+                    }),
+                ),
+                ("GET", "/api/v1/namespaces/default/secrets/edge-key") => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "v1", "kind": "Secret",
+                        "metadata": {"name": "edge-key", "namespace": "default"},
+                        "type": "kubernetes.io/ssh-auth",
+                        "data": {"ssh-privatekey": "a2V5"},
+                    }),
+                ),
+                ("PATCH", path) if path.ends_with("/playbookplans/web/status") => {
+                    (200, persisted.clone())
+                }
+                ("GET", _) => (404, api_status(404)),
+                _ => (500, api_status(500)),
+            }
+        });
+
+        reconcile(Arc::new(plan), context).await.unwrap();
+
+        let requests = server.await.unwrap();
+        let writes: Vec<_> = requests
+            .iter()
+            .filter(|(method, _, _)| method != http::Method::GET)
+            .collect();
+        let [(_, _, patch)] = writes.as_slice() else {
+            panic!("expected only a status write: {writes:?}");
+        };
+        serde_json::from_value(patch["status"].clone()).unwrap()
+    }
+
+    fn condition<'a>(
+        status: &'a PlaybookPlanStatus,
+        type_: &str,
+    ) -> &'a v1beta1::PlaybookPlanCondition {
+        status.conditions.iter().find(|c| c.type_ == type_).unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_playbook_that_does_not_render_is_reported_and_starts_no_run() {
+        let mut plan = edge_plan();
+        plan.spec.template.playbook = "- tasks: []\n".into();
+
+        let status = reconcile_starting_no_run(plan).await;
+
+        assert_eq!(status.eligible_hosts[0].hosts, vec!["ccu.fritz.box"]);
+        let ready = condition(&status, "Ready");
+        assert_eq!(ready.status, "False");
+        assert_eq!(ready.reason.as_deref(), Some("InvalidPlaybook"));
+        assert_eq!(
+            ready.message.as_deref(),
+            Some("the playbook is invalid: play 1 has no `hosts`")
+        );
+    }
+
+    #[tokio::test]
+    async fn a_run_whose_schedule_never_fires_is_reported_instead_of_forecast() {
+        let mut plan = edge_plan();
+        plan.spec.mode = ExecutionMode::Recurring;
+        plan.spec.schedule = Some("0 0 30 2 *".into());
+        plan.status = Some(PlaybookPlanStatus {
+            phase: Phase::Applying,
+            current_job_name: Some("apply-web-abc-1".into()),
+            ..Default::default()
+        });
+        let persisted = serde_json::to_value(&plan).unwrap();
+        let (context, server) = mocked_context(false, move |method, path, _| {
+            match (method.as_str(), path.split('?').next().unwrap()) {
+                ("PATCH", path) if path.ends_with("/playbookplans/web/status") => {
+                    (200, persisted.clone())
+                }
+                _ => (500, api_status(500)),
+            }
+        });
+
+        let action = reconcile(Arc::new(plan), context).await.unwrap();
+        assert_eq!(action, Action::await_change());
+
+        let requests = server.await.unwrap();
+        let [(_, _, patch)] = requests.as_slice() else {
+            panic!("expected only a status write: {requests:?}");
+        };
+        let status: PlaybookPlanStatus = serde_json::from_value(patch["status"].clone()).unwrap();
+        let degraded = condition(&status, "Degraded");
+        assert_eq!(degraded.status, "True");
+        assert!(
+            degraded.message.as_deref().unwrap().contains("never fires"),
+            "{degraded:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn a_suspended_plan_keeps_its_status_current_but_starts_no_run() {
+        let mut plan = edge_plan();
+        plan.spec.suspend = true;
+
+        let status = reconcile_starting_no_run(plan).await;
+
+        assert_eq!(status.eligible_hosts[0].hosts, vec!["ccu.fritz.box"]);
+        assert!(status.next_run.is_none());
+        let suspended = condition(&status, "Suspended");
+        assert_eq!(suspended.status, "True");
+        assert_eq!(suspended.reason.as_deref(), Some("SuspendedBySpec"));
+        assert_eq!(
+            condition(&status, "Ready").reason.as_deref(),
+            Some("HostsOutdated")
+        );
+    }
+
+    /// A mocked apiserver for `spawn_ansible_job` with no Job labelled for the run yet: a `create`
+    /// of any name in `taken` is refused with 409, and reading that Job back shows it labelled with
+    /// `holder`'s hash.
+    async fn spawn_against_taken_names(
+        taken: &'static [&'static str],
+        holder: ExecutionHash,
+    ) -> (PlaybookPlanStatus, Vec<Recorded>) {
+        let mut plan = deployed_plan();
+        plan.metadata.uid = Some("4a1c5b0e-0000-0000-0000-000000000000".into());
+        plan.spec.image = "docker.io/serversideup/ansible-core:2.18".into();
+        let hash = execution_evaluator::calculate_execution_hash("[]", std::iter::empty());
+
+        let (context, server) = mocked_context(false, move |method, path, body| {
+            let path = path.split('?').next().unwrap();
+            let named = path.rsplit_once("/jobs/").map(|(_, name)| name);
+            match (method.as_str(), named) {
+                ("GET", None) => (
+                    200,
+                    serde_json::json!({"apiVersion": "batch/v1", "kind": "JobList", "metadata": {}, "items": []}),
+                ),
+                ("GET", Some(name)) => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "batch/v1", "kind": "Job",
+                        "metadata": {
+                            "name": name,
+                            "labels": {labels::PLAYBOOKPLAN_HASH: holder.to_string()},
+                        },
+                    }),
+                ),
+                _ if taken
+                    .iter()
+                    .any(|name| body["metadata"]["name"].as_str().unwrap().ends_with(name)) =>
+                {
+                    (
+                        409,
+                        serde_json::json!({
+                            "kind": "Status", "apiVersion": "v1", "status": "Failure",
+                            "reason": "AlreadyExists", "message": "already exists", "code": 409,
+                        }),
+                    )
+                }
+                _ => (200, body.clone()),
+            }
+        });
+        let jobs_api = Api::<Job>::namespaced(context.client.clone(), "default");
+        let mut resource_status = PlaybookPlanStatus::default();
+
+        spawn_ansible_job(
+            &jobs_api,
+            hash,
+            &[],
+            &plan,
+            &plan.spec.image,
+            &RoleFiles::new(),
+            None,
+            &[],
+            &mut resource_status,
+        )
+        .await
+        .unwrap();
+        drop((jobs_api, context));
+
+        (resource_status, server.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn a_pending_job_for_other_workspace_content_is_replaced() {
+        let mut plan = deployed_plan();
+        plan.metadata.uid = Some("4a1c5b0e-0000-0000-0000-000000000000".into());
+        plan.spec.image = "docker.io/serversideup/ansible-core:2.18".into();
+        let hash = execution_evaluator::calculate_execution_hash("[]", std::iter::empty());
+        let pending = format!("apply-web-{}-2", utils::generate_id(*hash));
+
+        let listed = pending.clone();
+        let (context, server) =
+            mocked_context(false, move |method, _, body| match method.as_str() {
+                "GET" => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "batch/v1", "kind": "JobList", "metadata": {},
+                        "items": [{
+                            "metadata": {
+                                "name": listed,
+                                "resourceVersion": "7",
+                                "annotations": {labels::WORKSPACE_HASH: "old"},
+                                "creationTimestamp": "2026-01-01T00:00:00Z",
+                            },
+                            "status": {"active": 1},
+                        }],
+                    }),
+                ),
+                "DELETE" => (
+                    200,
+                    serde_json::json!({"kind": "Status", "status": "Success"}),
+                ),
+                _ => (200, body.clone()),
+            });
+        let jobs_api = Api::<Job>::namespaced(context.client.clone(), "default");
+        // The cached status lags behind the pending Job's attempt.
+        let mut resource_status = PlaybookPlanStatus {
+            retry_count: 1,
+            ..Default::default()
+        };
+
+        let replaced = spawn_ansible_job(
+            &jobs_api,
+            hash,
+            &[],
+            &plan,
+            &plan.spec.image,
+            &RoleFiles::new(),
+            Some("new"),
+            &[],
+            &mut resource_status,
+        )
+        .await
+        .unwrap();
+        drop((jobs_api, context));
+        let requests = server.await.unwrap();
+
+        assert_eq!(replaced.as_deref(), Some(pending.as_str()));
+        let (_, deleted, precondition) = &requests[1];
+        assert!(deleted.contains(&format!("/jobs/{pending}?")), "{deleted}");
+        assert_eq!(precondition["preconditions"]["resourceVersion"], "7");
+        let (method, _, created) = &requests[2];
+        assert_eq!(method, http::Method::POST);
+        assert_eq!(
+            created["metadata"]["annotations"][labels::WORKSPACE_HASH],
+            "new"
+        );
+        assert_eq!(resource_status.retry_count, 3);
+        assert!(
+            resource_status
+                .current_job_name
+                .as_deref()
+                .is_some_and(|name| name.ends_with("-3")),
+            "{resource_status:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn a_job_created_concurrently_by_another_reconcile_is_adopted() {
+        let hash = execution_evaluator::calculate_execution_hash("[]", std::iter::empty());
+        let (resource_status, requests) = spawn_against_taken_names(&["-1"], hash).await;
+
+        assert_eq!(resource_status.phase, Phase::Applying);
+        assert_eq!(resource_status.retry_count, 1);
+        assert!(
+            resource_status
+                .current_job_name
+                .as_deref()
+                .is_some_and(|name| name.starts_with("apply-web-") && name.ends_with("-1")),
+            "{resource_status:?}"
+        );
+        let posts = requests
+            .iter()
+            .filter(|(method, _, _)| method == http::Method::POST)
+            .count();
+        assert_eq!(posts, 1, "{requests:?}");
+    }
+
+    #[tokio::test]
+    async fn a_job_name_held_by_another_run_is_skipped_not_adopted() {
+        let other = "5eed".parse::<ExecutionHash>().unwrap();
+        let (resource_status, requests) = spawn_against_taken_names(&["-1", "-2"], other).await;
+
+        assert_eq!(resource_status.phase, Phase::Applying);
+        assert_eq!(resource_status.retry_count, 3);
+        assert!(
+            resource_status
+                .current_job_name
+                .as_deref()
+                .is_some_and(|name| name.ends_with("-3")),
+            "{resource_status:?}"
+        );
+        let (method, _, created) = requests.last().unwrap();
+        assert_eq!(method, http::Method::POST);
+        assert_eq!(
+            created["metadata"]["name"].as_str(),
+            resource_status.current_job_name.as_deref()
+        );
+    }
+
+    /// A context capped at `max` concurrent Jobs, enrolled in `default` and `team-a`, whose
+    /// apiserver lists `running` unfinished and one finished operator Job in each namespace.
+    fn throttled_context(
+        max: usize,
+        running: usize,
+    ) -> (
+        Arc<ReconciliationContext>,
+        tokio::task::JoinHandle<Vec<Recorded>>,
+    ) {
+        let (mut context, server) = mocked_context(false, move |method, path, _| {
+            assert_eq!(method, http::Method::GET);
+            assert!(path.contains("/jobs?&labelSelector=ansible.cloudbending.dev%2Fplaybookplan"));
+            let finished = serde_json::json!({
+                "metadata": { "name": "done" },
+                "status": { "conditions": [{ "type": "Complete", "status": "True" }] },
+            });
+            let items = std::iter::repeat_n(
+                serde_json::json!({ "metadata": { "name": "busy" } }),
+                running,
+            )
+            .chain([finished])
+            .collect::<Vec<_>>();
+            (
+                200,
+                serde_json::json!({
+                    "kind": "JobList", "apiVersion": "batch/v1", "metadata": {}, "items": items,
+                }),
+            )
+        });
+        let context_mut = Arc::get_mut(&mut context).unwrap();
+        context_mut.enrolled_namespaces =
+            Arc::new(["default".to_string(), "team-a".to_string()].into());
+        context_mut.max_concurrent_jobs = Some(max);
+        (context, server)
+    }
+
+    fn throttled_condition(status: &PlaybookPlanStatus) -> Option<&v1beta1::PlaybookPlanCondition> {
+        status.conditions.iter().find(|c| c.type_ == "Throttled")
+    }
+
+    #[tokio::test]
+    async fn running_operator_jobs_counts_unfinished_jobs_in_every_enrolled_namespace() {
+        let (context, server) = throttled_context(10, 2);
+
+        assert_eq!(running_operator_jobs(&context).await.unwrap(), 4);
+
+        drop(context);
+        let paths: Vec<_> = server.await.unwrap().into_iter().map(|r| r.1).collect();
+        assert!(paths[0].starts_with("/apis/batch/v1/namespaces/default/jobs?"));
+        assert!(paths[1].starts_with("/apis/batch/v1/namespaces/team-a/jobs?"));
+    }
+
+    #[tokio::test]
+    async fn a_run_is_deferred_while_the_job_cap_is_reached() {
+        let (context, server) = throttled_context(4, 2);
+        let mut status = PlaybookPlanStatus::default();
+
+        let requeue = throttle(&context, &mut status).await.unwrap();
+
+        assert_eq!(requeue, Some(JOB_RUNNING_REQUEUE));
+        let condition = throttled_condition(&status).unwrap();
+        assert_eq!(condition.status, "True");
+        assert_eq!(condition.reason.as_deref(), Some("MaxConcurrentJobs"));
+        drop(context);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_run_below_the_job_cap_starts_and_clears_throttled() {
+        let (context, server) = throttled_context(5, 2);
+        let mut status = PlaybookPlanStatus::default();
+        status::set_throttled_condition(&mut status, Some((5, 5)));
+
+        assert_eq!(throttle(&context, &mut status).await.unwrap(), None);
+
+        assert_eq!(throttled_condition(&status).unwrap().status, "False");
+        drop(context);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn without_a_job_cap_nothing_is_counted() {
+        let (context, server) = mocked_context(false, |_, path, _| panic!("unexpected {path}"));
+        let mut status = PlaybookPlanStatus::default();
+
+        assert_eq!(throttle(&context, &mut status).await.unwrap(), None);
+
+        assert!(throttled_condition(&status).is_none());
+        drop(context);
+        assert!(server.await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn an_inventory_only_plan_publishes_its_hosts_and_runs_nothing() {
+        let mut plan = deployed_plan();
+        plan.metadata.uid = Some("4a1c5b0e-0000-0000-0000-000000000000".into());
+        plan.metadata.finalizers = Some(vec![FINALIZER.into()]);
+        plan.spec.mode = ExecutionMode::InventoryOnly;
+        plan.spec.inventory_refs = vec![v1beta1::InventoryRef {
+            cluster_inventory: None,
+            static_inventory: Some("edge".into()),
+            ..Default::default()
+        }];
+        let persisted = serde_json::to_value(&plan).unwrap();
+
+        let (context, server) = mocked_context(false, move |method, path, body| {
+            match (method.as_str(), path.split('?').next().unwrap()) {
+                (
+                    "GET",
+                    "/apis/ansible.cloudbending.dev/v1beta1/namespaces/default/staticinventories/edge",
+                ) => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "ansible.cloudbending.dev/v1beta1", "kind": "StaticInventory",
+                        "metadata": {"name": "edge", "namespace": "default"},
+                        "spec": {
+                            "hosts": [{"name": "routers", "hosts": ["ccu.fritz.box"]}],
+                            "ssh": {"user": "admin", "secretRef": {"name": "edge-key"}},
+                        },
+                    }),
+                ),
+                ("POST", "/api/v1/namespaces/default/configmaps") => (201, body.clone()),
+                ("PATCH", _) => (200, persisted.clone()),
+                _ => (
+                    404,
+                    serde_json::json!({
+                        "kind": "Status", "apiVersion": "v1", "status": "Failure",
+                        "reason": "NotFound", "code": 404,
+                    }),
+                ),
+            }
+        });
+
+        let action = reconcile(Arc::new(plan), context).await.unwrap();
+        assert_eq!(action, Action::requeue(INVENTORY_REFRESH_INTERVAL));
+
+        let requests = server.await.unwrap();
+        let writes: Vec<_> = requests
+            .iter()
+            .filter(|(method, _, _)| method != http::Method::GET)
+            .collect();
+        let [(_, _, config_map), (_, path, body)] = writes.as_slice() else {
+            panic!("expected the inventory ConfigMap and the status patch, got {writes:?}");
+        };
+        assert_eq!(config_map["metadata"]["name"], "ansible-inventory-web");
+        let inventory = config_map["data"]["inventory.yml"].as_str().unwrap();
+        assert!(inventory.contains("ccu.fritz.box"), "{inventory}");
+
+        assert!(path.contains("/playbookplans/web/status"), "{path}");
+        assert_eq!(body["status"]["phase"], "InventoryResolved");
+        assert_eq!(
+            body["status"]["eligibleHosts"][0]["hosts"],
+            serde_json::json!(["ccu.fritz.box"])
+        );
+    }
+
+    #[tokio::test]
+    async fn a_reconcile_that_computes_the_persisted_status_again_writes_no_status() {
+        let mut plan = deployed_plan();
+        plan.metadata.uid = Some("4a1c5b0e-0000-0000-0000-000000000000".into());
+        plan.metadata.finalizers = Some(vec![FINALIZER.into()]);
+        plan.spec.mode = ExecutionMode::InventoryOnly;
+        plan.spec.inventory_refs = vec![v1beta1::InventoryRef {
+            cluster_inventory: None,
+            static_inventory: Some("edge".into()),
+            ..Default::default()
+        }];
+
+        let respond = |plan: &PlaybookPlan| {
+            let persisted = serde_json::to_value(plan).unwrap();
+            move |method: &http::Method, path: &str, body: &serde_json::Value| match (
+                method.as_str(),
+                path.split('?').next().unwrap(),
+            ) {
+                (
+                    "GET",
+                    "/apis/ansible.cloudbending.dev/v1beta1/namespaces/default/staticinventories/edge",
+                ) => (
+                    200,
+                    serde_json::json!({
+                        "apiVersion": "ansible.cloudbending.dev/v1beta1", "kind": "StaticInventory",
+                        "metadata": {"name": "edge", "namespace": "default"},
+                        "spec": {
+                            "hosts": [{"name": "routers", "hosts": ["ccu.fritz.box"]}],
+                            "ssh": {"user": "admin", "secretRef": {"name": "edge-key"}},
+                        },
+                    }),
+                ),
+                ("POST", "/api/v1/namespaces/default/configmaps") => (201, body.clone()),
+                ("PATCH", _) => (200, persisted.clone()),
+                _ => (
+                    404,
+                    serde_json::json!({
+                        "kind": "Status", "apiVersion": "v1", "status": "Failure",
+                        "reason": "NotFound", "code": 404,
+                    }),
+                ),
+            }
+        };
+        let status_writes = |requests: Vec<Recorded>| -> Vec<serde_json::Value> {
+            requests
+                .into_iter()
+                .filter(|(method, path, _)| method != http::Method::GET && path.contains("/status"))
+                .map(|(_, _, body)| body)
+                .collect()
+        };
+
+        // First reconcile: the status is new, so it is written.
+        let (context, server) = mocked_context(false, respond(&plan));
+        reconcile(Arc::new(plan.clone()), context).await.unwrap();
+        let [written] = status_writes(server.await.unwrap()).try_into().unwrap();
+
+        // Second reconcile of the plan as persisted: the same status comes out, so nothing is sent.
+        plan.status = Some(serde_json::from_value(written["status"].clone()).unwrap());
+        let (context, server) = mocked_context(false, respond(&plan));
+        let action = reconcile(Arc::new(plan), context).await.unwrap();
+        assert_eq!(action, Action::requeue(INVENTORY_REFRESH_INTERVAL));
+
+        let writes = status_writes(server.await.unwrap());
+        assert!(
+            writes.is_empty(),
+            "unchanged status was written: {writes:?}"
+        );
+    }
+
+    #[test]
+    fn a_new_generation_with_reset_makes_every_host_outdated_and_deletes_its_state() {
+        let hash =
+            execution_evaluator::calculate_execution_hash("- hosts: all", std::iter::empty());
+        let previous = PlaybookPlanStatus {
+            eligible_hosts: vec![ResolvedHosts {
+                name: "all".into(),
+                hosts: vec!["host-1".into(), "host-2".into()],
+            }],
+            hosts_status: Some(BTreeMap::from_iter(["host-1", "host-2"].map(|host| {
+                let status = v1beta1::HostStatus {
+                    last_applied_hash: hash.to_string(),
+                    ..Default::default()
+                };
+                (host.to_string(), status)
+            }))),
+            last_processed_generation: Some(1),
+            ..Default::default()
+        };
+        assert!(find_outdated_hosts(&previous, &hash).unwrap().is_empty());
+
+        let mut status = previous.clone();
+        assert!(status::reset_hosts_on_generation_change(
+            &mut status,
+            2,
+            true
+        ));
+
+        assert_eq!(
+            find_outdated_hosts(&status, &hash).unwrap(),
+            vec!["host-1".to_string(), "host-2".to_string()]
+        );
+        let patch = status_merge_patch(Some(&previous), &status).unwrap();
+        assert_eq!(patch["status"]["lastProcessedGeneration"], 2);
+        assert!(patch["status"]["hostsStatus"]["host-1"].is_null());
+        assert!(patch["status"]["hostsStatus"]["host-2"].is_null());
+    }
+
+    #[tokio::test]
+    async fn a_missing_secret_hashes_as_empty_and_other_errors_fail_the_hash() {
+        let data = BTreeMap::from([(
+            "variables.yaml".to_string(),
+            k8s_openapi::ByteString(b"greeting: hello".to_vec()),
+        )]);
+        let present = serde_json::json!({
+            "apiVersion": "v1", "kind": "Secret",
+            "metadata": {"name": "present", "namespace": "default"},
+            "data": {"variables.yaml": "Z3JlZXRpbmc6IGhlbGxv"},
+        });
+        let (context, _server) = mocked_context(false, move |_, path, _| {
+            match path.rsplit('/').next().unwrap() {
+                "present" => (200, present.clone()),
+                "flaky" => (500, api_status(500)),
+                _ => (404, api_status(404)),
+            }
+        });
+        let secrets_api = Api::<Secret>::namespaced(context.client.clone(), "default");
+        let config_maps_api = Api::<ConfigMap>::namespaced(context.client.clone(), "default");
+        let names = |names: &[&str]| names.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        let with_missing = names(&["present", "missing"]);
+        let (hash, missing) = hash_playbook_inputs(
+            "- hosts: all",
+            &with_missing.iter().collect::<Vec<_>>(),
+            &[],
+            &secrets_api,
+            &[],
+            &config_maps_api,
+            &[],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            hash,
+            execution_evaluator::calculate_execution_hash("- hosts: all", [&data])
+        );
+        assert_eq!(missing.secrets, vec!["missing".to_string()]);
+        assert!(missing.secret_keys.is_empty());
+
+        let present_name = "present".to_string();
+        let (_, missing) = hash_playbook_inputs(
+            "- hosts: all",
+            &[&present_name, &present_name],
+            &[
+                (&present_name, "variables.yaml"),
+                (&present_name, "values.yml"),
+                (&present_name, "values.yml"),
+            ],
+            &secrets_api,
+            &[],
+            &config_maps_api,
+            &[],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            missing.secret_keys,
+            vec![("present".to_string(), "values.yml".to_string())]
+        );
+
+        let with_flaky = names(&["present", "flaky"]);
+        let result = hash_playbook_inputs(
+            "- hosts: all",
+            &with_flaky.iter().collect::<Vec<_>>(),
+            &[],
+            &secrets_api,
+            &[],
+            &config_maps_api,
+            &[],
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn variable_config_maps_are_hashed_like_secrets_and_reported_when_missing() {
+        let config_map = |greeting: &str| {
+            serde_json::json!({
+                "apiVersion": "v1", "kind": "ConfigMap",
+                "metadata": {"name": "defaults", "namespace": "default"},
+                "data": {"variables.yaml": format!("greeting: {greeting}")},
+            })
+        };
+        let hash_with = |response: (u16, serde_json::Value)| async move {
+            let (context, _server) = mocked_context(false, move |_, path, _| {
+                match path.rsplit('/').next().unwrap() {
+                    "defaults" => response.clone(),
+                    _ => (404, api_status(404)),
+                }
+            });
+            let secrets_api = Api::<Secret>::namespaced(context.client.clone(), "default");
+            let config_maps_api = Api::<ConfigMap>::namespaced(context.client.clone(), "default");
+            let name = "defaults".to_string();
+            hash_playbook_inputs(
+                "- hosts: all",
+                &[],
+                &[],
+                &secrets_api,
+                &[&name],
+                &config_maps_api,
+                &[],
+            )
+            .await
+        };
+
+        let (hello, missing) = hash_with((200, config_map("hello"))).await.unwrap();
+        assert_eq!(missing, MissingInputs::default());
+        let data = BTreeMap::from([(
+            "variables.yaml".to_string(),
+            k8s_openapi::ByteString(b"greeting: hello".to_vec()),
+        )]);
+        assert_eq!(
+            hello,
+            execution_evaluator::calculate_execution_hash("- hosts: all", [&data])
+        );
+
+        let (goodbye, _) = hash_with((200, config_map("goodbye"))).await.unwrap();
+        assert_ne!(goodbye, hello);
+
+        let (_, missing) = hash_with((404, api_status(404))).await.unwrap();
+        assert_eq!(missing.config_maps, vec!["defaults".to_string()]);
+        assert!(missing.secrets.is_empty());
+
+        assert!(hash_with((500, api_status(500))).await.is_err());
+    }
+
+    /// A referenced Secret going away and coming back with the same content keeps the hash the plan
+    /// had all along, so its hosts stay current; the plan only reports the Secret as missing.
+    #[tokio::test]
+    async fn a_secret_that_goes_missing_and_comes_back_keeps_the_hash() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let exists = Arc::new(AtomicBool::new(true));
+        let secret = serde_json::json!({
+            "apiVersion": "v1", "kind": "Secret",
+            "metadata": {"name": "vars", "namespace": "default"},
+            "data": {"variables.yaml": "Z3JlZXRpbmc6IGhlbGxv"},
+        });
+        let (context, _server) = mocked_context(false, {
+            let exists = exists.clone();
+            move |_, _, _| match exists.load(Ordering::SeqCst) {
+                true => (200, secret.clone()),
+                false => (404, api_status(404)),
+            }
+        });
+        let secrets_api = Api::<Secret>::namespaced(context.client.clone(), "default");
+        let config_maps_api = Api::<ConfigMap>::namespaced(context.client.clone(), "default");
+        let name = "vars".to_string();
+
+        let mut current_hash = String::new();
+        let mut hashes = Vec::new();
+        let mut all_missing = Vec::new();
+        for present in [true, false, true] {
+            exists.store(present, Ordering::SeqCst);
+            let (computed, missing) = hash_playbook_inputs(
+                "- hosts: all",
+                &[&name],
+                &[],
+                &secrets_api,
+                &[],
+                &config_maps_api,
+                &[],
+            )
+            .await
+            .unwrap();
+            current_hash =
+                stable_execution_hash(computed, &missing.secrets, &current_hash).to_string();
+            hashes.push(current_hash.clone());
+            all_missing.push(missing.secrets);
+        }
+
+        assert_eq!(hashes[0], hashes[1]);
+        assert_eq!(hashes[1], hashes[2]);
+        assert_eq!(
+            all_missing,
+            vec![vec![], vec!["vars".to_string()], Vec::<String>::new()]
+        );
+    }
+
+    #[test]
+    fn stable_execution_hash_takes_the_computed_hash_without_a_previous_one() {
+        let hash = |n: u64| format!("{n:x}").parse::<ExecutionHash>().unwrap();
+        let missing = vec!["vars".to_string()];
+
+        assert_eq!(stable_execution_hash(hash(1), &missing, ""), hash(1));
+        assert_eq!(stable_execution_hash(hash(1), &missing, "2"), hash(2));
+        assert_eq!(stable_execution_hash(hash(1), &[], "2"), hash(1));
+    }
+
     #[test]
     fn get_related_secrets_collects_variable_and_file_secrets_but_not_inline_or_image_sources() {
         let yaml = r#"
@@ -1555,6 +4301,13 @@ spec:
           key: value
       - secretRef:
           name: secret-with-variables
+    groupVariables:
+      - group: webservers
+        variables:
+          - inline:
+              key: value
+          - secretRef:
+              name: secret-with-group-variables
     files:
       - name: binary-assets
         image:
@@ -1576,7 +4329,11 @@ spec:
 
         assert_eq!(
             secrets,
-            vec!["secret-with-variables", "secret-with-config-files"]
+            vec![
+                "secret-with-variables",
+                "secret-with-group-variables",
+                "secret-with-config-files"
+            ]
         );
     }
 
@@ -1630,6 +4387,47 @@ spec:
         ));
     }
 
+    #[test]
+    fn select_hosts_to_trigger_honours_recurring_targets() {
+        let outdated = vec!["host-2".to_string()];
+        let all = vec!["host-1".to_string(), "host-2".to_string()];
+        let select = |mode, targets| select_hosts_to_trigger(&mode, targets, &outdated, &all);
+
+        assert_eq!(
+            select(ExecutionMode::Recurring, RecurringTargets::AllHosts),
+            all
+        );
+        assert_eq!(
+            select(ExecutionMode::Recurring, RecurringTargets::OutdatedOnly),
+            outdated
+        );
+        // `recurringTargets` only affects Recurring plans.
+        for targets in [RecurringTargets::AllHosts, RecurringTargets::OutdatedOnly] {
+            assert_eq!(select(ExecutionMode::OneShot, targets), outdated);
+            assert!(select(ExecutionMode::InventoryOnly, targets).is_empty());
+        }
+    }
+
+    #[test]
+    fn only_a_converged_outdated_only_plan_settles_like_oneshot() {
+        use RecurringTargets::{AllHosts, OutdatedOnly};
+
+        let recurring = ExecutionMode::Recurring;
+        assert!(matches!(
+            terminal_mode(&recurring, OutdatedOnly, 0),
+            ExecutionMode::OneShot
+        ));
+        // Hosts still outdated (e.g. failed) -> the next tick retries them.
+        assert!(matches!(
+            terminal_mode(&recurring, OutdatedOnly, 1),
+            ExecutionMode::Recurring
+        ));
+        assert!(matches!(
+            terminal_mode(&recurring, AllHosts, 0),
+            ExecutionMode::Recurring
+        ));
+    }
+
     #[test]
     fn is_eligible_to_start_suspended_never_starts() {
         // `spec.suspend` overrides everything else: whatever the mode/schedule/host state would
@@ -1655,58 +4453,4 @@ spec:
             true
         ));
     }
-
-    #[test]
-    fn decide_terminal_oneshot_all_current_succeeds() {
-        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
-        let outcome = decide_terminal(&ExecutionMode::OneShot, None, 0, 3, now);
-
-        assert_eq!(outcome.phase, Phase::Succeeded);
-        assert_eq!(outcome.next_run, None);
-        assert_eq!(outcome.summary, "3/3 up-to-date");
-        assert_eq!(outcome.requeue, None);
-    }
-
-    #[test]
-    fn decide_terminal_oneshot_with_outdated_fails_and_never_reschedules() {
-        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
-        // A schedule is irrelevant in OneShot — even with one set it must resolve terminally and
-        // never reschedule.
-        let outcome = decide_terminal(&ExecutionMode::OneShot, Some("0 3 * * *"), 1, 3, now);
-
-        assert_eq!(outcome.phase, Phase::Failed);
-        assert_eq!(outcome.next_run, None);
-        assert_eq!(outcome.summary, "1/3 outdated");
-        assert_eq!(outcome.requeue, None);
-    }
-
-    #[test]
-    fn decide_terminal_recurring_with_schedule_reschedules_to_next_slot() {
-        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
-        let outcome = decide_terminal(&ExecutionMode::Recurring, Some("0 3 * * *"), 0, 2, now);
-
-        assert_eq!(outcome.phase, Phase::Scheduled);
-        assert_eq!(
-            outcome.next_run,
-            Some(
-                "2025-08-13T03:00:00Z"
-                    .parse::<DateTime<FixedOffset>>()
-                    .unwrap()
-            )
-        );
-        // Overrides the caller's default requeue so the plan wakes up at the next slot.
-        assert!(outcome.requeue.is_some());
-    }
-
-    #[test]
-    fn decide_terminal_recurring_without_schedule_is_a_dead_end() {
-        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
-        let outcome = decide_terminal(&ExecutionMode::Recurring, None, 0, 2, now);
-
-        // Nothing to reschedule against, so the plan holds at Applying (the eligibility gate
-        // normally prevents a schedule-less Recurring plan from ever starting a run).
-        assert_eq!(outcome.phase, Phase::Applying);
-        assert_eq!(outcome.next_run, None);
-        assert_eq!(outcome.requeue, None);
-    }
 }
@@ -0,0 +1,115 @@
+//! The files of `template.roles`: the keys of each role's Secret or ConfigMap, read once per
+//! reconcile so the Job can lay them out as the role's directory tree (see
+//! `job_builder::configure_job_for_roles`). Their contents join the execution hash like any other
+//! referenced Secret or ConfigMap.
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::Api;
+
+use crate::v1beta1::{PlaybookPlan, controllers::reconcile_error::ReconcileError};
+
+/// Role name -> the keys of its Secret or ConfigMap, sorted. A role whose source doesn't exist has
+/// no entry; the execution hash reports it as missing, and no run starts meanwhile.
+pub type RoleFiles = BTreeMap<String, Vec<String>>;
+
+/// Returns the files of every role of `plan`. Makes no apiserver call for a plan without roles.
+pub async fn resolve(
+    plan: &PlaybookPlan,
+    secrets_api: &Api<Secret>,
+    config_maps_api: &Api<ConfigMap>,
+) -> Result<RoleFiles, ReconcileError> {
+    let mut files = RoleFiles::new();
+    for role in plan.spec.template.roles.iter().flatten() {
+        // Validation guarantees exactly one of the two.
+        let keys = if let Some(secret_ref) = &role.secret_ref {
+            secrets_api
+                .get_opt(&secret_ref.name)
+                .await?
+                .map(secret_keys)
+        } else if let Some(config_map_ref) = &role.config_map_ref {
+            config_maps_api
+                .get_opt(&config_map_ref.name)
+                .await?
+                .map(config_map_keys)
+        } else {
+            None
+        };
+        if let Some(keys) = keys {
+            files.insert(role.name.clone(), keys);
+        }
+    }
+    Ok(files)
+}
+
+/// Keys may be in `data` or (on objects not yet round-tripped through the apiserver) `stringData`.
+fn secret_keys(secret: Secret) -> Vec<String> {
+    let mut keys: Vec<String> = secret
+        .data
+        .into_iter()
+        .flat_map(BTreeMap::into_keys)
+        .chain(secret.string_data.into_iter().flat_map(BTreeMap::into_keys))
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+fn config_map_keys(config_map: ConfigMap) -> Vec<String> {
+    let mut keys: Vec<String> = config_map
+        .data
+        .into_iter()
+        .flat_map(BTreeMap::into_keys)
+        .chain(
+            config_map
+                .binary_data
+                .into_iter()
+                .flat_map(BTreeMap::into_keys),
+        )
+        .collect();
+    keys.sort();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::ByteString;
+
+    use super::*;
+
+    #[test]
+    fn keys_are_read_from_every_data_field_sorted_and_once() {
+        let secret = Secret {
+            data: Some(BTreeMap::from([
+                ("tasks__main.yml".to_string(), ByteString(Vec::new())),
+                ("defaults__main.yml".to_string(), ByteString(Vec::new())),
+            ])),
+            string_data: Some(BTreeMap::from([
+                ("tasks__main.yml".to_string(), String::new()),
+                ("meta__main.yml".to_string(), String::new()),
+            ])),
+            ..Default::default()
+        };
+        assert_eq!(
+            secret_keys(secret),
+            ["defaults__main.yml", "meta__main.yml", "tasks__main.yml"]
+        );
+
+        let config_map = ConfigMap {
+            data: Some(BTreeMap::from([(
+                "tasks__main.yml".to_string(),
+                String::new(),
+            )])),
+            binary_data: Some(BTreeMap::from([(
+                "files__logo.png".to_string(),
+                ByteString(Vec::new()),
+            )])),
+            ..Default::default()
+        };
+        assert_eq!(
+            config_map_keys(config_map),
+            ["files__logo.png", "tasks__main.yml"]
+        );
+    }
+}
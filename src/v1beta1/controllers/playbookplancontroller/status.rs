@@ -1,14 +1,20 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use k8s_openapi::api::batch;
+use chrono::{DateTime, FixedOffset, TimeZone};
+use k8s_openapi::{api::batch, apimachinery::pkg::apis::meta::v1::Time};
 
 use crate::{
     utils::upsert_condition,
-    v1beta1::{HostOutcome, PlaybookPlanCondition, PlaybookPlanStatus},
+    v1beta1::{
+        ExecutionMode, HostOutcome, JobResult, Phase, PlaybookPlanCondition, PlaybookPlanStatus,
+    },
 };
 
 use super::{
-    callback_output::CallbackOutput, execution_evaluator::ExecutionHash, locking::BlockedBy,
+    callback_output::CallbackOutput,
+    execution_evaluator::ExecutionHash,
+    locking::BlockedBy,
+    triggers::{duration_until_or_zero, forecast_next_run},
 };
 
 /// Whether this run's single Job has reached a terminal state — `Complete` or `Failed`.
@@ -24,17 +30,75 @@ pub fn job_finished(job: &batch::v1::Job) -> bool {
         .unwrap_or(false)
 }
 
+/// The terminal result a Job reports through its `Complete`/`Failed` condition, or `None` while it
+/// is still running.
+pub fn job_result(job: &batch::v1::Job) -> Option<JobResult> {
+    job.status
+        .as_ref()?
+        .conditions
+        .as_ref()?
+        .iter()
+        .filter(|c| c.status == "True")
+        .find_map(|c| match c.type_.as_str() {
+            "Complete" => Some(JobResult::Succeeded),
+            "Failed" => Some(JobResult::Failed),
+            _ => None,
+        })
+}
+
+/// When the Job started, from `.status.startTime`.
+fn job_start_time(job: &batch::v1::Job) -> Option<DateTime<FixedOffset>> {
+    job.status
+        .as_ref()?
+        .start_time
+        .as_ref()
+        .and_then(to_datetime)
+}
+
+/// When the Job finished. Kubernetes only sets `.status.completionTime` for a Job that succeeded, so
+/// a failed Job falls back to the transition time of its `Failed` condition. `None` while running.
+fn job_completion_time(job: &batch::v1::Job) -> Option<DateTime<FixedOffset>> {
+    let status = job.status.as_ref()?;
+
+    status
+        .completion_time
+        .as_ref()
+        .and_then(to_datetime)
+        .or_else(|| {
+            status
+                .conditions
+                .as_ref()?
+                .iter()
+                .find(|c| c.type_ == "Failed" && c.status == "True")?
+                .last_transition_time
+                .as_ref()
+                .and_then(to_datetime)
+        })
+}
+
+fn to_datetime(time: &Time) -> Option<DateTime<FixedOffset>> {
+    DateTime::from_timestamp(time.0.as_second(), 0).map(|t| t.fixed_offset())
+}
+
 /// Updates `hosts_status` for every host targeted this run, from the parsed callback output (or
 /// `Unknown` for all of them if it couldn't be parsed). Only `Succeeded` outcomes bump
-/// `last_applied_hash`, which is what `find_outdated_hosts` reads for retry/idempotency.
+/// `last_applied_hash`, which is what `find_outdated_hosts` reads for retry/idempotency. Every
+/// targeted host — failed ones included — also records which Job ran it and that Job's start,
+/// completion and result; `job` is `None` when the Job was reaped before its result was read, which
+/// leaves only the name.
 pub fn evaluate_host_outcomes(
     target_hosts: &[String],
     parsed: Option<&CallbackOutput>,
     hash: &ExecutionHash,
+    job_name: &str,
+    job: Option<&batch::v1::Job>,
     status: &mut PlaybookPlanStatus,
 ) {
     let hosts_status = status.hosts_status.get_or_insert_with(BTreeMap::new);
     let now = chrono::Local::now().fixed_offset();
+    let start_time = job.and_then(job_start_time);
+    let completion_time = job.and_then(job_completion_time);
+    let result = job.and_then(job_result);
 
     for host in target_hosts {
         let outcome = match parsed {
@@ -52,8 +116,192 @@ pub fn evaluate_host_outcomes(
             entry.last_applied_hash = hash.to_string();
         }
 
+        // A run's outcome may be evaluated again if the status write that recorded it was lost, so
+        // only count a Job the host hasn't already been charged for.
+        if entry.last_job_name.as_deref() != Some(job_name) {
+            match outcome {
+                HostOutcome::Succeeded => entry.consecutive_failures = 0,
+                HostOutcome::Failed => entry.consecutive_failures += 1,
+                HostOutcome::NotReached | HostOutcome::Unknown | HostOutcome::Skipped => {}
+            }
+        }
+
         entry.last_outcome = outcome;
         entry.last_transition_time = Some(now);
+        entry.last_job_name = Some(job_name.to_string());
+        entry.last_start_time = start_time;
+        entry.last_completion_time = completion_time;
+        entry.last_result = result.clone();
+    }
+}
+
+/// Stamps the run of `job_name` (whose host outcomes `evaluate_host_outcomes` has just recorded)
+/// into `last_run_time`, and into `last_successful_run_time` as well if its Job completed and every
+/// targeted host succeeded. The time is the Job's own completion time, so a result read late still
+/// dates the run correctly; `now` stands in for a Job that was reaped before it was read, which
+/// never counts as a success since nothing is known about its outcome.
+pub fn record_run_times(
+    target_hosts: &[String],
+    job_name: &str,
+    job: Option<&batch::v1::Job>,
+    now: DateTime<FixedOffset>,
+    status: &mut PlaybookPlanStatus,
+) {
+    let finished = job.and_then(job_completion_time).unwrap_or(now);
+    status.last_run_time = Some(finished);
+
+    let hosts_status = status.hosts_status.as_ref();
+    let every_host_succeeded = target_hosts.iter().all(|host| {
+        hosts_status
+            .and_then(|hosts| hosts.get(host))
+            .is_some_and(|host_status| {
+                host_status.last_outcome == HostOutcome::Succeeded
+                    && host_status.last_job_name.as_deref() == Some(job_name)
+            })
+    });
+    if job.and_then(job_result) == Some(JobResult::Succeeded) && every_host_succeeded {
+        status.last_successful_run_time = Some(finished);
+    }
+}
+
+/// Records `skipped` (see `skipUnschedulableNodes`) as this run's outcome for those hosts. Their
+/// `last_applied_hash` is left alone, so they stay out of date, and so are the details of the Job
+/// that last ran them.
+pub fn record_skipped_hosts<Tz: TimeZone>(
+    status: &mut PlaybookPlanStatus,
+    skipped: &[String],
+    now: DateTime<Tz>,
+) {
+    if skipped.is_empty() {
+        return;
+    }
+
+    let hosts_status = status.hosts_status.get_or_insert_with(BTreeMap::new);
+    for host in skipped {
+        let entry = hosts_status.entry(host.clone()).or_default();
+        if entry.last_outcome != HostOutcome::Skipped {
+            entry.last_outcome = HostOutcome::Skipped;
+            entry.last_transition_time = Some(now.fixed_offset());
+        }
+    }
+}
+
+/// Sets the `groups` of every `hosts_status` entry to the `eligible_hosts` groups listing its host.
+pub fn record_host_groups(status: &mut PlaybookPlanStatus) {
+    let Some(hosts_status) = status.hosts_status.as_mut() else {
+        return;
+    };
+
+    let mut groups_of: BTreeMap<&String, BTreeSet<&String>> = BTreeMap::new();
+    for group in &status.eligible_hosts {
+        for host in &group.hosts {
+            groups_of.entry(host).or_default().insert(&group.name);
+        }
+    }
+
+    for (host, host_status) in hosts_status.iter_mut() {
+        host_status.groups = groups_of
+            .get(host)
+            .into_iter()
+            .flatten()
+            .map(|group| group.to_string())
+            .collect();
+    }
+}
+
+/// Drops the `hosts_status` entries of hosts that are in none of the plan's current
+/// `eligible_hosts` groups. Returns the dropped host names, sorted, for logging and the Event.
+pub fn prune_removed_hosts(status: &mut PlaybookPlanStatus) -> Vec<String> {
+    let Some(hosts_status) = status.hosts_status.as_mut() else {
+        return Vec::new();
+    };
+
+    let eligible: BTreeSet<&String> = status
+        .eligible_hosts
+        .iter()
+        .flat_map(|group| group.hosts.iter())
+        .collect();
+
+    let mut removed = Vec::new();
+    hosts_status.retain(|host, _| {
+        let keep = eligible.contains(host);
+        if !keep {
+            removed.push(host.clone());
+        }
+        keep
+    });
+
+    removed
+}
+
+/// Implements `resetHostStatusOnChange`: when `reset` is set and `generation` hasn't been processed
+/// yet, drops every `hosts_status` entry so all hosts count as out of date. Returns whether it did.
+///
+/// `last_processed_generation` is tracked whether or not `reset` is set, but never advanced while a
+/// run is `Applying` — clearing the state that run is about to report on would lose its outcome, so
+/// the reset waits for the first reconcile after it.
+pub fn reset_hosts_on_generation_change(
+    status: &mut PlaybookPlanStatus,
+    generation: i64,
+    reset: bool,
+) -> bool {
+    if status.phase == Phase::Applying
+        || status
+            .last_processed_generation
+            .is_some_and(|processed| processed >= generation)
+    {
+        return false;
+    }
+
+    status.last_processed_generation = Some(generation);
+    if !reset {
+        return false;
+    }
+
+    status.hosts_status.as_mut().is_some_and(|hosts_status| {
+        let had_hosts = !hosts_status.is_empty();
+        hosts_status.clear();
+        had_hosts
+    })
+}
+
+/// Recomputes the rollout counters (`hosts_applied`, `hosts_failed`, `progress`, `progress_percent`)
+/// over the plan's current `eligible_hosts`. Counted from scratch on every reconcile rather than
+/// adjusted incrementally, so hosts joining or leaving the inventory are reflected straight away; a
+/// departed host's lingering `hosts_status` entry is simply not counted. A host in several groups
+/// counts once, in the percentage too.
+pub fn evaluate_progress(status: &mut PlaybookPlanStatus) {
+    let hosts: BTreeSet<&String> = status
+        .eligible_hosts
+        .iter()
+        .flat_map(|group| group.hosts.iter())
+        .collect();
+    let host_status = |host: &String| status.hosts_status.as_ref().and_then(|s| s.get(host));
+
+    let applied = hosts
+        .iter()
+        .filter(|host| {
+            host_status(host).is_some_and(|s| s.last_applied_hash == status.current_hash)
+        })
+        .count() as u32;
+    let failed = hosts
+        .iter()
+        .filter(|host| host_status(host).is_some_and(|s| s.last_outcome == HostOutcome::Failed))
+        .count() as u32;
+    let progress = format!("{applied}/{}", hosts.len());
+
+    status.hosts_applied = applied;
+    status.hosts_failed = failed;
+    status.progress = Some(progress);
+    status.progress_percent = Some(progress_percent(applied, hosts.len() as u32));
+}
+
+/// `applied` out of `total` as a whole percentage, rounded down so a single outdated host keeps it
+/// below 100. No hosts at all is 100: there's nothing left to do.
+fn progress_percent(applied: u32, total: u32) -> u32 {
+    match total {
+        0 => 100,
+        total => applied * 100 / total,
     }
 }
 
@@ -79,6 +327,7 @@ pub fn set_blocked_condition(status: &mut PlaybookPlanStatus, blocked: Option<&B
                     blocked.host
                 )),
                 last_transition_time: Some(now),
+                observed_generation: status.observed_generation,
             }
         }
         None => PlaybookPlanCondition {
@@ -87,12 +336,103 @@ pub fn set_blocked_condition(status: &mut PlaybookPlanStatus, blocked: Option<&B
             reason: None,
             message: None,
             last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
+        },
+    };
+
+    upsert_condition(&mut status.conditions, condition);
+}
+
+/// Sets the plan-level `Suspended` condition from `spec.suspend`: `True` (reason `SuspendedBySpec`)
+/// while no new run may start, `False` otherwise. Mirrors the `Suspended` printer column, for tools
+/// that only read conditions.
+pub fn set_suspended_condition(status: &mut PlaybookPlanStatus, suspended: bool) {
+    let condition = PlaybookPlanCondition {
+        type_: "Suspended".into(),
+        status: if suspended { "True" } else { "False" }.into(),
+        reason: suspended.then(|| "SuspendedBySpec".into()),
+        message: suspended
+            .then(|| "spec.suspend is set; no new run starts, one in progress finishes".into()),
+        last_transition_time: Some(chrono::Local::now().fixed_offset()),
+        observed_generation: status.observed_generation,
+    };
+
+    upsert_condition(&mut status.conditions, condition);
+}
+
+/// Sets the plan-level `Throttled` condition. `Some((running, max))` — the operator-wide
+/// `--max-concurrent-jobs` cap is reached, so the run waits — sets it `True`; `None` sets it
+/// `False`. Like `Blocked`, a transient overlay on the plan's lifecycle rather than a phase.
+pub fn set_throttled_condition(status: &mut PlaybookPlanStatus, throttled: Option<(usize, usize)>) {
+    let now = chrono::Local::now().fixed_offset();
+
+    let condition = match throttled {
+        Some((running, max)) => PlaybookPlanCondition {
+            type_: "Throttled".into(),
+            status: "True".into(),
+            reason: Some("MaxConcurrentJobs".into()),
+            message: Some(format!(
+                "{running} operator Jobs are running, the most allowed at once is {max}; waiting for one to finish"
+            )),
+            last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
+        },
+        None => PlaybookPlanCondition {
+            type_: "Throttled".into(),
+            status: "False".into(),
+            reason: None,
+            message: None,
+            last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
         },
     };
 
     upsert_condition(&mut status.conditions, condition);
 }
 
+/// Sets the plan-level `FilesValid` condition: `False` naming every `template.files` entry that
+/// isn't a valid Kubernetes volume (`invalid` holds their names and why), `True` otherwise. Whether
+/// such a plan still runs — without them — is up to `template.skipInvalidFiles`.
+pub fn set_files_valid_condition(status: &mut PlaybookPlanStatus, invalid: &[(String, String)]) {
+    let message = invalid
+        .iter()
+        .map(|(name, reason)| format!("'{name}': {reason}"))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let condition = PlaybookPlanCondition {
+        type_: "FilesValid".into(),
+        status: if invalid.is_empty() { "True" } else { "False" }.into(),
+        reason: (!invalid.is_empty()).then(|| "InvalidVolume".into()),
+        message: (!invalid.is_empty()).then(|| format!("invalid files volume(s) {message}")),
+        last_transition_time: Some(chrono::Local::now().fixed_offset()),
+        observed_generation: status.observed_generation,
+    };
+
+    upsert_condition(&mut status.conditions, condition);
+}
+
+/// Sets the plan-level `DependenciesReady` condition: `False` (reason `SecretMissing`, or
+/// `ConfigMapMissing`) while a Secret or ConfigMap a run needs is missing or incomplete — no run
+/// starts meanwhile — `True` otherwise. Unlike
+/// `Degraded`, which reports only the most fundamental of several problems, this one always says
+/// whether the plan's inputs are all there.
+pub fn set_dependencies_ready_condition(
+    status: &mut PlaybookPlanStatus,
+    problem: Option<&SecretProblem>,
+) {
+    let condition = PlaybookPlanCondition {
+        type_: "DependenciesReady".into(),
+        status: if problem.is_some() { "False" } else { "True" }.into(),
+        reason: problem.map(|problem| problem.reason().into()),
+        message: problem.map(ToString::to_string),
+        last_transition_time: Some(chrono::Local::now().fixed_offset()),
+        observed_generation: status.observed_generation,
+    };
+
+    upsert_condition(&mut status.conditions, condition);
+}
+
 /// Sets the plan-level `WaitingForNodes` condition, reporting whether this run is currently waiting
 /// for managed-ssh proxy pods to become Ready on one or more target nodes (a node may be `NotReady`
 /// or its proxy pod still starting). `Some(hosts)` sets it `True` naming the pending hosts; `None` —
@@ -115,6 +455,7 @@ pub fn set_waiting_for_nodes_condition(
                 hosts.join(", ")
             )),
             last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
         },
         None => PlaybookPlanCondition {
             type_: "WaitingForNodes".into(),
@@ -122,15 +463,254 @@ pub fn set_waiting_for_nodes_condition(
             reason: None,
             message: None,
             last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
         },
     };
 
     upsert_condition(&mut status.conditions, condition);
 }
 
+/// Whether the `WaitingForNodes` condition is `True`: an earlier tick of the run in progress took
+/// its host locks and is waiting for proxy pods.
+pub fn is_waiting_for_nodes(status: &PlaybookPlanStatus) -> bool {
+    status
+        .conditions
+        .iter()
+        .any(|c| c.type_ == "WaitingForNodes" && c.status == "True")
+}
+
+/// Sets the `Reachable` condition from the recap of a run's `spec.preflight` ping: `True` when every
+/// host answered, `False` naming the hosts that failed or were unreachable (the run's playbook then
+/// didn't start), `Unknown` when there's no recap — the preflight container crashed, or the Job was
+/// gone before its result was read.
+pub fn set_reachable_condition(
+    status: &mut PlaybookPlanStatus,
+    preflight: Option<&CallbackOutput>,
+) {
+    let now = chrono::Local::now().fixed_offset();
+
+    let (state, reason, message) = match preflight {
+        Some(recap) => {
+            let unreachable: Vec<&str> = recap
+                .processed
+                .iter()
+                .filter(|(_, stats)| stats.is_failure())
+                .map(|(host, _)| host.as_str())
+                .collect();
+            if unreachable.is_empty() {
+                ("True", "AllHostsReachable", None)
+            } else {
+                (
+                    "False",
+                    "HostsUnreachable",
+                    Some(format!(
+                        "host(s) did not answer the preflight ping: {}",
+                        unreachable.join(", ")
+                    )),
+                )
+            }
+        }
+        None => (
+            "Unknown",
+            "PreflightResultMissing",
+            Some("the preflight ping left no recap".to_string()),
+        ),
+    };
+
+    let condition = PlaybookPlanCondition {
+        type_: "Reachable".into(),
+        status: state.into(),
+        reason: Some(reason.into()),
+        message,
+        last_transition_time: Some(now),
+        observed_generation: status.observed_generation,
+    };
+
+    upsert_condition(&mut status.conditions, condition);
+}
+
+/// Why a Secret a run needs can't be used: a `StaticInventory`'s SSH key Secret, or one referenced
+/// by `template.variables`/`template.files`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretProblem {
+    /// The SSH key Secret doesn't exist in the plan's namespace.
+    Missing { secret: String },
+    /// The SSH key Secret exists but lacks a key the inventory's `ssh` settings read a private key
+    /// from.
+    MissingKey { secret: String, key: String },
+    /// Secrets referenced by the plan's template don't exist in its namespace.
+    Unresolved { secrets: Vec<String> },
+    /// ConfigMaps the plan's template reads variables from don't exist in its namespace.
+    UnresolvedConfigMaps { config_maps: Vec<String> },
+    /// A Secret the plan's template reads variables from exists but lacks the key they are read
+    /// from.
+    MissingVariablesKey { secret: String, key: String },
+}
+
+impl SecretProblem {
+    /// The reason of the conditions reporting the problem.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            SecretProblem::UnresolvedConfigMaps { .. } => "ConfigMapMissing",
+            _ => "SecretMissing",
+        }
+    }
+}
+
+impl std::fmt::Display for SecretProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretProblem::Missing { secret } => {
+                write!(f, "SSH Secret '{secret}' does not exist")
+            }
+            SecretProblem::MissingKey { secret, key } => {
+                write!(f, "SSH Secret '{secret}' has no key '{key}'")
+            }
+            SecretProblem::MissingVariablesKey { secret, key } => {
+                write!(f, "variables Secret '{secret}' has no key '{key}'")
+            }
+            SecretProblem::Unresolved { secrets } => {
+                let names = secrets.iter().map(|s| format!("'{s}'")).collect::<Vec<_>>();
+                write!(f, "referenced Secret(s) {} do not exist", names.join(", "))
+            }
+            SecretProblem::UnresolvedConfigMaps { config_maps } => {
+                let names = config_maps
+                    .iter()
+                    .map(|c| format!("'{c}'"))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "referenced ConfigMap(s) {} do not exist",
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Sets the plan-level `Degraded` condition: `True` when something needs a human — the spec is
+/// invalid (`InvalidSpec`), a Secret a run needs is missing or incomplete (`SecretMissing`; no run
+/// is started meanwhile), or a targeted host's last run failed (`JobsFailed`) — else `False`. The
+/// first cause in that order wins, since fixing it is a prerequisite for the others to clear.
+///
+/// Kept apart from `Ready`, which only says whether every host is on the current hash: a plan
+/// waiting for its first run or mid-rollout is not ready, but nothing is wrong with it either, so
+/// alerting on `Degraded` stays quiet for it.
+pub fn evaluate_degraded_condition(
+    status: &mut PlaybookPlanStatus,
+    invalid_spec: Option<&str>,
+    secret_problem: Option<&SecretProblem>,
+) {
+    let eligible_hosts: Vec<String> = status
+        .eligible_hosts
+        .iter()
+        .flat_map(|group| group.hosts.iter().cloned())
+        .collect();
+
+    let cause = if let Some(invalid) = invalid_spec {
+        Some(("InvalidSpec", invalid.to_string()))
+    } else if let Some(problem) = secret_problem {
+        Some((problem.reason(), format!("{problem}; no run is started")))
+    } else {
+        worst_failing_host(&eligible_hosts, status).map(|(host, failures)| {
+            (
+                "JobsFailed",
+                format!("host '{host}' has failed {failures} consecutive run(s)"),
+            )
+        })
+    };
+
+    let condition = PlaybookPlanCondition {
+        type_: "Degraded".into(),
+        status: if cause.is_some() { "True" } else { "False" }.into(),
+        reason: cause.as_ref().map(|(reason, _)| reason.to_string()),
+        message: cause.map(|(_, message)| message),
+        last_transition_time: Some(chrono::Local::now().fixed_offset()),
+        observed_generation: status.observed_generation,
+    };
+
+    upsert_condition(&mut status.conditions, condition);
+}
+
+/// How much of a playbook's render error `Ready` carries; YAML errors can quote long snippets.
+const MAX_PLAYBOOK_ERROR_CHARS: usize = 512;
+
+/// Sets `Ready` to `False` with reason `InvalidPlaybook` while the playbook doesn't render (`problem`
+/// is the render error); no run starts meanwhile. Once it renders again, a `Ready` still blaming it
+/// turns `Unknown` until the next run's outcome settles it. Any other `Ready` is left alone.
+pub fn evaluate_playbook_ready(status: &mut PlaybookPlanStatus, problem: Option<&str>) {
+    let now = chrono::Local::now().fixed_offset();
+
+    let condition = match problem {
+        Some(problem) => {
+            let mut message: String = problem.chars().take(MAX_PLAYBOOK_ERROR_CHARS).collect();
+            if message.len() < problem.len() {
+                message.push('…');
+            }
+            PlaybookPlanCondition {
+                type_: "Ready".into(),
+                status: "False".into(),
+                reason: Some("InvalidPlaybook".into()),
+                message: Some(message),
+                last_transition_time: Some(now),
+                observed_generation: status.observed_generation,
+            }
+        }
+        None => {
+            let ready = status.conditions.iter().find(|c| c.type_ == "Ready");
+            if ready.is_none_or(|c| c.reason.as_deref() != Some("InvalidPlaybook")) {
+                return;
+            }
+            PlaybookPlanCondition {
+                type_: "Ready".into(),
+                status: "Unknown".into(),
+                reason: Some("PlaybookFixed".into()),
+                message: Some("the playbook renders again; waiting for the next run".into()),
+                last_transition_time: Some(now),
+                observed_generation: status.observed_generation,
+            }
+        }
+    };
+
+    upsert_condition(&mut status.conditions, condition);
+}
+
+/// Flips a `True` (or not yet set) `Ready` to `Unknown` with reason `HostsOutdated` once eligible
+/// hosts are no longer on the current hash — a spec edit, a changed Secret, a newly matched Node —
+/// so `Ready` never keeps vouching for a previous revision. `Unknown`, not `False`: nothing has
+/// failed, the run that settles it just hasn't happened yet. Any other `Ready` is left alone: a
+/// `False` describes a failed run, which says more than "outdated" would, and an `Unknown` already
+/// says a run is pending.
+pub fn mark_ready_outdated(status: &mut PlaybookPlanStatus, outdated_hosts: &[String]) {
+    if outdated_hosts.is_empty() {
+        return;
+    }
+
+    let ready = status.conditions.iter().find(|c| c.type_ == "Ready");
+    if ready.is_some_and(|c| c.status != "True") {
+        return;
+    }
+
+    let total: usize = status.eligible_hosts.iter().map(|g| g.hosts.len()).sum();
+    let condition = PlaybookPlanCondition {
+        type_: "Ready".into(),
+        status: "Unknown".into(),
+        reason: Some("HostsOutdated".into()),
+        message: Some(format!(
+            "{}/{total} hosts are not yet applied at the current hash",
+            outdated_hosts.len()
+        )),
+        last_transition_time: Some(chrono::Local::now().fixed_offset()),
+        observed_generation: status.observed_generation,
+    };
+
+    upsert_condition(&mut status.conditions, condition);
+}
+
 /// Recomputes the plan-level `Running`/`Ready` conditions from this run's host-outcome tally,
 /// using the parsed callback output as the only host-level signal (there's exactly one Job per
-/// run now, so there's nothing to count across Jobs).
+/// run now, so there's nothing to count across Jobs). Like every condition here, they are stamped
+/// with `status.observed_generation`, which the reconciler sets to the plan's current generation.
 pub fn evaluate_playbookplan_conditions(
     target_hosts: &[String],
     job_is_finished: bool,
@@ -146,6 +726,7 @@ pub fn evaluate_playbookplan_conditions(
             reason: Some("JobRunning".into()),
             message: Some("the run's Job is still active".into()),
             last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
         }
     } else {
         PlaybookPlanCondition {
@@ -154,12 +735,25 @@ pub fn evaluate_playbookplan_conditions(
             reason: None,
             message: None,
             last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
         }
     };
 
     upsert_condition(&mut status.conditions, running_condition);
 
+    // `Ready` only turns `True`/`False` on a run's outcome; until then it's `Unknown`, so
+    // `kubectl wait --for=condition=Ready` neither returns early on a stale `True` nor gives up on a
+    // `False` the run in flight may still fix.
     if !job_is_finished {
+        let ready_condition = PlaybookPlanCondition {
+            type_: "Ready".into(),
+            status: "Unknown".into(),
+            reason: Some("RunInProgress".into()),
+            message: Some("waiting for the run's results".into()),
+            last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
+        };
+        upsert_condition(&mut status.conditions, ready_condition);
         return;
     }
 
@@ -172,6 +766,7 @@ pub fn evaluate_playbookplan_conditions(
                 "the operator could not parse per-host results for this run's Job logs".into(),
             ),
             last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
         },
         Some(output) => {
             let total = target_hosts.len();
@@ -193,14 +788,23 @@ pub fn evaluate_playbookplan_conditions(
                     reason: Some("AllHostsSucceeded".into()),
                     message: Some(format!("{succeeded}/{total} hosts completed successfully")),
                     last_transition_time: Some(now),
+                    observed_generation: status.observed_generation,
                 }
             } else {
+                let mut message = format!("{succeeded}/{total} hosts completed successfully");
+                if let Some((host, failures)) = worst_failing_host(target_hosts, status) {
+                    message.push_str(&format!(
+                        "; host '{host}' has failed {failures} consecutive run(s)"
+                    ));
+                }
+
                 PlaybookPlanCondition {
                     type_: "Ready".into(),
                     status: "False".into(),
                     reason: Some("SomeHostsDidNotSucceed".into()),
-                    message: Some(format!("{succeeded}/{total} hosts completed successfully")),
+                    message: Some(message),
                     last_transition_time: Some(now),
+                    observed_generation: status.observed_generation,
                 }
             }
         }
@@ -209,10 +813,186 @@ pub fn evaluate_playbookplan_conditions(
     upsert_condition(&mut status.conditions, ready_condition);
 }
 
+/// Sets `Ready` for an `InventoryOnly` plan, which is ready as soon as its inventories resolved —
+/// there is no run whose outcome it could wait for. `Running` is cleared likewise.
+pub fn evaluate_inventory_only_conditions(status: &mut PlaybookPlanStatus) {
+    let now = chrono::Local::now().fixed_offset();
+    let total: usize = status.eligible_hosts.iter().map(|g| g.hosts.len()).sum();
+
+    upsert_condition(
+        &mut status.conditions,
+        PlaybookPlanCondition {
+            type_: "Running".into(),
+            status: "False".into(),
+            reason: None,
+            message: None,
+            last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
+        },
+    );
+    upsert_condition(
+        &mut status.conditions,
+        PlaybookPlanCondition {
+            type_: "Ready".into(),
+            status: "True".into(),
+            reason: Some("InventoryResolved".into()),
+            message: Some(format!("resolved {total} host(s); no playbook is run")),
+            last_transition_time: Some(now),
+            observed_generation: status.observed_generation,
+        },
+    );
+}
+
+/// Which of `target_hosts` ended `Failed` in the Job `job_name`, per their recorded outcomes.
+pub fn hosts_failed_in(
+    job_name: &str,
+    target_hosts: &[String],
+    status: &PlaybookPlanStatus,
+) -> Vec<String> {
+    let Some(hosts_status) = status.hosts_status.as_ref() else {
+        return Vec::new();
+    };
+
+    target_hosts
+        .iter()
+        .filter(|host| {
+            hosts_status.get(*host).is_some_and(|host_status| {
+                host_status.last_outcome == HostOutcome::Failed
+                    && host_status.last_job_name.as_deref() == Some(job_name)
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// The targeted host with the most consecutive failures, if any host has failed at all. Ties go to
+/// the host listed first, so the message doesn't flap between equally bad hosts.
+fn worst_failing_host(
+    target_hosts: &[String],
+    status: &PlaybookPlanStatus,
+) -> Option<(String, u32)> {
+    let hosts_status = status.hosts_status.as_ref()?;
+
+    target_hosts
+        .iter()
+        .filter_map(|host| {
+            let failures = hosts_status.get(host)?.consecutive_failures;
+            (failures > 0).then(|| (host.clone(), failures))
+        })
+        .rev()
+        .max_by_key(|(_, failures)| *failures)
+}
+
+/// The terminal-state decision for a finished run: what the plan's `phase`, `next_run`, `summary`,
+/// and the caller's requeue duration become once this run's Job has reached a terminal state. Pure
+/// (every wall-clock/inventory input is passed in) so the per-mode matrix is unit-testable without a
+/// kube client:
+///   - OneShot resolves solely by how many hosts are still outdated and never reschedules:
+///     `Succeeded` if none, `Failed` if all, `PartiallyFailed` if only some.
+///   - Recurring with a schedule reschedules to the next slot and requeues until then, failures or
+///     not — they're already recorded per host in `hostsStatus` and retried on the next slot.
+///   - Recurring with an `interval` instead reschedules to `now + interval`.
+///   - Recurring with neither, or with a schedule that never fires, is the dead-end validation
+///     normally prevents (the caller logs it): nothing to reschedule against, so the plan stays
+///     `Applying`.
+pub struct TerminalOutcome {
+    pub phase: Phase,
+    pub next_run: Option<DateTime<FixedOffset>>,
+    pub summary: String,
+    pub requeue: Option<std::time::Duration>,
+}
+
+pub fn decide_terminal<Tz: TimeZone>(
+    mode: &ExecutionMode,
+    schedule: Option<&str>,
+    jitter: chrono::Duration,
+    interval: Option<chrono::Duration>,
+    outdated_count: usize,
+    total_count: usize,
+    now: DateTime<Tz>,
+) -> TerminalOutcome {
+    let summary = match outdated_count {
+        0 => format!("{total_count}/{total_count} up-to-date"),
+        n => format!("{n}/{total_count} outdated"),
+    };
+
+    match mode {
+        // `InventoryOnly` plans never run, so never finish one; see `reconciler::publish_inventory`.
+        ExecutionMode::OneShot | ExecutionMode::InventoryOnly => TerminalOutcome {
+            phase: oneshot_phase(outdated_count, total_count),
+            next_run: None,
+            summary,
+            requeue: None,
+        },
+        ExecutionMode::Recurring => match (schedule, interval) {
+            (Some(schedule), _)
+                if let Some(next) = forecast_next_run(
+                    schedule,
+                    now.clone(),
+                    Some(chrono::Duration::seconds(-5)),
+                    jitter,
+                ) =>
+            {
+                TerminalOutcome {
+                    phase: Phase::Scheduled,
+                    next_run: Some(next.clone().fixed_offset()),
+                    summary,
+                    requeue: Some(duration_until_or_zero(next, now)),
+                }
+            }
+            (None, Some(interval)) => TerminalOutcome {
+                phase: Phase::Scheduled,
+                next_run: Some((now + interval).fixed_offset()),
+                summary,
+                requeue: Some(interval.to_std().unwrap_or_default()),
+            },
+            // Any prior forecast is now unreachable, so clear `next_run` and hold at `Applying`.
+            _ => TerminalOutcome {
+                phase: Phase::Applying,
+                next_run: None,
+                summary,
+                requeue: None,
+            },
+        },
+    }
+}
+
+/// Overrides `outcome` for `failurePolicy: Halt` after a run in which `failed_hosts` failed: the
+/// plan stops in `Halted`, with no next run to wait for. `outcome` is kept as-is without failures.
+pub fn halt_on_failure(outcome: TerminalOutcome, failed_hosts: &[String]) -> TerminalOutcome {
+    if failed_hosts.is_empty() {
+        return outcome;
+    }
+
+    TerminalOutcome {
+        phase: Phase::Halted,
+        next_run: None,
+        summary: format!(
+            "{}; halted after host(s) failed: {}",
+            outcome.summary,
+            failed_hosts.join(", ")
+        ),
+        requeue: None,
+    }
+}
+
+/// The terminal phase of a finished OneShot run, by how many of its `total_count` hosts it left
+/// outdated.
+pub fn oneshot_phase(outdated_count: usize, total_count: usize) -> Phase {
+    match outdated_count {
+        0 => Phase::Succeeded,
+        n if n < total_count => Phase::PartiallyFailed,
+        _ => Phase::Failed,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::v1beta1::controllers::playbookplancontroller::callback_output::HostStats;
+    use crate::v1beta1::{
+        HostStatus, ResolvedHosts, controllers::playbookplancontroller::callback_output::HostStats,
+    };
+    use chrono::Utc;
 
     fn hash() -> ExecutionHash {
         crate::v1beta1::controllers::playbookplancontroller::execution_evaluator::calculate_execution_hash(
@@ -221,6 +1001,35 @@ mod tests {
         )
     }
 
+    #[test]
+    fn skipped_hosts_stay_outdated_and_keep_their_transition_time_while_skipped() {
+        let h = hash();
+        let mut status = PlaybookPlanStatus::default();
+        let hosts = vec!["host-1".to_string(), "host-2".to_string()];
+        evaluate_host_outcomes(
+            &hosts,
+            Some(&recap(&[], &["host-1", "host-2"])),
+            &h,
+            "apply-p-a-1",
+            None,
+            &mut status,
+        );
+
+        let first = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let skipped = vec!["host-2".to_string()];
+        record_skipped_hosts(&mut status, &skipped, first);
+        record_skipped_hosts(&mut status, &skipped, first + chrono::Duration::hours(1));
+
+        let hosts_status = status.hosts_status.as_ref().unwrap();
+        assert_eq!(hosts_status["host-1"].last_outcome, HostOutcome::Succeeded);
+        let host_2 = &hosts_status["host-2"];
+        assert_eq!(host_2.last_outcome, HostOutcome::Skipped);
+        assert_eq!(host_2.last_transition_time, Some(first.fixed_offset()));
+        // Still applied with the last hash it succeeded on, and still named after that run's Job.
+        assert_eq!(host_2.last_applied_hash, h.to_string());
+        assert_eq!(host_2.last_job_name.as_deref(), Some("apply-p-a-1"));
+    }
+
     #[test]
     fn succeeded_host_bumps_hash_others_do_not() {
         let mut status = PlaybookPlanStatus::default();
@@ -250,6 +1059,8 @@ mod tests {
             ],
             Some(&output),
             &h,
+            "apply-plan-abcde-1",
+            None,
             &mut status,
         );
 
@@ -269,17 +1080,552 @@ mod tests {
         let mut status = PlaybookPlanStatus::default();
         let h = hash();
 
-        evaluate_host_outcomes(&["host-1".to_string()], None, &h, &mut status);
+        evaluate_host_outcomes(
+            &["host-1".to_string()],
+            None,
+            &h,
+            "apply-plan-abcde-1",
+            None,
+            &mut status,
+        );
 
         let hosts_status = status.hosts_status.unwrap();
         assert_eq!(hosts_status["host-1"].last_outcome, HostOutcome::Unknown);
     }
 
-    #[test]
-    fn blocked_condition_names_the_holder_then_clears_in_place() {
-        let mut status = PlaybookPlanStatus::default();
+    /// A `list` response as the apiserver returns it: one Job that completed, one that failed, and
+    /// one still running.
+    fn jobs() -> kube::core::ObjectList<batch::v1::Job> {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "List",
+            "metadata": {},
+            "items": [
+                {
+                    "metadata": { "name": "apply-plan-abcde-1" },
+                    "status": {
+                        "startTime": "2025-08-12T20:00:00Z",
+                        "completionTime": "2025-08-12T20:05:00Z",
+                        "conditions": [
+                            { "type": "Complete", "status": "True" }
+                        ]
+                    }
+                },
+                {
+                    "metadata": { "name": "apply-plan-abcde-2" },
+                    "status": {
+                        "startTime": "2025-08-12T21:00:00Z",
+                        "conditions": [
+                            { "type": "FailureTarget", "status": "True", "lastTransitionTime": "2025-08-12T21:02:00Z" },
+                            { "type": "Failed", "status": "True", "lastTransitionTime": "2025-08-12T21:03:00Z" }
+                        ]
+                    }
+                },
+                {
+                    "metadata": { "name": "apply-plan-abcde-3" },
+                    "status": {
+                        "startTime": "2025-08-12T22:00:00Z",
+                        "active": 1
+                    }
+                }
+            ]
+        }))
+        .unwrap()
+    }
 
-        set_blocked_condition(
+    fn job_named<'a>(
+        list: &'a kube::core::ObjectList<batch::v1::Job>,
+        name: &str,
+    ) -> &'a batch::v1::Job {
+        list.items
+            .iter()
+            .find(|j| j.metadata.name.as_deref() == Some(name))
+            .unwrap()
+    }
+
+    fn at(s: &str) -> Option<DateTime<FixedOffset>> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn succeeded_job_records_its_name_times_and_result_per_host() {
+        let list = jobs();
+        let job = job_named(&list, "apply-plan-abcde-1");
+        let mut status = PlaybookPlanStatus::default();
+
+        evaluate_host_outcomes(
+            &["host-1".to_string()],
+            None,
+            &hash(),
+            "apply-plan-abcde-1",
+            Some(job),
+            &mut status,
+        );
+
+        let host = &status.hosts_status.unwrap()["host-1"];
+        assert_eq!(host.last_job_name.as_deref(), Some("apply-plan-abcde-1"));
+        assert_eq!(host.last_start_time, at("2025-08-12T20:00:00Z"));
+        assert_eq!(host.last_completion_time, at("2025-08-12T20:05:00Z"));
+        assert_eq!(host.last_result, Some(JobResult::Succeeded));
+    }
+
+    #[test]
+    fn failed_job_updates_every_host_and_falls_back_to_the_failed_condition_time() {
+        let list = jobs();
+        let job = job_named(&list, "apply-plan-abcde-2");
+        let mut status = PlaybookPlanStatus::default();
+        let mut processed = BTreeMap::new();
+        processed.insert(
+            "host-1".to_string(),
+            HostStats {
+                ok: 1,
+                ..Default::default()
+            },
+        );
+        processed.insert(
+            "host-2".to_string(),
+            HostStats {
+                failed: 1,
+                ..Default::default()
+            },
+        );
+
+        evaluate_host_outcomes(
+            &["host-1".to_string(), "host-2".to_string()],
+            Some(&CallbackOutput { processed }),
+            &hash(),
+            "apply-plan-abcde-2",
+            Some(job),
+            &mut status,
+        );
+
+        let hosts_status = status.hosts_status.unwrap();
+        for host in ["host-1", "host-2"] {
+            let entry = &hosts_status[host];
+            assert_eq!(entry.last_job_name.as_deref(), Some("apply-plan-abcde-2"));
+            assert_eq!(entry.last_start_time, at("2025-08-12T21:00:00Z"));
+            // No completionTime on a failed Job — the `Failed` condition's time stands in.
+            assert_eq!(entry.last_completion_time, at("2025-08-12T21:03:00Z"));
+            assert_eq!(entry.last_result, Some(JobResult::Failed));
+        }
+        // The per-host outcome still distinguishes the host that actually failed.
+        assert_eq!(hosts_status["host-1"].last_outcome, HostOutcome::Succeeded);
+        assert_eq!(hosts_status["host-2"].last_outcome, HostOutcome::Failed);
+    }
+
+    #[test]
+    fn in_progress_job_has_a_start_but_no_completion_or_result() {
+        let list = jobs();
+        let job = job_named(&list, "apply-plan-abcde-3");
+
+        assert!(!job_finished(job));
+        assert_eq!(job_start_time(job), at("2025-08-12T22:00:00Z"));
+        assert_eq!(job_completion_time(job), None);
+        assert_eq!(job_result(job), None);
+    }
+
+    #[test]
+    fn reaped_job_still_records_its_name() {
+        let mut status = PlaybookPlanStatus::default();
+
+        evaluate_host_outcomes(
+            &["host-1".to_string()],
+            None,
+            &hash(),
+            "apply-plan-abcde-4",
+            None,
+            &mut status,
+        );
+
+        let host = &status.hosts_status.unwrap()["host-1"];
+        assert_eq!(host.last_job_name.as_deref(), Some("apply-plan-abcde-4"));
+        assert_eq!(host.last_start_time, None);
+        assert_eq!(host.last_result, None);
+    }
+
+    #[test]
+    fn run_times_date_a_run_by_its_job_and_only_a_clean_run_counts_as_successful() {
+        let list = jobs();
+        let hosts = ["host-1".to_string(), "host-2".to_string()];
+        let now = at("2025-08-13T09:00:00Z").unwrap();
+        let mut status = PlaybookPlanStatus::default();
+
+        let succeeded = job_named(&list, "apply-plan-abcde-1");
+        evaluate_host_outcomes(
+            &hosts,
+            Some(&recap(&[], &["host-1", "host-2"])),
+            &hash(),
+            "apply-plan-abcde-1",
+            Some(succeeded),
+            &mut status,
+        );
+        record_run_times(
+            &hosts,
+            "apply-plan-abcde-1",
+            Some(succeeded),
+            now,
+            &mut status,
+        );
+        assert_eq!(status.last_run_time, at("2025-08-12T20:05:00Z"));
+        assert_eq!(status.last_successful_run_time, at("2025-08-12T20:05:00Z"));
+
+        // A failed Job moves the last run on, but not the last success.
+        let failed = job_named(&list, "apply-plan-abcde-2");
+        evaluate_host_outcomes(
+            &hosts,
+            Some(&recap(&["host-2"], &["host-1"])),
+            &hash(),
+            "apply-plan-abcde-2",
+            Some(failed),
+            &mut status,
+        );
+        record_run_times(&hosts, "apply-plan-abcde-2", Some(failed), now, &mut status);
+        assert_eq!(status.last_run_time, at("2025-08-12T21:03:00Z"));
+        assert_eq!(status.last_successful_run_time, at("2025-08-12T20:05:00Z"));
+
+        // Nothing is known about a reaped Job's run but that it is over.
+        evaluate_host_outcomes(
+            &hosts,
+            None,
+            &hash(),
+            "apply-plan-abcde-4",
+            None,
+            &mut status,
+        );
+        record_run_times(&hosts, "apply-plan-abcde-4", None, now, &mut status);
+        assert_eq!(status.last_run_time, Some(now));
+        assert_eq!(status.last_successful_run_time, at("2025-08-12T20:05:00Z"));
+    }
+
+    #[test]
+    fn a_complete_job_with_an_unreached_host_is_not_a_successful_run() {
+        let list = jobs();
+        let job = job_named(&list, "apply-plan-abcde-1");
+        let hosts = ["host-1".to_string(), "host-2".to_string()];
+        let mut status = PlaybookPlanStatus::default();
+
+        evaluate_host_outcomes(
+            &hosts,
+            Some(&recap(&[], &["host-1"])),
+            &hash(),
+            "apply-plan-abcde-1",
+            Some(job),
+            &mut status,
+        );
+        record_run_times(
+            &hosts,
+            "apply-plan-abcde-1",
+            Some(job),
+            at("2025-08-13T09:00:00Z").unwrap(),
+            &mut status,
+        );
+
+        assert_eq!(status.last_run_time, at("2025-08-12T20:05:00Z"));
+        assert_eq!(status.last_successful_run_time, None);
+    }
+
+    fn recap(failed: &[&str], ok: &[&str]) -> CallbackOutput {
+        let mut processed = BTreeMap::new();
+        for host in failed {
+            processed.insert(
+                host.to_string(),
+                HostStats {
+                    failed: 1,
+                    ..Default::default()
+                },
+            );
+        }
+        for host in ok {
+            processed.insert(
+                host.to_string(),
+                HostStats {
+                    ok: 1,
+                    ..Default::default()
+                },
+            );
+        }
+        CallbackOutput { processed }
+    }
+
+    #[test]
+    fn consecutive_failures_count_each_job_once_and_reset_on_success() {
+        let hosts = ["host-1".to_string()];
+        let h = hash();
+        let mut status = PlaybookPlanStatus::default();
+        let failures = |status: &PlaybookPlanStatus| {
+            status.hosts_status.as_ref().unwrap()["host-1"].consecutive_failures
+        };
+
+        let failed = recap(&["host-1"], &[]);
+        evaluate_host_outcomes(&hosts, Some(&failed), &h, "apply-p-a-0", None, &mut status);
+        assert_eq!(failures(&status), 1);
+
+        // The same Job evaluated again (e.g. after a lost status write) is not counted twice.
+        evaluate_host_outcomes(&hosts, Some(&failed), &h, "apply-p-a-0", None, &mut status);
+        assert_eq!(failures(&status), 1);
+
+        evaluate_host_outcomes(&hosts, Some(&failed), &h, "apply-p-a-1", None, &mut status);
+        assert_eq!(failures(&status), 2);
+
+        // A run that says nothing about the host leaves the streak alone...
+        evaluate_host_outcomes(&hosts, None, &h, "apply-p-a-2", None, &mut status);
+        assert_eq!(failures(&status), 2);
+
+        // ...and a success ends it.
+        let ok = recap(&[], &["host-1"]);
+        evaluate_host_outcomes(&hosts, Some(&ok), &h, "apply-p-a-3", None, &mut status);
+        assert_eq!(failures(&status), 0);
+    }
+
+    #[test]
+    fn ready_condition_names_the_host_with_the_most_consecutive_failures() {
+        let hosts = [
+            "host-1".to_string(),
+            "host-2".to_string(),
+            "host-3".to_string(),
+        ];
+        let h = hash();
+        let mut status = PlaybookPlanStatus::default();
+
+        let first = recap(&["host-2"], &["host-1", "host-3"]);
+        evaluate_host_outcomes(&hosts, Some(&first), &h, "apply-p-a-0", None, &mut status);
+        let second = recap(&["host-2", "host-3"], &["host-1"]);
+        evaluate_host_outcomes(&hosts, Some(&second), &h, "apply-p-a-1", None, &mut status);
+        evaluate_playbookplan_conditions(&hosts, true, Some(&second), &mut status);
+
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.type_ == "Ready")
+            .unwrap();
+        assert_eq!(ready.status, "False");
+        assert_eq!(
+            ready.message.as_deref(),
+            Some("1/3 hosts completed successfully; host 'host-2' has failed 2 consecutive run(s)")
+        );
+    }
+
+    #[test]
+    fn progress_counts_only_currently_eligible_hosts() {
+        let h = hash();
+        let hosts = [
+            "host-1".to_string(),
+            "host-2".to_string(),
+            "host-3".to_string(),
+        ];
+        let mut status = PlaybookPlanStatus {
+            current_hash: h.to_string(),
+            eligible_hosts: vec![ResolvedHosts {
+                name: "all".into(),
+                hosts: hosts.to_vec(),
+            }],
+            ..Default::default()
+        };
+
+        let output = recap(&["host-2"], &["host-1"]);
+        evaluate_host_outcomes(&hosts, Some(&output), &h, "apply-p-a-0", None, &mut status);
+        evaluate_progress(&mut status);
+        assert_eq!(status.hosts_applied, 1);
+        assert_eq!(status.hosts_failed, 1);
+        assert_eq!(status.progress.as_deref(), Some("1/3"));
+
+        // host-2 leaves the inventory and host-4 joins: the stale entry stops counting, the new
+        // host counts towards the total but not (yet) as applied.
+        status.eligible_hosts[0].hosts = vec!["host-1".into(), "host-3".into(), "host-4".into()];
+        evaluate_progress(&mut status);
+        assert_eq!(status.hosts_applied, 1);
+        assert_eq!(status.hosts_failed, 0);
+        assert_eq!(status.progress.as_deref(), Some("1/3"));
+
+        // A new spec version makes every host outdated again.
+        status.current_hash = "something-else".into();
+        evaluate_progress(&mut status);
+        assert_eq!(status.progress.as_deref(), Some("0/3"));
+        assert_eq!(status.progress_percent, Some(0));
+    }
+
+    #[test]
+    fn progress_counts_a_host_in_several_groups_once() {
+        let h = hash();
+        let hosts = ["host-1".to_string(), "host-2".to_string()];
+        let mut status = PlaybookPlanStatus {
+            current_hash: h.to_string(),
+            eligible_hosts: vec![
+                ResolvedHosts {
+                    name: "web".into(),
+                    hosts: hosts.to_vec(),
+                },
+                ResolvedHosts {
+                    name: "db".into(),
+                    hosts: vec!["host-1".into()],
+                },
+            ],
+            ..Default::default()
+        };
+
+        let output = recap(&["host-1"], &["host-2"]);
+        evaluate_host_outcomes(&hosts, Some(&output), &h, "apply-p-a-0", None, &mut status);
+        evaluate_progress(&mut status);
+        assert_eq!(status.hosts_applied, 1);
+        assert_eq!(status.hosts_failed, 1);
+        assert_eq!(status.progress.as_deref(), Some("1/2"));
+        assert_eq!(status.progress_percent, Some(50));
+    }
+
+    #[test]
+    fn hosts_leaving_the_inventory_are_pruned_from_hosts_status() {
+        let h = hash();
+        let hosts = ["host-1".to_string(), "host-2".to_string()];
+        let mut status = PlaybookPlanStatus {
+            current_hash: h.to_string(),
+            eligible_hosts: vec![ResolvedHosts {
+                name: "all".into(),
+                hosts: hosts.to_vec(),
+            }],
+            ..Default::default()
+        };
+        let output = recap(&[], &["host-1", "host-2"]);
+        evaluate_host_outcomes(&hosts, Some(&output), &h, "apply-p-a-0", None, &mut status);
+
+        assert!(prune_removed_hosts(&mut status).is_empty());
+
+        status.eligible_hosts[0].hosts = vec!["host-1".into()];
+        assert_eq!(prune_removed_hosts(&mut status), vec!["host-2"]);
+        let remaining: Vec<&String> = status.hosts_status.as_ref().unwrap().keys().collect();
+        assert_eq!(remaining, vec!["host-1"]);
+    }
+
+    #[test]
+    fn hosts_record_every_group_they_are_in() {
+        let h = hash();
+        let hosts = ["db-1".to_string(), "web-1".to_string()];
+        let group = |name: &str, hosts: &[&str]| ResolvedHosts {
+            name: name.into(),
+            hosts: hosts.iter().map(ToString::to_string).collect(),
+        };
+        let mut status = PlaybookPlanStatus {
+            eligible_hosts: vec![
+                group("webservers", &["web-1"]),
+                group("monitored", &["web-1", "db-1"]),
+                group("databases", &["db-1"]),
+            ],
+            ..Default::default()
+        };
+        evaluate_host_outcomes(
+            &hosts,
+            Some(&recap(&[], &["db-1", "web-1"])),
+            &h,
+            "apply-p-a-0",
+            None,
+            &mut status,
+        );
+
+        record_host_groups(&mut status);
+        let groups = |status: &PlaybookPlanStatus, host: &str| {
+            status.hosts_status.as_ref().unwrap()[host].groups.clone()
+        };
+        assert_eq!(groups(&status, "web-1"), ["monitored", "webservers"]);
+        assert_eq!(groups(&status, "db-1"), ["databases", "monitored"]);
+        assert_eq!(status.hosts_status.as_ref().unwrap().len(), 2);
+
+        // A host no group lists anymore keeps its entry (without pruning) but no groups.
+        status.eligible_hosts = vec![group("webservers", &["web-1"])];
+        record_host_groups(&mut status);
+        assert_eq!(groups(&status, "web-1"), ["webservers"]);
+        assert!(groups(&status, "db-1").is_empty());
+    }
+
+    #[test]
+    fn host_status_is_reset_once_per_new_generation_only_when_asked() {
+        let h = hash();
+        let hosts = ["host-1".to_string()];
+        let mut status = PlaybookPlanStatus {
+            last_processed_generation: Some(1),
+            ..Default::default()
+        };
+        evaluate_host_outcomes(
+            &hosts,
+            Some(&recap(&[], &["host-1"])),
+            &h,
+            "apply-p-a-0",
+            None,
+            &mut status,
+        );
+
+        // Same generation: nothing to do.
+        assert!(!reset_hosts_on_generation_change(&mut status, 1, true));
+        assert_eq!(status.hosts_status.as_ref().unwrap().len(), 1);
+
+        // Without the flag the generation is tracked but the state kept.
+        assert!(!reset_hosts_on_generation_change(&mut status, 2, false));
+        assert_eq!(status.last_processed_generation, Some(2));
+        assert_eq!(status.hosts_status.as_ref().unwrap().len(), 1);
+
+        // Deferred while a run is in flight, applied once it has finished.
+        status.phase = Phase::Applying;
+        assert!(!reset_hosts_on_generation_change(&mut status, 3, true));
+        assert_eq!(status.last_processed_generation, Some(2));
+        status.phase = Phase::Succeeded;
+        assert!(reset_hosts_on_generation_change(&mut status, 3, true));
+        assert_eq!(status.last_processed_generation, Some(3));
+        assert!(status.hosts_status.as_ref().unwrap().is_empty());
+
+        // ...and only once.
+        evaluate_host_outcomes(
+            &hosts,
+            Some(&recap(&[], &["host-1"])),
+            &h,
+            "apply-p-b-0",
+            None,
+            &mut status,
+        );
+        assert!(!reset_hosts_on_generation_change(&mut status, 3, true));
+        assert_eq!(status.hosts_status.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn a_failed_host_halts_the_plan_and_a_clean_run_does_not() {
+        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let outcome = || {
+            decide_terminal(
+                &ExecutionMode::Recurring,
+                Some("0 3 * * *"),
+                chrono::Duration::zero(),
+                None,
+                1,
+                3,
+                now,
+            )
+        };
+
+        let kept = halt_on_failure(outcome(), &[]);
+        assert_eq!(kept.phase, Phase::Scheduled);
+        assert!(kept.next_run.is_some());
+
+        let halted = halt_on_failure(outcome(), &["cp-1".into()]);
+        assert_eq!(halted.phase, Phase::Halted);
+        assert_eq!(halted.next_run, None);
+        assert_eq!(halted.requeue, None);
+        assert_eq!(
+            halted.summary,
+            "1/3 outdated; halted after host(s) failed: cp-1"
+        );
+    }
+
+    #[test]
+    fn progress_percent_rounds_down_and_treats_no_hosts_as_done() {
+        assert_eq!(progress_percent(0, 3), 0);
+        assert_eq!(progress_percent(1, 3), 33);
+        assert_eq!(progress_percent(199, 200), 99);
+        assert_eq!(progress_percent(200, 200), 100);
+        assert_eq!(progress_percent(0, 0), 100);
+    }
+
+    #[test]
+    fn blocked_condition_names_the_holder_then_clears_in_place() {
+        let mut status = PlaybookPlanStatus::default();
+
+        set_blocked_condition(
             &mut status,
             Some(&BlockedBy {
                 host: "homelab-ctrl-0".into(),
@@ -339,6 +1685,33 @@ mod tests {
         assert!(message.contains("another run"), "{message}");
     }
 
+    #[test]
+    fn reachable_condition_names_hosts_that_failed_the_preflight() {
+        let reachable = |status: &PlaybookPlanStatus| {
+            status
+                .conditions
+                .iter()
+                .find(|c| c.type_ == "Reachable")
+                .cloned()
+                .unwrap()
+        };
+        let mut status = PlaybookPlanStatus::default();
+
+        set_reachable_condition(&mut status, Some(&recap(&["host-2"], &["host-1"])));
+        let condition = reachable(&status);
+        assert_eq!(condition.status, "False");
+        assert_eq!(condition.reason.as_deref(), Some("HostsUnreachable"));
+        let message = condition.message.unwrap();
+        assert!(message.contains("host-2"), "{message}");
+        assert!(!message.contains("host-1"), "{message}");
+
+        set_reachable_condition(&mut status, Some(&recap(&[], &["host-1", "host-2"])));
+        assert_eq!(reachable(&status).status, "True");
+
+        set_reachable_condition(&mut status, None);
+        assert_eq!(reachable(&status).status, "Unknown");
+    }
+
     #[test]
     fn waiting_for_nodes_condition_names_hosts_then_clears_in_place() {
         let mut status = PlaybookPlanStatus::default();
@@ -357,6 +1730,7 @@ mod tests {
         let message = waiting.message.as_deref().unwrap();
         assert!(message.contains("worker-1"), "{message}");
         assert!(message.contains("worker-2"), "{message}");
+        assert!(is_waiting_for_nodes(&status));
 
         set_waiting_for_nodes_condition(&mut status, None);
         assert_eq!(
@@ -374,6 +1748,7 @@ mod tests {
             .find(|c| c.type_ == "WaitingForNodes")
             .unwrap();
         assert_eq!(cleared.status, "False");
+        assert!(!is_waiting_for_nodes(&status));
     }
 
     #[test]
@@ -390,6 +1765,254 @@ mod tests {
         assert_eq!(ready.reason.as_deref(), Some("RecapUnavailable"));
     }
 
+    fn degraded(status: &PlaybookPlanStatus) -> (&str, Option<&str>) {
+        let condition = status
+            .conditions
+            .iter()
+            .find(|c| c.type_ == "Degraded")
+            .unwrap();
+        (condition.status.as_str(), condition.reason.as_deref())
+    }
+
+    fn status_with_failing_host(failures: u32) -> PlaybookPlanStatus {
+        PlaybookPlanStatus {
+            eligible_hosts: vec![ResolvedHosts {
+                name: "workers".into(),
+                hosts: vec!["host-1".into()],
+            }],
+            hosts_status: Some(BTreeMap::from([(
+                "host-1".to_string(),
+                HostStatus {
+                    consecutive_failures: failures,
+                    ..Default::default()
+                },
+            )])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn degraded_reports_the_most_fundamental_cause() {
+        let missing = SecretProblem::MissingKey {
+            secret: "router-key".into(),
+            key: "id_ed25519".into(),
+        };
+
+        let mut status = status_with_failing_host(0);
+        evaluate_degraded_condition(&mut status, None, None);
+        assert_eq!(degraded(&status), ("False", None));
+
+        let mut status = status_with_failing_host(2);
+        evaluate_degraded_condition(&mut status, None, None);
+        assert_eq!(degraded(&status), ("True", Some("JobsFailed")));
+
+        evaluate_degraded_condition(&mut status, None, Some(&missing));
+        assert_eq!(degraded(&status), ("True", Some("SecretMissing")));
+        let message = status.conditions[0].message.as_deref().unwrap();
+        assert!(message.contains("router-key"), "{message}");
+        assert!(message.contains("id_ed25519"), "{message}");
+
+        evaluate_degraded_condition(&mut status, Some("schedule never fires"), Some(&missing));
+        assert_eq!(degraded(&status), ("True", Some("InvalidSpec")));
+    }
+
+    #[test]
+    fn dependencies_ready_names_the_missing_secrets_until_they_exist() {
+        let missing = SecretProblem::Unresolved {
+            secrets: vec!["db-credentials".into(), "tls".into()],
+        };
+        let dependencies_ready = |status: &PlaybookPlanStatus| {
+            let condition = status
+                .conditions
+                .iter()
+                .find(|c| c.type_ == "DependenciesReady")
+                .unwrap();
+            (condition.status.clone(), condition.reason.clone())
+        };
+
+        let mut status = PlaybookPlanStatus::default();
+        set_dependencies_ready_condition(&mut status, Some(&missing));
+        assert_eq!(
+            dependencies_ready(&status),
+            ("False".into(), Some("SecretMissing".into()))
+        );
+        assert_eq!(
+            status.conditions[0].message.as_deref(),
+            Some("referenced Secret(s) 'db-credentials', 'tls' do not exist")
+        );
+
+        let missing_config_map = SecretProblem::UnresolvedConfigMaps {
+            config_maps: vec!["defaults".into()],
+        };
+        set_dependencies_ready_condition(&mut status, Some(&missing_config_map));
+        assert_eq!(
+            dependencies_ready(&status),
+            ("False".into(), Some("ConfigMapMissing".into()))
+        );
+        assert_eq!(
+            status.conditions[0].message.as_deref(),
+            Some("referenced ConfigMap(s) 'defaults' do not exist")
+        );
+
+        let missing_key = SecretProblem::MissingVariablesKey {
+            secret: "app".into(),
+            key: "values.yml".into(),
+        };
+        set_dependencies_ready_condition(&mut status, Some(&missing_key));
+        assert_eq!(
+            dependencies_ready(&status),
+            ("False".into(), Some("SecretMissing".into()))
+        );
+        assert_eq!(
+            status.conditions[0].message.as_deref(),
+            Some("variables Secret 'app' has no key 'values.yml'")
+        );
+
+        set_dependencies_ready_condition(&mut status, None);
+        assert_eq!(status.conditions.len(), 1);
+        assert_eq!(dependencies_ready(&status), ("True".into(), None));
+    }
+
+    #[test]
+    fn degraded_clears_in_place_once_the_cause_is_gone() {
+        let mut status = status_with_failing_host(1);
+        evaluate_degraded_condition(&mut status, None, None);
+        assert_eq!(degraded(&status), ("True", Some("JobsFailed")));
+
+        status
+            .hosts_status
+            .as_mut()
+            .unwrap()
+            .get_mut("host-1")
+            .unwrap()
+            .consecutive_failures = 0;
+        evaluate_degraded_condition(&mut status, None, None);
+
+        assert_eq!(status.conditions.len(), 1);
+        assert_eq!(degraded(&status), ("False", None));
+    }
+
+    #[test]
+    fn ready_goes_unknown_when_outdated_but_keeps_a_failure_reason() {
+        let hosts = ["host-1".to_string()];
+        let mut status = status_with_failing_host(0);
+
+        // Not run yet: nothing to vouch for.
+        mark_ready_outdated(&mut status, &hosts);
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.type_ == "Ready")
+            .unwrap();
+        assert_eq!(ready.reason.as_deref(), Some("HostsOutdated"));
+
+        evaluate_playbookplan_conditions(&hosts, true, Some(&recap(&[], &["host-1"])), &mut status);
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.type_ == "Ready")
+            .unwrap();
+        assert_eq!(ready.status, "True");
+
+        // A new hash makes the host outdated again.
+        mark_ready_outdated(&mut status, &hosts);
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.type_ == "Ready")
+            .unwrap();
+        assert_eq!(ready.status, "Unknown");
+        assert_eq!(ready.reason.as_deref(), Some("HostsOutdated"));
+
+        evaluate_playbookplan_conditions(&hosts, true, None, &mut status);
+        mark_ready_outdated(&mut status, &hosts);
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.type_ == "Ready")
+            .unwrap();
+        assert_eq!(ready.reason.as_deref(), Some("RecapUnavailable"));
+    }
+
+    #[test]
+    fn an_invalid_playbook_fails_ready_until_it_renders_again() {
+        let ready = |status: &PlaybookPlanStatus| {
+            status
+                .conditions
+                .iter()
+                .find(|c| c.type_ == "Ready")
+                .cloned()
+                .unwrap()
+        };
+        let mut status = status_with_failing_host(0);
+
+        evaluate_playbook_ready(&mut status, Some(&"x".repeat(2000)));
+        let condition = ready(&status);
+        assert_eq!(condition.status, "False");
+        assert_eq!(condition.reason.as_deref(), Some("InvalidPlaybook"));
+        assert_eq!(
+            condition.message.unwrap().chars().count(),
+            MAX_PLAYBOOK_ERROR_CHARS + 1
+        );
+
+        evaluate_playbook_ready(&mut status, None);
+        assert_eq!(ready(&status).status, "Unknown");
+        assert_eq!(ready(&status).reason.as_deref(), Some("PlaybookFixed"));
+
+        // A `Ready` about something else is not the playbook check's to touch.
+        evaluate_playbookplan_conditions(&["host-1".into()], true, None, &mut status);
+        evaluate_playbook_ready(&mut status, None);
+        assert_eq!(ready(&status).reason.as_deref(), Some("RecapUnavailable"));
+    }
+
+    #[test]
+    fn conditions_carry_the_observed_generation_and_a_new_one_is_persisted() {
+        let mut status = PlaybookPlanStatus {
+            observed_generation: Some(1),
+            ..Default::default()
+        };
+        set_blocked_condition(&mut status, None);
+
+        // Same status and reason, but computed for a newer spec -> replaced, not skipped.
+        status.observed_generation = Some(2);
+        set_blocked_condition(&mut status, None);
+
+        let blocked: Vec<_> = status
+            .conditions
+            .iter()
+            .filter(|c| c.type_ == "Blocked")
+            .collect();
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].observed_generation, Some(2));
+    }
+
+    #[test]
+    fn every_condition_is_stamped_with_the_observed_generation() {
+        let mut status = PlaybookPlanStatus {
+            observed_generation: Some(7),
+            ..Default::default()
+        };
+        let hosts = ["host-1".to_string()];
+
+        evaluate_playbookplan_conditions(&hosts, true, None, &mut status);
+        set_blocked_condition(&mut status, None);
+        set_waiting_for_nodes_condition(&mut status, None);
+
+        let types: Vec<_> = status.conditions.iter().map(|c| c.type_.as_str()).collect();
+        assert_eq!(
+            types,
+            vec!["Running", "Ready", "Blocked", "WaitingForNodes"]
+        );
+        assert!(
+            status
+                .conditions
+                .iter()
+                .all(|c| c.observed_generation == Some(7)),
+            "{:?}",
+            status.conditions
+        );
+    }
+
     #[test]
     fn running_condition_true_while_job_not_finished() {
         let mut status = PlaybookPlanStatus::default();
@@ -401,9 +2024,187 @@ mod tests {
             .find(|c| c.type_ == "Running")
             .unwrap();
         assert_eq!(running.status, "True");
-        assert!(
-            status.conditions.iter().all(|c| c.type_ != "Ready"),
-            "Ready shouldn't be evaluated while the job is still running"
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.type_ == "Ready")
+            .unwrap();
+        assert_eq!(ready.status, "Unknown");
+        assert_eq!(ready.reason.as_deref(), Some("RunInProgress"));
+    }
+
+    #[test]
+    fn ready_moves_through_unknown_to_a_terminal_status_per_run() {
+        let hosts = ["host-1".to_string()];
+        let ready = |status: &PlaybookPlanStatus| {
+            let ready = status
+                .conditions
+                .iter()
+                .find(|c| c.type_ == "Ready")
+                .unwrap();
+            (
+                ready.status.clone(),
+                ready.reason.clone().unwrap_or_default(),
+            )
+        };
+        let mut status = status_with_failing_host(0);
+
+        // Run in flight -> Unknown; it fails -> False.
+        evaluate_playbookplan_conditions(&hosts, false, None, &mut status);
+        assert_eq!(ready(&status), ("Unknown".into(), "RunInProgress".into()));
+        evaluate_playbookplan_conditions(&hosts, true, Some(&recap(&["host-1"], &[])), &mut status);
+        assert_eq!(
+            ready(&status),
+            ("False".into(), "SomeHostsDidNotSucceed".into())
+        );
+
+        // The retry is in flight -> Unknown again; it succeeds -> True.
+        evaluate_playbookplan_conditions(&hosts, false, None, &mut status);
+        assert_eq!(ready(&status), ("Unknown".into(), "RunInProgress".into()));
+        evaluate_playbookplan_conditions(&hosts, true, Some(&recap(&[], &["host-1"])), &mut status);
+        assert_eq!(ready(&status), ("True".into(), "AllHostsSucceeded".into()));
+    }
+
+    #[test]
+    fn decide_terminal_oneshot_all_current_succeeds() {
+        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let outcome = decide_terminal(
+            &ExecutionMode::OneShot,
+            None,
+            chrono::Duration::zero(),
+            None,
+            0,
+            3,
+            now,
+        );
+
+        assert_eq!(outcome.phase, Phase::Succeeded);
+        assert_eq!(outcome.next_run, None);
+        assert_eq!(outcome.summary, "3/3 up-to-date");
+        assert_eq!(outcome.requeue, None);
+    }
+
+    #[test]
+    fn decide_terminal_oneshot_with_every_host_outdated_fails_and_never_reschedules() {
+        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        // A schedule is irrelevant in OneShot — even with one set it must resolve terminally and
+        // never reschedule.
+        let outcome = decide_terminal(
+            &ExecutionMode::OneShot,
+            Some("0 3 * * *"),
+            chrono::Duration::zero(),
+            None,
+            3,
+            3,
+            now,
+        );
+
+        assert_eq!(outcome.phase, Phase::Failed);
+        assert_eq!(outcome.next_run, None);
+        assert_eq!(outcome.summary, "3/3 outdated");
+        assert_eq!(outcome.requeue, None);
+    }
+
+    #[test]
+    fn decide_terminal_recurring_with_schedule_reschedules_to_next_slot() {
+        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let outcome = decide_terminal(
+            &ExecutionMode::Recurring,
+            Some("0 3 * * *"),
+            chrono::Duration::zero(),
+            None,
+            0,
+            2,
+            now,
+        );
+
+        assert_eq!(outcome.phase, Phase::Scheduled);
+        assert_eq!(
+            outcome.next_run,
+            Some(
+                "2025-08-13T03:00:00Z"
+                    .parse::<DateTime<FixedOffset>>()
+                    .unwrap()
+            )
         );
+        // Overrides the caller's default requeue so the plan wakes up at the next slot.
+        assert!(outcome.requeue.is_some());
+    }
+
+    #[test]
+    fn decide_terminal_recurring_with_interval_reschedules_an_interval_from_now() {
+        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let interval = chrono::Duration::minutes(30);
+        let outcome = decide_terminal(
+            &ExecutionMode::Recurring,
+            None,
+            chrono::Duration::zero(),
+            Some(interval),
+            0,
+            2,
+            now,
+        );
+
+        assert_eq!(outcome.phase, Phase::Scheduled);
+        assert_eq!(
+            outcome.next_run,
+            Some(
+                "2025-08-12T20:30:00Z"
+                    .parse::<DateTime<FixedOffset>>()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            outcome.requeue,
+            Some(std::time::Duration::from_secs(30 * 60))
+        );
+    }
+
+    #[test]
+    fn decide_terminal_recurring_without_schedule_is_a_dead_end() {
+        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let outcome = decide_terminal(
+            &ExecutionMode::Recurring,
+            None,
+            chrono::Duration::zero(),
+            None,
+            0,
+            2,
+            now,
+        );
+
+        // Nothing to reschedule against, so the plan holds at Applying (the eligibility gate
+        // normally prevents a schedule-less Recurring plan from ever starting a run).
+        assert_eq!(outcome.phase, Phase::Applying);
+        assert_eq!(outcome.next_run, None);
+        assert_eq!(outcome.requeue, None);
+    }
+
+    #[test]
+    fn oneshot_phase_distinguishes_partial_from_total_failure() {
+        assert_eq!(oneshot_phase(0, 3), Phase::Succeeded);
+        assert_eq!(oneshot_phase(1, 3), Phase::PartiallyFailed);
+        assert_eq!(oneshot_phase(2, 3), Phase::PartiallyFailed);
+        assert_eq!(oneshot_phase(3, 3), Phase::Failed);
+        // An empty run has nothing outdated.
+        assert_eq!(oneshot_phase(0, 0), Phase::Succeeded);
+    }
+
+    #[test]
+    fn decide_terminal_recurring_with_failures_still_reschedules() {
+        let now = "2025-08-12T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let outcome = decide_terminal(
+            &ExecutionMode::Recurring,
+            Some("0 3 * * *"),
+            chrono::Duration::zero(),
+            None,
+            1,
+            2,
+            now,
+        );
+
+        assert_eq!(outcome.phase, Phase::Scheduled);
+        assert_eq!(outcome.summary, "1/2 outdated");
+        assert!(outcome.next_run.is_some());
     }
 }
@@ -1,6 +1,11 @@
-use std::str::FromStr;
+use std::{
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
 
-use chrono::{DateTime, Duration, TimeZone};
+use chrono::{DateTime, Duration, FixedOffset, TimeZone};
+
+use crate::v1beta1::PlaybookPlan;
 
 /// Whether a playbook should run now or later
 #[derive(PartialEq, Eq, Debug)]
@@ -13,36 +18,88 @@ pub enum Timing<Tz: TimeZone> {
     Delayed(DateTime<Tz>),
 }
 
+/// Timing of a plan on `schedule`, `None` if the schedule doesn't parse or never fires (see
+/// `forecast_next_run`).
 pub fn evaluate_schedule<Tz: TimeZone>(
     schedule: Option<&str>,
     now: DateTime<Tz>,
     window: Duration,
-) -> Timing<Tz> {
-    if schedule.is_none() {
-        return Timing::Now(None);
-    }
+    jitter: Duration,
+) -> Option<Timing<Tz>> {
+    let Some(schedule) = schedule else {
+        return Some(Timing::Now(None));
+    };
 
-    let schedule = schedule.unwrap();
-    let next_run = forecast_next_run(schedule, now.clone(), Some(window));
+    let next_run = forecast_next_run(schedule, now.clone(), Some(window), jitter)?;
 
     let offset_now = now - window;
     let diff = next_run.clone() - offset_now;
 
     if diff <= window {
-        return Timing::Now(Some(next_run));
+        return Some(Timing::Now(Some(next_run)));
     }
 
-    Timing::Delayed(next_run)
+    Some(Timing::Delayed(next_run))
 }
 
+/// Timing of a plan recurring on `intervalSeconds`: due once `next_run` — set to an interval after
+/// the previous run finished — has passed, and right away when there is none yet.
+pub fn evaluate_interval<Tz: TimeZone>(
+    next_run: Option<DateTime<FixedOffset>>,
+    now: DateTime<Tz>,
+) -> Timing<Tz> {
+    match next_run {
+        Some(next_run) if next_run > now => {
+            Timing::Delayed(next_run.with_timezone(&now.timezone()))
+        }
+        _ => Timing::Now(None),
+    }
+}
+
+/// The next tick of `cron` (shifted later by `jitter`) at or after `now - window`. `None` if `cron`
+/// doesn't parse or has no next tick, such as `0 0 30 2 *`.
 pub fn forecast_next_run<Tz: TimeZone>(
     cron: &str,
     now: DateTime<Tz>,
     window: Option<Duration>,
-) -> DateTime<Tz> {
-    let offset_now = now - window.unwrap_or(Duration::zero());
-    let schedule = cron::Schedule::from_str(format!("0 {cron}").as_str()).unwrap();
-    schedule.after(&offset_now).next().unwrap()
+    jitter: Duration,
+) -> Option<DateTime<Tz>> {
+    let offset_now = now - window.unwrap_or(Duration::zero()) - jitter;
+    let schedule = parse_schedule(cron).ok()?;
+    Some(schedule.after(&offset_now).next()? + jitter)
+}
+
+/// How long to wait from `now` until `until`, as a requeue delay. Zero when `until` has already
+/// passed, which a slow reconcile or clock skew can cause even for a freshly forecast time.
+pub fn duration_until_or_zero<Tz: TimeZone>(
+    until: DateTime<Tz>,
+    now: DateTime<Tz>,
+) -> std::time::Duration {
+    (until - now).to_std().unwrap_or_default()
+}
+
+/// Offset of `plan`'s `schedule` ticks, see `jitter_offset`. Zero without a UID, i.e. for manifests
+/// not yet applied.
+pub fn schedule_jitter(plan: &PlaybookPlan) -> Duration {
+    match (&plan.metadata.uid, plan.spec.schedule_jitter_seconds) {
+        (Some(uid), Some(max_seconds)) => jitter_offset(uid, max_seconds),
+        _ => Duration::zero(),
+    }
+}
+
+/// A plan's share of `scheduleJitterSeconds`: an offset in `0..=max_seconds` derived from its UID,
+/// so it differs between plans but never between reconciles of the same plan.
+pub fn jitter_offset(uid: &str, max_seconds: u32) -> Duration {
+    let mut hasher = twox_hash::XxHash3_64::new();
+    uid.hash(&mut hasher);
+    let offset = hasher.finish() % (u64::from(max_seconds) + 1);
+    Duration::seconds(offset as i64)
+}
+
+/// Parses a plan's 5-field `schedule`. The `cron` crate wants a leading seconds field, pinned to 0
+/// here so a tick is always on the minute.
+pub fn parse_schedule(cron: &str) -> Result<cron::Schedule, cron::error::Error> {
+    cron::Schedule::from_str(format!("0 {cron}").as_str())
 }
 
 #[cfg(test)]
@@ -60,15 +117,122 @@ mod tests {
         let window = Duration::seconds(60);
 
         // When
-        let too_early = evaluate_schedule(schedule, parse("2025-08-12T19:59:00Z"), window);
-        let on_time = evaluate_schedule(schedule, parse("2025-08-12T20:00:00Z"), window);
-        let latest = evaluate_schedule(schedule, parse("2025-08-12T20:00:59Z"), window);
-        let too_late = evaluate_schedule(schedule, parse("2025-08-12T20:01:00Z"), window);
+        let no_jitter = Duration::zero();
+        let too_early =
+            evaluate_schedule(schedule, parse("2025-08-12T19:59:00Z"), window, no_jitter);
+        let on_time = evaluate_schedule(schedule, parse("2025-08-12T20:00:00Z"), window, no_jitter);
+        let latest = evaluate_schedule(schedule, parse("2025-08-12T20:00:59Z"), window, no_jitter);
+        let too_late =
+            evaluate_schedule(schedule, parse("2025-08-12T20:01:00Z"), window, no_jitter);
 
         // Then
-        assert_eq!(Timing::Delayed(parse("2025-08-12T20:00:00Z")), too_early);
-        assert_eq!(Timing::Now(Some(parse("2025-08-12T20:00:00Z"))), on_time);
-        assert_eq!(Timing::Now(Some(parse("2025-08-12T20:00:00Z"))), latest);
-        assert_eq!(Timing::Delayed(parse("2025-08-13T20:00:00Z")), too_late);
+        assert_eq!(
+            Some(Timing::Delayed(parse("2025-08-12T20:00:00Z"))),
+            too_early
+        );
+        assert_eq!(
+            Some(Timing::Now(Some(parse("2025-08-12T20:00:00Z")))),
+            on_time
+        );
+        assert_eq!(
+            Some(Timing::Now(Some(parse("2025-08-12T20:00:00Z")))),
+            latest
+        );
+        assert_eq!(
+            Some(Timing::Delayed(parse("2025-08-13T20:00:00Z"))),
+            too_late
+        );
+    }
+
+    #[test]
+    fn a_schedule_that_never_fires_has_no_timing() {
+        let now = parse("2025-08-12T20:00:00Z");
+        let window = Duration::seconds(60);
+
+        assert_eq!(
+            forecast_next_run("0 0 30 2 *", now, None, Duration::zero()),
+            None
+        );
+        assert_eq!(
+            evaluate_schedule(Some("0 0 30 2 *"), now, window, Duration::zero()),
+            None
+        );
+        assert_eq!(
+            evaluate_schedule(Some("not a schedule"), now, window, Duration::zero()),
+            None
+        );
+    }
+
+    #[test]
+    fn duration_until_a_past_time_is_zero() {
+        let now = parse("2025-08-12T20:00:00Z");
+
+        assert_eq!(
+            duration_until_or_zero(parse("2025-08-12T20:01:30Z"), now),
+            std::time::Duration::from_secs(90)
+        );
+        assert_eq!(duration_until_or_zero(now, now), std::time::Duration::ZERO);
+        assert_eq!(
+            duration_until_or_zero(parse("2025-08-12T19:59:59Z"), now),
+            std::time::Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn jitter_is_stable_per_uid_and_differs_between_uids() {
+        let a = jitter_offset("6f1c2a4e-0000-4000-8000-000000000001", 600);
+        let b = jitter_offset("6f1c2a4e-0000-4000-8000-000000000002", 600);
+
+        assert_eq!(
+            a,
+            jitter_offset("6f1c2a4e-0000-4000-8000-000000000001", 600)
+        );
+        assert_ne!(a, b);
+        for offset in [a, b] {
+            assert!(offset >= Duration::zero() && offset <= Duration::seconds(600));
+        }
+        assert_eq!(jitter_offset("any", 0), Duration::zero());
+    }
+
+    #[test]
+    fn jitter_shifts_every_tick_of_the_schedule() {
+        let schedule = Some("0 20 * * *");
+        let window = Duration::seconds(60);
+        let jitter = Duration::seconds(90);
+
+        assert_eq!(
+            evaluate_schedule(schedule, parse("2025-08-12T20:00:30Z"), window, jitter),
+            Some(Timing::Delayed(parse("2025-08-12T20:01:30Z")))
+        );
+        assert_eq!(
+            evaluate_schedule(schedule, parse("2025-08-12T20:01:30Z"), window, jitter),
+            Some(Timing::Now(Some(parse("2025-08-12T20:01:30Z"))))
+        );
+        // Still inside the (shifted) grace window.
+        assert_eq!(
+            evaluate_schedule(schedule, parse("2025-08-12T20:02:29Z"), window, jitter),
+            Some(Timing::Now(Some(parse("2025-08-12T20:01:30Z"))))
+        );
+    }
+
+    #[test]
+    fn interval_is_due_once_next_run_has_passed() {
+        let next_run = "2025-08-12T20:30:00Z"
+            .parse::<DateTime<FixedOffset>>()
+            .unwrap();
+
+        assert_eq!(
+            evaluate_interval(Some(next_run), parse("2025-08-12T20:29:59Z")),
+            Timing::Delayed(parse("2025-08-12T20:30:00Z"))
+        );
+        assert_eq!(
+            evaluate_interval(Some(next_run), parse("2025-08-12T20:30:00Z")),
+            Timing::Now(None)
+        );
+        // Never ran yet: start right away.
+        assert_eq!(
+            evaluate_interval(None, parse("2025-08-12T20:00:00Z")),
+            Timing::Now(None)
+        );
     }
 }
@@ -0,0 +1,824 @@
+//! Offline checks of a `PlaybookPlan` manifest, for `ansible-operator validate`: the mistakes the
+//! reconciler would otherwise only hit once the plan is applied, caught without a cluster — e.g. in
+//! CI for playbook repositories.
+
+use chrono::Utc;
+use k8s_openapi::api::core::v1::{Volume, VolumeMount};
+
+use crate::v1beta1::{DnsPolicy, ExecutionMode, FilesSource, PlaybookPlan, ansible};
+
+use super::{job_builder, triggers::parse_schedule};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ValidationError {
+    #[error("template.playbook is invalid: {0}")]
+    Playbook(#[from] ansible::RenderError),
+
+    #[error("template.verifyPlaybook is invalid: {0}")]
+    VerifyPlaybook(ansible::RenderError),
+
+    #[error("exactly one of template.playbook or template.playbookFrom must be set")]
+    PlaybookOrPlaybookFrom,
+
+    #[error("template.playbookFrom must name exactly one of configMapRef or secretRef")]
+    PlaybookFrom,
+
+    #[error("exactly one of image or imageFrom must be set")]
+    ImageOrImageFrom,
+
+    #[error("schedule {schedule:?} is not a valid 5-field cron expression: {reason}")]
+    Schedule { schedule: String, reason: String },
+
+    #[error("schedule {0:?} never fires")]
+    ScheduleNeverFires(String),
+
+    #[error("schedule and intervalSeconds are mutually exclusive")]
+    ScheduleAndInterval,
+
+    #[error("mode Recurring needs a schedule or intervalSeconds")]
+    RecurringWithoutCadence,
+
+    #[error("intervalSeconds must be greater than 0")]
+    ZeroInterval,
+
+    #[error("timeZone {0:?} is not a known IANA time zone")]
+    TimeZone(String),
+
+    #[error("inventoryRefs[{0}] must name exactly one of clusterInventory or staticInventory")]
+    InventoryRef(usize),
+
+    #[error("template.groupVariables[{index}].group {group:?} is not a usable group name")]
+    GroupName { index: usize, group: String },
+
+    #[error("template.roles[{0}] must name exactly one of configMapRef or secretRef")]
+    RoleSource(usize),
+
+    #[error("template.roles[{index}].name {name:?} is not a usable role name or is used twice")]
+    RoleName { index: usize, name: String },
+
+    #[error("dnsPolicy None needs at least one dnsConfig.nameservers entry")]
+    DnsNameservers,
+
+    #[error("template.galaxy.server must be set")]
+    GalaxyServer,
+
+    #[error(
+        "generateInventory false needs every inventoryRefs entry to be a staticInventory; cluster Nodes are only reachable through the generated inventory"
+    )]
+    GenerateInventory,
+
+    #[error("template.inventoryFile {0:?} is not a path below one of template.files")]
+    InventoryFile(String),
+
+    #[error("template.files entry {name:?} is not a valid volume: {reason}")]
+    File { name: String, reason: String },
+
+    #[error("extraVolumes[{index}] is not a valid volume: {reason}")]
+    ExtraVolume { index: usize, reason: String },
+
+    #[error(
+        "extraVolumes[{index}] is named {name:?}, which another volume of the pod already uses"
+    )]
+    ExtraVolumeName { index: usize, name: String },
+
+    #[error("extraVolumeMounts[{index}] is not a valid volume mount: {reason}")]
+    ExtraVolumeMount { index: usize, reason: String },
+
+    #[error("extraVolumeMounts[{index}] mounts {name:?}, which is not one of extraVolumes")]
+    ExtraVolumeMountName { index: usize, name: String },
+
+    #[error("extraVolumeMounts[{index}] mounts at {path:?}, which is reserved for the operator")]
+    ExtraVolumeMountPath { index: usize, path: String },
+}
+
+impl ValidationError {
+    /// Whether this leaves the reconciler unable to forecast the plan's next run, which it must do
+    /// in every phase, even mid-run.
+    pub fn breaks_schedule(&self) -> bool {
+        matches!(
+            self,
+            ValidationError::Schedule { .. }
+                | ValidationError::ScheduleNeverFires(_)
+                | ValidationError::TimeZone(_)
+        )
+    }
+}
+
+/// Runs every check against `plan` and returns all problems found, not just the first, so one run
+/// of the CLI reports everything there is to fix.
+pub fn validate(plan: &PlaybookPlan) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    // An `InventoryOnly` plan never runs, so it needs neither a playbook nor an image; one that has
+    // them anyway still gets them checked.
+    let runs_playbooks = !matches!(plan.spec.mode, ExecutionMode::InventoryOnly);
+
+    let template = &plan.spec.template;
+    // Stand-ins for what the reconciler substitutes, so a templated playbook parses the same way it
+    // will at run time.
+    let values = ansible::TemplateValues {
+        plan_name: "plan",
+        plan_namespace: "namespace",
+        execution_hash: "0",
+    };
+    let values = template.render_template.then_some(&values);
+    match &template.playbook_from {
+        None if template.playbook.is_empty() => {
+            if runs_playbooks {
+                errors.push(ValidationError::PlaybookOrPlaybookFrom)
+            }
+        }
+        None => {
+            if let Err(err) = ansible::render_playbook(&template.playbook, values) {
+                errors.push(err.into());
+            }
+        }
+        Some(_) if !template.playbook.is_empty() => {
+            errors.push(ValidationError::PlaybookOrPlaybookFrom)
+        }
+        // Its content lives in the cluster, so it can only be checked when a run renders it.
+        Some(source) => {
+            if source.config_map_ref.is_some() == source.secret_ref.is_some() {
+                errors.push(ValidationError::PlaybookFrom);
+            }
+        }
+    }
+
+    if let Some(Err(err)) = template
+        .verify_playbook
+        .as_ref()
+        .map(|verify_playbook| ansible::render_playbook(verify_playbook, values))
+    {
+        errors.push(ValidationError::VerifyPlaybook(err));
+    }
+
+    match (plan.spec.image.is_empty(), plan.spec.image_from.is_some()) {
+        (false, true) => errors.push(ValidationError::ImageOrImageFrom),
+        (true, false) if runs_playbooks => errors.push(ValidationError::ImageOrImageFrom),
+        _ => {}
+    }
+
+    if let Some(time_zone) = &plan.spec.time_zone
+        && plan.timezone().is_err()
+    {
+        errors.push(ValidationError::TimeZone(time_zone.clone()));
+    }
+
+    if let Some(schedule) = &plan.spec.schedule {
+        match parse_schedule(schedule) {
+            Err(err) => errors.push(ValidationError::Schedule {
+                schedule: schedule.clone(),
+                reason: err.to_string(),
+            }),
+            // `forecast_next_run` (what the reconciler calls) has nothing to forecast for a schedule
+            // without a next occurrence, such as `0 0 30 2 *`.
+            Ok(parsed) if parsed.upcoming(Utc).next().is_none() => {
+                errors.push(ValidationError::ScheduleNeverFires(schedule.clone()));
+            }
+            Ok(_) => {}
+        }
+    }
+
+    match (&plan.spec.schedule, plan.spec.interval_seconds) {
+        (Some(_), Some(_)) => errors.push(ValidationError::ScheduleAndInterval),
+        (None, None) if matches!(plan.spec.mode, ExecutionMode::Recurring) => {
+            errors.push(ValidationError::RecurringWithoutCadence)
+        }
+        (_, Some(0)) => errors.push(ValidationError::ZeroInterval),
+        _ => {}
+    }
+
+    for (index, inventory_ref) in plan.spec.inventory_refs.iter().enumerate() {
+        let named = [
+            inventory_ref.cluster_inventory.is_some(),
+            inventory_ref.static_inventory.is_some(),
+        ];
+        if named.iter().filter(|named| **named).count() != 1 {
+            errors.push(ValidationError::InventoryRef(index));
+        }
+    }
+
+    let group_variables = plan.spec.template.group_variables.iter().flatten();
+    for (index, group_variables) in group_variables.enumerate() {
+        // Becomes a directory below `group_vars/`, so it must be a single, visible path segment.
+        let group = &group_variables.group;
+        if group.is_empty() || group.contains('/') || group.starts_with('.') {
+            errors.push(ValidationError::GroupName {
+                index,
+                group: group.clone(),
+            });
+        }
+    }
+
+    let mut role_names = Vec::new();
+    for (index, role) in plan.spec.template.roles.iter().flatten().enumerate() {
+        if role.config_map_ref.is_some() == role.secret_ref.is_some() {
+            errors.push(ValidationError::RoleSource(index));
+        }
+        // Becomes a directory below `roles/`, like a group below `group_vars/`.
+        let name = &role.name;
+        if name.is_empty()
+            || name.contains('/')
+            || name.starts_with('.')
+            || role_names.contains(&name)
+        {
+            errors.push(ValidationError::RoleName {
+                index,
+                name: name.clone(),
+            });
+        }
+        role_names.push(name);
+    }
+
+    if plan.spec.dns_policy == Some(DnsPolicy::None)
+        && plan
+            .spec
+            .dns_config
+            .as_ref()
+            .and_then(|config| config.nameservers.as_ref())
+            .is_none_or(Vec::is_empty)
+    {
+        errors.push(ValidationError::DnsNameservers);
+    }
+
+    if plan
+        .spec
+        .template
+        .galaxy
+        .as_ref()
+        .is_some_and(|galaxy| galaxy.server.is_empty())
+    {
+        errors.push(ValidationError::GalaxyServer);
+    }
+
+    if plan.spec.generate_inventory == Some(false)
+        && plan
+            .spec
+            .inventory_refs
+            .iter()
+            .any(|inventory| inventory.cluster_inventory.is_some())
+    {
+        errors.push(ValidationError::GenerateInventory);
+    }
+
+    if let Some(inventory_file) = &plan.spec.template.inventory_file
+        && !below_files_entry(plan, inventory_file)
+    {
+        errors.push(ValidationError::InventoryFile(inventory_file.clone()));
+    }
+
+    if !plan.spec.template.skip_invalid_files {
+        for (name, reason) in job_builder::invalid_files(plan) {
+            errors.push(ValidationError::File { name, reason });
+        }
+    }
+
+    validate_extra_volumes(plan, &mut errors);
+
+    errors
+}
+
+/// Whether `path` is relative to the workspace and lies below `files/<name>/` of a `template.files`
+/// entry, the only place a file of the user's is mounted in the workspace.
+fn below_files_entry(plan: &PlaybookPlan, path: &str) -> bool {
+    let mut segments = path.split('/');
+    let (Some("files"), Some(name)) = (segments.next(), segments.next()) else {
+        return false;
+    };
+    let rest: Vec<_> = segments.collect();
+    !rest.is_empty()
+        && rest
+            .iter()
+            .all(|segment| !matches!(*segment, "" | "." | ".."))
+        && plan.spec.template.files.iter().flatten().any(
+            |(FilesSource::Secret { name: file, .. } | FilesSource::Other { name: file, .. })| {
+                file == name
+            },
+        )
+}
+
+fn validate_extra_volumes(plan: &PlaybookPlan, errors: &mut Vec<ValidationError>) {
+    let mut names = Vec::new();
+    for (index, volume) in plan.spec.extra_volumes.iter().flatten().enumerate() {
+        match serde_json::from_value::<Volume>(volume.0.clone()) {
+            Err(err) => errors.push(ValidationError::ExtraVolume {
+                index,
+                reason: err.to_string(),
+            }),
+            // k8s-openapi fills in missing required fields with their defaults.
+            Ok(Volume { name, .. }) if name.is_empty() => {
+                errors.push(ValidationError::ExtraVolume {
+                    index,
+                    reason: "name is not set".into(),
+                })
+            }
+            Ok(Volume { name, .. })
+                if job_builder::volume_name_reserved(plan, &name) || names.contains(&name) =>
+            {
+                errors.push(ValidationError::ExtraVolumeName { index, name })
+            }
+            Ok(Volume { name, .. }) => names.push(name),
+        }
+    }
+
+    for (index, mount) in plan.spec.extra_volume_mounts.iter().flatten().enumerate() {
+        match serde_json::from_value::<VolumeMount>(mount.0.clone()) {
+            Err(err) => errors.push(ValidationError::ExtraVolumeMount {
+                index,
+                reason: err.to_string(),
+            }),
+            Ok(VolumeMount { mount_path, .. }) if mount_path.is_empty() => {
+                errors.push(ValidationError::ExtraVolumeMount {
+                    index,
+                    reason: "mountPath is not set".into(),
+                })
+            }
+            Ok(VolumeMount { name, .. }) if !names.contains(&name) => {
+                errors.push(ValidationError::ExtraVolumeMountName { index, name })
+            }
+            Ok(VolumeMount { mount_path, .. }) if job_builder::mount_path_reserved(&mount_path) => {
+                errors.push(ValidationError::ExtraVolumeMountPath {
+                    index,
+                    path: mount_path,
+                })
+            }
+            Ok(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(yaml: &str) -> PlaybookPlan {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn a_well_formed_plan_has_no_errors() {
+        let pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: ok
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  schedule: "0 3 * * *"
+  timeZone: Europe/Berlin
+  inventoryRefs:
+    - clusterInventory: cluster-nodes
+    - staticInventory: edge
+  template:
+    playbook: |
+      - hosts: all
+        tasks: []
+"#,
+        );
+
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: broken
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  schedule: "every night"
+  timeZone: Mars/Olympus_Mons
+  inventoryRefs:
+    - clusterInventory: cluster-nodes
+      staticInventory: edge
+    - {}
+  template:
+    playbook: |
+      hosts: all
+    groupVariables:
+      - group: ../escape
+        variables: []
+"#,
+        );
+
+        let errors: Vec<String> = validate(&pp).iter().map(ToString::to_string).collect();
+
+        assert_eq!(errors.len(), 6, "{errors:?}");
+        assert!(errors[0].starts_with("template.playbook"), "{errors:?}");
+        assert!(errors[1].starts_with("timeZone"), "{errors:?}");
+        assert!(
+            errors[2].starts_with("schedule \"every night\""),
+            "{errors:?}"
+        );
+        assert!(errors[3].starts_with("inventoryRefs[0]"), "{errors:?}");
+        assert!(errors[4].starts_with("inventoryRefs[1]"), "{errors:?}");
+        assert!(
+            errors[5].starts_with("template.groupVariables[0]"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn roles_need_one_source_and_a_unique_directory_name() {
+        let pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: with-roles
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+    roles:
+      - name: hardening
+        secretRef:
+          name: hardening-role
+      - name: motd
+        configMapRef:
+          name: motd-role
+      - name: hardening
+        configMapRef:
+          name: other
+      - name: ../escape
+        configMapRef:
+          name: other
+      - name: neither
+"#,
+        );
+        let errors: Vec<String> = validate(&pp).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                r#"template.roles[2].name "hardening" is not a usable role name or is used twice"#,
+                r#"template.roles[3].name "../escape" is not a usable role name or is used twice"#,
+                "template.roles[4] must name exactly one of configMapRef or secretRef",
+            ]
+        );
+    }
+
+    #[test]
+    fn playbook_must_come_from_exactly_one_place() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: sourced
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  template:
+    playbookFrom:
+      configMapRef:
+        name: playbooks
+      key: site.yml
+"#,
+        );
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+
+        pp.spec.template.playbook = "[]".into();
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::PlaybookOrPlaybookFrom]
+        ));
+
+        pp.spec.template.playbook = String::new();
+        pp.spec.template.playbook_from.as_mut().unwrap().secret_ref =
+            Some(crate::v1beta1::SecretRef {
+                name: "playbooks".into(),
+            });
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::PlaybookFrom]
+        ));
+
+        pp.spec.template.playbook_from = None;
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::PlaybookOrPlaybookFrom]
+        ));
+
+        pp.spec.template.playbook = "[]".into();
+        pp.spec.template.verify_playbook = Some("hosts: all".into());
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::VerifyPlaybook(_)]
+        ));
+    }
+
+    #[test]
+    fn image_must_come_from_exactly_one_place() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: shared-runner
+spec:
+  imageFrom:
+    configMapRef:
+      name: ansible-runner
+    key: image
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+"#,
+        );
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+
+        pp.spec.image = "docker.io/serversideup/ansible-core:2.18".into();
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::ImageOrImageFrom]
+        ));
+
+        pp.spec.image = String::new();
+        pp.spec.image_from = None;
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::ImageOrImageFrom]
+        ));
+    }
+
+    #[test]
+    fn an_inventory_only_plan_needs_no_playbook_or_image() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: hosts-for-ci
+spec:
+  mode: InventoryOnly
+  inventoryRefs:
+    - clusterInventory: all-nodes
+"#,
+        );
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+
+        pp.spec.template.playbook = "not: a list".into();
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::Playbook(_)]
+        ));
+    }
+
+    #[test]
+    fn invalid_files_are_rejected_unless_skipped() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: with-files
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+    files:
+      - name: configs
+        configMap:
+          name: ansible-configs
+      - name: broken
+        configMap: ansible-configs
+"#,
+        );
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::File { name, .. }] if name == "broken"
+        ));
+
+        pp.spec.template.skip_invalid_files = true;
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+    }
+
+    #[test]
+    fn inventory_file_must_lie_below_a_files_entry() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: own-inventory
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+    inventoryFile: files/inventory/hosts.py
+    files:
+      - name: inventory
+        configMap:
+          name: inventory-script
+"#,
+        );
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+
+        for path in [
+            "/etc/ansible/hosts",
+            "files/inventory",
+            "files/inventory/",
+            "files/other/hosts.py",
+            "files/inventory/../../inventory.yml",
+        ] {
+            pp.spec.template.inventory_file = Some(path.into());
+            assert!(
+                matches!(validate(&pp).as_slice(), [ValidationError::InventoryFile(p)] if p == path),
+                "{path}: {:?}",
+                validate(&pp)
+            );
+        }
+    }
+
+    #[test]
+    fn a_host_list_inventory_only_reaches_static_inventories() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: routers
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  generateInventory: false
+  inventoryRefs:
+    - staticInventory: edge
+  template:
+    playbook: "[]"
+"#,
+        );
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+
+        pp.spec.inventory_refs.push(crate::v1beta1::InventoryRef {
+            cluster_inventory: Some("nodes".into()),
+            static_inventory: None,
+            ..Default::default()
+        });
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::GenerateInventory]
+        ));
+    }
+
+    #[test]
+    fn dns_policy_none_needs_a_nameserver() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: own-dns
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  dnsPolicy: None
+  dnsConfig:
+    searches: [corp.example.com]
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+"#,
+        );
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::DnsNameservers]
+        ));
+
+        pp.spec.dns_config.as_mut().unwrap().nameservers = Some(vec!["10.0.0.53".into()]);
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+    }
+
+    #[test]
+    fn recurring_needs_exactly_one_of_schedule_or_interval() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: periodic
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  mode: Recurring
+  intervalSeconds: 1800
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+"#,
+        );
+        assert!(validate(&pp).is_empty(), "{:?}", validate(&pp));
+
+        pp.spec.schedule = Some("0 3 * * *".into());
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::ScheduleAndInterval]
+        ));
+
+        pp.spec.schedule = None;
+        pp.spec.interval_seconds = None;
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::RecurringWithoutCadence]
+        ));
+
+        pp.spec.interval_seconds = Some(0);
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::ZeroInterval]
+        ));
+    }
+
+    #[test]
+    fn a_schedule_that_never_fires_is_rejected() {
+        let mut pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: never
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  template:
+    playbook: "[]"
+"#,
+        );
+        pp.spec.schedule = Some("0 0 30 2 *".into());
+
+        assert!(matches!(
+            validate(&pp).as_slice(),
+            [ValidationError::ScheduleNeverFires(_)]
+        ));
+    }
+
+    #[test]
+    fn extra_volumes_must_not_collide_with_the_operators_own() {
+        let pp = plan(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: volumes
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  extraVolumes:
+    - name: ca-bundle
+      configMap: { name: ca-bundle }
+    - name: playbook
+      emptyDir: {}
+    - name: secret-vars-0
+      emptyDir: {}
+    - name: ca-bundle
+      emptyDir: {}
+    - emptyDir: {}
+  extraVolumeMounts:
+    - { name: ca-bundle, mountPath: /etc/ssl/certs, readOnly: true }
+    - { name: collections, mountPath: /opt/collections }
+    - { name: ca-bundle, mountPath: /run/ansible-operator/ssh }
+    - { name: ca-bundle }
+  template:
+    playbook: |
+      - hosts: all
+        tasks: []
+    variables:
+      - secretRef:
+          name: site-vars
+"#,
+        );
+
+        let errors: Vec<String> = validate(&pp).iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            errors[..3],
+            [
+                r#"extraVolumes[1] is named "playbook", which another volume of the pod already uses"#,
+                r#"extraVolumes[2] is named "secret-vars-0", which another volume of the pod already uses"#,
+                r#"extraVolumes[3] is named "ca-bundle", which another volume of the pod already uses"#,
+            ],
+            "{errors:?}"
+        );
+        assert!(
+            errors[3].starts_with("extraVolumes[4] is not a valid volume"),
+            "{errors:?}"
+        );
+        assert_eq!(
+            errors[4..6],
+            [
+                r#"extraVolumeMounts[1] mounts "collections", which is not one of extraVolumes"#,
+                r#"extraVolumeMounts[2] mounts at "/run/ansible-operator/ssh", which is reserved for the operator"#,
+            ],
+            "{errors:?}"
+        );
+        assert!(
+            errors[6].starts_with("extraVolumeMounts[3] is not a valid volume mount"),
+            "{errors:?}"
+        );
+        assert_eq!(errors.len(), 7, "{errors:?}");
+    }
+}
@@ -1,11 +1,29 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
 
-use k8s_openapi::{api::core::v1::Secret, apimachinery::pkg::apis::meta::v1::OwnerReference};
-use kube::runtime::reflector::Lookup;
+use k8s_openapi::{
+    api::core::v1::{ConfigMap, Secret},
+    apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference},
+};
+use kube::{
+    Api,
+    api::{DeleteParams, ListParams},
+    runtime::reflector::Lookup,
+};
+use serde::{Serialize, de::DeserializeOwned};
 
-use crate::v1beta1::{
-    PlaybookPlan, ResolvedInventoryGroup, ansible, controllers::reconcile_error::ReconcileError,
-    playbookplancontroller::paths,
+use crate::{
+    utils,
+    v1beta1::{
+        GenericMap, PlaybookPlan, PlaybookVariableSource, ResolvedInventoryGroup, WorkspaceStorage,
+        ansible,
+        controllers::reconcile_error::ReconcileError,
+        labels,
+        playbookplancontroller::{execution_evaluator::ExecutionHash, paths},
+    },
 };
 
 /// Whether the workspace secret needs to be (re)rendered — on a generation change (spec edit),
@@ -26,13 +44,185 @@ pub fn is_outdated(object: &PlaybookPlan, run_starting: bool) -> bool {
     generation_changed || run_starting
 }
 
-pub async fn is_missing(secrets_api: &kube::Api<Secret>, name: &str) -> Result<bool, kube::Error> {
-    Ok(secrets_api.get_opt(name).await?.is_none())
+/// Workspace key of the playbook `spec.preflight` runs before the real one, see
+/// `job_builder::configure_job_for_preflight`.
+pub const PREFLIGHT_PLAYBOOK_KEY: &str = "preflight.yml";
+
+/// Workspace key of `template.verifyPlaybook`, run after `playbook.yml` in the same invocation.
+pub const VERIFY_PLAYBOOK_KEY: &str = "verify.yml";
+
+const PREFLIGHT_PLAYBOOK: &str = "- hosts: all
+  gather_facts: false
+  tasks:
+    - ansible.builtin.ping:
+";
+
+/// Name of the workspace Secret of the plan `plan_name` — or its ConfigMap, with `workspaceStorage:
+/// ConfigMap`. Prefixed, so it can't collide with a Secret of the user's that happens to share the
+/// plan's name; a plan name too long to fit next to the prefix is cut short by
+/// `utils::sanitize_name`.
+pub fn secret_name(plan_name: &str) -> String {
+    const PREFIX: &str = "ansible-workspace-";
+    let plan_name = utils::sanitize_name(plan_name, utils::MAX_NAME_LEN - PREFIX.len());
+    format!("{PREFIX}{plan_name}")
+}
+
+/// Whether the workspace `name` is missing from `api`, which is a Secret or ConfigMap API as the
+/// plan's `spec.workspaceStorage` says.
+pub async fn is_missing<K>(api: &kube::Api<K>, name: &str) -> Result<bool, kube::Error>
+where
+    K: Clone + DeserializeOwned + Debug,
+{
+    Ok(api.get_opt(name).await?.is_none())
+}
+
+/// A plan's rendered workspace, in the resource its `spec.workspaceStorage` picks.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Workspace {
+    Secret(Secret),
+    ConfigMap(ConfigMap),
+}
+
+impl Workspace {
+    /// The rendered file `key` of the workspace, e.g. `inventory.yml`.
+    pub fn file(&self, key: &str) -> Option<&str> {
+        let files = match self {
+            Workspace::Secret(secret) => secret.string_data.as_ref(),
+            Workspace::ConfigMap(config_map) => config_map.data.as_ref(),
+        };
+        files?.get(key).map(String::as_str)
+    }
+
+    /// The `labels::WORKSPACE_HASH` annotation of the workspace.
+    pub fn content_hash(&self) -> Option<&str> {
+        let metadata = match self {
+            Workspace::Secret(secret) => &secret.metadata,
+            Workspace::ConfigMap(config_map) => &config_map.metadata,
+        };
+        metadata
+            .annotations
+            .as_ref()?
+            .get(labels::WORKSPACE_HASH)
+            .map(String::as_str)
+    }
+}
+
+/// A hash of the workspace's files, keys and contents, for its `labels::WORKSPACE_HASH`
+/// annotation. Unlike the execution hash it covers what is rendered per run, e.g. the proxy pod
+/// IPs in `inventory.yml`, so it tells whether a Job was created for what the workspace holds now.
+pub fn content_hash(files: &BTreeMap<String, String>) -> String {
+    let mut hasher = twox_hash::XxHash3_64::new();
+    files.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+/// Renders the plan's workspace into a Secret (`render_secret`) or ConfigMap (`render_configmap`),
+/// as its `spec.workspaceStorage` says.
+pub fn render(
+    object: &PlaybookPlan,
+    playbook: &str,
+    execution_hash: &ExecutionHash,
+    target_groups: &[ResolvedInventoryGroup],
+    managed_ssh_hosts: &BTreeMap<String, ansible::ManagedSshHostInfo>,
+) -> Result<Workspace, ReconcileError> {
+    match object.spec.workspace_storage {
+        WorkspaceStorage::Secret => render_secret(
+            object,
+            playbook,
+            execution_hash,
+            target_groups,
+            managed_ssh_hosts,
+        )
+        .map(Workspace::Secret),
+        WorkspaceStorage::ConfigMap => render_configmap(
+            object,
+            playbook,
+            execution_hash,
+            target_groups,
+            managed_ssh_hosts,
+        )
+        .map(Workspace::ConfigMap),
+    }
+}
+
+/// Parts of a variable name that suggest its value is a credential.
+const SECRET_LOOKING_KEY_PARTS: &[&str] = &[
+    "password",
+    "passwd",
+    "passphrase",
+    "secret",
+    "token",
+    "private_key",
+    "api_key",
+    "apikey",
+    "credential",
+];
+
+/// Top-level keys of the plan's inline variables and inline group variables that look like they
+/// hold credentials (e.g. `db_password`), sorted and deduplicated. With `workspaceStorage:
+/// ConfigMap` these end up readable by anyone who can read ConfigMaps, so the reconciler warns.
+pub fn secret_looking_variables(object: &PlaybookPlan) -> Vec<String> {
+    let template = &object.spec.template;
+    let group_variables = template
+        .group_variables
+        .iter()
+        .flatten()
+        .flat_map(|group_variables| &group_variables.variables);
+
+    let keys: BTreeSet<String> = template
+        .variables
+        .iter()
+        .flatten()
+        .chain(group_variables)
+        .filter_map(|source| match source {
+            PlaybookVariableSource::Inline { inline } => inline.0.as_object(),
+            PlaybookVariableSource::SecretRef { .. }
+            | PlaybookVariableSource::ConfigMapRef { .. } => None,
+        })
+        .flat_map(|variables| variables.keys())
+        .filter(|key| {
+            let key = key.to_lowercase();
+            SECRET_LOOKING_KEY_PARTS
+                .iter()
+                .any(|part| key.contains(part))
+        })
+        .cloned()
+        .collect();
+
+    keys.into_iter().collect()
+}
+
+/// Renders the plan's `playbook` text as the workspace carries it: checked to be a list of plays,
+/// with the plan's metadata and `execution_hash` substituted in under `template.renderTemplate`.
+/// The reconciler also calls this before starting a run, to report a broken playbook up front.
+pub fn render_playbook(
+    object: &PlaybookPlan,
+    playbook: &str,
+    execution_hash: &ExecutionHash,
+) -> Result<String, ansible::RenderError> {
+    let execution_hash = execution_hash.to_string();
+    let template_values = ansible::TemplateValues {
+        plan_name: object.metadata.name.as_deref().unwrap_or_default(),
+        plan_namespace: object.metadata.namespace.as_deref().unwrap_or_default(),
+        execution_hash: &execution_hash,
+    };
+    ansible::render_playbook(
+        playbook,
+        object
+            .spec
+            .template
+            .render_template
+            .then_some(&template_values),
+    )
 }
 
-/// Creates a Kubernetes secret that contains an inventory.yml, a playbook.yml, the operator's
+/// Creates a Kubernetes secret that contains an inventory.yml (unless the plan doesn't use the
+/// generated one, see `PlaybookPlan::uses_generated_inventory`), a playbook.yml, the operator's
 /// recap callback plugin, and any static-variables*.yaml for a given PlaybookPlan so that the
-/// playbook can be executed afterwards. The workspace is host-agnostic.
+/// playbook can be executed afterwards. The workspace is host-agnostic. `playbook` is the plan's
+/// playbook text as resolved by `playbook_source::resolve`; with `template.renderTemplate` set, the
+/// plan's metadata and `execution_hash` are substituted into it.
 ///
 /// # Panics
 ///
@@ -40,9 +230,56 @@ pub async fn is_missing(secrets_api: &kube::Api<Secret>, name: &str) -> Result<b
 ///
 pub fn render_secret(
     object: &PlaybookPlan,
+    playbook: &str,
+    execution_hash: &ExecutionHash,
     target_groups: &[ResolvedInventoryGroup],
     managed_ssh_hosts: &BTreeMap<String, ansible::ManagedSshHostInfo>,
 ) -> Result<Secret, ReconcileError> {
+    let files = render_files(
+        object,
+        playbook,
+        execution_hash,
+        target_groups,
+        managed_ssh_hosts,
+    )?;
+    Ok(Secret {
+        metadata: workspace_metadata(object, &files),
+        string_data: Some(files),
+        ..Default::default()
+    })
+}
+
+/// Like `render_secret`, but for a plan with `workspaceStorage: ConfigMap`: the same files under
+/// the same name, in a ConfigMap.
+///
+/// # Panics
+///
+/// Panics if the playbookplan does not have a namespace, name or uid
+///
+pub fn render_configmap(
+    object: &PlaybookPlan,
+    playbook: &str,
+    execution_hash: &ExecutionHash,
+    target_groups: &[ResolvedInventoryGroup],
+    managed_ssh_hosts: &BTreeMap<String, ansible::ManagedSshHostInfo>,
+) -> Result<ConfigMap, ReconcileError> {
+    let files = render_files(
+        object,
+        playbook,
+        execution_hash,
+        target_groups,
+        managed_ssh_hosts,
+    )?;
+    Ok(ConfigMap {
+        metadata: workspace_metadata(object, &files),
+        data: Some(files),
+        ..Default::default()
+    })
+}
+
+/// Name, labels, content hash and owner of the plan's workspace holding `files`, whichever resource
+/// it's stored in.
+fn workspace_metadata(object: &PlaybookPlan, files: &BTreeMap<String, String>) -> ObjectMeta {
     let pb_namespace = object
         .metadata
         .namespace
@@ -61,38 +298,53 @@ pub fn render_secret(
         .as_ref()
         .expect(".metdata.uid must be set at this point");
 
-    let mut secret = Secret::default();
-
-    secret.metadata.namespace = Some(pb_namespace.into());
-    secret.metadata.name = Some(pb_name.into());
-
-    secret.metadata.owner_references = Some(vec![OwnerReference {
-        api_version: PlaybookPlan::api_version(&()).into(),
-        kind: PlaybookPlan::kind(&()).into(),
-        name: pb_name.into(),
-        uid: pb_uid.into(),
+    ObjectMeta {
+        namespace: Some(pb_namespace.into()),
+        name: Some(secret_name(pb_name)),
+        labels: Some(BTreeMap::from([(
+            labels::WORKSPACE_FOR.into(),
+            utils::label_value(pb_name),
+        )])),
+        annotations: Some(BTreeMap::from([(
+            labels::WORKSPACE_HASH.into(),
+            content_hash(files),
+        )])),
+        owner_references: Some(vec![OwnerReference {
+            api_version: PlaybookPlan::api_version(&()).into(),
+            kind: PlaybookPlan::kind(&()).into(),
+            name: pb_name.into(),
+            uid: pb_uid.into(),
+            ..Default::default()
+        }]),
         ..Default::default()
-    }]);
+    }
+}
 
-    let rendered_playbook = ansible::render_playbook(&object.spec)?;
+/// The workspace's files by key, see `render_secret`.
+fn render_files(
+    object: &PlaybookPlan,
+    playbook: &str,
+    execution_hash: &ExecutionHash,
+    target_groups: &[ResolvedInventoryGroup],
+    managed_ssh_hosts: &BTreeMap<String, ansible::ManagedSshHostInfo>,
+) -> Result<BTreeMap<String, String>, ReconcileError> {
+    let rendered_playbook = render_playbook(object, playbook, execution_hash)?;
+    let rendered_verify_playbook = object
+        .spec
+        .template
+        .verify_playbook
+        .as_deref()
+        .map(|verify| render_playbook(object, verify, execution_hash))
+        .transpose()?;
 
-    let managed_ssh_client_key_path = paths::managed_ssh_client_key_path();
-    let managed_ssh_known_hosts_path = paths::managed_ssh_known_hosts_path();
-    let ssh_paths_by_static_inventory = build_ssh_paths_map(target_groups);
-
-    let render_ctx = ansible::RenderContext {
-        managed_ssh_hosts,
-        managed_ssh_client_key_path: &managed_ssh_client_key_path,
-        managed_ssh_known_hosts_path: &managed_ssh_known_hosts_path,
-        ssh_paths_by_static_inventory: &ssh_paths_by_static_inventory,
-    };
-    let rendered_inventory = ansible::render_inventory(target_groups, &render_ctx)?;
+    let rendered_inventory = render_inventory(target_groups, managed_ssh_hosts)?;
 
     let inlined_variables = match &object.spec.template.variables {
         Some(variable_sources) => variable_sources
             .iter()
             .filter_map(|source| match source {
-                crate::v1beta1::PlaybookVariableSource::SecretRef { secret_ref: _ } => None,
+                crate::v1beta1::PlaybookVariableSource::SecretRef { .. } => None,
+                crate::v1beta1::PlaybookVariableSource::ConfigMapRef { .. } => None,
                 crate::v1beta1::PlaybookVariableSource::Inline { inline } => Some(inline),
             })
             .map(serde_yaml::to_string)
@@ -102,7 +354,13 @@ pub fn render_secret(
 
     let mut string_data = BTreeMap::new();
     string_data.insert("playbook.yml".into(), rendered_playbook);
-    string_data.insert("inventory.yml".into(), rendered_inventory);
+    if let Some(rendered_verify_playbook) = rendered_verify_playbook {
+        string_data.insert(VERIFY_PLAYBOOK_KEY.into(), rendered_verify_playbook);
+    }
+    // A plan bringing its own inventory gets none generated, so nothing can read the wrong one.
+    if object.uses_generated_inventory() {
+        string_data.insert("inventory.yml".into(), rendered_inventory);
+    }
     // Filename must stay exactly `ansible_operator_recap.py` — Ansible's `ANSIBLE_CALLBACKS_ENABLED`
     // matches local/adjacent plugins by filename, not CALLBACK_NAME, and must match the env var
     // set in `job_builder::configure_job_for_callback_plugin`.
@@ -115,34 +373,629 @@ pub fn render_secret(
         string_data.insert("requirements.yml".into(), requirements.to_owned());
     }
 
+    if object.spec.preflight {
+        string_data.insert(PREFLIGHT_PLAYBOOK_KEY.into(), PREFLIGHT_PLAYBOOK.into());
+    }
+
     for (index, variable_set) in inlined_variables.into_iter().enumerate() {
         string_data.insert(format!("static-variables-{index}.yml"), variable_set?);
     }
 
-    secret.string_data = Some(string_data);
+    for file in group_vars_files(object) {
+        if let GroupVarsSource::Workspace { key, variables } = file.source {
+            string_data.insert(key, serde_yaml::to_string(variables)?);
+        }
+    }
+
+    Ok(string_data)
+}
+
+/// One file of the run's `group_vars/` directory (`paths::GROUP_VARS_DIR`), carrying one entry of
+/// `template.groupVariables`.
+pub struct GroupVarsFile<'a> {
+    /// Path below `group_vars/`: `<group>/<nnn>.yml`, numbered per group in declaration order so
+    /// Ansible's sorted load of the directory keeps later entries winning.
+    pub path: String,
+    pub source: GroupVarsSource<'a>,
+}
+
+pub enum GroupVarsSource<'a> {
+    /// Inline variables, rendered into the workspace Secret under `key`.
+    Workspace {
+        key: String,
+        variables: &'a GenericMap,
+    },
+    /// The key `key` of the Secret `name`.
+    Secret { name: &'a str, key: &'a str },
+    /// The key `key` of the ConfigMap `name`.
+    ConfigMap { name: &'a str, key: &'a str },
+}
+
+/// Lays out `template.groupVariables` as `group_vars/` files. Shared by `render_secret` (which
+/// writes the inline ones) and `job_builder` (which projects every file into place), so the two
+/// always agree on keys and paths.
+pub fn group_vars_files(object: &PlaybookPlan) -> Vec<GroupVarsFile<'_>> {
+    let mut per_group: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut inline_count = 0;
+    let mut files = Vec::new();
+
+    let group_variables = object.spec.template.group_variables.iter().flatten();
+
+    for group_variables in group_variables {
+        for source in &group_variables.variables {
+            let index = per_group.entry(&group_variables.group).or_default();
+            let path = format!("{}/{index:03}.yml", group_variables.group);
+            *index += 1;
+
+            let source = match source {
+                PlaybookVariableSource::Inline { inline } => {
+                    let key = format!("group-variables-{inline_count}.yml");
+                    inline_count += 1;
+                    GroupVarsSource::Workspace {
+                        key,
+                        variables: inline,
+                    }
+                }
+                PlaybookVariableSource::SecretRef { secret_ref, key } => GroupVarsSource::Secret {
+                    name: &secret_ref.name,
+                    key: key
+                        .as_deref()
+                        .unwrap_or(PlaybookVariableSource::DEFAULT_VARIABLES_KEY),
+                },
+                PlaybookVariableSource::ConfigMapRef {
+                    config_map_ref,
+                    key,
+                } => GroupVarsSource::ConfigMap {
+                    name: &config_map_ref.name,
+                    key: key
+                        .as_deref()
+                        .unwrap_or(PlaybookVariableSource::DEFAULT_VARIABLES_KEY),
+                },
+            };
+
+            files.push(GroupVarsFile { path, source });
+        }
+    }
 
-    Ok(secret)
+    files
 }
 
-/// `StaticInventory` resource name -> (private key mount path, known_hosts mount path), for
-/// every distinct `StaticInventory` this run's groups reference.
-fn build_ssh_paths_map(groups: &[ResolvedInventoryGroup]) -> BTreeMap<String, (String, String)> {
+/// `StaticInventory` resource name -> its key/known_hosts mount paths, for every distinct
+/// `StaticInventory` this run's groups reference.
+/// The `inventory.yml` of a run over `target_groups`, with the key paths the Job pod mounts.
+/// Managed-ssh hosts missing from `managed_ssh_hosts` are rendered without an address.
+pub fn render_inventory(
+    target_groups: &[ResolvedInventoryGroup],
+    managed_ssh_hosts: &BTreeMap<String, ansible::ManagedSshHostInfo>,
+) -> Result<String, ReconcileError> {
+    let managed_ssh_client_key_path = paths::managed_ssh_client_key_path();
+    let managed_ssh_known_hosts_path = paths::managed_ssh_known_hosts_path();
+    let ssh_paths_by_static_inventory = build_ssh_paths_map(target_groups);
+
+    let render_ctx = ansible::RenderContext {
+        managed_ssh_hosts,
+        managed_ssh_client_key_path: &managed_ssh_client_key_path,
+        managed_ssh_known_hosts_path: &managed_ssh_known_hosts_path,
+        ssh_paths_by_static_inventory: &ssh_paths_by_static_inventory,
+    };
+    Ok(ansible::render_inventory(target_groups, &render_ctx)?)
+}
+
+/// Name of the ConfigMap the plan `plan_name` publishes its inventory in, cut short like
+/// `secret_name`.
+pub fn inventory_config_map_name(plan_name: &str) -> String {
+    const PREFIX: &str = "ansible-inventory-";
+    let plan_name = utils::sanitize_name(plan_name, utils::MAX_NAME_LEN - PREFIX.len());
+    format!("{PREFIX}{plan_name}")
+}
+
+/// The ConfigMap publishing `inventory` (an `inventory.yml` as `render_inventory` renders it) for
+/// readers without access to the workspace Secret. Owned by the plan, so it goes away with it.
+///
+/// # Panics
+///
+/// Panics if the playbookplan does not have a namespace, name or uid
+///
+pub fn render_inventory_config_map(object: &PlaybookPlan, inventory: String) -> ConfigMap {
+    let pb_name = object
+        .metadata
+        .name
+        .as_ref()
+        .expect(".metdata.name must be set at this point");
+
+    let mut config_map = ConfigMap::default();
+    config_map.metadata.namespace = object.metadata.namespace.clone();
+    config_map.metadata.name = Some(inventory_config_map_name(pb_name));
+    config_map.metadata.labels = Some(BTreeMap::from([(
+        labels::PLAYBOOKPLAN_NAME.into(),
+        utils::label_value(pb_name),
+    )]));
+    config_map.metadata.owner_references = Some(vec![OwnerReference {
+        api_version: PlaybookPlan::api_version(&()).into(),
+        kind: PlaybookPlan::kind(&()).into(),
+        name: pb_name.into(),
+        uid: object
+            .metadata
+            .uid
+            .clone()
+            .expect(".metdata.uid must be set at this point"),
+        ..Default::default()
+    }]);
+    config_map.data = Some(BTreeMap::from([("inventory.yml".into(), inventory)]));
+
+    config_map
+}
+
+fn build_ssh_paths_map(groups: &[ResolvedInventoryGroup]) -> BTreeMap<String, ansible::SshPaths> {
     let mut map = BTreeMap::new();
 
     for group in groups {
         if let ResolvedInventoryGroup::Ssh {
             static_inventory_name,
+            config,
             ..
         } = group
         {
-            map.entry(static_inventory_name.clone()).or_insert_with(|| {
-                (
-                    paths::static_inventory_ssh_key_path(static_inventory_name),
-                    paths::static_inventory_known_hosts_path(static_inventory_name),
-                )
-            });
+            map.entry(static_inventory_name.clone())
+                .or_insert_with(|| ansible::SshPaths {
+                    private_key: paths::static_inventory_ssh_key_path(
+                        static_inventory_name,
+                        config.effective_private_key_key(),
+                    ),
+                    additional_private_keys: config
+                        .additional_private_key_keys
+                        .iter()
+                        .flatten()
+                        .map(|key| paths::static_inventory_ssh_key_path(static_inventory_name, key))
+                        .collect(),
+                    known_hosts: paths::static_inventory_known_hosts_path(static_inventory_name),
+                });
         }
     }
 
     map
 }
+
+/// Deletes the workspaces in `api`'s namespace — Secrets or ConfigMaps, as `api` is — that no live
+/// plan renders to anymore (see `is_orphaned`) and returns their names. Besides the labelled ones
+/// this also looks at the object named exactly `plan_name`, which is where versions before the
+/// `ansible-workspace-` prefix rendered the plan's workspace (always a Secret): once its successor
+/// exists it's deleted, completing the rename. Nothing is carried over from it — a workspace is
+/// rendered from the plan alone, so its successor already holds everything it did.
+pub async fn delete_orphans<K>(
+    api: &Api<K>,
+    plans_api: &Api<PlaybookPlan>,
+    plan_name: &str,
+) -> Result<Vec<String>, kube::Error>
+where
+    K: kube::Resource + Clone + DeserializeOwned + Debug,
+{
+    let mut workspaces: Vec<ObjectMeta> = api
+        .list_metadata(&ListParams::default().labels(labels::WORKSPACE_FOR))
+        .await?
+        .into_iter()
+        .map(|workspace| workspace.metadata)
+        .collect();
+    if let Some(legacy) = api.get_metadata_opt(plan_name).await? {
+        workspaces.push(legacy.metadata);
+    }
+
+    let live_plans: BTreeMap<String, String> = plans_api
+        .list_metadata(&ListParams::default())
+        .await?
+        .into_iter()
+        .filter_map(|plan| Some((plan.metadata.name?, plan.metadata.uid?)))
+        .collect();
+
+    let mut deleted = Vec::new();
+    for workspace in workspaces.iter().filter(|w| is_orphaned(w, &live_plans)) {
+        let Some(name) = workspace.name.as_deref() else {
+            continue;
+        };
+        match api.delete(name, &DeleteParams::default()).await {
+            Err(kube::Error::Api(status)) if status.code == 404 => {}
+            result => {
+                result?;
+                deleted.push(name.to_string());
+            }
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Whether `workspace` is a workspace the operator rendered for a plan (it's owned by a
+/// PlaybookPlan) that the plan no longer uses: its owner is gone or was recreated under a new uid
+/// (`live_plans` maps names to uids), or it isn't named `secret_name(owner)` — an un-prefixed
+/// workspace from before the rename. An object without a PlaybookPlan owner is never the
+/// operator's to delete.
+fn is_orphaned(workspace: &ObjectMeta, live_plans: &BTreeMap<String, String>) -> bool {
+    let Some(owner) = workspace
+        .owner_references
+        .iter()
+        .flatten()
+        .find(|owner| owner.kind == PlaybookPlan::kind(&()))
+    else {
+        return false;
+    };
+
+    workspace.name.as_deref() != Some(secret_name(&owner.name).as_str())
+        || live_plans.get(&owner.name) != Some(&owner.uid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1beta1::{
+        ResolvedHosts, SecretRef, SshConfig,
+        playbookplancontroller::execution_evaluator::calculate_execution_hash,
+    };
+
+    fn ssh_group(
+        static_inventory_name: &str,
+        private_key_key: Option<&str>,
+    ) -> ResolvedInventoryGroup {
+        ResolvedInventoryGroup::Ssh {
+            hosts: ResolvedHosts {
+                name: static_inventory_name.into(),
+                hosts: vec!["host.example.com".into()],
+            },
+            static_inventory_name: static_inventory_name.into(),
+            config: SshConfig {
+                user: "root".into(),
+                secret_ref: SecretRef {
+                    name: format!("{static_inventory_name}-key"),
+                },
+                private_key_key: private_key_key.map(str::to_string),
+                additional_private_key_keys: None,
+            },
+            variables: None,
+        }
+    }
+
+    fn plan_with_group_variables() -> PlaybookPlan {
+        serde_yaml::from_str(
+            r#"
+apiVersion: ansible.cloudbending.dev/v1beta1
+kind: PlaybookPlan
+metadata:
+  name: web
+  namespace: default
+  uid: 00000000-0000-0000-0000-000000000000
+  generation: 1
+spec:
+  image: docker.io/serversideup/ansible-core:2.18
+  inventoryRefs: []
+  template:
+    playbook: |
+      - hosts: all
+        tasks: []
+    groupVariables:
+      - group: webservers
+        variables:
+          - inline:
+              http_port: 8080
+          - secretRef:
+              name: web-tls
+          - configMapRef:
+              name: web-defaults
+            key: webservers.yaml
+      - group: databases
+        variables:
+          - inline:
+              max_connections: 200
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn group_variables_are_laid_out_per_group_in_declaration_order() {
+        let plan = plan_with_group_variables();
+        let files = group_vars_files(&plan);
+
+        let layout: Vec<(&str, String)> = files
+            .iter()
+            .map(|file| {
+                let source = match &file.source {
+                    GroupVarsSource::Workspace { key, .. } => key.clone(),
+                    GroupVarsSource::Secret { name, key } => format!("secret:{name}/{key}"),
+                    GroupVarsSource::ConfigMap { name, key } => format!("configmap:{name}/{key}"),
+                };
+                (file.path.as_str(), source)
+            })
+            .collect();
+
+        assert_eq!(
+            layout,
+            vec![
+                ("webservers/000.yml", "group-variables-0.yml".to_string()),
+                (
+                    "webservers/001.yml",
+                    "secret:web-tls/variables.yaml".to_string()
+                ),
+                (
+                    "webservers/002.yml",
+                    "configmap:web-defaults/webservers.yaml".to_string()
+                ),
+                ("databases/000.yml", "group-variables-1.yml".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_group_variables_are_rendered_into_the_workspace() {
+        let secret = render_secret(
+            &plan_with_group_variables(),
+            "[]",
+            &calculate_execution_hash("[]", std::iter::empty()),
+            &[],
+            &BTreeMap::new(),
+        )
+        .unwrap();
+        let string_data = secret.string_data.unwrap();
+
+        assert_eq!(string_data["group-variables-0.yml"], "http_port: 8080\n");
+        assert_eq!(
+            string_data["group-variables-1.yml"],
+            "max_connections: 200\n"
+        );
+        // Group variables never leak into the playbook-wide extra vars.
+        assert!(!string_data.contains_key("static-variables-0.yml"));
+    }
+
+    #[test]
+    fn private_key_path_follows_the_configured_secret_key() {
+        let map = build_ssh_paths_map(&[
+            ssh_group("legacy", None),
+            ssh_group("ed25519", Some("id_ed25519")),
+            ssh_group("ssh-auth", Some("ssh-privatekey")),
+        ]);
+
+        assert_eq!(
+            map["legacy"].private_key,
+            "/run/ansible-operator/ssh/legacy/id_rsa"
+        );
+        assert_eq!(
+            map["ed25519"].private_key,
+            "/run/ansible-operator/ssh/ed25519/id_ed25519"
+        );
+        assert_eq!(
+            map["ssh-auth"].private_key,
+            "/run/ansible-operator/ssh/ssh-auth/ssh-privatekey"
+        );
+        assert_eq!(
+            map["ed25519"].known_hosts,
+            "/run/ansible-operator/ssh/ed25519/known_hosts"
+        );
+    }
+
+    fn workspace(name: &str, owner: Option<(&str, &str)>) -> ObjectMeta {
+        ObjectMeta {
+            name: Some(name.into()),
+            owner_references: owner.map(|(name, uid)| {
+                vec![OwnerReference {
+                    kind: "PlaybookPlan".into(),
+                    name: name.into(),
+                    uid: uid.into(),
+                    ..Default::default()
+                }]
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn orphaned_workspaces_are_those_no_live_plan_renders_to() {
+        let live_plans = BTreeMap::from([("web".to_string(), "uid-web".to_string())]);
+
+        let current = workspace("ansible-workspace-web", Some(("web", "uid-web")));
+        assert!(!is_orphaned(&current, &live_plans));
+
+        let recreated = workspace("ansible-workspace-web", Some(("web", "uid-old")));
+        assert!(is_orphaned(&recreated, &live_plans));
+
+        let renamed_away = workspace("ansible-workspace-db", Some(("db", "uid-db")));
+        assert!(is_orphaned(&renamed_away, &live_plans));
+
+        let legacy = workspace("web", Some(("web", "uid-web")));
+        assert!(is_orphaned(&legacy, &live_plans));
+
+        let users_own = workspace("web", None);
+        assert!(!is_orphaned(&users_own, &live_plans));
+    }
+
+    #[tokio::test]
+    async fn delete_orphans_cleans_up_orphaned_config_map_workspaces() {
+        use kube::client::Body;
+
+        let (service, mut handle) =
+            tower_test::mock::pair::<http::Request<Body>, http::Response<Body>>();
+        let client = kube::Client::new(service, "default");
+        let orphan = ConfigMap {
+            metadata: workspace("ansible-workspace-db", Some(("db", "uid-db"))),
+            ..Default::default()
+        };
+        let list = |items: serde_json::Value| serde_json::json!({"apiVersion": "v1", "kind": "List", "metadata": {}, "items": items});
+        let responses = [
+            (
+                http::Method::GET,
+                "/api/v1/namespaces/default/configmaps?",
+                200,
+                list(serde_json::json!([orphan])),
+            ),
+            (
+                http::Method::GET,
+                "/api/v1/namespaces/default/configmaps/web",
+                404,
+                serde_json::json!({
+                    "kind": "Status", "apiVersion": "v1", "status": "Failure",
+                    "reason": "NotFound", "code": 404,
+                }),
+            ),
+            (
+                http::Method::GET,
+                "/apis/ansible.cloudbending.dev/v1beta1/namespaces/default/playbookplans?",
+                200,
+                list(serde_json::json!([])),
+            ),
+            (
+                http::Method::DELETE,
+                "/api/v1/namespaces/default/configmaps/ansible-workspace-db",
+                200,
+                serde_json::to_value(&orphan).unwrap(),
+            ),
+        ];
+        let server = tokio::spawn(async move {
+            for (method, path, code, response) in responses {
+                let (request, send) = handle.next_request().await.expect("a request");
+                assert_eq!(request.method(), method);
+                assert!(
+                    request.uri().to_string().starts_with(path),
+                    "{}",
+                    request.uri()
+                );
+                send.send_response(
+                    http::Response::builder()
+                        .status(code)
+                        .body(Body::from(serde_json::to_vec(&response).unwrap()))
+                        .unwrap(),
+                );
+            }
+        });
+
+        let config_maps_api = Api::<ConfigMap>::namespaced(client.clone(), "default");
+        let plans_api = Api::<PlaybookPlan>::namespaced(client, "default");
+        let deleted = delete_orphans(&config_maps_api, &plans_api, "web")
+            .await
+            .unwrap();
+
+        assert_eq!(deleted, ["ansible-workspace-db"]);
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn render_secret_uses_the_prefixed_name_and_labels_the_plan() {
+        let plan = plan_with_group_variables();
+        let hash = calculate_execution_hash("", std::iter::empty());
+
+        let secret = render_secret(&plan, "[]", &hash, &[], &BTreeMap::new()).unwrap();
+
+        assert_eq!(
+            secret.metadata.name.as_deref(),
+            Some("ansible-workspace-web")
+        );
+        assert_eq!(
+            secret.metadata.labels.unwrap()[labels::WORKSPACE_FOR],
+            "web"
+        );
+    }
+
+    #[test]
+    fn long_plan_names_still_get_a_valid_workspace_name_and_label() {
+        let mut plan = plan_with_group_variables();
+        plan.metadata.name = Some("a".repeat(250));
+        let hash = calculate_execution_hash("", std::iter::empty());
+
+        let secret = render_secret(&plan, "[]", &hash, &[], &BTreeMap::new()).unwrap();
+
+        let name = secret.metadata.name.unwrap();
+        assert!(name.len() <= utils::MAX_NAME_LEN, "{name}");
+        assert!(name.starts_with("ansible-workspace-aaa"), "{name}");
+        assert_ne!(name, secret_name(&"a".repeat(251)));
+        assert!(utils::is_label_value(
+            &secret.metadata.labels.unwrap()[labels::WORKSPACE_FOR]
+        ));
+    }
+
+    #[test]
+    fn the_longest_plan_names_still_get_a_valid_inventory_config_map() {
+        let mut plan = plan_with_group_variables();
+        plan.metadata.name = Some("a".repeat(utils::MAX_NAME_LEN));
+
+        let config_map = render_inventory_config_map(&plan, "all: {}".into());
+
+        let name = config_map.metadata.name.unwrap();
+        assert!(name.len() <= utils::MAX_NAME_LEN, "{name}");
+        assert!(name.starts_with("ansible-inventory-aaa"), "{name}");
+        assert!(utils::is_label_value(
+            &config_map.metadata.labels.unwrap()[labels::PLAYBOOKPLAN_NAME]
+        ));
+        assert_eq!(inventory_config_map_name("web"), "ansible-inventory-web");
+    }
+
+    #[test]
+    fn render_secret_leaves_out_the_generated_inventory_for_an_inventory_file() {
+        let mut plan = plan_with_group_variables();
+        let hash = calculate_execution_hash("", std::iter::empty());
+
+        let secret = render_secret(&plan, "[]", &hash, &[], &BTreeMap::new()).unwrap();
+        assert!(secret.string_data.unwrap().contains_key("inventory.yml"));
+
+        plan.spec.template.inventory_file = Some("files/inventory/hosts.py".into());
+        let secret = render_secret(&plan, "[]", &hash, &[], &BTreeMap::new()).unwrap();
+        let data = secret.string_data.unwrap();
+        assert!(!data.contains_key("inventory.yml"));
+        assert!(data.contains_key("playbook.yml"));
+    }
+
+    #[test]
+    fn the_workspace_is_annotated_with_a_hash_of_its_content() {
+        let plan = plan_with_group_variables();
+        let hash = calculate_execution_hash("", std::iter::empty());
+        let render = |playbook: &str| {
+            let workspace = render(&plan, playbook, &hash, &[], &BTreeMap::new()).unwrap();
+            workspace.content_hash().unwrap().to_string()
+        };
+
+        assert_eq!(render("[]"), render("[]"));
+        assert_ne!(render("[]"), render("- hosts: all\n  tasks: []"));
+    }
+
+    #[test]
+    fn render_secret_writes_the_verify_playbook_only_when_set() {
+        let mut plan = plan_with_group_variables();
+        let hash = calculate_execution_hash("", std::iter::empty());
+
+        let secret = render_secret(&plan, "[]", &hash, &[], &BTreeMap::new()).unwrap();
+        assert!(
+            !secret
+                .string_data
+                .unwrap()
+                .contains_key(VERIFY_PLAYBOOK_KEY)
+        );
+
+        plan.spec.template.verify_playbook = Some("- hosts: all\n  tasks: []".into());
+        let secret = render_secret(&plan, "[]", &hash, &[], &BTreeMap::new()).unwrap();
+        assert_eq!(
+            secret.string_data.unwrap()[VERIFY_PLAYBOOK_KEY],
+            "- hosts: all\n  tasks: []\n"
+        );
+    }
+
+    #[test]
+    fn credential_looking_inline_variables_are_found_in_variables_and_group_variables() {
+        let mut plan = plan_with_group_variables();
+        plan.spec.template.variables = Some(vec![
+            PlaybookVariableSource::Inline {
+                inline: GenericMap(serde_json::json!({
+                    "DB_Password": "hunter2",
+                    "http_port": 8080,
+                })),
+            },
+            PlaybookVariableSource::Inline {
+                inline: GenericMap(serde_json::json!({"github_token": "ghp_x"})),
+            },
+        ]);
+        plan.spec.template.group_variables.as_mut().unwrap()[1].variables[0] =
+            PlaybookVariableSource::Inline {
+                inline: GenericMap(serde_json::json!({"db_password": "x", "max_connections": 1})),
+            };
+
+        assert_eq!(
+            secret_looking_variables(&plan),
+            vec!["DB_Password", "db_password", "github_token"]
+        );
+        assert!(secret_looking_variables(&plan_with_group_variables()).is_empty());
+    }
+}
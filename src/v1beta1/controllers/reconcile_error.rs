@@ -11,6 +11,25 @@ pub enum ReconcileError {
     #[error("Inventory group {group:?} sets variable {key:?}, which the operator manages")]
     ReservedInventoryVariable { group: String, key: String },
 
+    #[error(
+        "Inventory group {group:?} selects on matchFields key {field:?}, which is not supported (supported: {supported})",
+        supported = crate::v1beta1::controllers::nodeselector::SUPPORTED_NODE_FIELDS.join(", ")
+    )]
+    UnsupportedMatchField { group: String, field: String },
+
+    /// `template.playbookFrom` can't be read: its ConfigMap/Secret or key is missing.
+    #[error("{0}")]
+    PlaybookSource(String),
+
+    /// `imageFrom` can't be read: its ConfigMap or key is missing.
+    #[error("{0}")]
+    ImageSource(String),
+
+    /// Adding or removing the PlaybookPlan's finalizer failed. Errors of the reconcile itself are
+    /// unwrapped from `finalizer::Error` rather than ending up here.
+    #[error(transparent)]
+    Finalizer(Box<kube::runtime::finalizer::Error<ReconcileError>>),
+
     #[error(transparent)]
     RenderError(#[from] ansible::RenderError),
 
@@ -1,3 +1,8 @@
 pub const PLAYBOOKPLAN_NAME: &str = "ansible.cloudbending.dev/playbookplan";
 pub const PLAYBOOKPLAN_HOST: &str = "ansible.cloudbending.dev/target-host";
 pub const PLAYBOOKPLAN_HASH: &str = "ansible.cloudbending.dev/hash";
+/// On a workspace Secret: the name of the PlaybookPlan it was rendered for.
+pub const WORKSPACE_FOR: &str = "ansible.cloudbending.dev/workspace-for";
+/// Annotation on a workspace and on each Job mounting it: a hash of the workspace's files, see
+/// `workspace::content_hash`.
+pub const WORKSPACE_HASH: &str = "ansible.cloudbending.dev/workspace-hash";
@@ -4,7 +4,7 @@ use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::v1beta1::{AnsibleInventory, GenericMap, NodeSelectorTerm, ResolvedHosts};
+use crate::v1beta1::{AnsibleInventory, GenericMap, NodeSelectorTerm, ResolvedHosts, Variables};
 
 #[derive(CustomResource, Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
 #[kube(
@@ -22,6 +22,11 @@ pub struct ClusterInventorySpec {
     /// Tolerations applied to the managed-ssh proxy pods created for this inventory's hosts,
     /// e.g. to allow scheduling onto tainted controlplane nodes.
     pub tolerations: Option<Vec<Toleration>>,
+
+    /// When true, `.status.selectorDebug` reports what the group selectors were evaluated against,
+    /// to help find out why a selector matches fewer Nodes than expected. Off by default.
+    #[serde(default)]
+    pub debug_selectors: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
@@ -62,6 +67,53 @@ impl From<Toleration> for k8s_openapi::api::core::v1::Toleration {
 pub struct ClusterInventoryStatus {
     pub host_count: usize,
     pub resolved_hosts: Vec<ResolvedHosts>,
+    /// How many Nodes matched by a group with `readyNodesOnly` were left out because they are not
+    /// Ready.
+    #[serde(default)]
+    pub skipped_not_ready: usize,
+    /// Only set while `debugSelectors` is enabled.
+    pub selector_debug: Option<SelectorDebug>,
+    /// The labels and annotations groups' `exposeNodeMetadata` select, for each Node of such a
+    /// group.
+    #[serde(default)]
+    pub node_metadata: Vec<NodeMetadata>,
+}
+
+/// The labels and annotations of one Node that the groups it is in expose to playbooks. A Node in
+/// several such groups exposes the keys all of them select.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeMetadata {
+    pub node: String,
+    /// Set when a group selects labels; keys the Node doesn't carry are left out.
+    pub labels: Option<BTreeMap<String, String>>,
+    /// Set when a group selects annotations; keys the Node doesn't carry are left out.
+    pub annotations: Option<BTreeMap<String, String>>,
+}
+
+/// What a `ClusterInventory`'s selectors were evaluated against. Sizes are bounded, so the status
+/// stays small on large clusters.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectorDebug {
+    /// How many Nodes every group's selector was evaluated against.
+    pub nodes_considered: usize,
+    /// Label keys present on at least one of those Nodes, sorted, at most 100 of them.
+    pub label_keys: Vec<String>,
+    /// Whether `labelKeys` was cut off at its limit.
+    pub label_keys_truncated: bool,
+    pub groups: Vec<GroupSelectorDebug>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupSelectorDebug {
+    pub name: String,
+    /// How many Nodes the group's selector matched.
+    pub matched: usize,
+    /// Label keys the group's `matchLabels`/`matchExpressions` refer to that no Node carries —
+    /// typically a typo.
+    pub unknown_label_keys: Vec<String>,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
@@ -73,11 +125,38 @@ pub struct InventoryHosts {
     #[serde(flatten)]
     pub match_expressions: Option<BTreeMap<String, serde_json::Value>>, // todo: placeholder
 
+    /// Leave out control-plane Nodes — those labelled `node-role.kubernetes.io/control-plane` or the
+    /// legacy `node-role.kubernetes.io/master`, whatever the label's value — even where the
+    /// selector matches them. Off by default.
+    #[serde(default)]
+    pub exclude_control_plane: bool,
+
+    /// Leave out Nodes whose `Ready` condition is not `True` (`NotReady`, `Unknown`, or not yet
+    /// reported), e.g. while they reboot, rather than waiting for their proxy pod on every run. They
+    /// come back as soon as they are Ready again. Off by default.
+    #[serde(default)]
+    pub ready_nodes_only: bool,
+
     /// Group variables applied to every node this group resolves to, rendered as Ansible group
     /// `vars:`. Use it to set node facts the playbook author should not have to know, e.g.
     /// `ansible_python_interpreter`. Operator-managed connection variables (`ansible_host`,
     /// `ansible_user`, `ansible_port`, `ansible_ssh_*`) are rejected — the operator owns those.
+    #[schemars(with = "Option<Variables>")]
     pub variables: Option<GenericMap>,
+
+    /// Label and annotation keys of each Node to pass to the playbook as the host variables
+    /// `node_labels` and `node_annotations`, e.g. `{{ node_labels['topology.kubernetes.io/zone'] }}`.
+    /// A changed value changes the execution hash, so the plans targeting the Node run again.
+    pub expose_node_metadata: Option<ExposeNodeMetadata>,
+}
+
+/// Which Node labels and annotations a group exposes, by key.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ExposeNodeMetadata {
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub annotations: Vec<String>,
 }
 
 impl AnsibleInventory for ClusterInventory {
@@ -27,17 +27,48 @@ impl JsonSchema for UnsignedInt {
     }
 }
 
+/// Marker type for `#[schemars(with = "Variables")]` on `GenericMap` fields holding Ansible
+/// variables. `GenericMap` alone is an opaque object (`x-kubernetes-preserve-unknown-fields`); this
+/// spells out that it is a map from variable name to a value of any shape, so the apiserver rejects
+/// e.g. a list or a string given in its place. Use `Option<Variables>` for optional fields.
+pub struct Variables;
+
+impl JsonSchema for Variables {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("Variables")
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "additionalProperties": {
+                "x-kubernetes-preserve-unknown-fields": true
+            }
+        }))
+        .unwrap()
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NodeSelectorTerm {
     pub match_labels: Option<LabelMap>,
     pub match_expressions: Option<Vec<SelectorExpression>>,
+    /// Like `matchExpressions`, but on Node fields instead of labels, e.g. `{ key: metadata.name,
+    /// operator: In, values: [worker-1] }`. Only `metadata.name` is supported; a requirement on any
+    /// other field matches no Node.
+    pub match_fields: Option<Vec<SelectorExpression>>,
 }
 
-/// A `matchLabels` + `matchExpressions` label selector — structurally identical to
-/// `NodeSelectorTerm`, aliased for readability where the target is something other than a Node
-/// (e.g. a namespace selector). Kubernetes' own `metav1.LabelSelector` has the same two fields.
-pub type LabelSelector = NodeSelectorTerm;
+/// A `matchLabels` + `matchExpressions` label selector, where the target is selected by its labels
+/// alone (e.g. a namespace selector). Kubernetes' own `metav1.LabelSelector` has the same two
+/// fields. Unlike `NodeSelectorTerm` it has no `matchFields`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSelector {
+    pub match_labels: Option<LabelMap>,
+    pub match_expressions: Option<Vec<SelectorExpression>>,
+}
 
 #[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
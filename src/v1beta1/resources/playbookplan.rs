@@ -2,7 +2,7 @@ use std::{borrow::Cow, collections::BTreeMap};
 
 use crate::{
     utils::Condition,
-    v1beta1::{ResolvedHosts, UnsignedInt},
+    v1beta1::{ResolvedHosts, UnsignedInt, Variables},
 };
 use chrono::{DateTime, FixedOffset};
 use chrono_tz::Tz;
@@ -40,38 +40,141 @@ impl JsonSchema for GenericMap {
     printcolumn = r#"{"name":"Suspended","type":"boolean","jsonPath":".spec.suspend"}"#,
     printcolumn = r#"{"name":"Previous run","type":"string","jsonPath":".status.lastTriggeredRun"}"#,
     printcolumn = r#"{"name":"Next run","type":"string","jsonPath":".status.nextRun"}"#,
+    printcolumn = r#"{"name":"Last run","type":"date","jsonPath":".status.lastRunTime"}"#,
+    printcolumn = r#"{"name":"Last success","type":"date","jsonPath":".status.lastSuccessfulRunTime"}"#,
     printcolumn = r#"{"name":"Current hash","type":"string","jsonPath":".status.currentHash"}"#,
     printcolumn = r#"{"name":"Ready","type":"string","jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
     printcolumn = r#"{"name":"Running","type":"string","jsonPath":".status.conditions[?(@.type==\"Running\")].status"}"#,
+    printcolumn = r#"{"name":"Degraded","type":"string","jsonPath":".status.conditions[?(@.type==\"Degraded\")].status"}"#,
+    printcolumn = r#"{"name":"Progress","type":"string","jsonPath":".status.progress"}"#,
+    printcolumn = r#"{"name":"%","type":"integer","jsonPath":".status.progressPercent"}"#,
     printcolumn = r#"{"name":"Summary","type":"string","jsonPath":".status.summary"}"#,
     printcolumn = r#"{"name":"Phase","type":"string","jsonPath":".status.phase"}"#,
     printcolumn = r#"{"name":"Age","type":"date","jsonPath":".metadata.creationTimestamp"}"#
 )]
 #[serde(rename_all = "camelCase")]
 pub struct PlaybookPlanSpec {
-    /// An OCI image with Ansible and all required collections
+    /// An OCI image with Ansible and all required collections. Leave empty when using `imageFrom`
+    #[serde(default)]
     pub image: String,
 
+    /// Read the image from a ConfigMap key instead of setting it in `image`, so many plans can share
+    /// one runner version. Changing the value rolls out with the next run, without re-running hosts
+    /// that are already up to date
+    pub image_from: Option<ImageSource>,
+
     /// ServiceAccount the playbook pod runs as, letting tasks reach the Kubernetes API with that
     /// identity's RBAC. When set, the SA's token is auto-mounted (Ansible's `kubernetes.core`
     /// modules pick it up via in-cluster config). When unset, the pod runs with no API token at
     /// all — create the ServiceAccount and its Role/RoleBinding yourself and name it here.
     pub service_account_name: Option<String>,
 
+    /// Whether the playbook pod mounts its ServiceAccount's API token. Unset means "only when
+    /// `serviceAccountName` is set"; `false` keeps a named ServiceAccount (e.g. for its image pull
+    /// secrets) without its token, `true` mounts the namespace's `default` ServiceAccount's token
+    /// when no `serviceAccountName` is set
+    pub automount_service_account_token: Option<bool>,
+
+    /// Node labels the playbook pod must be scheduled onto, e.g. to keep ansible runners on a
+    /// dedicated node pool. Applied as the pod's `nodeSelector` whatever inventories the plan
+    /// targets; it only places the runner, not the hosts it manages. Unset places the pod anywhere
+    /// (still softly steered off the run's managed-ssh target nodes).
+    pub job_node_selector: Option<BTreeMap<String, String>>,
+
+    /// Pod-level security settings of the playbook pod, e.g. to run it as a non-root user or meet a
+    /// `restricted` Pod Security Admission profile. Only the runner pod is affected, never the
+    /// managed-ssh proxy pods on the target nodes.
+    pub pod_security_context: Option<PodSecurityContext>,
+
+    /// Container-level security settings of the playbook pod's containers (including the one
+    /// installing collections), e.g. dropping capabilities or a read-only root filesystem.
+    pub security_context: Option<ContainerSecurityContext>,
+
+    /// DNS policy of the playbook pod: `ClusterFirst` (the Kubernetes default), `Default` (the
+    /// node's resolver) or `None` (only what `dnsConfig` says), e.g. when SSH targets have internal
+    /// names the cluster DNS doesn't know
+    pub dns_policy: Option<DnsPolicy>,
+
+    /// Extra nameservers, search domains and resolver options for the playbook pod, merged into
+    /// what `dnsPolicy` generates
+    pub dns_config: Option<PodDnsConfig>,
+
+    /// Extra `/etc/hosts` entries for the playbook pod, for SSH targets without DNS entries
+    pub host_aliases: Option<Vec<HostAlias>>,
+
+    /// Additional volumes for the playbook pod, written as Kubernetes `Volume`s, for content that
+    /// must sit at a fixed path instead of below `template.files` — e.g. a CA bundle. Mounted only
+    /// where `extraVolumeMounts` says. Names must not clash with the pod's other volumes
+    pub extra_volumes: Option<Vec<GenericMap>>,
+
+    /// Where to mount `extraVolumes` in the playbook pod's containers, written as Kubernetes
+    /// `VolumeMount`s (`name`, `mountPath`, optionally `subPath` and `readOnly`). Paths below
+    /// `/run/ansible-operator` are reserved for the operator
+    pub extra_volume_mounts: Option<Vec<GenericMap>>,
+
+    /// Extra labels and annotations for the playbook Job (`metadata`) and its pod
+    /// (`template.metadata`), e.g. for cost allocation or NetworkPolicy selection. The operator's
+    /// own `ansible.cloudbending.dev/*` labels always win over ones set here
+    pub job_template: Option<JobTemplate>,
+
     /// Verbosity for `ansible-playbook`, mapped to `-v`…`-vvvv`. 0 (unset) adds no flag; values
     /// above 4 are clamped to 4. Affects log detail only — it is not part of the execution hash, so
     /// changing it does not re-run the playbook on already-current hosts.
     #[schemars(with = "Option<UnsignedInt>")]
     pub verbosity: Option<u8>,
 
-    /// Controls if a playbook is executed once or repeatedly
+    /// Name of the task to start the playbook at, skipping every task before it
+    /// (`--start-at-task`), e.g. to iterate on the end of a long playbook. Unlike `verbosity` it
+    /// changes what runs, so it is part of the execution hash
+    pub start_at_task: Option<String>,
+
+    /// Controls if a playbook is executed once or repeatedly, or never: `InventoryOnly` only
+    /// resolves the plan's inventories into `status.eligibleHosts`, e.g. for external automation
+    // The apiserver fills this in from the schema default; serde's own default covers manifests
+    // read straight from a file (`ansible-operator validate`).
+    #[serde(default)]
     #[schemars(default)]
     pub mode: ExecutionMode,
 
+    /// Also write each run's rendered `inventory.yml` to the ConfigMap `ansible-inventory-<plan>`,
+    /// readable without access to Secrets. `InventoryOnly` plans always publish it. Defaults to false
+    #[serde(default)]
+    pub publish_inventory: bool,
+
+    /// Where the run's workspace (the rendered playbook, inventory and inline variables) is stored:
+    /// the Secret `ansible-workspace-<plan>` (the default), or a ConfigMap of that name. Secrets
+    /// referenced for variables or files stay Secrets either way.
+    #[serde(default)]
+    #[schemars(default)]
+    pub workspace_storage: WorkspaceStorage,
+
+    /// Which hosts a `Recurring` plan runs on at each schedule tick: `AllHosts` (the default) runs
+    /// every eligible host, `OutdatedOnly` only those not yet on the current execution hash — like a
+    /// `OneShot` plan that only starts runs on schedule ticks. Ignored in the other modes.
+    #[serde(default)]
+    #[schemars(default)]
+    pub recurring_targets: RecurringTargets,
+
+    /// What happens after a run in which a host failed: `Continue` (the default) keeps starting
+    /// runs as usual, `Halt` stops starting any further runs and puts the plan in phase `Halted`
+    /// until its execution hash changes (i.e. the playbook or its inputs are fixed) or the policy is
+    /// set back to `Continue`. Hosts that were already applied keep their status.
+    #[serde(default)]
+    #[schemars(default)]
+    pub failure_policy: FailurePolicy,
+
+    /// Check every host of a run answers `ansible.builtin.ping` before running the playbook, so a
+    /// bad credential or an unreachable host fails the run in seconds rather than partway through a
+    /// long playbook. The playbook then doesn't run at all, and the `Reachable` condition names the
+    /// hosts that didn't answer. Defaults to false
+    #[serde(default)]
+    pub preflight: bool,
+
     /// When true, the operator stops starting new runs for this plan — the same idea as a
     /// CronJob's `.spec.suspend`. A run already in progress is left to finish; only the *starting*
-    /// of new runs is gated. While suspended the `Suspended` printer column reads `true` and
-    /// `.status.nextRun` is cleared; the plan's phase keeps reflecting its underlying state.
+    /// of new runs is gated. While suspended the `Suspended` printer column and condition read
+    /// `true` and `.status.nextRun` is cleared; the plan's phase keeps reflecting its underlying
+    /// state.
     /// Defaults to false.
     #[serde(default)]
     pub suspend: bool,
@@ -79,6 +182,17 @@ pub struct PlaybookPlanSpec {
     /// 5-part cron expression that tells at which time the playbook may execute
     pub schedule: Option<String>,
 
+    /// Delays every `schedule` tick of this plan by a fixed offset between 0 and this many seconds,
+    /// derived from the plan's UID, so plans sharing a schedule don't all start at once. The offset
+    /// stays the same for the plan's lifetime.
+    #[schemars(with = "Option<UnsignedInt>")]
+    pub schedule_jitter_seconds: Option<u32>,
+
+    /// For `Recurring` plans, an alternative to `schedule`: run again this many seconds after the
+    /// previous run finished, e.g. 1800 for "every 30 minutes". Mutually exclusive with `schedule`.
+    #[schemars(with = "Option<UnsignedInt>")]
+    pub interval_seconds: Option<u32>,
+
     /// Time zone for the _schedule_ field, if unset UTC is assumed
     pub time_zone: Option<String>,
 
@@ -87,17 +201,52 @@ pub struct PlaybookPlanSpec {
     /// absorbs the gap between a tick and the next reconcile (e.g. the operator was busy or
     /// restarting). If more than this many seconds pass past a tick without the run starting, that
     /// tick is skipped and the run waits for the next one. The same idea as a CronJob's
-    /// `.spec.startingDeadlineSeconds`. Only affects scheduled (`schedule`) plans. Defaults to 30.
+    /// `.spec.startingDeadlineSeconds`. Only affects scheduled (`schedule`) plans. Defaults to the operator's `--schedule-window`,
+    /// 30 unless changed.
     #[schemars(with = "Option<UnsignedInt>")]
     pub starting_deadline_seconds: Option<u32>,
 
     /// These host groups will be available in our playbook
     pub inventory_refs: Vec<InventoryRef>,
 
+    /// Whether `.status.hostsStatus` entries of hosts that are no longer eligible (a Node removed
+    /// from the cluster, a host dropped from an inventory or excluded) are deleted. Defaults to
+    /// true; set false to keep their last known state around.
+    pub prune_removed_hosts: Option<bool>,
+
+    /// Whether the operator writes an `inventory.yml` for each run. Defaults to true; set false to
+    /// run the playbook against a plain list of the run's hosts instead (`-i host-1,host-2,`),
+    /// leaving connection settings to the playbook or its `ansible.cfg`. Only for plans whose
+    /// `inventoryRefs` are all `staticInventory`
+    pub generate_inventory: Option<bool>,
+
+    /// Forget every host's recorded state (`.status.hostsStatus`) whenever the spec changes, so the
+    /// next run treats all hosts as out of date. Useful after restructuring inventories, when the
+    /// stored per-host state no longer makes sense. Off by default: normally only a changed
+    /// execution hash re-runs hosts.
+    #[serde(default)]
+    pub reset_host_status_on_change: bool,
+
+    /// Leave out cluster nodes that are cordoned (`spec.unschedulable`) when a run starts, instead
+    /// of waiting for their managed-ssh proxy pod, which can't be scheduled there, to time out. Such
+    /// hosts are recorded as `Skipped` and stay out of date until a later run reaches them. Off by
+    /// default
+    #[serde(default)]
+    pub skip_unschedulable_nodes: bool,
+
+    /// Glob patterns (`*`, `?`) a host name must match at least one of to be targeted. Applied on
+    /// top of the resolved inventories; unset or empty admits every host.
+    pub include_hosts: Option<Vec<String>>,
+
+    /// Glob patterns (`*`, `?`) for host names this plan must never target, whatever its
+    /// inventories resolve to, e.g. `prod-db-*`. Takes precedence over `includeHosts`. Hosts
+    /// dropped by either list are reported in `.status.excludedHosts`.
+    pub exclude_hosts: Option<Vec<String>>,
+
     /// How long a finished run's Job (and its pod) is kept before Kubernetes' TTL controller
-    /// reaps it. The operator never deletes the Job itself, so this governs the ansible pod's
-    /// lifetime. Values below 60 seconds are silently raised to 60; unset uses the operator's
-    /// default.
+    /// reaps it. The operator itself only deletes finished Jobs of earlier execution hashes (once
+    /// the next run finishes), so this governs the ansible pod's lifetime. Values below 60 seconds
+    /// are silently raised to 60; unset uses the operator's default.
     pub ttl_seconds_after_finished: Option<i32>,
 
     /// How many successful `Play` history records to keep for this plan before the oldest are
@@ -110,7 +259,9 @@ pub struct PlaybookPlanSpec {
     #[schemars(with = "Option<UnsignedInt>")]
     pub failed_plays_history_limit: Option<u32>,
 
-    /// The playbook will be built from this, some fields will be set automatically (vars, hosts)
+    /// The playbook will be built from this, some fields will be set automatically (vars, hosts).
+    /// Not needed in `InventoryOnly` mode
+    #[serde(default)]
     pub template: PlaybookTemplate,
 }
 
@@ -121,6 +272,33 @@ pub struct InventoryRef {
     pub cluster_inventory: Option<String>,
     /// Name of the StaticInventory resource being referenced
     pub static_inventory: Option<String>,
+    /// The order this inventory's hosts are listed in within each of their groups, which is the
+    /// order Ansible works through them, e.g. batch by batch with `serial:`. Defaults to `AsIs`.
+    #[serde(default)]
+    #[schemars(default)]
+    pub order: HostOrder,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub enum HostOrder {
+    /// As the inventory lists them: a StaticInventory in its own order, a ClusterInventory's Nodes
+    /// in the order it resolved them.
+    #[default]
+    AsIs,
+    /// Alphabetically by host name.
+    Sorted,
+    /// Shuffled, but the same way on every run of the plan: the order is seeded from its UID, and
+    /// adding or removing a host leaves the others in place.
+    Shuffled,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub enum WorkspaceStorage {
+    #[default]
+    Secret,
+    /// For workspaces holding nothing sensitive: anyone allowed to read ConfigMaps in the plan's
+    /// namespace can read the playbook, the inventory and the inline variables.
+    ConfigMap,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
@@ -128,22 +306,119 @@ pub enum ExecutionMode {
     #[default]
     OneShot,
     Recurring,
+    InventoryOnly,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub enum RecurringTargets {
+    #[default]
+    AllHosts,
+    OutdatedOnly,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub enum FailurePolicy {
+    #[default]
+    Continue,
+    Halt,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct PlaybookTemplate {
-    /// The actual playbook contents
+    /// The actual playbook contents. Leave empty when using `playbookFrom`
+    #[serde(default)]
     pub playbook: String,
 
+    /// Read the playbook from a ConfigMap or Secret key instead of inlining it in `playbook`
+    pub playbook_from: Option<PlaybookSource>,
+
+    /// Substitute `{{ plan_name }}`, `{{ plan_namespace }}` and `{{ execution_hash }}` in the
+    /// playbook before it runs. Other `{{ }}` expressions are left to Ansible. Defaults to false
+    #[serde(default)]
+    pub render_template: bool,
+
+    /// A second playbook run right after `playbook`, in the same `ansible-playbook` invocation, to
+    /// check its result. Hosts the playbook failed on are skipped, and a host failing
+    /// verification counts as failed, so it is retried like any other failure
+    pub verify_playbook: Option<String>,
+
     /// Variables for the playbook
     pub variables: Option<Vec<PlaybookVariableSource>>,
 
+    /// Variables scoped to a single inventory group, rendered as that group's `group_vars`
+    pub group_variables: Option<Vec<GroupVariables>>,
+
     /// Files for the playbook
     #[schemars(with = "Option<Vec<GenericMap>>")]
     pub files: Option<Vec<FilesSource>>,
 
+    /// Roles for the playbook, each read from a Secret or ConfigMap whose keys are the role's files.
+    /// `__` in a key stands for a subdirectory, e.g. `tasks__main.yml` becomes `tasks/main.yml`
+    pub roles: Option<Vec<RoleSource>>,
+
+    /// Leave out `files` entries that aren't valid Kubernetes volumes and run without them, instead
+    /// of refusing to run. Either way the `FilesValid` condition names them. Defaults to false
+    #[serde(default)]
+    pub skip_invalid_files: bool,
+
+    /// Inventory to run the playbook against instead of the one the operator generates from
+    /// `inventoryRefs`: a path below one of the `files` entries, e.g. `files/inventory/hosts.py` for
+    /// a dynamic inventory script. `inventoryRefs` still decide which hosts the plan tracks and locks
+    pub inventory_file: Option<String>,
+
     /// Runtime requirements (e.g. Ansible collections)
     pub requirements: Option<String>,
+
+    /// Whether an init container installs `requirements` with `ansible-galaxy` before the playbook
+    /// runs: `Auto` (the default) does whenever `requirements` is set, `Skip` never does — for
+    /// images that already bundle their collections, e.g. in air-gapped clusters — and `Force`
+    /// does with `--force`, reinstalling collections the image already has
+    #[serde(default)]
+    #[schemars(default)]
+    pub requirements_install: RequirementsInstall,
+
+    /// Galaxy server (e.g. an internal mirror) `requirements` are installed from, instead of the
+    /// public galaxy.ansible.com
+    pub galaxy: Option<GalaxyConfig>,
+
+    /// OCI image whose contents are mounted read-only as the Ansible collections directory, instead
+    /// of installing `requirements` at run time. Needs a cluster with image volumes, enabled in the
+    /// operator config (`image_volumes`)
+    pub collections_image: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GalaxyConfig {
+    /// URL of the Galaxy server, passed to `ansible-galaxy install` as `--server`
+    pub server: String,
+
+    /// Secret holding an `ansible-galaxy` token file (`token: <value>`, the format of
+    /// `~/.ansible/galaxy_token`), mounted read-only into the installing init container
+    pub token_secret_ref: Option<SecretRef>,
+
+    /// Key within the token Secret that holds the token file. Defaults to `galaxy_token`
+    pub token_key: Option<String>,
+}
+
+/// Secret key the Galaxy token file is read from when `tokenKey` is unset.
+pub const DEFAULT_GALAXY_TOKEN_KEY: &str = "galaxy_token";
+
+impl GalaxyConfig {
+    pub fn effective_token_key(&self) -> &str {
+        self.token_key
+            .as_deref()
+            .unwrap_or(DEFAULT_GALAXY_TOKEN_KEY)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub enum RequirementsInstall {
+    #[default]
+    Auto,
+    Skip,
+    Force,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -158,25 +433,325 @@ pub enum FilesSource {
     },
 }
 
+/// Schema description of `PlaybookVariableSource`'s `key`, shared by the Secret and ConfigMap
+/// variants.
+const KEY_DESCRIPTION: &str =
+    "Key of the Secret or ConfigMap holding the variables. Defaults to `variables.yaml`.";
+
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum PlaybookVariableSource {
-    /// Extra variables to read from a secret. These must be within `.data."variables.yaml"`.
+    /// Extra variables to read from a secret.
     #[serde(rename_all = "camelCase")]
     SecretRef {
         secret_ref: SecretRef,
+        /// Key of the Secret holding the variables. Defaults to `variables.yaml`.
+        // Both variants' `key` must have the same schema, description included, as the CRD
+        // schema merges the properties of untagged variants.
+        #[schemars(description = KEY_DESCRIPTION)]
+        key: Option<String>,
+    },
+    /// Extra variables to read from a ConfigMap, for values that aren't sensitive.
+    #[serde(rename_all = "camelCase")]
+    ConfigMapRef {
+        config_map_ref: ConfigMapRef,
+        /// Key of the ConfigMap holding the variables. Defaults to `variables.yaml`.
+        // Both variants' `key` must have the same schema, description included, as the CRD
+        // schema merges the properties of untagged variants.
+        #[schemars(description = KEY_DESCRIPTION)]
+        key: Option<String>,
     },
     Inline {
+        #[schemars(with = "Variables")]
         inline: GenericMap,
     },
 }
 
+impl PlaybookVariableSource {
+    /// Key a `secretRef` or `configMapRef` source reads its variables from when it names none.
+    pub const DEFAULT_VARIABLES_KEY: &str = "variables.yaml";
+}
+
+/// Where to read a playbook that isn't inlined, e.g. one synced from Git into a ConfigMap.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybookSource {
+    /// ConfigMap in the plan's namespace holding the playbook
+    pub config_map_ref: Option<ConfigMapRef>,
+
+    /// Secret in the plan's namespace holding the playbook
+    pub secret_ref: Option<SecretRef>,
+
+    /// Key of the ConfigMap or Secret whose value is the playbook
+    pub key: String,
+}
+
+/// Where to read a plan's image from when it isn't set inline.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageSource {
+    /// ConfigMap in the plan's namespace holding the image
+    pub config_map_ref: ConfigMapRef,
+
+    /// Key of the ConfigMap whose value is the image reference
+    pub key: String,
+}
+
+/// The part of a Kubernetes Job template a plan may set on its playbook Job.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JobTemplate {
+    /// Labels and annotations of the Job itself
+    pub metadata: Option<TemplateMetadata>,
+    /// The Job's pod template
+    pub template: Option<PodTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodTemplate {
+    /// Labels and annotations of the Job's pod
+    pub metadata: Option<TemplateMetadata>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateMetadata {
+    pub labels: Option<BTreeMap<String, String>>,
+    pub annotations: Option<BTreeMap<String, String>>,
+}
+
+/// The part of a Kubernetes `PodSecurityContext` a plan may set on its playbook pod.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodSecurityContext {
+    /// UID the pod's processes run as
+    pub run_as_user: Option<i64>,
+
+    /// GID the pod's processes run as
+    pub run_as_group: Option<i64>,
+
+    /// Refuse to start the pod if its image would run as root
+    pub run_as_non_root: Option<bool>,
+
+    /// GID that owns the pod's volumes
+    pub fs_group: Option<i64>,
+
+    /// Further GIDs added to the pod's processes
+    pub supplemental_groups: Option<Vec<i64>>,
+
+    /// Seccomp profile of the pod's containers
+    pub seccomp_profile: Option<SeccompProfile>,
+}
+
+impl From<PodSecurityContext> for k8s_openapi::api::core::v1::PodSecurityContext {
+    fn from(other: PodSecurityContext) -> Self {
+        Self {
+            run_as_user: other.run_as_user,
+            run_as_group: other.run_as_group,
+            run_as_non_root: other.run_as_non_root,
+            fs_group: other.fs_group,
+            supplemental_groups: other.supplemental_groups,
+            seccomp_profile: other.seccomp_profile.map(Into::into),
+            ..Default::default()
+        }
+    }
+}
+
+/// The part of a Kubernetes container `SecurityContext` a plan may set on its playbook pod's
+/// containers. `privileged` is deliberately not offered: the runner reaches its hosts over SSH and
+/// never needs it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerSecurityContext {
+    /// UID the container runs as, overriding the pod's
+    pub run_as_user: Option<i64>,
+
+    /// GID the container runs as, overriding the pod's
+    pub run_as_group: Option<i64>,
+
+    /// Refuse to start the container if its image would run as root
+    pub run_as_non_root: Option<bool>,
+
+    /// Whether a process may gain more privileges than its parent (e.g. via setuid binaries)
+    pub allow_privilege_escalation: Option<bool>,
+
+    /// Mount the container's root filesystem read-only. Ansible writes temporary files under
+    /// `~/.ansible`, so the image must then point it at a writable location
+    pub read_only_root_filesystem: Option<bool>,
+
+    /// Linux capabilities to add to or drop from the container
+    pub capabilities: Option<Capabilities>,
+
+    /// Seccomp profile of the container, overriding the pod's
+    pub seccomp_profile: Option<SeccompProfile>,
+}
+
+impl From<ContainerSecurityContext> for k8s_openapi::api::core::v1::SecurityContext {
+    fn from(other: ContainerSecurityContext) -> Self {
+        Self {
+            run_as_user: other.run_as_user,
+            run_as_group: other.run_as_group,
+            run_as_non_root: other.run_as_non_root,
+            allow_privilege_escalation: other.allow_privilege_escalation,
+            read_only_root_filesystem: other.read_only_root_filesystem,
+            capabilities: other
+                .capabilities
+                .map(|c| k8s_openapi::api::core::v1::Capabilities {
+                    add: c.add,
+                    drop: c.drop,
+                }),
+            seccomp_profile: other.seccomp_profile.map(Into::into),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct Capabilities {
+    /// Capabilities to add, e.g. `NET_RAW`
+    pub add: Option<Vec<String>>,
+
+    /// Capabilities to drop, e.g. `ALL`
+    pub drop: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeccompProfile {
+    /// `RuntimeDefault`, `Localhost` or `Unconfined`
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// Path of the profile on the node, relative to the kubelet's seccomp directory; only for
+    /// type `Localhost`
+    pub localhost_profile: Option<String>,
+}
+
+impl From<SeccompProfile> for k8s_openapi::api::core::v1::SeccompProfile {
+    fn from(other: SeccompProfile) -> Self {
+        Self {
+            type_: other.type_,
+            localhost_profile: other.localhost_profile,
+        }
+    }
+}
+
+/// A pod's `dnsPolicy`. `ClusterFirstWithHostNet` isn't offered: the playbook pod never uses the
+/// host network, and without it that policy is the same as `ClusterFirst`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub enum DnsPolicy {
+    ClusterFirst,
+    Default,
+    None,
+}
+
+impl DnsPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DnsPolicy::ClusterFirst => "ClusterFirst",
+            DnsPolicy::Default => "Default",
+            DnsPolicy::None => "None",
+        }
+    }
+}
+
+/// A Kubernetes `HostAlias`: one `/etc/hosts` line.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HostAlias {
+    /// IP address the host names resolve to
+    pub ip: String,
+
+    /// Host names for `ip`
+    pub hostnames: Vec<String>,
+}
+
+impl From<HostAlias> for k8s_openapi::api::core::v1::HostAlias {
+    fn from(other: HostAlias) -> Self {
+        Self {
+            ip: other.ip,
+            hostnames: Some(other.hostnames),
+        }
+    }
+}
+
+/// A Kubernetes `PodDNSConfig`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodDnsConfig {
+    /// Nameserver IPs, at most three including those from `dnsPolicy`
+    pub nameservers: Option<Vec<String>>,
+
+    /// Search domains for host-name lookups
+    pub searches: Option<Vec<String>>,
+
+    /// Resolver options, like `ndots`
+    pub options: Option<Vec<PodDnsConfigOption>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodDnsConfigOption {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+impl From<PodDnsConfig> for k8s_openapi::api::core::v1::PodDNSConfig {
+    fn from(other: PodDnsConfig) -> Self {
+        Self {
+            nameservers: other.nameservers,
+            searches: other.searches,
+            options: other.options.map(|options| {
+                options
+                    .into_iter()
+                    .map(|option| k8s_openapi::api::core::v1::PodDNSConfigOption {
+                        name: Some(option.name),
+                        value: option.value,
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// A role mounted below `roles/`, from exactly one of a Secret or a ConfigMap.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleSource {
+    /// Name the playbook refers to the role by
+    pub name: String,
+
+    /// Secret in the plan's namespace holding the role's files
+    pub secret_ref: Option<SecretRef>,
+
+    /// ConfigMap in the plan's namespace holding the role's files
+    pub config_map_ref: Option<ConfigMapRef>,
+}
+
+/// Variable sources applied only to the hosts of one inventory group.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupVariables {
+    /// Name of the group as it appears in the rendered inventory (the inventory group's `name`)
+    pub group: String,
+
+    /// Variables for the group; later entries win over earlier ones on key collisions
+    pub variables: Vec<PlaybookVariableSource>,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SecretRef {
     pub name: String,
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigMapRef {
+    pub name: String,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 pub enum Phase {
     /// Triggers have not yet been evaluated
@@ -192,24 +767,49 @@ pub enum Phase {
     /// Playbook is scheduled for reexecution.
     Scheduled,
 
-    /// Some or all jobs failed (for OneShot mode only)
+    /// The run finished without bringing any host current (for OneShot mode only)
     Failed,
 
+    /// The run finished with some hosts current and others not (for OneShot mode only)
+    PartiallyFailed,
+
     /// Jobs for all hosts ran successfully (for OneShot mode only)
     Succeeded,
 
+    /// A run failed on at least one host and `failurePolicy` is `Halt`, so no further runs start
+    /// until the execution hash changes or the policy is set back to `Continue`.
+    Halted,
+
     /// The PlaybookPlan's namespace is not enrolled for the operator (not in the chart's
     /// `watchNamespaces`), so the operator has no RBAC to read its Secrets or create its Job and
     /// refuses to run it. Terminal until an administrator enrols the namespace and the operator
     /// restarts (see R1 / T-INFO-1).
     UnauthorizedNamespace,
+
+    /// The plan's inventories are resolved into `eligibleHosts` (for `InventoryOnly` mode only,
+    /// which never runs a playbook).
+    InventoryResolved,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PlaybookPlanStatus {
     pub eligible_hosts: Vec<ResolvedHosts>,
+    /// Hosts the inventories resolved to but `includeHosts`/`excludeHosts` filtered out.
+    #[serde(default)]
+    pub excluded_hosts: Vec<String>,
+    /// Cordoned nodes `skipUnschedulableNodes` left out of the current (or last) run. Fixed when a
+    /// run starts, so the run keeps the same host set until it finishes.
+    #[serde(default)]
+    pub skipped_hosts: Vec<String>,
     pub last_rendered_generation: Option<i64>,
+    /// The `.metadata.generation` whose hosts' state `resetHostStatusOnChange` last reset against.
+    /// Unlike `observedGeneration` it only advances outside of a run, so an edit made while a run
+    /// is in flight still takes effect once that run has finished.
+    pub last_processed_generation: Option<i64>,
+    /// The `.metadata.generation` the operator last reconciled to completion. Lets generic tooling
+    /// (kstatus, Argo CD, `kubectl wait`) tell whether this status reflects the latest spec.
+    pub observed_generation: Option<i64>,
     pub conditions: Vec<PlaybookPlanCondition>,
     pub hosts_status: Option<BTreeMap<String, HostStatus>>,
     // `default` is required, not just nice-to-have: status patches are JSON Merge Patches, where
@@ -227,6 +827,15 @@ pub struct PlaybookPlanStatus {
     #[serde(default, with = "crate::v1beta1::resources::custom_rfc3339")]
     #[schemars(with = "Option<String>")]
     pub last_triggered_run: Option<DateTime<FixedOffset>>,
+    /// When the plan's last run finished, whatever its outcome: its Job's completion time, or when
+    /// the operator found the run over if the Job was already gone.
+    #[serde(default, with = "crate::v1beta1::resources::custom_rfc3339")]
+    #[schemars(with = "Option<String>")]
+    pub last_run_time: Option<DateTime<FixedOffset>>,
+    /// When a run last finished with its Job complete and every host it targeted succeeded.
+    #[serde(default, with = "crate::v1beta1::resources::custom_rfc3339")]
+    #[schemars(with = "Option<String>")]
+    pub last_successful_run_time: Option<DateTime<FixedOffset>>,
     pub phase: Phase,
     pub current_hash: String,
     pub summary: Option<String>,
@@ -239,9 +848,26 @@ pub struct PlaybookPlanStatus {
     /// `current_hash` changes; incremented once per Job actually created, in `spawn_ansible_job`.
     #[schemars(with = "UnsignedInt")]
     pub retry_count: u32,
+    /// How many of the currently eligible hosts have `currentHash` applied.
+    #[serde(default)]
+    #[schemars(with = "UnsignedInt")]
+    pub hosts_applied: u32,
+    /// How many of the currently eligible hosts failed their last run.
+    #[serde(default)]
+    #[schemars(with = "UnsignedInt")]
+    pub hosts_failed: u32,
+    /// `hostsApplied` out of all eligible hosts, preformatted as e.g. `12/40` for the `Progress`
+    /// printer column.
+    pub progress: Option<String>,
+    /// `hostsApplied` as a whole percentage of all eligible hosts, rounded down so it only reads 100
+    /// once every eligible host is current. 100 with no eligible hosts, as there's nothing to do.
+    #[schemars(with = "Option<UnsignedInt>")]
+    pub progress_percent: Option<u32>,
+    /// The image the plan's next Job runs: `spec.image`, or the value `spec.imageFrom` resolved to.
+    pub resolved_image: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HostStatus {
     /// The execution hash last SUCCESSFULLY applied to this host. Only bumped on `HostOutcome::Succeeded`.
@@ -251,6 +877,39 @@ pub struct HostStatus {
     #[serde(default, with = "crate::v1beta1::resources::custom_rfc3339")]
     #[schemars(with = "Option<String>")]
     pub last_transition_time: Option<DateTime<FixedOffset>>,
+    /// Name of the Job that last ran against this host, whether it succeeded or failed. The Job
+    /// itself may already have been reaped by its TTL; the matching `Play` keeps the same name.
+    pub last_job_name: Option<String>,
+    /// When that Job started (its `.status.startTime`).
+    #[serde(default, with = "crate::v1beta1::resources::custom_rfc3339")]
+    #[schemars(with = "Option<String>")]
+    pub last_start_time: Option<DateTime<FixedOffset>>,
+    /// When that Job finished (its `.status.completionTime`, or the `Failed` condition's transition
+    /// time — Kubernetes only sets `completionTime` on success).
+    #[serde(default, with = "crate::v1beta1::resources::custom_rfc3339")]
+    #[schemars(with = "Option<String>")]
+    pub last_completion_time: Option<DateTime<FixedOffset>>,
+    /// The terminal result of that Job as a whole, from its `Complete`/`Failed` condition. Unlike
+    /// `last_outcome` this is per-Job, not per-host: one failed host fails the whole Job. `None` when
+    /// the Job was already gone by the time its result was read.
+    pub last_result: Option<JobResult>,
+    /// How many runs in a row have ended with this host `Failed`. Reset to 0 when the host
+    /// succeeds; `NotReached`/`Unknown` runs leave it untouched, as they say nothing about the host
+    /// itself. Each Job is counted once, keyed on `last_job_name`.
+    #[serde(default)]
+    #[schemars(with = "UnsignedInt")]
+    pub consecutive_failures: u32,
+    /// The `eligibleHosts` groups this host is in, sorted. A host several inventory groups list is
+    /// still run once per run, so it keeps a single entry. Empty once it's in none, if
+    /// `pruneRemovedHosts` keeps the entry.
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum JobResult {
+    Succeeded,
+    Failed,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
@@ -264,9 +923,12 @@ pub enum HostOutcome {
     /// The host was in scope for this run but Ansible never reached it (e.g. an earlier host in its
     /// `serial` batch stopped the play).
     NotReached,
+    /// The host was left out of the run before it started, because its Node was cordoned and the
+    /// plan sets `skipUnschedulableNodes`.
+    Skipped,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PlaybookPlanCondition {
     #[serde(rename = "type")]
@@ -278,6 +940,8 @@ pub struct PlaybookPlanCondition {
     #[serde(default, with = "crate::v1beta1::resources::custom_rfc3339")]
     #[schemars(with = "Option<String>")]
     pub last_transition_time: Option<DateTime<FixedOffset>>,
+    /// The `.metadata.generation` this condition was computed from.
+    pub observed_generation: Option<i64>,
 }
 
 impl Condition for PlaybookPlanCondition {
@@ -292,6 +956,10 @@ impl Condition for PlaybookPlanCondition {
     fn reason(&self) -> Option<&str> {
         self.reason.as_deref()
     }
+
+    fn observed_generation(&self) -> Option<i64> {
+        self.observed_generation
+    }
 }
 
 impl PlaybookPlan {
@@ -302,6 +970,22 @@ impl PlaybookPlan {
             .map(|tz| tz.parse::<Tz>())
             .unwrap_or(Ok(Tz::UTC))
     }
+
+    pub fn interval(&self) -> Option<chrono::Duration> {
+        self.spec
+            .interval_seconds
+            .map(|seconds| chrono::Duration::seconds(seconds.into()))
+    }
+
+    pub fn prunes_removed_hosts(&self) -> bool {
+        self.spec.prune_removed_hosts.unwrap_or(true)
+    }
+
+    /// Whether runs use the `inventory.yml` the operator renders, rather than `template.inventoryFile`
+    /// or, with `generateInventory: false`, a bare host list.
+    pub fn uses_generated_inventory(&self) -> bool {
+        self.spec.template.inventory_file.is_none() && self.spec.generate_inventory.unwrap_or(true)
+    }
 }
 
 #[cfg(test)]
@@ -314,17 +998,43 @@ mod tests {
             "blubb",
             PlaybookPlanSpec {
                 image: "registry.tld/ansible:1.0.0".to_string(),
+                image_from: None,
                 service_account_name: None,
+                automount_service_account_token: None,
+                job_node_selector: None,
+                pod_security_context: None,
+                dns_policy: None,
+                dns_config: None,
+                host_aliases: None,
+                security_context: None,
+                extra_volumes: None,
+                extra_volume_mounts: None,
+                job_template: None,
                 verbosity: None,
+                start_at_task: None,
                 mode: ExecutionMode::Recurring,
+                publish_inventory: false,
+                workspace_storage: WorkspaceStorage::Secret,
+                recurring_targets: RecurringTargets::AllHosts,
+                failure_policy: FailurePolicy::Continue,
+                preflight: false,
                 suspend: false,
                 schedule: Some("0 1 * * *".into()),
+                schedule_jitter_seconds: None,
+                interval_seconds: None,
                 time_zone: None,
                 starting_deadline_seconds: None,
                 inventory_refs: vec![InventoryRef {
                     cluster_inventory: Some("controlplanes".into()),
                     static_inventory: Some("others".into()),
+                    order: HostOrder::AsIs,
                 }],
+                prune_removed_hosts: None,
+                generate_inventory: None,
+                reset_host_status_on_change: false,
+                skip_unschedulable_nodes: false,
+                include_hosts: None,
+                exclude_hosts: None,
                 ttl_seconds_after_finished: None,
                 successful_plays_history_limit: None,
                 failed_plays_history_limit: None,
@@ -333,6 +1043,7 @@ mod tests {
                         secret_ref: SecretRef {
                             name: "some-secret".into(),
                         },
+                        key: None,
                     }]),
                     files: Some(vec![FilesSource::Secret {
                         name: "some-name".into(),
@@ -458,4 +1169,23 @@ spec:
             None
         );
     }
+
+    /// Inline variables are validated as a map by the apiserver, while the values themselves stay
+    /// free-form.
+    #[test]
+    fn crd_schema_requires_inline_variables_to_be_a_map() {
+        use kube::CustomResourceExt as _;
+
+        let crd = serde_json::to_value(PlaybookPlan::crd()).unwrap();
+        let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+        let inline = &spec["properties"]["template"]["properties"]["variables"]["items"]["properties"]
+            ["inline"];
+
+        assert_eq!(inline["type"], "object", "{inline}");
+        assert_eq!(
+            inline["additionalProperties"]["x-kubernetes-preserve-unknown-fields"], true,
+            "{inline}"
+        );
+        assert!(inline.get("x-kubernetes-preserve-unknown-fields").is_none());
+    }
 }
@@ -2,7 +2,7 @@ use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::v1beta1::{AnsibleInventory, GenericMap, ResolvedHosts, SecretRef};
+use crate::v1beta1::{AnsibleInventory, GenericMap, ResolvedHosts, SecretRef, Variables};
 
 #[derive(CustomResource, Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
 #[kube(
@@ -36,6 +36,7 @@ pub struct StaticInventoryGroup {
     /// Group variables applied to every host in this group, rendered as Ansible group `vars:`,
     /// e.g. `ansible_python_interpreter`. Operator-managed connection variables (`ansible_user`,
     /// `ansible_ssh_*`, `ansible_host`, `ansible_port`) are rejected — the operator owns those.
+    #[schemars(with = "Option<Variables>")]
     pub variables: Option<GenericMap>,
 }
 
@@ -44,6 +45,33 @@ pub struct StaticInventoryGroup {
 pub struct SshConfig {
     pub user: String,
     pub secret_ref: SecretRef,
+
+    /// Key within the referenced Secret that holds the private key, e.g. `id_ed25519`. Defaults to
+    /// `ssh-privatekey` for a `kubernetes.io/ssh-auth` Secret and to `id_rsa` otherwise.
+    pub private_key_key: Option<String>,
+
+    /// Further keys in the same Secret holding private keys to offer, tried in order after
+    /// `privateKeyKey` — e.g. a preferred `id_ed25519` alongside a legacy RSA key while a fleet
+    /// migrates.
+    pub additional_private_key_keys: Option<Vec<String>>,
+}
+
+/// Secret key the private key is read from when `privateKeyKey` is unset.
+pub const DEFAULT_PRIVATE_KEY_KEY: &str = "id_rsa";
+
+/// Secret type Kubernetes defines for SSH credentials, and the key it mandates for the private key.
+pub const SSH_AUTH_SECRET_TYPE: &str = "kubernetes.io/ssh-auth";
+pub const SSH_AUTH_PRIVATE_KEY_KEY: &str = "ssh-privatekey";
+
+impl SshConfig {
+    /// The Secret key holding the private key. Only falls back to `id_rsa` here — the
+    /// `kubernetes.io/ssh-auth` default needs the Secret itself and is filled in when the inventory
+    /// is resolved.
+    pub fn effective_private_key_key(&self) -> &str {
+        self.private_key_key
+            .as_deref()
+            .unwrap_or(DEFAULT_PRIVATE_KEY_KEY)
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]